@@ -6,17 +6,160 @@ use crate::devops::{
         self, GhAuthStatus, GitHubComment, GitHubIssue, GitHubPullRequest, IssueAgentMetadata,
         IssueWithAgent, PrStatus,
     },
-    operations::agent_lifecycle::PrDetectionResult,
+    notifications::{self, AgentEventKind, AgentEventNotification, NotificationSettings},
+    operations::{self, agent_lifecycle::PrDetectionResult},
     orchestrator::{
-        self, AgentStatus, CompleteWorkResult, SpawnConfig, SpawnResult, WorkflowConfig,
+        self, AgentDoneStatus, AgentStatus, CompleteWorkResult, SpawnConfig, SpawnResult,
+        WorkflowConfig,
     },
     tmux::{self, AgentMetadata, RecoveredSession, RecoveryResult, TmuxSession},
     worktree::{self, CollisionCheck, WorktreeConfig, WorktreeCreateResult, WorktreeInfo},
     DevOpsDependencies,
 };
 use crate::settings;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{AppHandle, Emitter};
 
+/// Whether mutating commands are refused (observer mode for shared machines)
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Turn read-only observer mode on or off.
+pub fn set_read_only(enabled: bool) {
+    READ_ONLY.store(enabled, Ordering::Relaxed);
+}
+
+/// Commands that spawn, kill, merge or otherwise change agents, sandboxes,
+/// GitHub or Handy's own state. The invoke handler refuses these while
+/// read-only mode is on; `set_readonly_mode` itself stays allowed.
+const MUTATING_COMMANDS: &[&str] = &[
+    "create_tmux_session",
+    "kill_tmux_session",
+    "send_tmux_command",
+    "send_tmux_keys",
+    "resize_tmux_session",
+    "reattach_sandbox",
+    "restart_agent_in_session",
+    "pause_agent",
+    "resume_agent",
+    "recover_all_agent_sessions",
+    "create_git_worktree",
+    "create_git_worktree_existing_branch",
+    "remove_git_worktree",
+    "prune_git_worktrees",
+    "create_github_issue",
+    "comment_on_github_issue",
+    "assign_agent_to_issue",
+    "update_github_issue_labels",
+    "close_github_issue",
+    "reopen_github_issue",
+    "create_github_pr",
+    "merge_github_pr",
+    "close_github_pr",
+    "spawn_agent",
+    "spawn_agent_from_pr",
+    "drain_all_agents",
+    "cleanup_agent",
+    "dedupe_sessions",
+    "create_pr_from_agent",
+    "complete_agent_work",
+    "check_and_cleanup_merged_pr",
+    "mark_github_pr_ready",
+    "register_output_trigger",
+    "set_github_issue_project_status",
+    "add_issue_to_project",
+    "set_project_field",
+    "cleanup_orphaned_containers",
+    "clear_claude_auth_volume",
+    "launch_claude_auth_setup",
+    "cancel_claude_auth",
+    "create_epic",
+    "create_sub_issues",
+    "update_epic_progress",
+    "spawn_agent_from_issue",
+    "update_agent_metadata_comment",
+    "complete_agent_work_with_pr",
+    "start_epic_orchestration",
+    "update_epic_phase_status_on_github",
+    "mark_epic_phase_status",
+    "move_sub_issue_to_phase",
+    "abort_support_worker",
+    "merge_ready_pr",
+    "process_ready_prs",
+    "spawn_sandbox",
+    "stop_sandbox",
+    "remove_sandbox",
+    "setup_devcontainer",
+    "start_devcontainer",
+    "start_devcontainer_streaming",
+    "exec_in_devcontainer",
+    "recreate_agent_network",
+    "assign_issue_to_agent_pipeline",
+    "skip_issue",
+    "repair_pipeline_store",
+    "relocate_stores",
+    "requeue_rejected_items",
+    "launch_cli_auth",
+    "recover_sandboxes",
+    "ensure_master_tmux_session",
+    "cancel_spawn",
+    "remove_output_trigger",
+    "toggle_agent_enabled",
+    "set_enabled_agents",
+    "set_sandbox_enabled",
+    "set_sandbox_policy",
+    "set_monitor_enabled",
+    "set_monitor_interval",
+    "import_devops_config",
+    "set_default_work_repo",
+    "set_github_project_config",
+    "backup_claude_auth_volume",
+    "switch_active_epic",
+    "set_active_epic_state",
+    "set_active_epic_from_recovery",
+    "clear_active_epic_state",
+    "sync_active_epic_state",
+    "update_epic_sub_issue_agent",
+    "set_epic_local_repo_path",
+    "on_pipeline_item_complete",
+    "prewarm_sandbox_image",
+    "ensure_agent_network",
+    "prune_pipeline_history",
+    "set_pipeline_max_history",
+    "detect_and_link_prs",
+    "sync_all_pr_statuses",
+    "check_sessions_for_prs",
+    "set_auto_ready_draft_prs",
+    "set_auto_delete_merged_branches",
+    "set_command_timeouts",
+    "set_session_name_template",
+    "set_debug_commands",
+    "register_sensitive_pattern",
+    "remove_sensitive_pattern",
+    "set_stuck_threshold_secs",
+    "set_agent_assignee",
+    "set_notification_settings",
+    "update_pipeline_item_pr_status",
+    "link_pr_to_pipeline_item",
+    "archive_pipeline_item",
+    "remove_pipeline_item",
+];
+
+/// Whether read-only mode refuses the command invoked as `command`.
+pub fn blocked_by_read_only(command: &str) -> bool {
+    READ_ONLY.load(Ordering::Relaxed) && MUTATING_COMMANDS.contains(&command)
+}
+
+/// Refuse a mutating command while read-only mode is on.
+///
+/// Only for commands whose arguments decide whether they mutate; the rest
+/// are listed in `MUTATING_COMMANDS`.
+fn ensure_writable() -> Result<(), String> {
+    if READ_ONLY.load(Ordering::Relaxed) {
+        return Err("read-only mode".to_string());
+    }
+    Ok(())
+}
+
 /// Check if required DevOps dependencies (gh, tmux) are installed.
 /// Runs in a blocking task to avoid freezing the UI.
 #[tauri::command]
@@ -27,6 +170,27 @@ pub async fn check_devops_dependencies() -> Result<DevOpsDependencies, String> {
         .map_err(|e| format!("Failed to check dependencies: {}", e))
 }
 
+/// Report versions of the DevOps tools and installed agent CLIs, flagging ones
+/// older than Handy supports.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dependency_versions() -> Result<Vec<crate::devops::DependencyVersion>, String> {
+    tokio::task::spawn_blocking(crate::devops::get_dependency_versions)
+        .await
+        .map_err(|e| format!("Failed to check dependency versions: {}", e))
+}
+
+/// List known agent types with install status, enabled state, and sandbox support.
+/// Runs in a blocking task since it shells out to `which` for each agent.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_agent_types(app: AppHandle) -> Result<Vec<crate::devops::AgentTypeInfo>, String> {
+    let enabled_agents = settings::get_settings(&app).enabled_agents;
+    tokio::task::spawn_blocking(move || crate::devops::list_agent_types(&enabled_agents))
+        .await
+        .map_err(|e| format!("Failed to list agent types: {}", e))
+}
+
 /// Launch authentication flow for a CLI tool by creating a tmux session.
 /// Returns the session name so the user can attach to it.
 #[tauri::command]
@@ -167,6 +331,14 @@ pub fn create_tmux_session(
             .map(|h| h.to_string_lossy().to_string())
             .unwrap_or_else(|_| "unknown".to_string()),
         started_at: chrono::Utc::now().to_rfc3339(),
+        subpath: None,
+        base_ref: None,
+        pr_number: None,
+        verbose: false,
+        paused: false,
+        push_remote: None,
+        quarantine: false,
+        worktree_reused: false,
     };
 
     tmux::create_session(&session_name, working_dir.as_deref(), &metadata)
@@ -179,11 +351,23 @@ pub fn kill_tmux_session(session_name: String) -> Result<(), String> {
     tmux::kill_session(&session_name)
 }
 
-/// Get recent output from a tmux session.
+/// List the windows and panes of a tmux session.
+#[tauri::command]
+#[specta::specta]
+pub fn list_tmux_session_panes(session_name: String) -> Result<Vec<tmux::TmuxPane>, String> {
+    tmux::list_session_panes(&session_name)
+}
+
+/// Get recent output from a tmux session's active pane, or from `pane`
+/// (`<window>.<pane>`, see `list_tmux_session_panes`).
 #[tauri::command]
 #[specta::specta]
-pub fn get_tmux_session_output(session_name: String, lines: Option<u32>) -> Result<String, String> {
-    tmux::get_session_output(&session_name, lines)
+pub fn get_tmux_session_output(
+    session_name: String,
+    lines: Option<u32>,
+    pane: Option<String>,
+) -> Result<String, String> {
+    tmux::get_session_output(&session_name, lines, pane.as_deref())
 }
 
 /// Send a command to a tmux session (appends Enter key).
@@ -196,10 +380,23 @@ pub fn send_tmux_command(session_name: String, command: String) -> Result<(), St
 
 /// Send raw keys to a tmux session without appending Enter.
 /// Use for special keys: Enter, Escape, Tab, Space, BSpace, Up, Down, Left, Right, C-c, etc.
+/// Keys go to the active pane unless `pane` (`<window>.<pane>`) is given.
+#[tauri::command]
+#[specta::specta]
+pub fn send_tmux_keys(
+    session_name: String,
+    keys: String,
+    pane: Option<String>,
+) -> Result<(), String> {
+    tmux::send_keys(&session_name, &keys, pane.as_deref())
+}
+
+/// Resize a tmux session to match the UI terminal widget.
+/// Call when the terminal widget resizes so agent output wraps correctly.
 #[tauri::command]
 #[specta::specta]
-pub fn send_tmux_keys(session_name: String, keys: String) -> Result<(), String> {
-    tmux::send_keys(&session_name, &keys)
+pub fn resize_tmux_session(session_name: String, cols: u16, rows: u16) -> Result<(), String> {
+    tmux::resize_session(&session_name, cols, rows)
 }
 
 /// Recover agent sessions on startup.
@@ -209,16 +406,94 @@ pub fn recover_tmux_sessions() -> Result<Vec<RecoveredSession>, String> {
     tmux::recover_sessions()
 }
 
+/// Recover sandbox containers that outlived the app, with recommended actions.
+#[tauri::command]
+#[specta::specta]
+pub async fn recover_sandboxes(
+    app: AppHandle,
+) -> Result<Vec<crate::devops::orchestration::RecoveredSandbox>, String> {
+    tokio::task::spawn_blocking(move || {
+        let store = crate::devops::orchestration::TauriStore::new(&app);
+        let epics = crate::devops::orchestration::list_active_epics(&app);
+        crate::devops::orchestration::recover_sandboxes(&store, &epics)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get what startup recovery found still running from the previous run
+/// (None while it's still in progress). Also emitted as `startup-recovery`.
+#[tauri::command]
+#[specta::specta]
+pub fn get_startup_recovery() -> Option<crate::devops::orchestration::StartupRecoveryReport> {
+    crate::devops::orchestration::get_startup_recovery()
+}
+
+/// Reattach a running sandbox container to a new tmux session after its
+/// original session died, and relink its pipeline item.
+#[tauri::command]
+#[specta::specta]
+pub async fn reattach_sandbox(
+    app: AppHandle,
+    container_name: String,
+) -> Result<crate::devops::orchestration::ReattachResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let store = crate::devops::orchestration::TauriStore::new(&app);
+        crate::devops::orchestration::reattach_sandbox(&store, &container_name)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get token usage and estimated cost for an agent session.
+///
+/// Returns None when the agent hasn't recorded any usage data.
+#[tauri::command]
+#[specta::specta]
+pub fn get_agent_usage(
+    session_name: String,
+) -> Result<Option<crate::devops::usage::AgentUsage>, String> {
+    crate::devops::usage::get_agent_usage(&session_name)
+}
+
+/// Aggregate token usage across sessions (all agent sessions if none are given).
+#[tauri::command]
+#[specta::specta]
+pub async fn get_total_usage(
+    session_names: Option<Vec<String>>,
+) -> Result<crate::devops::usage::UsageSummary, String> {
+    tokio::task::spawn_blocking(move || crate::devops::usage::get_total_usage(session_names))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Restart an agent in an existing tmux session.
 ///
 /// Use this for recovery when a session exists but the agent process has stopped.
 /// This reads the session metadata and restarts the appropriate agent command.
+/// Uncommitted work in the worktree is first saved to a `handy-backup/` branch.
 #[tauri::command]
 #[specta::specta]
-pub fn restart_agent_in_session(session_name: String) -> Result<(), String> {
+pub fn restart_agent_in_session(session_name: String) -> Result<tmux::RestartResult, String> {
     tmux::restart_agent(&session_name)
 }
 
+/// Pause the agent in a session, keeping the session alive.
+///
+/// Sandboxed agents are paused with `docker pause`, local agents with SIGSTOP.
+#[tauri::command]
+#[specta::specta]
+pub fn pause_agent(session_name: String) -> Result<(), String> {
+    tmux::pause_agent(&session_name)
+}
+
+/// Resume an agent paused with `pause_agent`.
+#[tauri::command]
+#[specta::specta]
+pub fn resume_agent(session_name: String) -> Result<(), String> {
+    tmux::resume_agent(&session_name)
+}
+
 /// Recover all sessions that need attention.
 ///
 /// - `auto_restart`: If true, automatically restart agents in stopped sessions
@@ -270,6 +545,40 @@ pub fn get_git_worktree_info(
     worktree::get_worktree_info(&repo_path, &worktree_path)
 }
 
+/// List the worktrees that already have a branch checked out.
+#[tauri::command]
+#[specta::specta]
+pub fn find_branch_conflicts(
+    repo_path: String,
+    branch_name: String,
+) -> Result<Vec<WorktreeInfo>, String> {
+    worktree::find_branch_conflicts(&repo_path, &branch_name)
+}
+
+/// Compare a worktree's branch with its upstream: commits to push, commits
+/// behind, and whether there are uncommitted changes.
+#[tauri::command]
+#[specta::specta]
+pub fn get_branch_sync_status(worktree_path: String) -> Result<worktree::BranchSyncStatus, String> {
+    worktree::get_branch_sync_status(&worktree_path)
+}
+
+/// Run an allowlisted git command (status, log, diff, stash, cherry-pick,
+/// reset, fetch, pull) in an agent's worktree.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_git_in_worktree(
+    worktree_path: String,
+    args: Vec<String>,
+) -> Result<worktree::GitCommandOutput, String> {
+    if !worktree::is_read_only_git_command(&args) {
+        ensure_writable()?;
+    }
+    tokio::task::spawn_blocking(move || worktree::run_git_in_worktree(&worktree_path, &args))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Check for collisions before creating a worktree.
 #[tauri::command]
 #[specta::specta]
@@ -281,6 +590,25 @@ pub fn check_worktree_collision(
     worktree::check_collision(&repo_path, &worktree_path, &branch_name)
 }
 
+/// Preview the name and absolute path a new worktree would get, failing with
+/// the same errors `create_git_worktree` would (existing path, path inside
+/// the repo, relative base path).
+#[tauri::command]
+#[specta::specta]
+pub fn preview_worktree_path(
+    repo_path: String,
+    name: String,
+    prefix: Option<String>,
+    base_path: Option<String>,
+) -> Result<worktree::ResolvedWorktreePath, String> {
+    let config = WorktreeConfig {
+        prefix: prefix.unwrap_or_default(),
+        base_path,
+        ..Default::default()
+    };
+    worktree::resolve_worktree_path(&repo_path, &name, &config)
+}
+
 /// Create a new git worktree with a new branch.
 #[tauri::command]
 #[specta::specta]
@@ -290,11 +618,14 @@ pub fn create_git_worktree(
     prefix: Option<String>,
     base_path: Option<String>,
     base_branch: Option<String>,
+    push_remote: Option<String>,
 ) -> Result<WorktreeCreateResult, String> {
     let config = WorktreeConfig {
         prefix: prefix.unwrap_or_default(),
         base_path,
         delete_branch_on_merge: true,
+        base_ref: None,
+        push_remote,
     };
     worktree::create_worktree(&repo_path, &name, &config, base_branch.as_deref())
 }
@@ -312,6 +643,8 @@ pub fn create_git_worktree_existing_branch(
         prefix: prefix.unwrap_or_default(),
         base_path,
         delete_branch_on_merge: true,
+        base_ref: None,
+        push_remote: None,
     };
     worktree::create_worktree_existing_branch(&repo_path, &branch_name, &config)
 }
@@ -437,7 +770,13 @@ pub fn list_github_issues(
     let labels_ref: Option<Vec<&str>> = labels
         .as_ref()
         .map(|v| v.iter().map(|s| s.as_str()).collect());
-    github::list_issues(&repo, state_ref, labels_ref, limit)
+    github::list_issues(
+        &repo,
+        state_ref,
+        labels_ref,
+        limit,
+        github::IssueFields::All,
+    )
 }
 
 /// Get details of a specific GitHub issue.
@@ -454,6 +793,18 @@ pub fn get_github_issue_with_agent(repo: String, number: u64) -> Result<IssueWit
     github::get_issue_with_agent(&repo, number)
 }
 
+/// Get an issue with its body rendered to sanitized HTML (cached briefly).
+#[tauri::command]
+#[specta::specta]
+pub async fn get_issue_rendered(
+    repo: String,
+    number: u64,
+) -> Result<github::RenderedIssue, String> {
+    tokio::task::spawn_blocking(move || github::get_issue_rendered(&repo, number))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Create a new GitHub issue.
 #[tauri::command]
 #[specta::specta]
@@ -554,7 +905,7 @@ pub fn list_github_prs(
 ) -> Result<Vec<GitHubPullRequest>, String> {
     let state_ref = state.as_deref();
     let base_ref = base.as_deref();
-    github::list_prs(&repo, state_ref, base_ref, limit)
+    github::list_prs(&repo, state_ref, base_ref, limit, github::PrFields::All)
 }
 
 /// Get details of a specific GitHub pull request.
@@ -571,6 +922,17 @@ pub fn get_github_pr_status(repo: String, number: u64) -> Result<PrStatus, Strin
     github::get_pr_status(&repo, number)
 }
 
+/// Get the failing log output for a named CI check on a pull request.
+#[tauri::command]
+#[specta::specta]
+pub fn get_github_pr_check_logs(
+    repo: String,
+    number: u64,
+    check_name: String,
+) -> Result<String, String> {
+    github::get_pr_check_logs(&repo, number, &check_name)
+}
+
 /// Create a new GitHub pull request.
 #[tauri::command]
 #[specta::specta]
@@ -588,6 +950,8 @@ pub fn create_github_pr(
 }
 
 /// Merge a GitHub pull request.
+///
+/// Optional `commit_title`/`commit_body` control the merge commit message.
 #[tauri::command]
 #[specta::specta]
 pub fn merge_github_pr(
@@ -595,8 +959,17 @@ pub fn merge_github_pr(
     number: u64,
     method: Option<String>,
     delete_branch: bool,
+    commit_title: Option<String>,
+    commit_body: Option<String>,
 ) -> Result<(), String> {
-    github::merge_pr(&repo, number, method.as_deref(), delete_branch)
+    github::merge_pr(
+        &repo,
+        number,
+        method.as_deref(),
+        delete_branch,
+        commit_title.as_deref(),
+        commit_body.as_deref(),
+    )
 }
 
 /// Close a GitHub pull request without merging.
@@ -613,10 +986,23 @@ pub fn close_github_pr(repo: String, number: u64, comment: Option<String>) -> Re
 /// Spawn a new agent to work on an issue.
 ///
 /// Creates a worktree, tmux session (or Docker container if sandbox enabled),
-/// and updates the issue with metadata.
-#[tauri::command]
-#[specta::specta]
-pub fn spawn_agent(
+/// and updates the issue with metadata. Emits `spawn-progress` events as each
+/// step starts and finishes. Defaults not passed explicitly (an empty
+/// `agent_type`, labels, sandbox mode, ports) come from the repo's
+/// `.handy/config.toml` when present. With `resume`, an issue branch pushed
+/// by a previous attempt is checked out instead of creating a new one.
+/// A failed or cancelled spawn rolls back what it created. With
+/// `debug_capture`, the constructed agent command is logged and saved for
+/// `get_session_debug_info`. `base_ref` pins the worktree to a commit SHA or
+/// tag instead of the base branch's HEAD. With `target_machine_id`, the spawn
+/// is refused unless this is that machine. `on_branch_conflict` decides what
+/// happens when the issue's branch is already checked out in another worktree.
+/// `push_remote` pushes the branch to another remote (e.g., a fork) and opens
+/// the PR from it. `quarantine` runs an untrusted issue sandboxed without
+/// network, credentials or write access, and never opens a PR.
+#[tauri::command]
+#[specta::specta]
+pub async fn spawn_agent(
     app: AppHandle,
     repo: String,
     issue_number: u64,
@@ -626,12 +1012,40 @@ pub fn spawn_agent(
     worktree_prefix: Option<String>,
     working_labels: Option<Vec<String>>,
     use_sandbox: Option<bool>,
+    subpath: Option<String>,
+    base_branch: Option<String>,
+    base_ref: Option<String>,
+    focus_files: Option<Vec<String>>,
+    resume: Option<bool>,
+    debug_capture: Option<bool>,
+    target_machine_id: Option<String>,
+    on_branch_conflict: Option<orchestrator::BranchConflictResolution>,
+    verbose: Option<bool>,
+    push_remote: Option<String>,
+    quarantine: Option<bool>,
+    reuse_worktree: Option<String>,
 ) -> Result<SpawnResult, String> {
-    // Get sandbox setting from app settings if not explicitly provided
-    let sandbox_enabled = use_sandbox.unwrap_or_else(|| {
-        let app_settings = settings::get_settings(&app);
-        app_settings.sandbox_enabled
-    });
+    // The repo's .handy/config.toml fills in anything not passed explicitly
+    let repo_config = operations::load_repo_config(&repo_path)?.unwrap_or_default();
+
+    // Get sandbox setting from the repo config or app settings if not explicitly provided.
+    // Quarantine only exists inside a sandbox, so it always enables one
+    let quarantine = quarantine.unwrap_or(false);
+    let sandbox_enabled = quarantine
+        || use_sandbox
+            .or(repo_config.sandbox.enabled)
+            .unwrap_or_else(|| {
+                let app_settings = settings::get_settings(&app);
+                app_settings.sandbox_enabled
+            });
+
+    let agent_type = if agent_type.is_empty() {
+        repo_config
+            .agent_type
+            .unwrap_or_else(|| "claude".to_string())
+    } else {
+        agent_type
+    };
 
     let config = SpawnConfig {
         repo,
@@ -639,11 +1053,105 @@ pub fn spawn_agent(
         agent_type,
         session_name,
         worktree_prefix,
-        working_labels: working_labels.unwrap_or_default(),
+        working_labels: working_labels.unwrap_or(repo_config.start_labels),
         use_sandbox: sandbox_enabled,
-        sandbox_ports: vec![], // Auto-detect ports from project
+        sandbox_ports: repo_config.ports, // Empty means auto-detect from project
+        sandbox_docker_args: repo_config.sandbox.extra_docker_args,
+        subpath,
+        base_branch,
+        base_ref,
+        focus_files: focus_files.unwrap_or_default(),
+        resume: resume.unwrap_or(false),
+        debug_capture: debug_capture.unwrap_or(false),
+        target_machine_id,
+        on_branch_conflict: on_branch_conflict.unwrap_or_default(),
+        verbose: verbose.unwrap_or(false),
+        push_remote,
+        quarantine,
+        reuse_worktree,
+    };
+    let result = tokio::task::spawn_blocking({
+        let app = app.clone();
+        let config = config.clone();
+        move || {
+            orchestrator::spawn_agent_with_progress(&config, &repo_path, &mut |progress| {
+                let _ = app.emit("spawn-progress", progress);
+            })
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    if let Err(e) = &result {
+        notify_spawn_failure(&app, &config.repo, Some(config.issue_number), e);
+    }
+    result
+}
+
+/// Send a `Failed` notification for an agent that couldn't be spawned
+fn notify_spawn_failure(app: &AppHandle, repo: &str, issue_number: Option<u64>, error: &str) {
+    let mut notification = AgentEventNotification::new(AgentEventKind::Failed, error);
+    notification.repo = Some(repo.to_string());
+    notification.issue_number = issue_number;
+    notifications::notify(app, notification);
+}
+
+/// Spawn an agent on an existing PR to address its review comments.
+///
+/// An empty `agent_type` falls back to the repo's `.handy/config.toml`, then "claude".
+#[tauri::command]
+#[specta::specta]
+pub async fn spawn_agent_from_pr(
+    app: AppHandle,
+    repo: String,
+    pr_number: u64,
+    agent_type: String,
+    repo_path: String,
+) -> Result<orchestrator::PrSpawnResult, String> {
+    let repo_config = operations::load_repo_config(&repo_path)?.unwrap_or_default();
+    // PR agents always run directly in tmux
+    if repo_config.sandbox.enabled == Some(true) {
+        return Err(format!(
+            "{} requires a sandbox (.handy/config.toml); PR agents run without one",
+            repo_path
+        ));
+    }
+    let agent_type = if agent_type.is_empty() {
+        repo_config
+            .agent_type
+            .unwrap_or_else(|| "claude".to_string())
+    } else {
+        agent_type
     };
-    orchestrator::spawn_agent(&config, &repo_path)
+    let result = tokio::task::spawn_blocking({
+        let repo = repo.clone();
+        move || orchestrator::spawn_agent_from_pr(&repo, &repo_path, pr_number, &agent_type)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    if let Err(e) = &result {
+        notify_spawn_failure(&app, &repo, None, e);
+    }
+    result
+}
+
+/// Cancel an in-flight `spawn_agent` for an issue.
+///
+/// The spawn stops before its next step and rolls back the worktree, session
+/// and comment it created. Returns false if no spawn is running for the issue.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_spawn(repo: String, issue_number: u64) -> bool {
+    orchestrator::cancel_spawn(&repo, issue_number)
+}
+
+/// Get the command, env var names, mounts and ports a session was started
+/// with. Only available for sessions spawned with `debug_capture`.
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_debug_info(session_name: String) -> Result<tmux::SessionDebugInfo, String> {
+    tmux::get_session_debug_info(&session_name)
 }
 
 /// Get status of all active agents.
@@ -653,6 +1161,27 @@ pub fn list_agent_statuses() -> Result<Vec<AgentStatus>, String> {
     orchestrator::list_agent_statuses()
 }
 
+/// Gracefully stop all agents on this machine (e.g. before shutting it down).
+///
+/// With `commit_wip`, uncommitted work is saved to a backup branch first.
+/// Emits `agent-drained` for each agent and returns the per-agent results.
+#[tauri::command]
+#[specta::specta]
+pub async fn drain_all_agents(
+    app: AppHandle,
+    commit_wip: bool,
+) -> Result<Vec<orchestrator::DrainResult>, String> {
+    let results = tokio::task::spawn_blocking(move || orchestrator::drain_all_agents(commit_wip))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+
+    for result in &results {
+        let _ = app.emit("agent-drained", result.clone());
+    }
+
+    Ok(results)
+}
+
 /// Clean up an agent's resources after work is complete.
 #[tauri::command]
 #[specta::specta]
@@ -665,6 +1194,39 @@ pub fn cleanup_agent(
     orchestrator::cleanup_agent(&session_name, &repo_path, remove_worktree, delete_branch)
 }
 
+/// Find issues with more than one live agent session
+#[tauri::command]
+#[specta::specta]
+pub async fn find_duplicate_sessions() -> Result<Vec<orchestrator::DuplicateSessionGroup>, String> {
+    tokio::task::spawn_blocking(orchestrator::find_duplicate_sessions)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Kill an issue's duplicate agent sessions, keeping `keep_session`, and
+/// optionally their sandbox containers and worktrees
+#[tauri::command]
+#[specta::specta]
+pub async fn dedupe_sessions(
+    issue_number: u32,
+    keep_session: String,
+    repo_path: String,
+    remove_containers: Option<bool>,
+    remove_worktrees: Option<bool>,
+) -> Result<orchestrator::DedupeResult, String> {
+    tokio::task::spawn_blocking(move || {
+        orchestrator::dedupe_sessions(
+            issue_number,
+            &keep_session,
+            &repo_path,
+            remove_containers.unwrap_or(false),
+            remove_worktrees.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Create a PR from an agent's work.
 #[tauri::command]
 #[specta::specta]
@@ -679,35 +1241,130 @@ pub fn create_pr_from_agent(
 
 /// Complete an agent's work with workflow automation.
 ///
-/// Creates PR, updates issue with link, manages labels.
+/// Creates PR, updates issue with link, manages labels. When an agent
+/// assignee is configured, the issue's assignees are cleared. With
+/// `verify_before_pr`, the repo's tests run first and a failure is reported
+/// on the issue instead of creating a PR.
 #[tauri::command]
 #[specta::specta]
-pub fn complete_agent_work(
+pub async fn complete_agent_work(
+    app: AppHandle,
     session_name: String,
     pr_title: String,
     pr_body: Option<String>,
     working_labels: Vec<String>,
     pr_labels: Vec<String>,
     draft_pr: bool,
+    promote_draft_when_verified: Option<bool>,
+    verify_before_pr: Option<bool>,
 ) -> Result<CompleteWorkResult, String> {
     let config = WorkflowConfig {
         working_labels,
         pr_labels,
         draft_pr,
         close_on_merge: true,
+        promote_draft_when_verified: promote_draft_when_verified.unwrap_or(false),
+        remove_assignee: settings::get_settings(&app).agent_assignee,
+        verify_before_pr: verify_before_pr.unwrap_or(false),
+    };
+    let (issue_ref, result) = tokio::task::spawn_blocking({
+        let session_name = session_name.clone();
+        move || {
+            let issue_ref = tmux::get_session_metadata(&session_name)
+                .ok()
+                .and_then(|m| m.issue_ref);
+            let result = orchestrator::complete_agent_work(
+                &session_name,
+                &pr_title,
+                pr_body.as_deref(),
+                &config,
+            );
+            (issue_ref, result)
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    let mut notification = match &result {
+        Ok(CompleteWorkResult {
+            pull_request: Some(pr),
+            ..
+        }) => {
+            let mut notification =
+                AgentEventNotification::new(AgentEventKind::WorkCompleted, pr.title.clone());
+            notification.pr_url = Some(pr.url.clone());
+            notification
+        }
+        Ok(_) => AgentEventNotification::new(
+            AgentEventKind::Failed,
+            "Verification failed; no PR was created".to_string(),
+        ),
+        Err(e) => AgentEventNotification::new(AgentEventKind::Failed, e.clone()),
     };
-    orchestrator::complete_agent_work(&session_name, &pr_title, pr_body.as_deref(), &config)
+    if let Some(issue_ref) = &issue_ref {
+        notification = notification.with_issue_ref(issue_ref);
+    }
+    notification.session = Some(session_name);
+    notifications::notify(&app, notification);
+
+    result
 }
 
 /// Check if a PR has been merged and cleanup resources if so.
+///
+/// When `promote_draft_when_verified` is set, a draft PR whose checks have
+/// passed is marked ready for review.
 #[tauri::command]
 #[specta::specta]
 pub fn check_and_cleanup_merged_pr(
     session_name: String,
     repo_path: String,
     pr_number: u64,
+    promote_draft_when_verified: Option<bool>,
 ) -> Result<bool, String> {
-    orchestrator::check_and_cleanup_merged_pr(&session_name, &repo_path, pr_number)
+    let config = WorkflowConfig {
+        working_labels: vec![],
+        pr_labels: vec![],
+        draft_pr: false,
+        close_on_merge: true,
+        promote_draft_when_verified: promote_draft_when_verified.unwrap_or(false),
+        remove_assignee: None,
+        verify_before_pr: false,
+    };
+    orchestrator::check_and_cleanup_merged_pr(&session_name, &repo_path, pr_number, &config)
+}
+
+/// Check whether an agent has signalled completion via marker file or commit.
+#[tauri::command]
+#[specta::specta]
+pub fn detect_agent_done(session_name: String) -> Result<AgentDoneStatus, String> {
+    orchestrator::detect_agent_done(&session_name)
+}
+
+/// Check whether an agent asked a question on its issue and is waiting for a reply.
+///
+/// Emits `agent-question` when the agent is waiting so the dashboard can
+/// surface it.
+#[tauri::command]
+#[specta::specta]
+pub fn detect_agent_question(
+    app: AppHandle,
+    repo: String,
+    issue_number: u64,
+    session_name: String,
+) -> Result<orchestrator::AgentQuestionStatus, String> {
+    let status = orchestrator::detect_agent_question(&repo, issue_number, &session_name)?;
+    if status.waiting {
+        let _ = app.emit("agent-question", status.clone());
+    }
+    Ok(status)
+}
+
+/// Mark a draft GitHub pull request as ready for review.
+#[tauri::command]
+#[specta::specta]
+pub fn mark_github_pr_ready(repo: String, number: u64) -> Result<(), String> {
+    github::mark_pr_ready(&repo, number)
 }
 
 /// Get current machine identifier.
@@ -717,6 +1374,40 @@ pub fn get_current_machine_id() -> String {
     orchestrator::get_current_machine_id()
 }
 
+/// List machines known from agent session metadata, for picking a target machine.
+#[tauri::command]
+#[specta::specta]
+pub fn list_known_machines() -> Vec<orchestrator::KnownMachine> {
+    orchestrator::list_known_machines()
+}
+
+/// Watch an agent session's output for a regex `pattern` and run `action`
+/// (emit `agent-output-trigger`, comment on the issue, or kill the session)
+/// whenever a new line matches.
+#[tauri::command]
+#[specta::specta]
+pub fn register_output_trigger(
+    session_name: String,
+    pattern: String,
+    action: orchestrator::TriggerAction,
+) -> Result<orchestrator::OutputTrigger, String> {
+    orchestrator::register_output_trigger(&session_name, &pattern, action)
+}
+
+/// List registered output triggers.
+#[tauri::command]
+#[specta::specta]
+pub fn list_output_triggers() -> Vec<orchestrator::OutputTrigger> {
+    orchestrator::list_output_triggers()
+}
+
+/// Remove an output trigger. Returns false if it didn't exist.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_output_trigger(id: u64) -> bool {
+    orchestrator::remove_output_trigger(id)
+}
+
 /// List only agents running on this machine.
 #[tauri::command]
 #[specta::specta]
@@ -793,55 +1484,283 @@ pub fn set_sandbox_enabled(app: AppHandle, enabled: bool) -> bool {
     enabled
 }
 
-/// Clean up orphaned Docker containers from sandbox execution.
-///
-/// Finds and removes containers that match `handy-sandbox-*` or `handy-support-sandbox-*`
-/// but don't have a corresponding active tmux session.
-///
-/// Emits `orphan-container-cleaned` events for each cleaned container (for toast notifications).
+/// Get which agent types must, or must never, run sandboxed.
 #[tauri::command]
 #[specta::specta]
-pub fn cleanup_orphaned_containers(
-    app: AppHandle,
-) -> Result<crate::devops::docker::OrphanCleanupResult, String> {
-    let result = crate::devops::docker::cleanup_orphaned_containers()?;
-
-    // Emit events for each cleaned orphan so UI can show toast notifications
-    for orphan in &result.cleaned_orphans {
-        let _ = app.emit("orphan-container-cleaned", orphan.clone());
-    }
-
-    Ok(result)
+pub fn get_sandbox_policy(app: AppHandle) -> orchestrator::SandboxPolicy {
+    settings::get_settings(&app).sandbox_policy
 }
 
-/// Check the status of the Claude Code authentication volume.
-///
-/// Returns information about whether the volume exists and has credentials.
+/// Set which agent types must, or must never, run sandboxed.
 #[tauri::command]
 #[specta::specta]
-pub fn check_claude_auth_volume() -> Result<crate::devops::docker::ClaudeAuthVolumeStatus, String> {
-    crate::devops::docker::check_claude_auth_volume()
+pub fn set_sandbox_policy(
+    app: AppHandle,
+    policy: orchestrator::SandboxPolicy,
+) -> Result<orchestrator::SandboxPolicy, String> {
+    orchestrator::set_sandbox_policy(policy.clone())?;
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.sandbox_policy = policy.clone();
+    settings::write_settings(&app, app_settings);
+    Ok(policy)
 }
 
-/// Launch an interactive container for Claude Code authentication.
-///
-/// Opens a new Terminal window with a container where the user can run `claude /login`.
-/// Credentials are saved to a persistent Docker volume for future sandbox use.
+/// Get whether the PR-detection monitor is running.
 #[tauri::command]
 #[specta::specta]
-pub fn launch_claude_auth_setup() -> Result<String, String> {
-    crate::devops::docker::launch_claude_auth_in_terminal()
+pub fn is_monitor_enabled(app: AppHandle) -> bool {
+    settings::get_settings(&app).pr_monitor_enabled
 }
 
-// ===== Epic Workflow Operations =====
-
-/// Create a new epic issue with standardized structure
+/// Pause or resume the PR-detection monitor (e.g. during a rate-limit backoff).
 #[tauri::command]
 #[specta::specta]
-pub async fn create_epic(
-    config: crate::devops::operations::EpicConfig,
-) -> Result<crate::devops::operations::EpicInfo, String> {
-    crate::devops::operations::create_epic(config).await
+pub fn set_monitor_enabled(app: AppHandle, enabled: bool) -> bool {
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.pr_monitor_enabled = enabled;
+    settings::write_settings(&app, app_settings);
+    enabled
+}
+
+/// Get the minimum number of seconds between PR-detection checks.
+#[tauri::command]
+#[specta::specta]
+pub fn get_monitor_interval(app: AppHandle) -> u64 {
+    settings::get_settings(&app).pr_monitor_interval_secs
+}
+
+/// Set the minimum number of seconds between PR-detection checks.
+/// Returns the interval actually applied (clamped to a small minimum).
+#[tauri::command]
+#[specta::specta]
+pub fn set_monitor_interval(app: AppHandle, interval_secs: u64) -> u64 {
+    let interval_secs =
+        interval_secs.max(crate::devops::orchestration::MIN_PR_MONITOR_INTERVAL_SECS);
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.pr_monitor_interval_secs = interval_secs;
+    settings::write_settings(&app, app_settings);
+    interval_secs
+}
+
+/// Export DevOps settings (agents, sandbox, work repos, project board) as a JSON blob.
+#[tauri::command]
+#[specta::specta]
+pub fn export_devops_config(app: AppHandle) -> Result<String, String> {
+    let app_settings = settings::get_settings(&app);
+    let config = crate::devops::config::export_config(&app_settings);
+    serde_json::to_string_pretty(&config).map_err(|e| format!("Failed to serialize config: {}", e))
+}
+
+/// Import a DevOps configuration blob.
+///
+/// With `merge`, entries are added to the current settings; otherwise they replace them.
+/// Returns the resulting configuration.
+#[tauri::command]
+#[specta::specta]
+pub fn import_devops_config(
+    app: AppHandle,
+    json: String,
+    merge: bool,
+) -> Result<crate::devops::config::DevOpsConfig, String> {
+    let config = crate::devops::config::parse_config(&json)?;
+    let mut app_settings = settings::get_settings(&app);
+    crate::devops::config::apply_config(&mut app_settings, config, merge);
+
+    crate::devops::config::apply_runtime_settings(&app_settings);
+    let result = crate::devops::config::export_config(&app_settings);
+    settings::write_settings(&app, app_settings);
+    Ok(result)
+}
+
+/// Get the default work repository configured for a tracking repository.
+#[tauri::command]
+#[specta::specta]
+pub fn get_default_work_repo(app: AppHandle, tracking_repo: String) -> Option<String> {
+    let app_settings = settings::get_settings(&app);
+    app_settings.default_work_repos.get(&tracking_repo).cloned()
+}
+
+/// Set (or clear, when `work_repo` is None) the default work repository for a tracking repository.
+#[tauri::command]
+#[specta::specta]
+pub fn set_default_work_repo(
+    app: AppHandle,
+    tracking_repo: String,
+    work_repo: Option<String>,
+) -> Option<String> {
+    let mut app_settings = settings::get_settings(&app);
+    match work_repo.as_deref().map(str::trim) {
+        Some(repo) if !repo.is_empty() => {
+            app_settings
+                .default_work_repos
+                .insert(tracking_repo, repo.to_string());
+        }
+        _ => {
+            app_settings.default_work_repos.remove(&tracking_repo);
+        }
+    }
+    let result = work_repo.filter(|r| !r.trim().is_empty());
+    settings::write_settings(&app, app_settings);
+    result
+}
+
+/// Get the GitHub Projects (v2) board configuration used for status sync.
+#[tauri::command]
+#[specta::specta]
+pub fn get_github_project_config(app: AppHandle) -> Option<github::ProjectStatusConfig> {
+    settings::get_settings(&app).github_project
+}
+
+/// Set (or clear, when None) the GitHub Projects (v2) board configuration.
+#[tauri::command]
+#[specta::specta]
+pub fn set_github_project_config(app: AppHandle, config: Option<github::ProjectStatusConfig>) {
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.github_project = config;
+    settings::write_settings(&app, app_settings);
+}
+
+/// Move an issue's project board card to a status ("todo", "in_progress", "done").
+#[tauri::command]
+#[specta::specta]
+pub fn set_github_issue_project_status(
+    app: AppHandle,
+    repo: String,
+    number: u64,
+    status: String,
+) -> Result<(), String> {
+    let project = settings::get_settings(&app)
+        .github_project
+        .ok_or("No GitHub project configured")?;
+    github::set_issue_project_status(&project, &repo, number, &status)
+}
+
+/// Add an issue to a Projects (v2) board by project number. Returns the item ID.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_issue_to_project(
+    repo: String,
+    issue_number: u64,
+    project_number: u32,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        github::add_issue_to_project(&repo, issue_number, project_number)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Set a field (by name) on an issue's card in a Projects (v2) board.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_project_field(
+    repo: String,
+    project_number: u32,
+    issue_number: u64,
+    field: String,
+    value: String,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        github::set_project_field(&repo, project_number, issue_number, &field, &value)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Clean up orphaned Docker containers from sandbox execution.
+///
+/// Finds and removes containers that match `handy-sandbox-*` or `handy-support-sandbox-*`
+/// but don't have a corresponding active tmux session.
+///
+/// Emits `orphan-container-cleaned` events for each cleaned container (for toast notifications).
+#[tauri::command]
+#[specta::specta]
+pub fn cleanup_orphaned_containers(
+    app: AppHandle,
+) -> Result<crate::devops::docker::OrphanCleanupResult, String> {
+    let result = crate::devops::docker::cleanup_orphaned_containers()?;
+
+    // Emit events for each cleaned orphan so UI can show toast notifications
+    for orphan in &result.cleaned_orphans {
+        let _ = app.emit("orphan-container-cleaned", orphan.clone());
+    }
+
+    Ok(result)
+}
+
+/// Check the status of the Claude Code authentication volume.
+///
+/// Returns information about whether the volume exists and has credentials.
+#[tauri::command]
+#[specta::specta]
+pub fn check_claude_auth_volume() -> Result<crate::devops::docker::ClaudeAuthVolumeStatus, String> {
+    crate::devops::docker::check_claude_auth_volume()
+}
+
+/// List the files in the Claude Code authentication volume (names, sizes and
+/// modification times; contents are never read).
+#[tauri::command]
+#[specta::specta]
+pub fn list_claude_auth_files() -> Result<Vec<crate::devops::docker::ClaudeAuthFile>, String> {
+    crate::devops::docker::list_claude_auth_files()
+}
+
+/// Remove the Claude Code authentication volume to force a fresh login.
+///
+/// Returns false if there was no volume to remove.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_claude_auth_volume() -> Result<bool, String> {
+    crate::devops::docker::clear_claude_auth_volume()
+}
+
+/// Back up the Claude Code authentication volume to a `.tar.gz` at `path`.
+#[tauri::command]
+#[specta::specta]
+pub async fn backup_claude_auth_volume(path: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || crate::devops::docker::backup_claude_auth_volume(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Launch an interactive container for Claude Code authentication.
+///
+/// Opens a new Terminal window with a container where the user can run `claude /login`.
+/// Credentials are saved to a persistent Docker volume for future sandbox use.
+#[tauri::command]
+#[specta::specta]
+pub fn launch_claude_auth_setup() -> Result<String, String> {
+    crate::devops::docker::launch_claude_auth_in_terminal()
+}
+
+/// Remove the Claude auth container left behind when its terminal was closed
+/// without `exit`, reporting whether the login completed first.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_claude_auth() -> Result<crate::devops::docker::ClaudeAuthCancelResult, String> {
+    crate::devops::docker::cancel_claude_auth()
+}
+
+/// Wait up to `timeout_secs` (default 300) for the auth container to save
+/// fresh credentials. Returns false on timeout.
+#[tauri::command]
+#[specta::specta]
+pub async fn wait_for_claude_auth(timeout_secs: Option<u64>) -> Result<bool, String> {
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(300));
+    tokio::task::spawn_blocking(move || crate::devops::docker::wait_for_claude_auth(timeout))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ===== Epic Workflow Operations =====
+
+/// Create a new epic issue with standardized structure
+#[tauri::command]
+#[specta::specta]
+pub async fn create_epic(
+    config: crate::devops::operations::EpicConfig,
+) -> Result<crate::devops::operations::EpicInfo, String> {
+    crate::devops::operations::create_epic(config).await
 }
 
 /// Create multiple sub-issues for an epic in batch
@@ -871,19 +1790,51 @@ pub async fn update_epic_progress(
 #[tauri::command]
 #[specta::specta]
 pub async fn spawn_agent_from_issue(
+    app: AppHandle,
     config: crate::devops::operations::SpawnAgentConfig,
 ) -> Result<crate::devops::operations::AgentSpawnResult, String> {
-    crate::devops::operations::spawn_agent_from_issue(config).await
+    let tracking_repo = config.issue_ref.split('#').next().unwrap_or_default();
+    let default_work_repo = settings::get_settings(&app)
+        .default_work_repos
+        .get(tracking_repo)
+        .cloned();
+    crate::devops::operations::spawn_agent_from_issue(config, default_work_repo).await
 }
 
-/// Complete agent work by creating a PR
+/// Refresh an issue's agent metadata comment from the session's current metadata
+#[tauri::command]
+#[specta::specta]
+pub async fn update_agent_metadata_comment(
+    repo: String,
+    issue_number: u32,
+    session_name: String,
+) -> Result<u64, String> {
+    crate::devops::operations::update_agent_metadata_comment(repo, issue_number, session_name).await
+}
+
+/// Complete agent work by creating a PR, optionally running the repo's tests first
 #[tauri::command]
 #[specta::specta]
 pub async fn complete_agent_work_with_pr(
     session: String,
     pr_title: Option<String>,
+    run_tests: Option<bool>,
 ) -> Result<crate::devops::operations::AgentCompletionResult, String> {
-    crate::devops::operations::complete_agent_work(session, pr_title).await
+    crate::devops::operations::complete_agent_work(session, pr_title, run_tests.unwrap_or(false))
+        .await
+}
+
+/// Detect how a worktree's tests are run from its project files
+#[tauri::command]
+#[specta::specta]
+pub async fn detect_test_command(
+    worktree_path: String,
+) -> Result<Vec<crate::devops::operations::DetectedTestCommand>, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::devops::operations::detect_test_command(&worktree_path)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 /// Plan an Epic from a markdown file using AI agent
@@ -900,6 +1851,26 @@ pub async fn plan_epic_from_markdown(
     crate::devops::operations::plan_from_markdown(config, enabled_agents).await
 }
 
+/// Build an Epic configuration from a JSON plan, without an AI agent
+///
+/// The result can be passed to `create_epic`.
+#[tauri::command]
+#[specta::specta]
+pub fn import_epic_plan(
+    json: String,
+    repo: String,
+    work_repo: Option<String>,
+) -> Result<crate::devops::operations::EpicConfig, String> {
+    crate::devops::operations::import_epic_plan(&json, repo, work_repo)
+}
+
+/// Export an Epic configuration as a JSON plan that `import_epic_plan` accepts
+#[tauri::command]
+#[specta::specta]
+pub fn export_epic_plan(config: crate::devops::operations::EpicConfig) -> Result<String, String> {
+    crate::devops::operations::export_epic_plan(&config)
+}
+
 /// List all available Epic plan templates from docs/plans directory
 #[tauri::command]
 #[specta::specta]
@@ -940,10 +1911,15 @@ pub fn list_epic_plan_templates(
 #[tauri::command]
 #[specta::specta]
 pub async fn start_epic_orchestration(
+    app: AppHandle,
     epic: crate::devops::operations::EpicInfo,
     config: crate::devops::operations::StartOrchestrationConfig,
 ) -> Result<crate::devops::operations::OrchestrationResult, String> {
-    crate::devops::operations::start_orchestration(&epic, config).await
+    let result = crate::devops::operations::start_orchestration(&epic, config).await?;
+    for (issue_number, error) in &result.failed_spawns {
+        notify_spawn_failure(&app, &epic.repo, Some(*issue_number as u64), error);
+    }
+    Ok(result)
 }
 
 /// Get status of all phases in an epic
@@ -1000,6 +1976,39 @@ pub async fn load_epic_for_recovery(
     crate::devops::operations::load_epic_for_recovery(repo, epic_number).await
 }
 
+/// Summarize an epic's PRs by state and check status, listing the PRs that
+/// need attention (failing checks, conflicts, awaiting review).
+#[tauri::command]
+#[specta::specta]
+pub async fn get_epic_pr_summary(
+    repo: String,
+    epic_number: u32,
+) -> Result<crate::devops::operations::EpicPrSummary, String> {
+    crate::devops::operations::get_epic_pr_summary(repo, epic_number).await
+}
+
+/// Build the dependency graph of an epic's sub-issues (nodes, edges and a
+/// topological order). Fails if the dependencies form a cycle.
+#[tauri::command]
+#[specta::specta]
+pub async fn build_epic_dependency_graph(
+    repo: String,
+    epic_number: u32,
+) -> Result<crate::devops::operations::EpicDependencyGraph, String> {
+    crate::devops::operations::build_epic_dependency_graph(repo, epic_number).await
+}
+
+/// Check an epic for orphaned sub-issues, phases without sub-issues and
+/// sub-issues missing required body sections.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_epic_integrity(
+    repo: String,
+    epic_number: u32,
+) -> Result<crate::devops::operations::EpicIntegrityReport, String> {
+    crate::devops::operations::validate_epic_integrity(repo, epic_number).await
+}
+
 /// Manually mark a phase's status on GitHub.
 ///
 /// Use this for phases that were completed manually (without sub-issues)
@@ -1018,7 +2027,7 @@ pub async fn mark_epic_phase_status(
 
 // ===== Epic State Persistence Commands =====
 
-/// Get the currently active Epic state (persisted across app restarts).
+/// Get the selected active Epic state (persisted across app restarts).
 #[tauri::command]
 #[specta::specta]
 pub fn get_active_epic_state(
@@ -1027,7 +2036,26 @@ pub fn get_active_epic_state(
     crate::devops::orchestration::get_active_epic(&app)
 }
 
-/// Set the active Epic from an EpicInfo (when linking an Epic).
+/// List every active Epic.
+#[tauri::command]
+#[specta::specta]
+pub fn list_active_epics(app: AppHandle) -> Vec<crate::devops::orchestration::ActiveEpicState> {
+    crate::devops::orchestration::list_active_epics(&app)
+}
+
+/// Select the active Epic that `get_active_epic_state` returns and Epic
+/// commands operate on.
+#[tauri::command]
+#[specta::specta]
+pub fn switch_active_epic(
+    app: AppHandle,
+    repo: String,
+    epic_number: u32,
+) -> Result<crate::devops::orchestration::ActiveEpicState, String> {
+    crate::devops::orchestration::switch_active_epic(&app, &repo, epic_number)
+}
+
+/// Link an Epic from an EpicInfo and select it.
 #[tauri::command]
 #[specta::specta]
 pub fn set_active_epic_state(
@@ -1037,7 +2065,7 @@ pub fn set_active_epic_state(
     crate::devops::orchestration::set_active_epic(&app, &epic_info)
 }
 
-/// Set the active Epic from recovery info (more complete data with sub-issues).
+/// Link an Epic from recovery info (more complete data with sub-issues) and select it.
 #[tauri::command]
 #[specta::specta]
 pub fn set_active_epic_from_recovery(
@@ -1047,7 +2075,7 @@ pub fn set_active_epic_from_recovery(
     crate::devops::orchestration::set_active_epic_from_recovery(&app, &recovery)
 }
 
-/// Clear the active Epic state. If archive is true, moves to history.
+/// Clear the selected Epic state. If archive is true, moves to history.
 #[tauri::command]
 #[specta::specta]
 pub fn clear_active_epic_state(
@@ -1057,7 +2085,9 @@ pub fn clear_active_epic_state(
     crate::devops::orchestration::clear_active_epic(&app, archive)
 }
 
-/// Sync the active Epic state with GitHub to get latest sub-issue status.
+/// Sync all active Epics with GitHub to get latest sub-issue status.
+///
+/// Returns the selected Epic.
 #[tauri::command]
 #[specta::specta]
 pub async fn sync_active_epic_state(
@@ -1066,23 +2096,41 @@ pub async fn sync_active_epic_state(
     crate::devops::orchestration::sync_active_epic(&app).await
 }
 
-/// Update a sub-issue's agent assignment in the active Epic.
+/// Update a sub-issue's agent assignment in the active Epic that tracks it.
 #[tauri::command]
 #[specta::specta]
 pub fn update_epic_sub_issue_agent(
     app: AppHandle,
+    repo: String,
     issue_number: u32,
     session_name: Option<String>,
     agent_type: Option<String>,
 ) -> Result<(), String> {
     crate::devops::orchestration::update_epic_sub_issue_agent(
         &app,
+        &repo,
         issue_number,
         session_name.as_deref(),
         agent_type.as_deref(),
     )
 }
 
+/// Move a sub-issue to a different phase of its Epic.
+///
+/// Rewrites the issue's `**Phase**` line on GitHub and updates the active Epic's
+/// phase tracking if the issue belongs to it.
+#[tauri::command]
+#[specta::specta]
+pub async fn move_sub_issue_to_phase(
+    app: AppHandle,
+    repo: String,
+    issue_number: u32,
+    new_phase: u32,
+) -> Result<Option<crate::devops::orchestration::ActiveEpicState>, String> {
+    crate::devops::orchestration::move_sub_issue_to_phase(&app, &repo, issue_number, new_phase)
+        .await
+}
+
 /// Update the local repository path for the active Epic.
 ///
 /// This path is used when spawning agents to know where to create worktrees.
@@ -1092,6 +2140,23 @@ pub fn set_epic_local_repo_path(app: AppHandle, local_repo_path: String) -> Resu
     crate::devops::orchestration::set_epic_local_repo_path(&app, &local_repo_path)
 }
 
+/// Abort the support workers (e.g. a merge in progress) for an issue.
+///
+/// Interrupts and kills their sessions and containers. When `reset_worktree`
+/// is true, the worktree is reset to its state before the merge started.
+#[tauri::command]
+#[specta::specta]
+pub async fn abort_support_worker(
+    issue_number: u32,
+    reset_worktree: Option<bool>,
+) -> Result<operations::AbortSupportWorkerResult, String> {
+    tokio::task::spawn_blocking(move || {
+        operations::abort_support_worker(issue_number, reset_worktree.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Handle pipeline item completion and optionally update Epic on GitHub.
 ///
 /// Call this when a sub-issue is completed (PR merged, issue closed).
@@ -1101,10 +2166,17 @@ pub fn set_epic_local_repo_path(app: AppHandle, local_repo_path: String) -> Resu
 #[specta::specta]
 pub async fn on_pipeline_item_complete(
     app: AppHandle,
+    repo: String,
     issue_number: u32,
     update_github: bool,
 ) -> Result<(), String> {
-    crate::devops::orchestration::on_pipeline_item_complete(&app, issue_number, update_github).await
+    crate::devops::orchestration::on_pipeline_item_complete(
+        &app,
+        &repo,
+        issue_number,
+        update_github,
+    )
+    .await
 }
 
 /// Merge a PR for a sub-issue that's in "Ready" state
@@ -1118,12 +2190,14 @@ pub async fn on_pipeline_item_complete(
 #[specta::specta]
 pub async fn merge_ready_pr(
     app: AppHandle,
+    repo: String,
     issue_number: u32,
     merge_method: Option<String>,
     delete_branch: bool,
 ) -> Result<crate::devops::orchestration::MergeResult, String> {
     crate::devops::orchestration::merge_ready_pr(
         &app,
+        &repo,
         issue_number,
         merge_method.as_deref(),
         delete_branch,
@@ -1133,7 +2207,8 @@ pub async fn merge_ready_pr(
 
 /// Process all "Ready" sub-issues for the active Epic
 ///
-/// This command finds all sub-issues with PRs (Ready state) and merges them.
+/// This command finds all sub-issues with PRs (Ready state) and merges those whose
+/// required approvals are met; the rest are reported as skipped.
 /// After each merge, it checks if the phase is complete and can start the next phase.
 #[tauri::command]
 #[specta::specta]
@@ -1152,6 +2227,29 @@ pub async fn process_ready_prs(
     .await
 }
 
+/// Watch a PR's CI checks until they pass, fail, or the timeout elapses
+///
+/// Emits `pr-checks-updated` events whenever the check status changes so the
+/// frontend can show live CI progress without its own polling loop.
+#[tauri::command]
+#[specta::specta]
+pub async fn watch_pr_checks(
+    app: AppHandle,
+    repo: String,
+    number: u64,
+    interval_secs: Option<u64>,
+    timeout_secs: Option<u64>,
+) -> Result<github::PrCheckStatus, String> {
+    crate::devops::orchestration::watch_pr_checks(
+        &app,
+        &repo,
+        number,
+        interval_secs.unwrap_or(15),
+        timeout_secs.unwrap_or(1800),
+    )
+    .await
+}
+
 // ============================================================================
 // Docker Sandbox Commands
 // ============================================================================
@@ -1163,6 +2261,29 @@ pub fn is_docker_available() -> bool {
     crate::devops::docker::is_docker_available()
 }
 
+/// Prewarm the sandbox image (pull base image and build `handy-agent-base`).
+///
+/// Emits `sandbox-prewarm-progress` events with `{ stage, line }` while running
+/// and returns the built image tag.
+#[tauri::command]
+#[specta::specta]
+pub async fn prewarm_sandbox_image(
+    app: AppHandle,
+    base_image: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut on_progress = |stage: &str, line: &str| {
+            let _ = app.emit(
+                "sandbox-prewarm-progress",
+                serde_json::json!({ "stage": stage, "line": line }),
+            );
+        };
+        crate::devops::docker::prewarm_sandbox_image(base_image.as_deref(), &mut on_progress)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Spawn a sandboxed agent in a Docker container
 ///
 /// This creates an isolated container where the agent can run with
@@ -1194,6 +2315,15 @@ pub fn get_sandbox_logs(container_name: String, tail: Option<u32>) -> Result<Str
     crate::devops::docker::get_sandbox_logs(&container_name, tail)
 }
 
+/// List files a sandbox container added, changed or deleted under /workspace
+#[tauri::command]
+#[specta::specta]
+pub fn get_container_diff(
+    container_name: String,
+) -> Result<Vec<crate::devops::docker::ContainerChange>, String> {
+    crate::devops::docker::get_container_diff(&container_name)
+}
+
 /// Stop a sandbox container
 #[tauri::command]
 #[specta::specta]
@@ -1225,7 +2355,8 @@ pub fn is_devcontainer_cli_available() -> bool {
 /// Setup a devcontainer configuration for a worktree
 ///
 /// Creates a .devcontainer/devcontainer.json file with the official
-/// Anthropic Claude Code feature configured.
+/// Anthropic Claude Code feature configured, plus any features and
+/// post-create commands from the repo's `.handy/config.toml`.
 #[tauri::command]
 #[specta::specta]
 pub fn setup_devcontainer(
@@ -1251,6 +2382,26 @@ pub fn start_devcontainer(worktree_path: String) -> Result<String, String> {
     crate::devops::docker::start_devcontainer(&worktree_path)
 }
 
+/// Start a devcontainer, streaming its build log.
+///
+/// Emits a `devcontainer-build` event with each (credential-redacted) log line
+/// and returns the full log whether or not the build succeeded.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_devcontainer_streaming(
+    app: AppHandle,
+    worktree_path: String,
+) -> Result<crate::devops::docker::DevcontainerBuildLog, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut on_line = |line: &str| {
+            let _ = app.emit("devcontainer-build", line);
+        };
+        crate::devops::docker::start_devcontainer_streaming(&worktree_path, &mut on_line)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Execute a command inside a running devcontainer
 #[tauri::command]
 #[specta::specta]
@@ -1258,6 +2409,18 @@ pub fn exec_in_devcontainer(worktree_path: String, command: String) -> Result<St
     crate::devops::docker::exec_in_devcontainer(&worktree_path, &command)
 }
 
+/// Preview the ports a sandboxed agent would expose for a worktree: the repo
+/// config's `ports` when declared, otherwise auto-detected ones, each with the
+/// file or dependency that triggered it. Nothing is spawned; use
+/// `sandbox_ports` on spawn to override.
+#[tauri::command]
+#[specta::specta]
+pub fn detect_ports(
+    worktree_path: String,
+) -> Result<Vec<crate::devops::orchestrator::DetectedPort>, String> {
+    crate::devops::orchestrator::preview_detected_ports(&worktree_path)
+}
+
 /// Ensure the shared agent network exists for inter-container communication
 ///
 /// Creates the 'handy-agents' Docker network if it doesn't exist.
@@ -1275,10 +2438,11 @@ pub fn ensure_agent_network() -> Result<(), String> {
 #[tauri::command]
 #[specta::specta]
 pub fn get_agent_network_info(
+    repo: String,
     issue_number: u64,
     container_ports: Vec<u16>,
 ) -> crate::devops::docker::AgentNetworkInfo {
-    crate::devops::docker::get_agent_network_info(issue_number, &container_ports)
+    crate::devops::docker::get_agent_network_info(&repo, issue_number, &container_ports)
 }
 
 /// List all containers on the agent network
@@ -1290,6 +2454,38 @@ pub fn list_network_containers() -> Result<Vec<String>, String> {
     crate::devops::docker::list_network_containers()
 }
 
+/// Get the agent network topology
+///
+/// Lists every container on the network with its issue, ports, running state
+/// and the peers it can reach, so the UI can draw which agents can talk to which.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_network_topology() -> Result<crate::devops::docker::NetworkTopology, String> {
+    tokio::task::spawn_blocking(crate::devops::docker::get_network_topology)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// List the host ports published by running Handy containers, flagging collisions
+#[tauri::command]
+#[specta::specta]
+pub async fn list_allocated_ports() -> Result<crate::devops::docker::PortAllocationTable, String> {
+    tokio::task::spawn_blocking(crate::devops::docker::list_allocated_ports)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Recreate the agent network
+///
+/// Stops and disconnects every container on it, removes the network and
+/// creates it again. Fails if non-Handy containers are running on it.
+/// Returns the affected containers.
+#[tauri::command]
+#[specta::specta]
+pub fn recreate_agent_network() -> Result<Vec<String>, String> {
+    crate::devops::docker::recreate_agent_network()
+}
+
 // ===== Pipeline Orchestration Commands =====
 
 /// Assign an issue to an agent, creating worktree and tmux session.
@@ -1299,7 +2495,14 @@ pub fn assign_issue_to_agent_pipeline(
     app: AppHandle,
     config: crate::devops::orchestration::AssignIssueConfig,
 ) -> Result<crate::devops::orchestration::AssignIssueResult, String> {
-    crate::devops::orchestration::assign_issue_to_agent(&app, &config)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    let app_settings = settings::get_settings(&app);
+    let result =
+        crate::devops::orchestration::assign_issue_to_agent(&store, &app_settings, &config);
+    if let Err(e) = &result {
+        notify_spawn_failure(&app, &config.tracking_repo, Some(config.issue_number), e);
+    }
+    result
 }
 
 /// Skip an issue and update its labels.
@@ -1309,7 +2512,8 @@ pub fn skip_issue(
     app: AppHandle,
     config: crate::devops::orchestration::SkipIssueConfig,
 ) -> Result<crate::devops::pipeline::PipelineItem, String> {
-    crate::devops::orchestration::skip_issue(&app, &config)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::skip_issue(&store, &config)
 }
 
 /// List all pipeline items, aggregating from multiple sources.
@@ -1319,7 +2523,8 @@ pub fn list_pipeline_items(
     app: AppHandle,
     work_repo: Option<String>,
 ) -> Result<Vec<crate::devops::pipeline::PipelineItem>, String> {
-    crate::devops::orchestration::list_pipeline_items(&app, work_repo.as_deref())
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::list_pipeline_items(&store, work_repo.as_deref())
 }
 
 /// Get pipeline history (completed items).
@@ -1329,14 +2534,100 @@ pub fn get_pipeline_history(
     app: AppHandle,
     limit: Option<usize>,
 ) -> Vec<crate::devops::pipeline::PipelineItem> {
-    crate::devops::orchestration::get_pipeline_history(&app, limit)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::get_pipeline_history(&store, limit)
+}
+
+/// Prune pipeline history to the newest `keep` items. Returns how many were removed.
+#[tauri::command]
+#[specta::specta]
+pub fn prune_pipeline_history(app: AppHandle, keep: usize) -> usize {
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::prune_pipeline_history(&store, keep)
+}
+
+/// Check whether the pipeline store parses, and what a repair would keep.
+#[tauri::command]
+#[specta::specta]
+pub fn diagnose_pipeline_store(
+    app: AppHandle,
+) -> Result<crate::devops::orchestration::PipelineStoreDiagnosis, String> {
+    crate::devops::orchestration::diagnose_pipeline_store(&app)
+}
+
+/// Back up a corrupt pipeline store and rebuild it from the entries that parse.
+#[tauri::command]
+#[specta::specta]
+pub fn repair_pipeline_store(
+    app: AppHandle,
+) -> Result<crate::devops::orchestration::PipelineStoreRepair, String> {
+    crate::devops::orchestration::repair_pipeline_store(&app)
+}
+
+/// Get the maximum number of completed items kept in pipeline history.
+#[tauri::command]
+#[specta::specta]
+pub fn get_pipeline_max_history(app: AppHandle) -> usize {
+    settings::get_settings(&app).pipeline_max_history
+}
+
+/// Set the maximum number of completed items kept in pipeline history.
+/// Existing history beyond the new limit is pruned immediately.
+#[tauri::command]
+#[specta::specta]
+pub fn set_pipeline_max_history(app: AppHandle, max_history: usize) -> usize {
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.pipeline_max_history = max_history;
+    settings::write_settings(&app, app_settings);
+
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::prune_pipeline_history(&store, max_history);
+    max_history
+}
+
+/// Get where the pipeline and Epic stores are kept.
+#[tauri::command]
+#[specta::specta]
+pub fn get_store_location(
+    app: AppHandle,
+) -> Result<crate::devops::orchestration::StoreLocation, String> {
+    crate::devops::orchestration::get_store_location(&app)
+}
+
+/// Move the pipeline and Epic stores to `new_dir`, migrating their state.
+/// Pass None (or an empty path) to move them back to the app data dir.
+#[tauri::command]
+#[specta::specta]
+pub fn relocate_stores(
+    app: AppHandle,
+    new_dir: Option<String>,
+) -> Result<crate::devops::orchestration::StoreLocation, String> {
+    let location = crate::devops::orchestration::relocate_stores(&app, new_dir.as_deref())?;
+
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.store_directory = location.custom.then(|| location.directory.clone());
+    settings::write_settings(&app, app_settings);
+    Ok(location)
+}
+
+/// Get pipeline throughput metrics (time to complete, completions per day,
+/// skip and failure rates), optionally only for items finished since an RFC 3339 time.
+#[tauri::command]
+#[specta::specta]
+pub fn get_pipeline_metrics(
+    app: AppHandle,
+    since: Option<String>,
+) -> Result<crate::devops::orchestration::PipelineMetrics, String> {
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::get_pipeline_metrics(&store, since.as_deref())
 }
 
 /// Get pipeline summary statistics.
 #[tauri::command]
 #[specta::specta]
 pub fn get_pipeline_summary(app: AppHandle) -> crate::devops::orchestration::PipelineSummary {
-    crate::devops::orchestration::get_pipeline_summary(&app)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::get_pipeline_summary(&store)
 }
 
 /// Detect and link PRs to pipeline items.
@@ -1346,16 +2637,343 @@ pub fn detect_and_link_prs(
     app: AppHandle,
     work_repo: String,
 ) -> Result<Vec<crate::devops::pipeline::PipelineItem>, String> {
-    crate::devops::orchestration::detect_and_link_prs(&app, &work_repo)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::detect_and_link_prs(&store, &work_repo)
 }
 
 /// Sync PR status for all pipeline items with PRs.
+///
+/// With auto-ready enabled, draft PRs whose issue's acceptance criteria are all
+/// checked and whose checks pass are marked ready, emitting `pr-auto-ready`.
+/// With auto-delete enabled, branches of PRs that just merged are deleted
+/// (protected branches excepted), emitting `merged-branches-deleted`.
+/// Items whose PR was just closed without merging emit `pipeline-pr-rejected`.
 #[tauri::command]
 #[specta::specta]
-pub fn sync_all_pr_statuses(
+pub async fn sync_all_pr_statuses(
     app: AppHandle,
 ) -> Result<Vec<crate::devops::pipeline::PipelineItem>, String> {
-    crate::devops::orchestration::sync_all_pr_statuses(&app)
+    tokio::task::spawn_blocking(move || {
+        let store = crate::devops::orchestration::TauriStore::new(&app);
+        let app_settings = settings::get_settings(&app);
+        let updated = crate::devops::orchestration::sync_all_pr_statuses(
+            &store,
+            app_settings.github_project.as_ref(),
+        )?;
+
+        for item in updated
+            .iter()
+            .filter(|item| item.status == crate::devops::pipeline::PipelineStatus::PrRejected)
+        {
+            let _ = app.emit(
+                "pipeline-pr-rejected",
+                serde_json::json!({
+                    "item_id": item.id,
+                    "repo": item.work_repo,
+                    "issue_number": item.issue_number,
+                    "pr_number": item.pr_number,
+                    "pr_url": item.pr_url,
+                }),
+            );
+        }
+
+        if app_settings.auto_delete_merged_branches {
+            let deleted = crate::devops::orchestration::delete_merged_branches(&updated);
+            if !deleted.is_empty() {
+                let _ = app.emit("merged-branches-deleted", &deleted);
+            }
+        }
+
+        if app_settings.auto_ready_draft_prs {
+            for item in crate::devops::orchestration::auto_ready_draft_prs(&store)? {
+                let _ = app.emit(
+                    "pr-auto-ready",
+                    serde_json::json!({
+                        "item_id": item.id,
+                        "repo": item.work_repo,
+                        "issue_number": item.issue_number,
+                        "pr_number": item.pr_number,
+                        "pr_url": item.pr_url,
+                    }),
+                );
+            }
+        }
+
+        Ok(updated)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Requeue all pipeline items whose PR was closed without merging.
+#[tauri::command]
+#[specta::specta]
+pub async fn requeue_rejected_items(
+    app: AppHandle,
+) -> Result<Vec<crate::devops::pipeline::PipelineItem>, String> {
+    tokio::task::spawn_blocking(move || {
+        let store = crate::devops::orchestration::TauriStore::new(&app);
+        let app_settings = settings::get_settings(&app);
+        crate::devops::orchestration::requeue_rejected_items(
+            &store,
+            app_settings.github_project.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// List open PRs awaiting the user's review across all pipeline and epic repos.
+///
+/// Each PR is annotated with the Handy pipeline item that produced it, if any.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_prs_awaiting_review(
+    app: AppHandle,
+) -> Result<Vec<crate::devops::orchestration::ReviewQueueItem>, String> {
+    tokio::task::spawn_blocking(move || {
+        let store = crate::devops::orchestration::TauriStore::new(&app);
+        let epics = crate::devops::orchestration::load_epic_state(&app);
+        crate::devops::orchestration::list_prs_awaiting_review(&store, &epics)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Get whether draft PRs are automatically marked ready when their work is done.
+#[tauri::command]
+#[specta::specta]
+pub fn get_auto_ready_draft_prs(app: AppHandle) -> bool {
+    settings::get_settings(&app).auto_ready_draft_prs
+}
+
+/// Set whether draft PRs are automatically marked ready when all acceptance
+/// criteria on the issue are checked and the PR checks pass.
+#[tauri::command]
+#[specta::specta]
+pub fn set_auto_ready_draft_prs(app: AppHandle, enabled: bool) -> bool {
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.auto_ready_draft_prs = enabled;
+    settings::write_settings(&app, app_settings);
+    enabled
+}
+
+/// Get whether branches are deleted automatically once their PR is merged.
+#[tauri::command]
+#[specta::specta]
+pub fn get_auto_delete_merged_branches(app: AppHandle) -> bool {
+    settings::get_settings(&app).auto_delete_merged_branches
+}
+
+/// Set whether `sync_all_pr_statuses` deletes the remote and local branches
+/// of PRs it sees merge. Protected branches are never deleted.
+#[tauri::command]
+#[specta::specta]
+pub fn set_auto_delete_merged_branches(app: AppHandle, enabled: bool) -> bool {
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.auto_delete_merged_branches = enabled;
+    settings::write_settings(&app, app_settings);
+    enabled
+}
+
+/// Get the timeout overrides for gh and docker pull commands.
+#[tauri::command]
+#[specta::specta]
+pub fn get_command_timeouts(app: AppHandle) -> crate::devops::process::CommandTimeouts {
+    settings::get_settings(&app).command_timeouts
+}
+
+/// Set the timeout overrides for gh and docker pull commands (None = default).
+#[tauri::command]
+#[specta::specta]
+pub fn set_command_timeouts(
+    app: AppHandle,
+    timeouts: crate::devops::process::CommandTimeouts,
+) -> crate::devops::process::CommandTimeouts {
+    crate::devops::process::set_command_timeouts(&timeouts);
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.command_timeouts = timeouts.clone();
+    settings::write_settings(&app, app_settings);
+    timeouts
+}
+
+/// Get the template for new agent session names (None = "handy-agent-{issue}").
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_name_template(app: AppHandle) -> Option<String> {
+    settings::get_settings(&app).session_name_template
+}
+
+/// Set the template for new agent session names.
+///
+/// Supports `{issue}`, `{agent}` and `{short_ts}`; a name already in use gets
+/// a `-2`, `-3`, ... suffix. None or an empty template restores the default.
+#[tauri::command]
+#[specta::specta]
+pub fn set_session_name_template(app: AppHandle, template: Option<String>) -> Option<String> {
+    let template = template.filter(|t| !t.trim().is_empty());
+    tmux::set_session_name_template(template.as_deref());
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.session_name_template = template.clone();
+    settings::write_settings(&app, app_settings);
+    template
+}
+
+/// Get whether executed gh/docker/tmux commands are logged and recorded.
+#[tauri::command]
+#[specta::specta]
+pub fn get_debug_commands(app: AppHandle) -> bool {
+    settings::get_settings(&app).debug_commands
+}
+
+/// Set whether executed gh/docker/tmux commands are logged (credential-sanitized)
+/// and recorded for `get_last_commands`. Turning it off clears the history.
+#[tauri::command]
+#[specta::specta]
+pub fn set_debug_commands(app: AppHandle, enabled: bool) -> bool {
+    crate::devops::process::set_command_debug(enabled);
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.debug_commands = enabled;
+    settings::write_settings(&app, app_settings);
+    enabled
+}
+
+/// Get the custom regexes redacted from logs and GitHub comments.
+#[tauri::command]
+#[specta::specta]
+pub fn get_sensitive_patterns(app: AppHandle) -> Vec<String> {
+    settings::get_settings(&app).custom_sensitive_patterns
+}
+
+/// Add a regex for a secret format (e.g. AWS keys) to redact alongside the
+/// built-in token patterns. Returns the updated list.
+#[tauri::command]
+#[specta::specta]
+pub fn register_sensitive_pattern(app: AppHandle, pattern: String) -> Result<Vec<String>, String> {
+    let mut app_settings = settings::get_settings(&app);
+    if !app_settings.custom_sensitive_patterns.contains(&pattern) {
+        app_settings.custom_sensitive_patterns.push(pattern);
+    }
+    crate::devops::docker::set_custom_sensitive_patterns(&app_settings.custom_sensitive_patterns)?;
+    let patterns = app_settings.custom_sensitive_patterns.clone();
+    settings::write_settings(&app, app_settings);
+    Ok(patterns)
+}
+
+/// Stop redacting a custom pattern. Returns the updated list.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_sensitive_pattern(app: AppHandle, pattern: String) -> Result<Vec<String>, String> {
+    let mut app_settings = settings::get_settings(&app);
+    app_settings
+        .custom_sensitive_patterns
+        .retain(|p| p != &pattern);
+    crate::devops::docker::set_custom_sensitive_patterns(&app_settings.custom_sensitive_patterns)?;
+    let patterns = app_settings.custom_sensitive_patterns.clone();
+    settings::write_settings(&app, app_settings);
+    Ok(patterns)
+}
+
+/// Get how many seconds an agent's output may stay unchanged before it's flagged as stuck.
+#[tauri::command]
+#[specta::specta]
+pub fn get_stuck_threshold_secs(app: AppHandle) -> u64 {
+    settings::get_settings(&app).stuck_threshold_secs
+}
+
+/// Set how many seconds an agent's output may stay unchanged before the
+/// heartbeat marks it `potentially_stuck` and emits `agent-stuck`.
+#[tauri::command]
+#[specta::specta]
+pub fn set_stuck_threshold_secs(app: AppHandle, threshold_secs: u64) -> u64 {
+    let threshold_secs = threshold_secs.max(1);
+    crate::devops::orchestrator::set_stuck_threshold_secs(threshold_secs);
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.stuck_threshold_secs = threshold_secs;
+    settings::write_settings(&app, app_settings);
+    threshold_secs
+}
+
+/// Get the GitHub account assigned to issues while an agent works on them.
+#[tauri::command]
+#[specta::specta]
+pub fn get_agent_assignee(app: AppHandle) -> Option<String> {
+    settings::get_settings(&app).agent_assignee
+}
+
+/// Set the GitHub account (e.g., a bot user) assigned to issues when an agent
+/// starts on them and unassigned when its PR is created. None or an empty
+/// login turns assignment off.
+#[tauri::command]
+#[specta::specta]
+pub fn set_agent_assignee(app: AppHandle, login: Option<String>) -> Option<String> {
+    let login = login
+        .map(|l| l.trim().trim_start_matches('@').to_string())
+        .filter(|l| !l.is_empty());
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.agent_assignee = login.clone();
+    settings::write_settings(&app, app_settings);
+    login
+}
+
+/// Get the notification channels for agent events.
+#[tauri::command]
+#[specta::specta]
+pub fn get_notification_settings(app: AppHandle) -> NotificationSettings {
+    settings::get_settings(&app).notifications
+}
+
+/// Set the notification channels (desktop, webhook) used when an agent opens
+/// a PR, finishes its work or fails. An empty webhook URL turns the webhook off.
+#[tauri::command]
+#[specta::specta]
+pub fn set_notification_settings(
+    app: AppHandle,
+    notifications: NotificationSettings,
+) -> Result<NotificationSettings, String> {
+    let webhook_url = notifications
+        .webhook_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &webhook_url {
+        notifications::validate_webhook_url(url)?;
+    }
+    let notifications = NotificationSettings {
+        webhook_url,
+        ..notifications
+    };
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.notifications = notifications.clone();
+    settings::write_settings(&app, app_settings);
+    Ok(notifications)
+}
+
+/// Get whether read-only observer mode is on.
+#[tauri::command]
+#[specta::specta]
+pub fn get_readonly_mode(app: AppHandle) -> bool {
+    settings::get_settings(&app).readonly
+}
+
+/// Set read-only observer mode. While on, commands that spawn, kill, merge or
+/// otherwise change agents, sandboxes or GitHub fail with "read-only mode";
+/// read commands keep working.
+#[tauri::command]
+#[specta::specta]
+pub fn set_readonly_mode(app: AppHandle, enabled: bool) -> bool {
+    set_read_only(enabled);
+    let mut app_settings = settings::get_settings(&app);
+    app_settings.readonly = enabled;
+    settings::write_settings(&app, app_settings);
+    enabled
+}
+
+/// Get the `n` most recently executed commands (newest first) with their exit
+/// codes and truncated output. Empty unless command debugging is on.
+#[tauri::command]
+#[specta::specta]
+pub fn get_last_commands(n: u32) -> Vec<crate::devops::process::ExecutedCommand> {
+    crate::devops::process::last_commands(n as usize)
 }
 
 /// Update a specific pipeline item's PR status.
@@ -1365,7 +2983,8 @@ pub fn update_pipeline_item_pr_status(
     app: AppHandle,
     item_id: String,
 ) -> Result<Option<crate::devops::pipeline::PipelineItem>, String> {
-    crate::devops::orchestration::update_pipeline_item_pr_status(&app, &item_id)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::update_pipeline_item_pr_status(&store, &item_id)
 }
 
 /// Get a pipeline item by ID.
@@ -1375,7 +2994,8 @@ pub fn get_pipeline_item(
     app: AppHandle,
     item_id: String,
 ) -> Option<crate::devops::pipeline::PipelineItem> {
-    crate::devops::orchestration::get_pipeline_item(&app, &item_id)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::get_pipeline_item(&store, &item_id)
 }
 
 /// Find a pipeline item by issue.
@@ -1386,7 +3006,8 @@ pub fn find_pipeline_item_by_issue(
     repo: String,
     issue_number: u64,
 ) -> Option<crate::devops::pipeline::PipelineItem> {
-    crate::devops::orchestration::find_pipeline_item_by_issue(&app, &repo, issue_number)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::find_pipeline_item_by_issue(&store, &repo, issue_number)
 }
 
 /// Find a pipeline item by session name.
@@ -1396,7 +3017,8 @@ pub fn find_pipeline_item_by_session(
     app: AppHandle,
     session_name: String,
 ) -> Option<crate::devops::pipeline::PipelineItem> {
-    crate::devops::orchestration::find_pipeline_item_by_session(&app, &session_name)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::find_pipeline_item_by_session(&store, &session_name)
 }
 
 /// Link a PR to a pipeline item.
@@ -1410,7 +3032,8 @@ pub fn link_pr_to_pipeline_item(
 ) -> Result<crate::devops::pipeline::PipelineItem, String> {
     // Fetch the PR first
     let pr = github::get_pr(&work_repo, pr_number)?;
-    crate::devops::orchestration::link_pr_to_pipeline_item(&app, &item_id, &pr)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::link_pr_to_pipeline_item(&store, &item_id, &pr)
 }
 
 /// Archive a completed pipeline item.
@@ -1420,7 +3043,8 @@ pub fn archive_pipeline_item(
     app: AppHandle,
     item_id: String,
 ) -> Result<Option<crate::devops::pipeline::PipelineItem>, String> {
-    crate::devops::orchestration::archive_pipeline_item(&app, &item_id)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::archive_pipeline_item(&store, &item_id)
 }
 
 /// Remove a pipeline item (for cleanup).
@@ -1430,15 +3054,48 @@ pub fn remove_pipeline_item(
     app: AppHandle,
     item_id: String,
 ) -> Result<Option<crate::devops::pipeline::PipelineItem>, String> {
-    crate::devops::orchestration::remove_pipeline_item(&app, &item_id)
+    let store = crate::devops::orchestration::TauriStore::new(&app);
+    crate::devops::orchestration::remove_pipeline_item(&store, &item_id)
 }
 
 /// Check all active agent sessions for PR creation.
 ///
-/// Returns a list of PR detection results for all active sessions.
+/// Returns a list of PR detection results for all active sessions, or `None`
+/// when the check was skipped (monitor paused or polled before its interval).
 /// Each result indicates whether a PR was found and if it's newly detected.
 #[tauri::command]
 #[specta::specta]
-pub async fn check_sessions_for_prs(app: AppHandle) -> Result<Vec<PrDetectionResult>, String> {
+pub async fn check_sessions_for_prs(
+    app: AppHandle,
+) -> Result<Option<Vec<PrDetectionResult>>, String> {
     crate::devops::orchestration::check_sessions_for_prs(&app).await
 }
+
+/// Find the PR an agent created when it didn't use the `issue-{number}` branch.
+///
+/// Returns candidates ranked by confidence; prompt the user when `ambiguous` is set.
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_agent_pr(
+    session_name: String,
+) -> Result<crate::devops::operations::PrVerificationResult, String> {
+    crate::devops::operations::verify_agent_pr(&session_name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutating_commands_are_registered() {
+        let registered = include_str!("../lib.rs");
+        for command in MUTATING_COMMANDS {
+            assert!(
+                registered.contains(&format!("commands::devops::{},", command)),
+                "{} is not a registered command",
+                command
+            );
+        }
+        assert!(!MUTATING_COMMANDS.contains(&"set_readonly_mode"));
+    }
+}
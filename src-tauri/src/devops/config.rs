@@ -0,0 +1,372 @@
+//! Export and import of DevOps configuration.
+//!
+//! Bundles the DevOps settings (enabled agents, sandbox defaults and policy,
+//! default work repos, project boards, PR automation, timeouts, notifications,
+//! ...) into a single versioned JSON blob so a team can share one
+//! configuration instead of toggling settings by hand. Machine-local and
+//! secret settings (store directory, read-only mode, webhook URL) are never
+//! exported and are ignored on import.
+
+use super::github::ProjectStatusConfig;
+use super::notifications::NotificationSettings;
+use super::orchestrator::SandboxPolicy;
+use super::process::CommandTimeouts;
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+/// Current schema version of the exported configuration
+pub const DEVOPS_CONFIG_VERSION: u32 = 1;
+
+/// Shareable DevOps configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DevOpsConfig {
+    /// Schema version (see `DEVOPS_CONFIG_VERSION`)
+    pub version: u32,
+    /// Agent types enabled for spawning (e.g., "claude", "aider")
+    #[serde(default)]
+    pub enabled_agents: Vec<String>,
+    /// Whether agents run in Docker sandboxes by default (None leaves it unchanged on import)
+    #[serde(default)]
+    pub sandbox_enabled: Option<bool>,
+    /// Default work repo per tracking repo (tracking_repo -> work_repo)
+    #[serde(default)]
+    pub default_work_repos: HashMap<String, String>,
+    /// GitHub Projects (v2) board kept in sync with pipeline status
+    #[serde(default)]
+    pub github_project: Option<ProjectStatusConfig>,
+    /// Whether the PR-detection monitor runs
+    #[serde(default)]
+    pub pr_monitor_enabled: Option<bool>,
+    /// Minimum seconds between PR-detection checks
+    #[serde(default)]
+    pub pr_monitor_interval_secs: Option<u64>,
+    /// Maximum completed items kept in the pipeline store
+    #[serde(default)]
+    pub pipeline_max_history: Option<usize>,
+    /// Mark draft PRs ready once acceptance criteria are checked and checks pass
+    #[serde(default)]
+    pub auto_ready_draft_prs: Option<bool>,
+    /// Delete remote and local branches once their PR is merged
+    #[serde(default)]
+    pub auto_delete_merged_branches: Option<bool>,
+    /// Timeout overrides for gh and docker pull commands
+    #[serde(default)]
+    pub command_timeouts: Option<CommandTimeouts>,
+    /// Template for new agent session names
+    #[serde(default)]
+    pub session_name_template: Option<String>,
+    /// Log executed gh/docker/tmux commands
+    #[serde(default)]
+    pub debug_commands: Option<bool>,
+    /// Extra regexes for secrets redacted from logs and GitHub comments
+    #[serde(default)]
+    pub custom_sensitive_patterns: Vec<String>,
+    /// Seconds of unchanged agent output before a session is flagged as stuck
+    #[serde(default)]
+    pub stuck_threshold_secs: Option<u64>,
+    /// GitHub account assigned to issues while an agent works on them
+    #[serde(default)]
+    pub agent_assignee: Option<String>,
+    /// Notification channels for agent events (the webhook URL is not shared)
+    #[serde(default)]
+    pub notifications: Option<NotificationSettings>,
+    /// Agent types that must (or must never) run in a sandbox
+    #[serde(default)]
+    pub sandbox_policy: Option<SandboxPolicy>,
+}
+
+/// Build a configuration blob from the current settings.
+pub fn export_config(settings: &AppSettings) -> DevOpsConfig {
+    DevOpsConfig {
+        version: DEVOPS_CONFIG_VERSION,
+        enabled_agents: settings.enabled_agents.clone(),
+        sandbox_enabled: Some(settings.sandbox_enabled),
+        default_work_repos: settings.default_work_repos.clone(),
+        github_project: settings.github_project.clone(),
+        pr_monitor_enabled: Some(settings.pr_monitor_enabled),
+        pr_monitor_interval_secs: Some(settings.pr_monitor_interval_secs),
+        pipeline_max_history: Some(settings.pipeline_max_history),
+        auto_ready_draft_prs: Some(settings.auto_ready_draft_prs),
+        auto_delete_merged_branches: Some(settings.auto_delete_merged_branches),
+        command_timeouts: Some(settings.command_timeouts.clone()),
+        session_name_template: settings.session_name_template.clone(),
+        debug_commands: Some(settings.debug_commands),
+        custom_sensitive_patterns: settings.custom_sensitive_patterns.clone(),
+        stuck_threshold_secs: Some(settings.stuck_threshold_secs),
+        agent_assignee: settings.agent_assignee.clone(),
+        notifications: Some(NotificationSettings {
+            webhook_url: None,
+            ..settings.notifications.clone()
+        }),
+        sandbox_policy: Some(settings.sandbox_policy.clone()),
+    }
+}
+
+/// Parse and validate a configuration blob, migrating older versions.
+pub fn parse_config(json: &str) -> Result<DevOpsConfig, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid config JSON: {}", e))?;
+
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or("Config is missing a numeric 'version' field")?;
+
+    let config: DevOpsConfig = match version {
+        1 => serde_json::from_value(value).map_err(|e| format!("Invalid config: {}", e))?,
+        v if v > DEVOPS_CONFIG_VERSION as u64 => {
+            return Err(format!(
+                "Config version {} is newer than supported version {}; update Handy to import it",
+                v, DEVOPS_CONFIG_VERSION
+            ));
+        }
+        v => return Err(format!("Unsupported config version: {}", v)),
+    };
+
+    validate_config(&config)?;
+    Ok(config)
+}
+
+/// Check that repo names, agent types and the other settings are well formed.
+fn validate_config(config: &DevOpsConfig) -> Result<(), String> {
+    if let Some(agent) = config.enabled_agents.iter().find(|a| a.trim().is_empty()) {
+        return Err(format!("Invalid agent type: '{}'", agent));
+    }
+
+    for (tracking_repo, work_repo) in &config.default_work_repos {
+        for repo in [tracking_repo, work_repo] {
+            if !is_valid_repo(repo) {
+                return Err(format!(
+                    "Invalid repository '{}'. Expected format: owner/repo",
+                    repo
+                ));
+            }
+        }
+    }
+
+    if let Some(project) = &config.github_project {
+        if project.project_number.is_none()
+            && (project.project_id.is_empty() || project.status_field_id.is_empty())
+        {
+            return Err(
+                "GitHub project config requires project_number, or project_id and status_field_id"
+                    .to_string(),
+            );
+        }
+    }
+
+    for pattern in &config.custom_sensitive_patterns {
+        super::docker::validate_sensitive_pattern(pattern)?;
+    }
+    if let Some(policy) = &config.sandbox_policy {
+        policy.validate()?;
+    }
+
+    Ok(())
+}
+
+/// Whether a string looks like `owner/repo`
+fn is_valid_repo(repo: &str) -> bool {
+    match repo.split_once('/') {
+        Some((owner, name)) => !owner.is_empty() && !name.is_empty() && !name.contains('/'),
+        None => false,
+    }
+}
+
+/// Apply a configuration to settings.
+///
+/// With `merge`, imported agents, work repos and patterns are added to the
+/// existing ones (imported entries win on conflict) and optional settings only
+/// override when set. Otherwise the DevOps settings are replaced. Flags and
+/// numbers missing from the config are left unchanged either way, as are the
+/// machine-local store directory, read-only mode and webhook URL.
+pub fn apply_config(settings: &mut AppSettings, config: DevOpsConfig, merge: bool) {
+    if merge {
+        for agent in config.enabled_agents {
+            if !settings.enabled_agents.contains(&agent) {
+                settings.enabled_agents.push(agent);
+            }
+        }
+        for pattern in config.custom_sensitive_patterns {
+            if !settings.custom_sensitive_patterns.contains(&pattern) {
+                settings.custom_sensitive_patterns.push(pattern);
+            }
+        }
+        settings
+            .default_work_repos
+            .extend(config.default_work_repos);
+        if config.github_project.is_some() {
+            settings.github_project = config.github_project;
+        }
+        if config.session_name_template.is_some() {
+            settings.session_name_template = config.session_name_template;
+        }
+        if config.agent_assignee.is_some() {
+            settings.agent_assignee = config.agent_assignee;
+        }
+    } else {
+        settings.enabled_agents = config.enabled_agents;
+        settings.default_work_repos = config.default_work_repos;
+        settings.github_project = config.github_project;
+        settings.custom_sensitive_patterns = config.custom_sensitive_patterns;
+        settings.session_name_template = config.session_name_template;
+        settings.agent_assignee = config.agent_assignee;
+    }
+
+    if let Some(sandbox_enabled) = config.sandbox_enabled {
+        settings.sandbox_enabled = sandbox_enabled;
+    }
+    if let Some(enabled) = config.pr_monitor_enabled {
+        settings.pr_monitor_enabled = enabled;
+    }
+    if let Some(secs) = config.pr_monitor_interval_secs {
+        settings.pr_monitor_interval_secs = secs;
+    }
+    if let Some(max_history) = config.pipeline_max_history {
+        settings.pipeline_max_history = max_history;
+    }
+    if let Some(enabled) = config.auto_ready_draft_prs {
+        settings.auto_ready_draft_prs = enabled;
+    }
+    if let Some(enabled) = config.auto_delete_merged_branches {
+        settings.auto_delete_merged_branches = enabled;
+    }
+    if let Some(timeouts) = config.command_timeouts {
+        settings.command_timeouts = timeouts;
+    }
+    if let Some(enabled) = config.debug_commands {
+        settings.debug_commands = enabled;
+    }
+    if let Some(secs) = config.stuck_threshold_secs {
+        settings.stuck_threshold_secs = secs.max(1);
+    }
+    if let Some(notifications) = config.notifications {
+        settings.notifications.desktop = notifications.desktop;
+    }
+    if let Some(policy) = config.sandbox_policy {
+        settings.sandbox_policy = policy;
+    }
+}
+
+/// Push the DevOps settings kept in process-wide state (timeouts, session
+/// names, command logging, redaction patterns, stuck threshold, sandbox
+/// policy) into their modules. Invalid values are logged and skipped.
+pub fn apply_runtime_settings(settings: &AppSettings) {
+    super::process::set_command_timeouts(&settings.command_timeouts);
+    super::tmux::set_session_name_template(settings.session_name_template.as_deref());
+    super::process::set_command_debug(settings.debug_commands);
+    if let Err(e) =
+        super::docker::set_custom_sensitive_patterns(&settings.custom_sensitive_patterns)
+    {
+        log::warn!("Ignoring custom sensitive patterns: {}", e);
+    }
+    super::orchestrator::set_stuck_threshold_secs(settings.stuck_threshold_secs);
+    if let Err(e) = super::orchestrator::set_sandbox_policy(settings.sandbox_policy.clone()) {
+        log::warn!("Ignoring sandbox policy: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with(agents: &[&str], repos: &[(&str, &str)]) -> AppSettings {
+        let mut settings = crate::settings::get_default_settings();
+        settings.enabled_agents = agents.iter().map(|a| a.to_string()).collect();
+        settings.default_work_repos = repos
+            .iter()
+            .map(|(t, w)| (t.to_string(), w.to_string()))
+            .collect();
+        settings
+    }
+
+    #[test]
+    fn test_export_parse_roundtrip() {
+        let settings = settings_with(&["claude"], &[("org/issues", "org/app")]);
+        let config = export_config(&settings);
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(parse_config(&json).unwrap(), config);
+    }
+
+    #[test]
+    fn test_local_settings_not_shared() {
+        let mut source = settings_with(&["claude"], &[]);
+        source.store_directory = Some("/data/handy".to_string());
+        source.readonly = true;
+        source.notifications = NotificationSettings {
+            desktop: true,
+            webhook_url: Some("https://hooks.example.com/T000/secret".to_string()),
+        };
+        let json = serde_json::to_string(&export_config(&source)).unwrap();
+        assert!(!json.contains("/data/handy"));
+        assert!(!json.contains("secret"));
+        assert!(!json.contains("readonly"));
+
+        // Fields from a hand-edited or older export are ignored too
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["store_directory"] = "/data/handy".into();
+        value["readonly"] = true.into();
+        value["notifications"]["webhook_url"] = "https://hooks.example.com/x".into();
+
+        for merge in [true, false] {
+            let mut target = settings_with(&[], &[]);
+            target.notifications.webhook_url = Some("https://local.example.com".to_string());
+            apply_config(
+                &mut target,
+                parse_config(&value.to_string()).unwrap(),
+                merge,
+            );
+            assert_eq!(target.store_directory, None);
+            assert!(!target.readonly);
+            assert!(target.notifications.desktop);
+            assert_eq!(
+                target.notifications.webhook_url.as_deref(),
+                Some("https://local.example.com")
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_config_merge_and_replace() {
+        let incoming = DevOpsConfig {
+            version: DEVOPS_CONFIG_VERSION,
+            enabled_agents: vec!["aider".to_string()],
+            sandbox_enabled: None,
+            default_work_repos: HashMap::from([("org/b".to_string(), "org/b-app".to_string())]),
+            github_project: None,
+            custom_sensitive_patterns: vec!["AKIA[0-9A-Z]{16}".to_string()],
+            stuck_threshold_secs: Some(600),
+            agent_assignee: Some("handy-bot".to_string()),
+            ..parse_config(r#"{"version": 1}"#).unwrap()
+        };
+
+        let mut merged = settings_with(&["claude"], &[("org/a", "org/a-app")]);
+        let sandbox_before = merged.sandbox_enabled;
+        apply_config(&mut merged, incoming.clone(), true);
+        assert_eq!(merged.enabled_agents, vec!["claude", "aider"]);
+        assert_eq!(merged.default_work_repos.len(), 2);
+        assert_eq!(merged.sandbox_enabled, sandbox_before);
+        assert_eq!(merged.stuck_threshold_secs, 600);
+        assert_eq!(merged.agent_assignee.as_deref(), Some("handy-bot"));
+
+        let mut replaced = settings_with(&["claude"], &[("org/a", "org/a-app")]);
+        apply_config(&mut replaced, incoming, false);
+        assert_eq!(replaced.enabled_agents, vec!["aider"]);
+        assert_eq!(replaced.default_work_repos.len(), 1);
+        assert_eq!(replaced.custom_sensitive_patterns.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_config_rejects_invalid() {
+        assert!(parse_config("not json").is_err());
+        assert!(parse_config(r#"{"enabled_agents": []}"#).is_err());
+        assert!(parse_config(r#"{"version": 99}"#)
+            .unwrap_err()
+            .contains("newer"));
+        assert!(parse_config(r#"{"version": 1, "default_work_repos": {"org/a": "bad"}}"#).is_err());
+        assert!(parse_config(r#"{"version": 1, "custom_sensitive_patterns": [".*"]}"#).is_err());
+        assert!(parse_config(r#"{"version": 1}"#).is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 //! Dependency detection for DevOps features.
 //!
-//! Checks for required CLI tools: gh (GitHub CLI), tmux, and claude (Claude Code CLI).
+//! Checks for required CLI tools: gh (GitHub CLI), tmux, and claude (Claude Code CLI),
+//! and reports their versions for troubleshooting.
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -52,6 +53,9 @@ pub struct DevOpsDependencies {
     pub available_agents: Vec<String>,
     /// Whether sandboxed (Docker) agents are available
     pub sandbox_available: bool,
+    /// Problems that don't block setup but will affect agents (e.g. running as root)
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 /// Check if a command exists and get its version
@@ -94,26 +98,13 @@ fn run_command_with_timeout(
     args: &[&str],
     timeout_secs: u64,
 ) -> Option<(bool, String)> {
-    use std::sync::mpsc;
-    use std::time::Duration;
-
-    let name = name.to_string();
-    let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
-
-    let (tx, rx) = mpsc::channel();
-
-    std::thread::spawn(move || {
-        let result = Command::new(&name).args(&args).output();
-        let _ = tx.send(result);
-    });
-
-    match rx.recv_timeout(Duration::from_secs(timeout_secs)) {
-        Ok(Ok(output)) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            Some((output.status.success(), stdout))
-        }
-        _ => None,
-    }
+    let output = super::process::run_with_timeout(
+        Command::new(name).args(args),
+        std::time::Duration::from_secs(timeout_secs),
+    )
+    .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    Some((output.status.success(), stdout))
 }
 
 /// Check if GitHub CLI is authenticated and get the username
@@ -407,6 +398,11 @@ pub fn check_all_dependencies() -> DevOpsDependencies {
     // Sandbox is available if Docker is installed and daemon is running
     let sandbox_available = docker.installed && docker.authenticated.unwrap_or(false);
 
+    let mut warnings = Vec::new();
+    if claude.installed && is_running_as_root() {
+        warnings.push(ROOT_CLAUDE_WARNING.to_string());
+    }
+
     DevOpsDependencies {
         gh,
         tmux,
@@ -419,13 +415,243 @@ pub fn check_all_dependencies() -> DevOpsDependencies {
         all_satisfied,
         available_agents,
         sandbox_available,
+        warnings,
+    }
+}
+
+/// Explanation shown when Claude Code would be started as root outside a sandbox
+pub const ROOT_CLAUDE_WARNING: &str = "Handy is running as root. Claude Code refuses \
+--dangerously-skip-permissions under root, so non-sandboxed Claude agents cannot run \
+unattended. Run Handy as a regular user or enable sandbox mode.";
+
+/// Whether the current process runs with an effective UID of 0
+pub fn is_running_as_root() -> bool {
+    if !cfg!(unix) {
+        return false;
+    }
+    Command::new("id")
+        .arg("-u")
+        .output()
+        .map(|o| o.status.success() && String::from_utf8_lossy(&o.stdout).trim() == "0")
+        .unwrap_or(false)
+}
+
+/// Availability of an agent type for spawning
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AgentTypeInfo {
+    /// Agent type identifier (e.g., "claude")
+    pub agent_type: String,
+    /// Whether the agent's CLI is installed locally (always true for "manual")
+    pub installed: bool,
+    /// Path to the executable if installed
+    pub path: Option<String>,
+    /// Whether the agent is enabled in settings
+    pub enabled: bool,
+    /// Whether the agent can run in a Docker sandbox
+    pub supports_sandbox: bool,
+    /// Why the agent can't be used, if it can't (for greying out in the UI)
+    pub unavailable_reason: Option<String>,
+}
+
+/// Known agent types, the binaries that provide them, and sandbox support
+/// (the sandbox image only installs claude-code)
+const KNOWN_AGENT_TYPES: &[(&str, &[&str], bool)] = &[
+    ("claude", &["claude"], true),
+    ("aider", &["aider"], false),
+    ("codex", &["codex"], false),
+    ("gemini", &["gemini", "gemini-cli"], false),
+    ("ollama", &["ollama"], false),
+    ("manual", &[], false),
+];
+
+/// Find the first installed binary from a list, returning its path
+fn find_binary(names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        Command::new("which")
+            .arg(name)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    })
+}
+
+/// List known agent types with local install status and enabled state
+pub fn list_agent_types(enabled_agents: &[String]) -> Vec<AgentTypeInfo> {
+    KNOWN_AGENT_TYPES
+        .iter()
+        .map(|(agent_type, binaries, supports_sandbox)| {
+            let path = find_binary(binaries);
+            // Manual sessions need no CLI
+            let installed = binaries.is_empty() || path.is_some();
+            let enabled = enabled_agents.iter().any(|a| a == agent_type);
+
+            let unavailable_reason = if !installed {
+                Some(format!("{} is not installed", binaries.join(" or ")))
+            } else if !enabled {
+                Some("Disabled in settings".to_string())
+            } else {
+                None
+            };
+
+            AgentTypeInfo {
+                agent_type: agent_type.to_string(),
+                installed,
+                path,
+                enabled,
+                supports_sandbox: *supports_sandbox,
+                unavailable_reason,
+            }
+        })
+        .collect()
+}
+
+/// Installed version of a CLI tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DependencyVersion {
+    /// Tool or agent type name (e.g., "tmux", "claude")
+    pub name: String,
+    /// Binary that was queried (differs from `name` for e.g. "gemini-cli")
+    pub binary: String,
+    /// Whether the binary was found
+    pub installed: bool,
+    /// First line of the tool's version output
+    pub raw: Option<String>,
+    /// Version number parsed out of `raw` (e.g., "3.4")
+    pub version: Option<String>,
+    /// Oldest version Handy supports, if it has a requirement
+    pub minimum: Option<String>,
+    /// Why the installed version is a problem (too old or unparseable)
+    pub warning: Option<String>,
+}
+
+/// Minimum supported versions and what they are needed for
+const MINIMUM_VERSIONS: &[(&str, &str, &str)] = &[("tmux", "2.6", "for `-L` socket behavior")];
+
+/// Tools whose versions are reported alongside the agent CLIs
+const VERSIONED_TOOLS: &[(&str, &[&str])] = &[
+    ("gh", &["--version"]),
+    ("tmux", &["-V"]),
+    ("docker", &["--version"]),
+    ("devcontainer", &["--version"]),
+];
+
+/// Extract the first dotted version number (e.g., "2.40.0") from version output.
+///
+/// A leading `v` is allowed and trailing suffixes are dropped, so
+/// "tmux 3.3a" gives "3.3" and "v20.11.0" gives "20.11.0".
+pub fn parse_semver(raw: &str) -> Option<String> {
+    raw.split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+        .filter_map(|token| {
+            let token = token
+                .rsplit_once('-')
+                .map(|(_, t)| t)
+                .unwrap_or(token)
+                .trim_start_matches('v');
+            let version: String = token
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let version = version.trim_end_matches('.');
+            (version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit()))
+                .then(|| version.to_string())
+        })
+        .next()
+}
+
+/// Compare dotted versions numerically, treating missing parts as 0
+fn version_at_least(version: &str, minimum: &str) -> bool {
+    let parts = |v: &str| -> Vec<u32> { v.split('.').map(|p| p.parse().unwrap_or(0)).collect() };
+    let (mut have, mut need) = (parts(version), parts(minimum));
+    let len = have.len().max(need.len());
+    have.resize(len, 0);
+    need.resize(len, 0);
+    have >= need
+}
+
+/// Build a version report from a tool's raw version output
+fn dependency_version(
+    name: &str,
+    binary: &str,
+    raw: Option<String>,
+    installed: bool,
+) -> DependencyVersion {
+    let version = raw.as_deref().and_then(parse_semver);
+    let requirement = MINIMUM_VERSIONS.iter().find(|(tool, _, _)| *tool == name);
+
+    let warning = match (installed, requirement, &version) {
+        (false, _, _) => None,
+        (true, Some((_, minimum, reason)), Some(v)) if !version_at_least(v, minimum) => Some(
+            format!("{} {} is too old, need ≥ {} {}", name, v, minimum, reason),
+        ),
+        (true, Some((_, minimum, _)), None) => Some(format!(
+            "Could not determine the {} version; need ≥ {}",
+            name, minimum
+        )),
+        _ => None,
+    };
+
+    DependencyVersion {
+        name: name.to_string(),
+        binary: binary.to_string(),
+        installed,
+        raw,
+        version,
+        minimum: requirement.map(|(_, minimum, _)| minimum.to_string()),
+        warning,
     }
 }
 
+/// Report versions of the DevOps tools and every installed agent CLI.
+///
+/// Agents are listed once per agent type using the first installed binary.
+pub fn get_dependency_versions() -> Vec<DependencyVersion> {
+    let tools = VERSIONED_TOOLS.iter().map(|(name, args)| {
+        let (installed, raw, _) = check_command(name, args);
+        dependency_version(name, name, raw, installed)
+    });
+
+    let agents = KNOWN_AGENT_TYPES
+        .iter()
+        .filter(|(_, binaries, _)| !binaries.is_empty())
+        .map(|(agent_type, binaries, _)| {
+            binaries
+                .iter()
+                .map(|binary| (binary, check_command(binary, &["--version"])))
+                .find(|(_, (installed, _, _))| *installed)
+                .map(|(binary, (installed, raw, _))| {
+                    dependency_version(agent_type, binary, raw, installed)
+                })
+                .unwrap_or_else(|| dependency_version(agent_type, binaries[0], None, false))
+        });
+
+    tools.chain(agents).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_list_agent_types() {
+        let agents = list_agent_types(&["manual".to_string()]);
+        assert_eq!(agents.len(), KNOWN_AGENT_TYPES.len());
+
+        let manual = agents.iter().find(|a| a.agent_type == "manual").unwrap();
+        assert!(manual.installed);
+        assert!(manual.enabled);
+        assert!(!manual.supports_sandbox);
+        assert!(manual.unavailable_reason.is_none());
+
+        let claude = agents.iter().find(|a| a.agent_type == "claude").unwrap();
+        assert!(!claude.enabled);
+        assert!(claude.supports_sandbox);
+        assert!(claude.unavailable_reason.is_some());
+
+        let aider = agents.iter().find(|a| a.agent_type == "aider").unwrap();
+        assert!(!aider.supports_sandbox);
+    }
+
     #[test]
     fn test_check_dependencies() {
         let deps = check_all_dependencies();
@@ -433,4 +659,36 @@ mod tests {
         assert!(!deps.gh.name.is_empty());
         assert!(!deps.tmux.name.is_empty());
     }
+
+    #[test]
+    fn test_dependency_versions() {
+        assert_eq!(
+            parse_semver("gh version 2.40.0 (2024-01-01)").as_deref(),
+            Some("2.40.0")
+        );
+        assert_eq!(parse_semver("tmux 3.3a").as_deref(), Some("3.3"));
+        assert_eq!(parse_semver("tmux next-3.4").as_deref(), Some("3.4"));
+        assert_eq!(
+            parse_semver("Docker version 24.0.7, build afdd53b").as_deref(),
+            Some("24.0.7")
+        );
+        assert_eq!(
+            parse_semver("1.0.30 (Claude Code)").as_deref(),
+            Some("1.0.30")
+        );
+        assert_eq!(parse_semver("v0.62.1").as_deref(), Some("0.62.1"));
+        assert_eq!(parse_semver("unknown"), None);
+
+        let old = dependency_version("tmux", "tmux", Some("tmux 1.8".to_string()), true);
+        assert_eq!(old.minimum.as_deref(), Some("2.6"));
+        assert_eq!(
+            old.warning.as_deref(),
+            Some("tmux 1.8 is too old, need ≥ 2.6 for `-L` socket behavior")
+        );
+        let new = dependency_version("tmux", "tmux", Some("tmux 3.4".to_string()), true);
+        assert!(new.warning.is_none());
+        let missing = dependency_version("tmux", "tmux", None, false);
+        assert!(missing.warning.is_none());
+        assert!(version_at_least("2.10", "2.6"));
+    }
 }
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::process::Command;
 
+use super::process::CommandRetryExt;
+
 /// Regex patterns for sanitizing sensitive data from content before posting to GitHub.
 static SENSITIVE_PATTERNS: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)(sk-ant-[a-zA-Z0-9\-_]+|ghp_[a-zA-Z0-9]+|gho_[a-zA-Z0-9]+|github_pat_[a-zA-Z0-9_]+|ANTHROPIC_API_KEY=[^\s]+|GH_TOKEN=[^\s]+|GITHUB_TOKEN=[^\s]+|Bearer\s+[a-zA-Z0-9\-_.]+)").unwrap()
@@ -21,13 +23,15 @@ static SENSITIVE_PATTERNS: Lazy<Regex> = Lazy::new(|| {
 /// - GitHub tokens (ghp_*, gho_*, github_pat_*)
 /// - Environment variable assignments with sensitive values
 /// - Bearer tokens
+/// - Matches of custom patterns (see `docker::set_custom_sensitive_patterns`)
 /// - Home directory paths (replaced with ~)
 ///
 /// This function should be called on any content derived from error messages,
 /// logs, or other system output before posting to GitHub.
 pub fn sanitize_for_github(content: &str) -> String {
-    // Redact known sensitive patterns
+    // Redact known sensitive patterns, then the user's own formats
     let sanitized = SENSITIVE_PATTERNS.replace_all(content, "[REDACTED]");
+    let sanitized = super::docker::redact_custom_patterns(&sanitized);
 
     // Replace home directory with ~ to avoid leaking username
     if let Ok(home) = std::env::var("HOME") {
@@ -39,6 +43,79 @@ pub fn sanitize_for_github(content: &str) -> String {
     sanitized.to_string()
 }
 
+/// Wrap text in a Markdown code block whose fence is longer than any run of
+/// backticks inside it, so the text can't close the block early.
+pub fn code_block(content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}\n{content}\n{fence}")
+}
+
+/// Env vars checked for a GitHub token, in resolution order.
+///
+/// CI and headless setups inject a token directly, so these take precedence
+/// over the token `gh auth login` stored.
+pub const GH_TOKEN_ENV_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// Classic token scopes agents need (push branches, open PRs, edit workflows)
+const REQUIRED_TOKEN_SCOPES: [&str; 2] = ["repo", "workflow"];
+
+/// Where the GitHub token came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GhTokenSource {
+    /// The `GH_TOKEN` env var
+    GhTokenEnv,
+    /// The `GITHUB_TOKEN` env var
+    GithubTokenEnv,
+    /// `gh auth token`
+    GhCli,
+}
+
+/// A GitHub token and where it came from
+#[derive(Debug, Clone)]
+pub struct ResolvedGhToken {
+    /// The token
+    pub token: String,
+    /// Where it came from
+    pub source: GhTokenSource,
+}
+
+/// Resolve a token from env vars first, then the `gh` CLI
+fn resolve_gh_token_from(
+    env: impl Fn(&str) -> Option<String>,
+    gh_cli: impl FnOnce() -> Option<String>,
+) -> Option<ResolvedGhToken> {
+    let non_empty = |t: String| Some(t.trim().to_string()).filter(|t| !t.is_empty());
+    for (var, source) in GH_TOKEN_ENV_VARS
+        .iter()
+        .zip([GhTokenSource::GhTokenEnv, GhTokenSource::GithubTokenEnv])
+    {
+        if let Some(token) = env(var).and_then(non_empty) {
+            return Some(ResolvedGhToken { token, source });
+        }
+    }
+    gh_cli().and_then(non_empty).map(|token| ResolvedGhToken {
+        token,
+        source: GhTokenSource::GhCli,
+    })
+}
+
+/// Resolve the GitHub token: `GH_TOKEN`, then `GITHUB_TOKEN`, then `gh auth token`.
+pub fn resolve_gh_token() -> Option<ResolvedGhToken> {
+    resolve_gh_token_from(
+        |var| std::env::var(var).ok(),
+        || {
+            Command::new("gh")
+                .args(["auth", "token"])
+                .output_with_timeout()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        },
+    )
+}
+
 /// GitHub authentication status.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct GhAuthStatus {
@@ -50,6 +127,12 @@ pub struct GhAuthStatus {
     pub scopes: Vec<String>,
     /// Error message if not authenticated
     pub error: Option<String>,
+    /// Where the token came from
+    #[serde(default)]
+    pub token_source: Option<GhTokenSource>,
+    /// Problems with the token that don't block auth (e.g. missing scopes)
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 /// A GitHub issue.
@@ -69,6 +152,9 @@ pub struct GitHubIssue {
     pub labels: Vec<String>,
     /// Assignees
     pub assignees: Vec<String>,
+    /// Milestone title (if any)
+    #[serde(default)]
+    pub milestone: Option<String>,
     /// Author username
     pub author: String,
     /// Created timestamp
@@ -121,75 +207,320 @@ pub struct IssueWithAgent {
 const METADATA_START: &str = "<!-- HANDY_AGENT_METADATA";
 const METADATA_END: &str = "-->";
 
-/// Check GitHub CLI authentication status.
+/// Marker an agent adds to a comment that asks the human a question
+pub const QUESTION_MARKER: &str = "<!-- HANDY_QUESTION -->";
+
+// ============================================================================
+// gh JSON Output
+// ============================================================================
+//
+// Shapes of `gh ... --json <fields>` output. Everything except the number is
+// defaulted so a missing or renamed field degrades to an empty value instead
+// of failing the whole request.
+
+/// Fields requested for issues
+const ISSUE_JSON_FIELDS: &str =
+    "number,title,body,state,url,labels,assignees,milestone,author,createdAt,updatedAt";
+
+/// Fields requested for pull requests
+const PR_JSON_FIELDS: &str = "number,title,body,state,url,headRefName,baseRefName,isDraft,mergeable,labels,assignees,milestone,author,createdAt,updatedAt";
+
+/// Which issue fields a listing requests from `gh`.
+///
+/// Fields that aren't requested come back empty, so callers pick the smallest
+/// set covering what they read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueFields {
+    /// Every field of `GitHubIssue`
+    All,
+    /// Number, state and body (epic progress and sub-issue lookups)
+    Progress,
+}
+
+impl IssueFields {
+    /// Value for `gh --json`
+    fn json_fields(self) -> &'static str {
+        match self {
+            Self::All => ISSUE_JSON_FIELDS,
+            Self::Progress => "number,state,body",
+        }
+    }
+}
+
+/// Which pull request fields a listing requests from `gh`.
+///
+/// Fields that aren't requested come back empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrFields {
+    /// Every field of `GitHubPullRequest`
+    All,
+    /// Number, state, URL, branches and draft flag (matching PRs to branches)
+    Head,
+}
+
+impl PrFields {
+    /// Value for `gh --json`
+    fn json_fields(self) -> &'static str {
+        match self {
+            Self::All => PR_JSON_FIELDS,
+            Self::Head => "number,state,url,headRefName,baseRefName,isDraft",
+        }
+    }
+}
+
+/// A user (author, assignee) in gh JSON output
+#[derive(Debug, Default, Deserialize)]
+struct GhUser {
+    #[serde(default)]
+    login: String,
+}
+
+/// A label in gh JSON output
+#[derive(Debug, Deserialize)]
+struct GhLabel {
+    #[serde(default)]
+    name: String,
+}
+
+/// A milestone in gh JSON output
+#[derive(Debug, Deserialize)]
+struct GhMilestone {
+    #[serde(default)]
+    title: String,
+}
+
+/// An issue in gh JSON output
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhIssue {
+    number: u64,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+    #[serde(default)]
+    assignees: Vec<GhUser>,
+    #[serde(default)]
+    milestone: Option<GhMilestone>,
+    #[serde(default)]
+    author: Option<GhUser>,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    updated_at: String,
+}
+
+impl GhIssue {
+    fn into_issue(self, repo: &str) -> GitHubIssue {
+        GitHubIssue {
+            number: self.number,
+            title: self.title,
+            body: self.body,
+            state: self.state,
+            url: self.url,
+            labels: self.labels.into_iter().map(|l| l.name).collect(),
+            assignees: self.assignees.into_iter().map(|a| a.login).collect(),
+            milestone: self.milestone.map(|m| m.title),
+            author: self.author.unwrap_or_default().login,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            repo: repo.to_string(),
+        }
+    }
+}
+
+/// A pull request in gh JSON output
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhPr {
+    number: u64,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    head_ref_name: String,
+    #[serde(default)]
+    base_ref_name: String,
+    #[serde(default)]
+    is_draft: bool,
+    #[serde(default)]
+    mergeable: Option<String>,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+    #[serde(default)]
+    assignees: Vec<GhUser>,
+    #[serde(default)]
+    milestone: Option<GhMilestone>,
+    #[serde(default)]
+    author: Option<GhUser>,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    updated_at: String,
+}
+
+impl GhPr {
+    fn into_pr(self, repo: &str) -> GitHubPullRequest {
+        GitHubPullRequest {
+            number: self.number,
+            title: self.title,
+            body: self.body,
+            state: self.state,
+            url: self.url,
+            head_branch: self.head_ref_name,
+            base_branch: self.base_ref_name,
+            is_draft: self.is_draft,
+            // UNKNOWN while GitHub is still computing mergeability
+            mergeable: self
+                .mergeable
+                .filter(|m| m != "UNKNOWN")
+                .map(|m| m == "MERGEABLE"),
+            labels: self.labels.into_iter().map(|l| l.name).collect(),
+            assignees: self.assignees.into_iter().map(|a| a.login).collect(),
+            milestone: self.milestone.map(|m| m.title),
+            author: self.author.unwrap_or_default().login,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            repo: repo.to_string(),
+        }
+    }
+}
+
+/// Parse gh JSON output, naming the command in the error
+fn parse_gh_json<T: serde::de::DeserializeOwned>(
+    stdout: &[u8],
+    command: &str,
+) -> Result<T, String> {
+    serde_json::from_slice(stdout).map_err(|e| format!("Failed to parse {} output: {}", command, e))
+}
+
+/// Number of the issue or PR created by `gh issue create` / `gh pr create`.
+///
+/// Those commands have no `--json` flag and print the new URL as the last
+/// line of stdout (possibly after warnings), so scan for it.
+fn number_from_created_url(stdout: &str, kind: &str) -> Result<u64, String> {
+    stdout
+        .lines()
+        .rev()
+        .map(str::trim)
+        .filter(|line| line.starts_with("https://"))
+        .find_map(|url| url.rsplit('/').next()?.parse::<u64>().ok())
+        .ok_or_else(|| {
+            format!(
+                "Failed to parse {} number from gh output: {}",
+                kind,
+                stdout.trim()
+            )
+        })
+}
+
+/// Parse `gh api user --include` output into (login, scopes).
+///
+/// Scopes are `None` when GitHub sent no `X-OAuth-Scopes` header, which is the
+/// case for fine-grained PATs and app tokens.
+fn parse_user_response(output: &str) -> (Option<String>, Option<Vec<String>>) {
+    let (headers, body) = output
+        .split_once("\r\n\r\n")
+        .or_else(|| output.split_once("\n\n"))
+        .unwrap_or((output, ""));
+
+    let scopes = headers.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("x-oauth-scopes").then(|| {
+            value
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+    });
+
+    let login = serde_json::from_str::<serde_json::Value>(body.trim())
+        .ok()
+        .and_then(|v| v["login"].as_str().map(|s| s.to_string()));
+
+    (login, scopes)
+}
+
+/// Warnings for required scopes a classic token lacks
+fn missing_scope_warnings(scopes: &[String]) -> Vec<String> {
+    REQUIRED_TOKEN_SCOPES
+        .iter()
+        .filter(|required| !scopes.iter().any(|s| s == *required))
+        .map(|required| format!("GitHub token is missing the '{}' scope", required))
+        .collect()
+}
+
+/// Check GitHub authentication status.
+///
+/// Resolves the token (see `resolve_gh_token`) and validates it with
+/// `gh api user`, warning if a classic token lacks the `repo` or `workflow`
+/// scope.
 pub fn check_auth_status() -> GhAuthStatus {
+    let mut status = GhAuthStatus {
+        authenticated: false,
+        username: None,
+        scopes: vec![],
+        error: None,
+        token_source: None,
+        warnings: vec![],
+    };
+
+    let Some(resolved) = resolve_gh_token() else {
+        status.error = Some(
+            "No GitHub token found: set GH_TOKEN or GITHUB_TOKEN, or run `gh auth login`"
+                .to_string(),
+        );
+        return status;
+    };
+    status.token_source = Some(resolved.source);
+
     let output = Command::new("gh")
-        .args(["auth", "status", "--show-token"])
-        .output();
+        .args(["api", "user", "--include"])
+        .env("GH_TOKEN", &resolved.token)
+        .output_with_retry();
 
     match output {
+        Ok(output) if output.status.success() => {
+            let (username, scopes) = parse_user_response(&String::from_utf8_lossy(&output.stdout));
+            status.authenticated = true;
+            status.username = username;
+            if let Some(scopes) = scopes {
+                status.warnings = missing_scope_warnings(&scopes);
+                status.scopes = scopes;
+            }
+        }
         Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
-            let combined = format!("{}{}", stdout, stderr);
-
-            if output.status.success() || combined.contains("Logged in to") {
-                // Parse username from output
-                let username = combined
-                    .lines()
-                    .find(|line| line.contains("Logged in to"))
-                    .and_then(|line| {
-                        // Format: "✓ Logged in to github.com account username (keyring)"
-                        line.split("account ")
-                            .nth(1)
-                            .map(|s| s.split_whitespace().next().unwrap_or("").to_string())
-                    });
-
-                // Parse scopes
-                let scopes = combined
-                    .lines()
-                    .find(|line| line.contains("Token scopes:"))
-                    .map(|line| {
-                        line.split("Token scopes:")
-                            .nth(1)
-                            .unwrap_or("")
-                            .split(',')
-                            .map(|s| s.trim().trim_matches('\'').to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
-                GhAuthStatus {
-                    authenticated: true,
-                    username,
-                    scopes,
-                    error: None,
-                }
-            } else {
-                GhAuthStatus {
-                    authenticated: false,
-                    username: None,
-                    scopes: vec![],
-                    error: Some(combined.trim().to_string()),
-                }
-            }
+            status.error = Some(sanitize_for_github(stderr.trim()));
         }
-        Err(e) => GhAuthStatus {
-            authenticated: false,
-            username: None,
-            scopes: vec![],
-            error: Some(format!("Failed to run gh: {}", e)),
-        },
+        Err(e) => status.error = Some(format!("Failed to run gh: {}", e)),
     }
+
+    for warning in &status.warnings {
+        log::warn!("{}", warning);
+    }
+    status
 }
 
-/// List issues from a repository.
+/// List issues from a repository, requesting only `fields`.
 pub fn list_issues(
     repo: &str,
     state: Option<&str>,
     labels: Option<Vec<&str>>,
     limit: Option<u32>,
+    fields: IssueFields,
 ) -> Result<Vec<GitHubIssue>, String> {
     let mut args = vec![
         "issue",
@@ -197,7 +528,7 @@ pub fn list_issues(
         "--repo",
         repo,
         "--json",
-        "number,title,body,state,url,labels,assignees,author,createdAt,updatedAt",
+        fields.json_fields(),
     ];
 
     let state_str;
@@ -223,7 +554,7 @@ pub fn list_issues(
 
     let output = Command::new("gh")
         .args(&args)
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -233,54 +564,9 @@ pub fn list_issues(
         ));
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
-
-    // Parse the JSON response
-    #[derive(Deserialize)]
-    struct GhIssue {
-        number: u64,
-        title: String,
-        body: Option<String>,
-        state: String,
-        url: String,
-        labels: Vec<GhLabel>,
-        assignees: Vec<GhUser>,
-        author: GhUser,
-        #[serde(rename = "createdAt")]
-        created_at: String,
-        #[serde(rename = "updatedAt")]
-        updated_at: String,
-    }
-
-    #[derive(Deserialize)]
-    struct GhLabel {
-        name: String,
-    }
-
-    #[derive(Deserialize)]
-    struct GhUser {
-        login: String,
-    }
-
-    let gh_issues: Vec<GhIssue> =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse gh output: {}", e))?;
+    let gh_issues: Vec<GhIssue> = parse_gh_json(&output.stdout, "gh issue list")?;
 
-    Ok(gh_issues
-        .into_iter()
-        .map(|i| GitHubIssue {
-            number: i.number,
-            title: i.title,
-            body: i.body,
-            state: i.state,
-            url: i.url,
-            labels: i.labels.into_iter().map(|l| l.name).collect(),
-            assignees: i.assignees.into_iter().map(|a| a.login).collect(),
-            author: i.author.login,
-            created_at: i.created_at,
-            updated_at: i.updated_at,
-            repo: repo.to_string(),
-        })
-        .collect())
+    Ok(gh_issues.into_iter().map(|i| i.into_issue(repo)).collect())
 }
 
 /// Get details of a specific issue.
@@ -293,9 +579,9 @@ pub fn get_issue(repo: &str, number: u64) -> Result<GitHubIssue, String> {
             "--repo",
             repo,
             "--json",
-            "number,title,body,state,url,labels,assignees,author,createdAt,updatedAt",
+            ISSUE_JSON_FIELDS,
         ])
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -305,50 +591,101 @@ pub fn get_issue(repo: &str, number: u64) -> Result<GitHubIssue, String> {
         ));
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
+    let gh_issue: GhIssue = parse_gh_json(&output.stdout, "gh issue view")?;
+    Ok(gh_issue.into_issue(repo))
+}
 
-    #[derive(Deserialize)]
-    struct GhIssue {
-        number: u64,
-        title: String,
-        body: Option<String>,
-        state: String,
-        url: String,
-        labels: Vec<GhLabel>,
-        assignees: Vec<GhUser>,
-        author: GhUser,
-        #[serde(rename = "createdAt")]
-        created_at: String,
-        #[serde(rename = "updatedAt")]
-        updated_at: String,
+/// An issue with its body rendered to HTML by GitHub.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct RenderedIssue {
+    /// Issue number
+    pub number: u64,
+    /// Issue title
+    pub title: String,
+    /// Body HTML, sanitized by GitHub with `#refs` linked and task lists as checkboxes
+    pub body_html: String,
+    /// Last update timestamp
+    pub updated_at: String,
+    /// URL to the issue
+    pub url: String,
+}
+
+/// Issue as returned by the REST API with the HTML media type
+#[derive(Debug, Deserialize)]
+struct GhRenderedIssue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body_html: Option<String>,
+    updated_at: String,
+    html_url: String,
+}
+
+/// How long a rendered issue is served from the cache
+const RENDERED_ISSUE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Rendered issues by (repo, number), with when they were fetched
+type RenderedIssueCache =
+    std::collections::HashMap<(String, u64), (std::time::Instant, RenderedIssue)>;
+
+static RENDERED_ISSUE_CACHE: Lazy<std::sync::Mutex<RenderedIssueCache>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+fn parse_rendered_issue(stdout: &[u8]) -> Result<RenderedIssue, String> {
+    let issue: GhRenderedIssue = parse_gh_json(stdout, "gh api issue")?;
+    Ok(RenderedIssue {
+        number: issue.number,
+        title: issue.title,
+        body_html: issue.body_html.unwrap_or_default(),
+        updated_at: issue.updated_at,
+        url: issue.html_url,
+    })
+}
+
+/// Get an issue with its body rendered to HTML for in-app display.
+///
+/// Uses GitHub's HTML media type, so the markup is already sanitized and
+/// cross-references and checkboxes match github.com. Results are cached for
+/// a few minutes; editing the body through `update_issue_body_async` refreshes it.
+pub fn get_issue_rendered(repo: &str, number: u64) -> Result<RenderedIssue, String> {
+    let key = (repo.to_string(), number);
+    if let Some((fetched_at, issue)) = RENDERED_ISSUE_CACHE.lock().unwrap().get(&key) {
+        if fetched_at.elapsed() < RENDERED_ISSUE_TTL {
+            return Ok(issue.clone());
+        }
     }
 
-    #[derive(Deserialize)]
-    struct GhLabel {
-        name: String,
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "-H",
+            "Accept: application/vnd.github.html+json",
+            &format!("repos/{}/issues/{}", repo, number),
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh api issue failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
     }
 
-    #[derive(Deserialize)]
-    struct GhUser {
-        login: String,
-    }
-
-    let gh_issue: GhIssue =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse gh output: {}", e))?;
-
-    Ok(GitHubIssue {
-        number: gh_issue.number,
-        title: gh_issue.title,
-        body: gh_issue.body,
-        state: gh_issue.state,
-        url: gh_issue.url,
-        labels: gh_issue.labels.into_iter().map(|l| l.name).collect(),
-        assignees: gh_issue.assignees.into_iter().map(|a| a.login).collect(),
-        author: gh_issue.author.login,
-        created_at: gh_issue.created_at,
-        updated_at: gh_issue.updated_at,
-        repo: repo.to_string(),
-    })
+    let issue = parse_rendered_issue(&output.stdout)?;
+    RENDERED_ISSUE_CACHE
+        .lock()
+        .unwrap()
+        .insert(key, (std::time::Instant::now(), issue.clone()));
+    Ok(issue)
+}
+
+/// Drop a cached rendered issue so the next `get_issue_rendered` refetches it.
+pub fn invalidate_rendered_issue(repo: &str, number: u64) {
+    RENDERED_ISSUE_CACHE
+        .lock()
+        .unwrap()
+        .remove(&(repo.to_string(), number));
 }
 
 /// Create a new issue.
@@ -376,7 +713,7 @@ pub fn create_issue(
 
     let output = Command::new("gh")
         .args(&args)
-        .output()
+        .output_with_timeout()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -386,13 +723,7 @@ pub fn create_issue(
         ));
     }
 
-    // Output is the issue URL, parse the number from it
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    let number = url
-        .split('/')
-        .last()
-        .and_then(|s| s.parse::<u64>().ok())
-        .ok_or_else(|| "Failed to parse issue number from URL".to_string())?;
+    let number = number_from_created_url(&String::from_utf8_lossy(&output.stdout), "issue")?;
 
     // Fetch the full issue details
     get_issue(repo, number)
@@ -410,7 +741,7 @@ pub fn add_comment(repo: &str, number: u64, body: &str) -> Result<(), String> {
             "--body",
             body,
         ])
-        .output()
+        .output_with_timeout()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -453,6 +784,91 @@ pub fn add_agent_metadata_comment(
     add_comment(repo, number, &body)
 }
 
+/// Delete an issue comment by its numeric ID.
+pub fn delete_comment(repo: &str, comment_id: u64) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "-X",
+            "DELETE",
+            &format!("repos/{}/issues/comments/{}", repo, comment_id),
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh api delete comment failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Find the ID of the agent metadata comment posted for `session`.
+pub fn find_agent_metadata_comment_id(comments: &[GitHubComment], session: &str) -> Option<u64> {
+    comments
+        .iter()
+        .rev()
+        .find(|c| extract_metadata_from_comment(&c.body).is_some_and(|m| m.session == session))
+        .map(|c| c.id)
+        .filter(|&id| id != 0)
+}
+
+/// Edit an issue comment in place by its numeric ID.
+pub fn edit_comment(repo: &str, comment_id: u64, body: &str) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "-X",
+            "PATCH",
+            &format!("repos/{}/issues/comments/{}", repo, comment_id),
+            "-f",
+            &format!("body={}", body),
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh api edit comment failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Session named in a metadata comment, whichever metadata shape posted it
+fn metadata_comment_session(body: &str) -> Option<String> {
+    let start_idx = body.find(METADATA_START)?;
+    let end_idx = body[start_idx..].find(METADATA_END)?;
+    let json_str = body[start_idx + METADATA_START.len()..start_idx + end_idx].trim();
+
+    let value: serde_json::Value = serde_json::from_str(json_str).ok()?;
+    value.get("session")?.as_str().map(|s| s.to_string())
+}
+
+/// Find the metadata comment to refresh for `session`.
+///
+/// Prefers the latest comment naming `session`; falls back to the latest
+/// metadata comment of any session so a renamed session still finds the
+/// comment posted under its old name.
+pub fn find_metadata_comment_to_update(comments: &[GitHubComment], session: &str) -> Option<u64> {
+    let marked: Vec<&GitHubComment> = comments
+        .iter()
+        .filter(|c| c.id != 0 && c.body.contains(METADATA_START))
+        .collect();
+
+    marked
+        .iter()
+        .rev()
+        .find(|c| metadata_comment_session(&c.body).as_deref() == Some(session))
+        .or_else(|| marked.last())
+        .map(|c| c.id)
+}
+
 /// List comments on an issue.
 pub fn list_comments(repo: &str, number: u64) -> Result<Vec<GitHubComment>, String> {
     let output = Command::new("gh")
@@ -465,7 +881,7 @@ pub fn list_comments(repo: &str, number: u64) -> Result<Vec<GitHubComment>, Stri
             "--json",
             "comments",
         ])
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -475,42 +891,45 @@ pub fn list_comments(repo: &str, number: u64) -> Result<Vec<GitHubComment>, Stri
         ));
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
-
     #[derive(Deserialize)]
     struct GhComments {
+        #[serde(default)]
         comments: Vec<GhComment>,
     }
 
     #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
     struct GhComment {
-        id: String,
+        #[serde(default)]
+        url: String,
+        #[serde(default)]
         body: String,
-        author: GhUser,
-        #[serde(rename = "createdAt")]
+        #[serde(default)]
+        author: Option<GhUser>,
+        #[serde(default)]
         created_at: String,
     }
 
-    #[derive(Deserialize)]
-    struct GhUser {
-        login: String,
-    }
-
-    let gh_comments: GhComments =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse gh output: {}", e))?;
+    let gh_comments: GhComments = parse_gh_json(&output.stdout, "gh issue view")?;
 
     Ok(gh_comments
         .comments
         .into_iter()
         .map(|c| GitHubComment {
-            id: c.id.parse().unwrap_or(0),
+            // gh's `id` is a GraphQL node ID; the numeric ID is in the URL fragment
+            id: comment_id_from_url(&c.url).unwrap_or(0),
             body: c.body,
-            author: c.author.login,
+            author: c.author.unwrap_or_default().login,
             created_at: c.created_at,
         })
         .collect())
 }
 
+/// Numeric comment ID from a comment URL (`...#issuecomment-123`)
+fn comment_id_from_url(url: &str) -> Option<u64> {
+    url.rsplit_once("#issuecomment-")?.1.parse().ok()
+}
+
 /// Parse agent metadata from issue comments.
 pub fn parse_agent_metadata(comments: &[GitHubComment]) -> Option<IssueAgentMetadata> {
     for comment in comments.iter().rev() {
@@ -521,6 +940,17 @@ pub fn parse_agent_metadata(comments: &[GitHubComment]) -> Option<IssueAgentMeta
     None
 }
 
+/// Find the agent's most recent question on an issue that nobody has answered.
+///
+/// A question is a comment bearing `QUESTION_MARKER`. Agents and humans often
+/// post under the same `gh` login, so authors are ignored: any later comment
+/// without the marker counts as the answer.
+pub fn find_unanswered_question(comments: &[GitHubComment]) -> Option<&GitHubComment> {
+    comments
+        .last()
+        .filter(|comment| comment.body.contains(QUESTION_MARKER))
+}
+
 /// Extract metadata from a comment body.
 fn extract_metadata_from_comment(body: &str) -> Option<IssueAgentMetadata> {
     let start_idx = body.find(METADATA_START)?;
@@ -562,7 +992,7 @@ pub fn update_labels(
                 "--add-label",
                 label,
             ])
-            .output()
+            .output_with_timeout()
             .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
         if !output.status.success() {
@@ -591,7 +1021,7 @@ pub fn update_labels(
                 "--remove-label",
                 label,
             ])
-            .output()
+            .output_with_timeout()
             .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
         if !output.status.success() {
@@ -611,49 +1041,411 @@ pub fn update_labels(
     Ok(())
 }
 
-/// Close an issue with an optional comment.
-pub fn close_issue(repo: &str, number: u64, comment: Option<&str>) -> Result<(), String> {
-    // Add closing comment if provided
-    if let Some(c) = comment {
-        add_comment(repo, number, c)?;
+/// Build the `gh issue edit` arguments that add and remove labels in one call.
+fn label_edit_args(repo: &str, number: u64, add: &[&str], remove: &[&str]) -> Vec<String> {
+    let mut args = vec![
+        "issue".to_string(),
+        "edit".to_string(),
+        number.to_string(),
+        "--repo".to_string(),
+        repo.to_string(),
+    ];
+    for label in add {
+        args.push("--add-label".to_string());
+        args.push(label.to_string());
+    }
+    for label in remove {
+        args.push("--remove-label".to_string());
+        args.push(label.to_string());
+    }
+    args
+}
+
+/// Add and remove issue labels with a single `gh issue edit` call.
+///
+/// If any label doesn't exist in the repo, gh rejects the whole edit; in that
+/// case this falls back to `update_labels`, which applies labels one at a time
+/// and skips the missing ones.
+pub fn apply_label_changes(
+    repo: &str,
+    number: u64,
+    add: &[&str],
+    remove: &[&str],
+) -> Result<(), String> {
+    if add.is_empty() && remove.is_empty() {
+        return Ok(());
     }
 
     let output = Command::new("gh")
-        .args(["issue", "close", &number.to_string(), "--repo", repo])
-        .output()
+        .args(label_edit_args(repo, number, add, remove))
+        .output_with_timeout()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
-    if !output.status.success() {
-        return Err(format!(
-            "gh issue close failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+    if output.status.success() {
+        return Ok(());
     }
 
-    Ok(())
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("not found") {
+        return update_labels(repo, number, add.to_vec(), remove.to_vec());
+    }
+    Err(format!("gh issue edit (labels) failed: {}", stderr))
 }
 
-/// Reopen a closed issue.
-pub fn reopen_issue(repo: &str, number: u64) -> Result<(), String> {
+/// Issues labeled per GraphQL request in `add_labels_batch`
+const LABEL_BATCH_SIZE: usize = 50;
+
+/// An issue `add_labels_batch` could not label
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct LabelFailure {
+    /// Issue number
+    pub issue_number: u64,
+    /// Why labeling failed
+    pub error: String,
+}
+
+/// Outcome of labeling many issues at once
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct BatchLabelResult {
+    /// Issues that now carry the labels (labels missing from the repo are skipped)
+    pub succeeded: Vec<u64>,
+    /// Issues that could not be labeled
+    pub failed: Vec<LabelFailure>,
+    /// Whether some issues were labeled one at a time after the batch failed
+    pub fell_back: bool,
+}
+
+/// GraphQL string literal for `value`
+fn graphql_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Query resolving label and issue node IDs, aliased `l<i>` and `i<i>`.
+fn label_lookup_query(owner: &str, name: &str, issue_numbers: &[u64], labels: &[String]) -> String {
+    let labels = labels
+        .iter()
+        .enumerate()
+        .map(|(i, label)| format!("l{}: label(name: {}) {{ id }}", i, graphql_string(label)));
+    let issues = issue_numbers
+        .iter()
+        .enumerate()
+        .map(|(i, number)| format!("i{}: issue(number: {}) {{ id }}", i, number));
+    format!(
+        "query {{ repository(owner: {}, name: {}) {{ {} }} }}",
+        graphql_string(owner),
+        graphql_string(name),
+        labels.chain(issues).collect::<Vec<_>>().join(" ")
+    )
+}
+
+/// Mutation adding `label_ids` to each issue node, aliased `m<i>`.
+fn add_labels_mutation(issue_ids: &[String], label_ids: &[String]) -> String {
+    let label_ids = label_ids
+        .iter()
+        .map(|id| graphql_string(id))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mutations = issue_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            format!(
+                "m{}: addLabelsToLabelable(input: {{labelableId: {}, labelIds: [{}]}}) {{ clientMutationId }}",
+                i,
+                graphql_string(id),
+                label_ids
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("mutation {{ {} }}", mutations)
+}
+
+/// Node ID under alias `<prefix><index>` in a GraphQL `data` object, if resolved
+fn aliased_node_id(data: &serde_json::Value, prefix: &str, index: usize) -> Option<String> {
+    data.get(format!("{}{}", prefix, index))?
+        .get("id")?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// Run a GraphQL document through `gh api graphql` and return its `data`.
+///
+/// GraphQL reports per-field errors alongside partial data, so `data` is
+/// returned even when gh exits non-zero; callers check individual fields.
+fn run_graphql(query: &str) -> Result<serde_json::Value, String> {
     let output = Command::new("gh")
-        .args(["issue", "reopen", &number.to_string(), "--repo", repo])
-        .output()
+        .args(["api", "graphql", "-f", &format!("query={}", query)])
+        .output_with_timeout()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
-    if !output.status.success() {
-        return Err(format!(
-            "gh issue reopen failed: {}",
+    let response: Option<serde_json::Value> = serde_json::from_slice(&output.stdout).ok();
+    match response.and_then(|r| r.get("data").cloned()) {
+        Some(data) if !data.is_null() => Ok(data),
+        _ => Err(format!(
+            "gh api graphql failed: {}",
             String::from_utf8_lossy(&output.stderr)
-        ));
+        )),
     }
-
-    Ok(())
 }
 
-// ============================================================================
-// Pull Request Functions
-// ============================================================================
-
+/// Label one chunk of issues with two GraphQL calls; returns the issues labeled.
+fn add_labels_graphql(
+    owner: &str,
+    name: &str,
+    issue_numbers: &[u64],
+    labels: &[String],
+) -> Result<Vec<u64>, String> {
+    let lookup = run_graphql(&label_lookup_query(owner, name, issue_numbers, labels))?;
+    let repository = lookup
+        .get("repository")
+        .filter(|r| !r.is_null())
+        .ok_or_else(|| format!("Repository {}/{} not found", owner, name))?;
+
+    // Like `update_labels`, labels that don't exist in the repo are skipped
+    let label_ids: Vec<String> = (0..labels.len())
+        .filter_map(|i| aliased_node_id(repository, "l", i))
+        .collect();
+    let issues: Vec<(u64, String)> = issue_numbers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &number)| aliased_node_id(repository, "i", i).map(|id| (number, id)))
+        .collect();
+    if label_ids.is_empty() {
+        return Ok(issues.into_iter().map(|(number, _)| number).collect());
+    }
+    if issues.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<String> = issues.iter().map(|(_, id)| id.clone()).collect();
+    let data = run_graphql(&add_labels_mutation(&ids, &label_ids))?;
+    Ok(issues
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| data.get(format!("m{}", i)).is_some_and(|m| !m.is_null()))
+        .map(|(_, (number, _))| *number)
+        .collect())
+}
+
+/// Add `labels` to many issues with as few API calls as possible.
+///
+/// Issues are labeled through GraphQL in chunks of `LABEL_BATCH_SIZE` (two
+/// calls per chunk instead of one per issue). Any issue the batch didn't
+/// label is retried on its own with `apply_label_changes`, and the result
+/// reports which issues ended up labeled.
+pub fn add_labels_batch(
+    repo: &str,
+    issue_numbers: &[u64],
+    labels: &[String],
+) -> Result<BatchLabelResult, String> {
+    let (owner, name) = repo
+        .split_once('/')
+        .ok_or_else(|| format!("Invalid repo '{}', expected owner/name", repo))?;
+
+    let mut result = BatchLabelResult::default();
+    if labels.is_empty() {
+        result.succeeded = issue_numbers.to_vec();
+        return Ok(result);
+    }
+
+    let label_refs: Vec<&str> = labels.iter().map(|s| s.as_str()).collect();
+    for chunk in issue_numbers.chunks(LABEL_BATCH_SIZE) {
+        let labeled = match add_labels_graphql(owner, name, chunk, labels) {
+            Ok(labeled) => labeled,
+            Err(e) => {
+                log::warn!(
+                    "Batch labeling failed, labeling issues one at a time: {}",
+                    e
+                );
+                Vec::new()
+            }
+        };
+
+        for &number in chunk {
+            if labeled.contains(&number) {
+                result.succeeded.push(number);
+                continue;
+            }
+            result.fell_back = true;
+            match apply_label_changes(repo, number, &label_refs, &[]) {
+                Ok(()) => result.succeeded.push(number),
+                Err(error) => result.failed.push(LabelFailure {
+                    issue_number: number,
+                    error,
+                }),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Add assignees to an issue (e.g., the bot account an agent works as).
+pub fn set_issue_assignees(repo: &str, number: u64, assignees: &[&str]) -> Result<(), String> {
+    if assignees.is_empty() {
+        return Ok(());
+    }
+
+    let output = Command::new("gh")
+        .args([
+            "issue",
+            "edit",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--add-assignee",
+            &assignees.join(","),
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh issue edit (add assignee) failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+/// Remove one assignee from an issue (e.g., the bot account an agent works as),
+/// leaving any other assignees in place.
+pub fn remove_issue_assignee(repo: &str, number: u64, assignee: &str) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args([
+            "issue",
+            "edit",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--remove-assignee",
+            assignee,
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh issue edit (remove assignee) failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+/// Close an issue with an optional comment.
+pub fn close_issue(repo: &str, number: u64, comment: Option<&str>) -> Result<(), String> {
+    // Add closing comment if provided
+    if let Some(c) = comment {
+        add_comment(repo, number, c)?;
+    }
+
+    let output = Command::new("gh")
+        .args(["issue", "close", &number.to_string(), "--repo", repo])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh issue close failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reopen a closed issue.
+pub fn reopen_issue(repo: &str, number: u64) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args(["issue", "reopen", &number.to_string(), "--repo", repo])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh issue reopen failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Checklists
+// ============================================================================
+
+/// Heading of the acceptance criteria checklist in sub-issue bodies
+pub const ACCEPTANCE_CRITERIA_HEADING: &str = "Acceptance Criteria";
+
+/// A markdown task list item (`- [ ] text` / `- [x] text`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ChecklistItem {
+    /// Item text
+    pub text: String,
+    /// Whether the box is checked
+    pub checked: bool,
+}
+
+/// Parse a single task list line, or None if the line isn't a checkbox item
+fn parse_checklist_line(line: &str) -> Option<ChecklistItem> {
+    let rest = line
+        .trim_start()
+        .strip_prefix("- ")
+        .or_else(|| line.trim_start().strip_prefix("* "))?;
+    let (checked, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, text)
+    } else {
+        return None;
+    };
+    Some(ChecklistItem {
+        text: text.trim().to_string(),
+        checked,
+    })
+}
+
+/// Parse the checklist under a markdown heading (e.g. "## Acceptance Criteria").
+///
+/// Collects checkbox items until the next heading. Returns an empty list if
+/// the heading is missing.
+pub fn parse_checklist_section(body: &str, heading: &str) -> Vec<ChecklistItem> {
+    let mut in_section = false;
+    let mut items = Vec::new();
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            if in_section {
+                break;
+            }
+            in_section = trimmed.trim_start_matches('#').trim() == heading;
+            continue;
+        }
+        if in_section {
+            items.extend(parse_checklist_line(line));
+        }
+    }
+
+    items
+}
+
+/// Whether an issue body has acceptance criteria and all of them are checked.
+pub fn acceptance_criteria_complete(body: &str) -> bool {
+    let items = parse_checklist_section(body, ACCEPTANCE_CRITERIA_HEADING);
+    !items.is_empty() && items.iter().all(|i| i.checked)
+}
+
+// ============================================================================
+// Pull Request Functions
+// ============================================================================
+
 /// A GitHub Pull Request.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct GitHubPullRequest {
@@ -677,6 +1469,12 @@ pub struct GitHubPullRequest {
     pub mergeable: Option<bool>,
     /// Labels on the PR
     pub labels: Vec<String>,
+    /// Assignees
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    /// Milestone title (if any)
+    #[serde(default)]
+    pub milestone: Option<String>,
     /// Author username
     pub author: String,
     /// Created timestamp
@@ -688,7 +1486,7 @@ pub struct GitHubPullRequest {
 }
 
 /// PR check status.
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
 pub struct PrCheckStatus {
     /// Overall status (pending, success, failure)
     pub state: String,
@@ -713,6 +1511,38 @@ pub struct PrReviewStatus {
     pub pending: u32,
 }
 
+/// Per-reviewer review decisions on a PR.
+///
+/// Only each reviewer's latest approving or change-requesting review counts,
+/// matching how GitHub decides whether a PR is approved.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct ReviewSummary {
+    /// Reviewers whose latest decision is an approval
+    pub approved_by: Vec<String>,
+    /// Reviewers whose latest decision requests changes
+    pub changes_requested_by: Vec<String>,
+    /// Reviewers (users or teams) asked to review who haven't yet
+    pub pending: Vec<String>,
+    /// Whether the approvals required by branch protection are in, and
+    /// nobody requests changes
+    pub required_approvals_met: bool,
+}
+
+/// A review comment left on a PR, either a review summary or an inline comment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct PrReviewComment {
+    /// Login of the reviewer
+    pub author: String,
+    /// Comment text
+    pub body: String,
+    /// File the comment is attached to (None for review summaries)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Line in `path` the comment is attached to, if still part of the diff
+    #[serde(default)]
+    pub line: Option<u32>,
+}
+
 /// Full PR status including checks and reviews.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct PrStatus {
@@ -722,23 +1552,20 @@ pub struct PrStatus {
     pub checks: PrCheckStatus,
     /// Review status
     pub reviews: PrReviewStatus,
+    /// Per-reviewer review decisions
+    #[serde(default)]
+    pub review_summary: ReviewSummary,
 }
 
-/// List pull requests from a repository.
+/// List pull requests from a repository, requesting only `fields`.
 pub fn list_prs(
     repo: &str,
     state: Option<&str>,
     base: Option<&str>,
     limit: Option<u32>,
+    fields: PrFields,
 ) -> Result<Vec<GitHubPullRequest>, String> {
-    let mut args = vec![
-        "pr",
-        "list",
-        "--repo",
-        repo,
-        "--json",
-        "number,title,body,state,url,headRefName,baseRefName,isDraft,mergeable,labels,author,createdAt,updatedAt",
-    ];
+    let mut args = vec!["pr", "list", "--repo", repo, "--json", fields.json_fields()];
 
     let state_str;
     if let Some(s) = state {
@@ -763,7 +1590,7 @@ pub fn list_prs(
 
     let output = Command::new("gh")
         .args(&args)
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -773,62 +1600,101 @@ pub fn list_prs(
         ));
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
+    let gh_prs: Vec<GhPr> = parse_gh_json(&output.stdout, "gh pr list")?;
 
-    #[derive(Deserialize)]
-    struct GhPr {
-        number: u64,
-        title: String,
-        body: Option<String>,
-        state: String,
-        url: String,
-        #[serde(rename = "headRefName")]
-        head_ref_name: String,
-        #[serde(rename = "baseRefName")]
-        base_ref_name: String,
-        #[serde(rename = "isDraft")]
-        is_draft: bool,
-        mergeable: Option<String>,
-        labels: Vec<GhLabel>,
-        author: GhUser,
-        #[serde(rename = "createdAt")]
-        created_at: String,
-        #[serde(rename = "updatedAt")]
-        updated_at: String,
-    }
+    Ok(gh_prs.into_iter().map(|p| p.into_pr(repo)).collect())
+}
 
-    #[derive(Deserialize)]
-    struct GhLabel {
-        name: String,
+/// List open pull requests matching a GitHub search query (e.g. `review-requested:@me`).
+pub fn search_prs(repo: &str, query: &str, limit: u32) -> Result<Vec<GitHubPullRequest>, String> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "list",
+            "--repo",
+            repo,
+            "--search",
+            query,
+            "--limit",
+            &limit.to_string(),
+            "--json",
+            PR_JSON_FIELDS,
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr list failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
     }
 
-    #[derive(Deserialize)]
-    struct GhUser {
-        login: String,
+    let gh_prs: Vec<GhPr> = parse_gh_json(&output.stdout, "gh pr list")?;
+
+    Ok(gh_prs.into_iter().map(|p| p.into_pr(repo)).collect())
+}
+
+/// Find the open PR whose head is `branch_name`, if any.
+pub fn find_open_pr_for_branch(
+    repo: &str,
+    branch_name: &str,
+) -> Result<Option<GitHubPullRequest>, String> {
+    Ok(
+        list_prs(repo, Some("open"), None, Some(100), PrFields::All)?
+            .into_iter()
+            .find(|pr| pr.head_branch == branch_name),
+    )
+}
+
+/// Whether a remote branch is protected, or None if the branch doesn't exist.
+pub fn get_branch_protection(repo: &str, branch: &str) -> Result<Option<bool>, String> {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            &format!("repos/{}/branches/{}", repo, branch),
+            "--jq",
+            ".protected",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("Branch not found") || stderr.contains("HTTP 404") {
+            return Ok(None);
+        }
+        return Err(format!("gh api branches failed: {}", stderr));
     }
 
-    let gh_prs: Vec<GhPr> =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse gh output: {}", e))?;
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout).trim() == "true",
+    ))
+}
 
-    Ok(gh_prs
-        .into_iter()
-        .map(|p| GitHubPullRequest {
-            number: p.number,
-            title: p.title,
-            body: p.body,
-            state: p.state,
-            url: p.url,
-            head_branch: p.head_ref_name,
-            base_branch: p.base_ref_name,
-            is_draft: p.is_draft,
-            mergeable: p.mergeable.map(|m| m == "MERGEABLE"),
-            labels: p.labels.into_iter().map(|l| l.name).collect(),
-            author: p.author.login,
-            created_at: p.created_at,
-            updated_at: p.updated_at,
-            repo: repo.to_string(),
-        })
-        .collect())
+/// Delete a branch on GitHub.
+///
+/// Returns false if the branch was already gone.
+pub fn delete_remote_branch(repo: &str, branch: &str) -> Result<bool, String> {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "-X",
+            "DELETE",
+            &format!("repos/{}/git/refs/heads/{}", repo, branch),
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("Reference does not exist") {
+            return Ok(false);
+        }
+        return Err(format!("gh api delete ref failed: {}", stderr));
+    }
+
+    Ok(true)
 }
 
 /// Get details of a specific pull request.
@@ -841,9 +1707,9 @@ pub fn get_pr(repo: &str, number: u64) -> Result<GitHubPullRequest, String> {
             "--repo",
             repo,
             "--json",
-            "number,title,body,state,url,headRefName,baseRefName,isDraft,mergeable,labels,author,createdAt,updatedAt",
+            PR_JSON_FIELDS,
         ])
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -853,59 +1719,8 @@ pub fn get_pr(repo: &str, number: u64) -> Result<GitHubPullRequest, String> {
         ));
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
-
-    #[derive(Deserialize)]
-    struct GhPr {
-        number: u64,
-        title: String,
-        body: Option<String>,
-        state: String,
-        url: String,
-        #[serde(rename = "headRefName")]
-        head_ref_name: String,
-        #[serde(rename = "baseRefName")]
-        base_ref_name: String,
-        #[serde(rename = "isDraft")]
-        is_draft: bool,
-        mergeable: Option<String>,
-        labels: Vec<GhLabel>,
-        author: GhUser,
-        #[serde(rename = "createdAt")]
-        created_at: String,
-        #[serde(rename = "updatedAt")]
-        updated_at: String,
-    }
-
-    #[derive(Deserialize)]
-    struct GhLabel {
-        name: String,
-    }
-
-    #[derive(Deserialize)]
-    struct GhUser {
-        login: String,
-    }
-
-    let gh_pr: GhPr =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse gh output: {}", e))?;
-
-    Ok(GitHubPullRequest {
-        number: gh_pr.number,
-        title: gh_pr.title,
-        body: gh_pr.body,
-        state: gh_pr.state,
-        url: gh_pr.url,
-        head_branch: gh_pr.head_ref_name,
-        base_branch: gh_pr.base_ref_name,
-        is_draft: gh_pr.is_draft,
-        mergeable: gh_pr.mergeable.map(|m| m == "MERGEABLE"),
-        labels: gh_pr.labels.into_iter().map(|l| l.name).collect(),
-        author: gh_pr.author.login,
-        created_at: gh_pr.created_at,
-        updated_at: gh_pr.updated_at,
-        repo: repo.to_string(),
-    })
+    let gh_pr: GhPr = parse_gh_json(&output.stdout, "gh pr view")?;
+    Ok(gh_pr.into_pr(repo))
 }
 
 /// Create a new pull request.
@@ -941,7 +1756,7 @@ pub fn create_pr(
 
     let output = Command::new("gh")
         .args(&args)
-        .output()
+        .output_with_timeout()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
@@ -951,13 +1766,7 @@ pub fn create_pr(
         ));
     }
 
-    // Output is the PR URL, parse the number from it
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    let number = url
-        .split('/')
-        .last()
-        .and_then(|s| s.parse::<u64>().ok())
-        .ok_or_else(|| "Failed to parse PR number from URL".to_string())?;
+    let number = number_from_created_url(&String::from_utf8_lossy(&output.stdout), "PR")?;
 
     // Fetch the full PR details
     get_pr(repo, number)
@@ -973,26 +1782,29 @@ pub fn get_pr_checks(repo: &str, number: u64) -> Result<PrCheckStatus, String> {
             "--repo",
             repo,
             "--json",
-            "name,state,conclusion",
+            "name,state,bucket",
         ])
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     // gh pr checks returns non-zero if checks are failing, so we parse regardless
-    let json_str = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_pr_checks(&String::from_utf8_lossy(&output.stdout)))
+}
 
+/// Summarize `gh pr checks --json name,state,bucket` output.
+///
+/// `bucket` is gh's normalized result: pass, fail, pending, skipping or cancel.
+fn parse_pr_checks(json: &str) -> PrCheckStatus {
     #[derive(Deserialize)]
     struct GhCheck {
-        #[allow(dead_code)]
-        name: String,
-        state: String,
-        conclusion: Option<String>,
+        #[serde(default)]
+        bucket: String,
     }
 
-    let checks: Vec<GhCheck> = if json_str.trim().is_empty() {
+    let checks: Vec<GhCheck> = if json.trim().is_empty() {
         vec![]
     } else {
-        serde_json::from_str(&json_str).unwrap_or_default()
+        serde_json::from_str(json).unwrap_or_default()
     };
 
     let mut passing = 0u32;
@@ -1000,15 +1812,10 @@ pub fn get_pr_checks(repo: &str, number: u64) -> Result<PrCheckStatus, String> {
     let mut pending = 0u32;
 
     for check in &checks {
-        match check.state.as_str() {
-            "COMPLETED" => {
-                if check.conclusion.as_deref() == Some("SUCCESS") {
-                    passing += 1;
-                } else {
-                    failing += 1;
-                }
-            }
-            "IN_PROGRESS" | "QUEUED" | "PENDING" => pending += 1,
+        match check.bucket.as_str() {
+            "pass" => passing += 1,
+            "fail" | "cancel" => failing += 1,
+            "pending" => pending += 1,
             _ => {}
         }
     }
@@ -1024,157 +1831,937 @@ pub fn get_pr_checks(repo: &str, number: u64) -> Result<PrCheckStatus, String> {
         "unknown".to_string()
     };
 
-    Ok(PrCheckStatus {
+    PrCheckStatus {
         state,
         passing,
         failing,
         pending,
         total,
-    })
+    }
 }
 
-/// Get PR review status.
-pub fn get_pr_reviews(repo: &str, number: u64) -> Result<PrReviewStatus, String> {
+/// Maximum number of log lines returned by `get_pr_check_logs`.
+const MAX_CHECK_LOG_LINES: usize = 500;
+
+/// Get the failing log output for a PR check.
+///
+/// Resolves the check's GitHub Actions run from its link and returns the
+/// tail of `gh run view --log-failed`, sanitized for display.
+pub fn get_pr_check_logs(repo: &str, number: u64, check_name: &str) -> Result<String, String> {
     let output = Command::new("gh")
         .args([
             "pr",
-            "view",
+            "checks",
             &number.to_string(),
             "--repo",
             repo,
             "--json",
-            "reviews",
+            "name,link",
         ])
-        .output()
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    // gh pr checks returns non-zero if checks are failing, so we parse regardless
+    let json_str = String::from_utf8_lossy(&output.stdout);
+
+    #[derive(Deserialize)]
+    struct GhCheckLink {
+        name: String,
+        link: Option<String>,
+    }
+
+    let checks: Vec<GhCheckLink> = serde_json::from_str(&json_str).map_err(|e| {
+        format!(
+            "Failed to parse checks for PR #{}: {} ({})",
+            number,
+            e,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+
+    let check = checks
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(check_name))
+        .ok_or_else(|| format!("Check '{}' not found on PR #{}", check_name, number))?;
+
+    let link = check
+        .link
+        .as_deref()
+        .ok_or_else(|| format!("Check '{}' has no details link", check_name))?;
+
+    let (run_id, job_id) = parse_actions_run_link(link).ok_or_else(|| {
+        format!(
+            "Check '{}' is not a GitHub Actions run, logs unavailable: {}",
+            check_name, link
+        )
+    })?;
+
+    let mut args = vec!["run", "view", &run_id, "--repo", repo, "--log-failed"];
+    if let Some(ref job) = job_id {
+        args.push("--job");
+        args.push(job);
+    }
+
+    let output = Command::new("gh")
+        .args(&args)
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
         return Err(format!(
-            "gh pr view failed: {}",
+            "gh run view failed: {}",
             String::from_utf8_lossy(&output.stderr)
         ));
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
+    let logs = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = logs.lines().collect();
+    let start = lines.len().saturating_sub(MAX_CHECK_LOG_LINES);
 
-    #[derive(Deserialize)]
-    struct GhReviews {
-        reviews: Vec<GhReview>,
+    Ok(sanitize_for_github(&lines[start..].join("\n")))
+}
+
+/// Extract the run ID and optional job ID from a GitHub Actions check link.
+///
+/// Links look like `https://github.com/owner/repo/actions/runs/123/job/456`.
+fn parse_actions_run_link(link: &str) -> Option<(String, Option<String>)> {
+    let after_runs = link.split("/actions/runs/").nth(1)?;
+    let mut parts = after_runs.split('/');
+
+    let run_id = parts
+        .next()
+        .filter(|s| s.chars().all(|c| c.is_ascii_digit()))?;
+    if run_id.is_empty() {
+        return None;
+    }
+
+    let job_id = match (parts.next(), parts.next()) {
+        (Some("job"), Some(job)) if job.chars().all(|c| c.is_ascii_digit()) => {
+            Some(job.to_string())
+        }
+        _ => None,
+    };
+
+    Some((run_id.to_string(), job_id))
+}
+
+/// Parse `gh pr view --json reviews,reviewDecision,reviewRequests` output
+fn parse_pr_reviews(stdout: &[u8]) -> Result<(PrReviewStatus, ReviewSummary), String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct GhReviews {
+        #[serde(default)]
+        reviews: Vec<GhReview>,
+        #[serde(default)]
+        review_decision: Option<String>,
+        #[serde(default)]
+        review_requests: Vec<GhReviewRequest>,
+    }
+
+    #[derive(Deserialize)]
+    struct GhReview {
+        #[serde(default)]
+        author: Option<GhUser>,
+        #[serde(default)]
+        state: String,
+    }
+
+    /// A requested reviewer: users have a login, teams a name
+    #[derive(Deserialize)]
+    struct GhReviewRequest {
+        #[serde(default)]
+        login: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+    }
+
+    let reviews: GhReviews = parse_gh_json(stdout, "gh pr view")?;
+
+    let mut approved = 0u32;
+    let mut changes_requested = 0u32;
+    let mut pending = 0u32;
+    // Latest decision per reviewer, in first-review order
+    let mut decisions: Vec<(String, String)> = Vec::new();
+
+    for review in &reviews.reviews {
+        match review.state.as_str() {
+            "APPROVED" => approved += 1,
+            "CHANGES_REQUESTED" => changes_requested += 1,
+            "PENDING" => pending += 1,
+            _ => {}
+        }
+        if matches!(
+            review.state.as_str(),
+            "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED"
+        ) {
+            let login = review
+                .author
+                .as_ref()
+                .map(|a| a.login.clone())
+                .unwrap_or_default();
+            match decisions.iter_mut().find(|(l, _)| *l == login) {
+                Some(decision) => decision.1 = review.state.clone(),
+                None => decisions.push((login, review.state.clone())),
+            }
+        }
+    }
+
+    let reviewers_with = |state: &str| -> Vec<String> {
+        decisions
+            .iter()
+            .filter(|(login, s)| s == state && !login.is_empty())
+            .map(|(login, _)| login.clone())
+            .collect()
+    };
+    let approved_by = reviewers_with("APPROVED");
+    let changes_requested_by = reviewers_with("CHANGES_REQUESTED");
+    // Without branch protection GitHub reports no decision, so nothing is required
+    let required_approvals_met = changes_requested_by.is_empty()
+        && matches!(
+            reviews.review_decision.as_deref(),
+            Some("APPROVED") | Some("") | None
+        );
+
+    let summary = ReviewSummary {
+        approved_by,
+        changes_requested_by,
+        pending: reviews
+            .review_requests
+            .into_iter()
+            .filter_map(|r| r.login.or(r.name))
+            .collect(),
+        required_approvals_met,
+    };
+
+    Ok((
+        PrReviewStatus {
+            approved,
+            changes_requested,
+            pending,
+        },
+        summary,
+    ))
+}
+
+/// Get review counts and per-reviewer decisions for a PR.
+pub fn get_pr_reviews(repo: &str, number: u64) -> Result<(PrReviewStatus, ReviewSummary), String> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "view",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--json",
+            "reviews,reviewDecision,reviewRequests",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr view failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    parse_pr_reviews(&output.stdout)
+}
+
+/// Combine review summaries (`gh pr view --json reviews`) and inline
+/// comments (`gh api repos/{repo}/pulls/{n}/comments`), skipping empty bodies.
+fn parse_pr_review_comments(
+    reviews_json: &[u8],
+    inline_json: &[u8],
+) -> Result<Vec<PrReviewComment>, String> {
+    #[derive(Deserialize)]
+    struct GhReviews {
+        #[serde(default)]
+        reviews: Vec<GhReview>,
+    }
+
+    #[derive(Deserialize)]
+    struct GhReview {
+        author: Option<GhUser>,
+        #[serde(default)]
+        body: String,
+    }
+
+    #[derive(Deserialize)]
+    struct GhInlineComment {
+        user: Option<GhUser>,
+        #[serde(default)]
+        body: String,
+        path: Option<String>,
+        line: Option<u32>,
+    }
+
+    let reviews: GhReviews = parse_gh_json(reviews_json, "gh pr view")?;
+    // `gh api --paginate` prints one JSON array per page
+    let inline: Vec<GhInlineComment> = serde_json::Deserializer::from_slice(inline_json)
+        .into_iter::<Vec<GhInlineComment>>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to parse gh api pulls comments output: {}", e))?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let summaries = reviews.reviews.into_iter().map(|r| PrReviewComment {
+        author: r.author.unwrap_or_default().login,
+        body: r.body,
+        path: None,
+        line: None,
+    });
+    let inline = inline.into_iter().map(|c| PrReviewComment {
+        author: c.user.unwrap_or_default().login,
+        body: c.body,
+        path: c.path,
+        line: c.line,
+    });
+
+    Ok(summaries
+        .chain(inline)
+        .filter(|c| !c.body.trim().is_empty())
+        .collect())
+}
+
+/// Get the review comments on a PR: review summaries first, then inline comments.
+pub fn get_pr_review_comments(repo: &str, number: u64) -> Result<Vec<PrReviewComment>, String> {
+    let reviews = Command::new("gh")
+        .args([
+            "pr",
+            "view",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--json",
+            "reviews",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !reviews.status.success() {
+        return Err(format!(
+            "gh pr view failed: {}",
+            String::from_utf8_lossy(&reviews.stderr)
+        ));
+    }
+
+    let inline = Command::new("gh")
+        .args([
+            "api",
+            "--paginate",
+            &format!("repos/{}/pulls/{}/comments?per_page=100", repo, number),
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !inline.status.success() {
+        return Err(format!(
+            "gh api pulls comments failed: {}",
+            String::from_utf8_lossy(&inline.stderr)
+        ));
+    }
+
+    parse_pr_review_comments(&reviews.stdout, &inline.stdout)
+}
+
+/// Check out a PR's branch in `worktree_path` under the local name `branch`.
+///
+/// Uses `gh pr checkout`, which also handles PRs from forks.
+pub fn checkout_pr(
+    worktree_path: &str,
+    repo: &str,
+    number: u64,
+    branch: &str,
+) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "checkout",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--branch",
+            branch,
+        ])
+        .current_dir(worktree_path)
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr checkout failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Get full PR status including checks and reviews.
+pub fn get_pr_status(repo: &str, number: u64) -> Result<PrStatus, String> {
+    let pr = get_pr(repo, number)?;
+    let checks = get_pr_checks(repo, number)?;
+    let (reviews, review_summary) = get_pr_reviews(repo, number)?;
+
+    Ok(PrStatus {
+        pr,
+        checks,
+        reviews,
+        review_summary,
+    })
+}
+
+/// Matches closing keywords in a PR body (e.g., "Closes #42", "Fixes org/repo#42").
+static CLOSING_ISSUE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?):?\s+([\w.-]+/[\w.-]+)?#(\d+)\b")
+        .unwrap()
+});
+
+/// Whether a PR body contains a closing keyword for the given issue
+pub fn body_closes_issue(body: &str, issue_number: u32) -> bool {
+    CLOSING_ISSUE_PATTERN
+        .captures_iter(body)
+        .filter_map(|caps| caps.get(2)?.as_str().parse::<u32>().ok())
+        .any(|n| n == issue_number)
+}
+
+/// Find the issue a PR is linked to, from closing keywords in its body
+/// or an `issue-N` branch name.
+pub fn linked_issue_number(pr: &GitHubPullRequest) -> Option<u64> {
+    pr.body
+        .as_deref()
+        .and_then(|body| {
+            // Only same-repo references; "Closes org/other#N" belongs elsewhere
+            CLOSING_ISSUE_PATTERN
+                .captures_iter(body)
+                .find(|caps| caps.get(1).is_none())
+        })
+        .and_then(|caps| caps.get(2))
+        .and_then(|m| m.as_str().parse().ok())
+        .or_else(|| {
+            pr.head_branch
+                .split_once("issue-")
+                .map(|(_, rest)| {
+                    let end = rest
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(rest.len());
+                    &rest[..end]
+                })
+                .and_then(|n| n.parse().ok())
+        })
+}
+
+/// Merge a pull request.
+///
+/// `commit_title` and `commit_body` set the merge commit message. For squash
+/// merges where neither is given, the PR title is used as the subject with a
+/// `Closes #N` trailer for the linked issue, instead of gh's commit list.
+pub fn merge_pr(
+    repo: &str,
+    number: u64,
+    method: Option<&str>,
+    delete_branch: bool,
+    commit_title: Option<&str>,
+    commit_body: Option<&str>,
+) -> Result<(), String> {
+    let number_str = number.to_string();
+    let mut args = vec!["pr", "merge", &number_str, "--repo", repo];
+
+    match method {
+        Some("squash") => args.push("--squash"),
+        Some("rebase") => args.push("--rebase"),
+        _ => args.push("--merge"),
+    }
+
+    if delete_branch {
+        args.push("--delete-branch");
+    }
+
+    let (subject, body) =
+        if method == Some("squash") && commit_title.is_none() && commit_body.is_none() {
+            let pr = get_pr(repo, number)?;
+            let body = linked_issue_number(&pr)
+                .map(|issue| format!("Closes #{}", issue))
+                .unwrap_or_default();
+            (Some(pr.title), Some(body))
+        } else {
+            (
+                commit_title.map(|s| s.to_string()),
+                commit_body.map(|s| s.to_string()),
+            )
+        };
+
+    if let Some(ref subject) = subject {
+        args.push("--subject");
+        args.push(subject);
+    }
+    if let Some(ref body) = body {
+        args.push("--body");
+        args.push(body);
+    }
+
+    let output = Command::new("gh")
+        .args(&args)
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr merge failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Mark a draft pull request as ready for review.
+pub fn mark_pr_ready(repo: &str, number: u64) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args(["pr", "ready", &number.to_string(), "--repo", repo])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr ready failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Close a pull request without merging.
+pub fn close_pr(repo: &str, number: u64, comment: Option<&str>) -> Result<(), String> {
+    if let Some(c) = comment {
+        // Add comment first
+        let comment_output = Command::new("gh")
+            .args([
+                "pr",
+                "comment",
+                &number.to_string(),
+                "--repo",
+                repo,
+                "--body",
+                c,
+            ])
+            .output_with_timeout()
+            .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+        if !comment_output.status.success() {
+            return Err(format!(
+                "gh pr comment failed: {}",
+                String::from_utf8_lossy(&comment_output.stderr)
+            ));
+        }
+    }
+
+    let output = Command::new("gh")
+        .args(["pr", "close", &number.to_string(), "--repo", repo])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr close failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// GitHub Projects (v2)
+// ============================================================================
+
+/// Project board status keys used by pipeline transitions.
+pub const PROJECT_STATUS_TODO: &str = "todo";
+pub const PROJECT_STATUS_IN_PROGRESS: &str = "in_progress";
+pub const PROJECT_STATUS_DONE: &str = "done";
+
+/// Configuration for driving a GitHub Projects (v2) status field.
+///
+/// The board is addressed either by node IDs (`project_id`, `status_field_id`
+/// and `status_options`) or, when `project_number` is set, by the names shown
+/// in the GitHub UI, which are looked up through `gh project`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ProjectStatusConfig {
+    /// Project node ID (e.g., "PVT_kwDO..."); unused with `project_number`
+    #[serde(default)]
+    pub project_id: String,
+    /// Status field node ID (e.g., "PVTSSF_lADO..."); unused with `project_number`
+    #[serde(default)]
+    pub status_field_id: String,
+    /// Single-select option IDs keyed by status ("todo", "in_progress", "done")
+    #[serde(default)]
+    pub status_options: std::collections::HashMap<String, String>,
+    /// Project number (as shown in the board URL), owned by the repo owner
+    #[serde(default)]
+    pub project_number: Option<u32>,
+    /// Name of the single-select field used for board columns (with `project_number`)
+    #[serde(default = "default_project_status_field")]
+    pub status_field: String,
+    /// Column name overrides keyed by status (with `project_number`)
+    #[serde(default)]
+    pub columns: std::collections::HashMap<String, String>,
+}
+
+impl ProjectStatusConfig {
+    /// Board column for a status key, falling back to GitHub's default columns
+    pub fn column_for(&self, status: &str) -> Option<String> {
+        if let Some(column) = self.columns.get(status) {
+            return Some(column.clone());
+        }
+        match status {
+            PROJECT_STATUS_TODO => Some("Todo".to_string()),
+            PROJECT_STATUS_IN_PROGRESS => Some("In Progress".to_string()),
+            PROJECT_STATUS_DONE => Some("Done".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Whether a status key maps to a column or option on the board
+    pub fn maps_status(&self, status: &str) -> bool {
+        match self.project_number {
+            Some(_) => self.column_for(status).is_some(),
+            None => self.status_options.contains_key(status),
+        }
+    }
+}
+
+/// Add an issue to a project (or find its existing card) and return the item ID.
+pub fn ensure_project_item(project_id: &str, repo: &str, number: u64) -> Result<String, String> {
+    // Resolve the issue's node ID
+    let output = Command::new("gh")
+        .args([
+            "issue",
+            "view",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--json",
+            "id",
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh issue view failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    #[derive(Deserialize)]
+    struct GhNode {
+        id: String,
+    }
+
+    let issue: GhNode = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse issue JSON: {}", e))?;
+
+    // addProjectV2ItemById returns the existing item if the issue is already on the board
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "graphql",
+            "-f",
+            "query=mutation($project: ID!, $content: ID!) { addProjectV2ItemById(input: {projectId: $project, contentId: $content}) { item { id } } }",
+            "-f",
+            &format!("project={}", project_id),
+            "-f",
+            &format!("content={}", issue.id),
+            "--jq",
+            ".data.addProjectV2ItemById.item.id",
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh api graphql failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let item_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if item_id.is_empty() {
+        return Err(format!("No project item returned for {}#{}", repo, number));
+    }
+
+    Ok(item_id)
+}
+
+/// Set the status field of a project item.
+///
+/// `status` is a key into `project.status_options` (e.g., "in_progress").
+pub fn set_project_item_status(
+    project: &ProjectStatusConfig,
+    item_id: &str,
+    status: &str,
+) -> Result<(), String> {
+    let option_id = project
+        .status_options
+        .get(status)
+        .ok_or_else(|| format!("No project option configured for status '{}'", status))?;
+
+    let output = Command::new("gh")
+        .args([
+            "project",
+            "item-edit",
+            "--id",
+            item_id,
+            "--project-id",
+            &project.project_id,
+            "--field-id",
+            &project.status_field_id,
+            "--single-select-option-id",
+            option_id,
+        ])
+        .output_with_timeout()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh project item-edit failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Move an issue's project card to the given status, adding it to the board if needed.
+pub fn set_issue_project_status(
+    project: &ProjectStatusConfig,
+    repo: &str,
+    number: u64,
+    status: &str,
+) -> Result<(), String> {
+    match project.project_number {
+        Some(project_number) => {
+            let column = project
+                .column_for(status)
+                .ok_or_else(|| format!("No project column configured for status '{}'", status))?;
+            set_project_field(repo, project_number, number, &project.status_field, &column)
+        }
+        None => {
+            let item_id = ensure_project_item(&project.project_id, repo, number)?;
+            set_project_item_status(project, &item_id, status)
+        }
     }
+}
 
-    #[derive(Deserialize)]
-    struct GhReview {
-        state: String,
+/// Move an issue's project card to the given status.
+///
+/// No-op when no project is configured or the status has no mapped column,
+/// so label-only workflows are unaffected. Failures are logged, not returned.
+pub fn sync_issue_project_status(
+    project: Option<&ProjectStatusConfig>,
+    repo: &str,
+    number: u64,
+    status: &str,
+) {
+    let Some(project) = project else {
+        return;
+    };
+    if !project.maps_status(status) {
+        return;
     }
 
-    let reviews: GhReviews =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse gh output: {}", e))?;
-
-    let mut approved = 0u32;
-    let mut changes_requested = 0u32;
-    let mut pending = 0u32;
-
-    for review in &reviews.reviews {
-        match review.state.as_str() {
-            "APPROVED" => approved += 1,
-            "CHANGES_REQUESTED" => changes_requested += 1,
-            "PENDING" => pending += 1,
-            _ => {}
-        }
+    match set_issue_project_status(project, repo, number, status) {
+        Ok(()) => log::info!("Moved {}#{} to project status '{}'", repo, number, status),
+        Err(e) => log::warn!(
+            "Failed to update project status for {}#{}: {}",
+            repo,
+            number,
+            e
+        ),
     }
+}
 
-    Ok(PrReviewStatus {
-        approved,
-        changes_requested,
-        pending,
-    })
+/// Default name of the Projects (v2) field holding the board column
+fn default_project_status_field() -> String {
+    "Status".to_string()
 }
 
-/// Get full PR status including checks and reviews.
-pub fn get_pr_status(repo: &str, number: u64) -> Result<PrStatus, String> {
-    let pr = get_pr(repo, number)?;
-    let checks = get_pr_checks(repo, number)?;
-    let reviews = get_pr_reviews(repo, number)?;
+/// A field of a Projects (v2) board, as returned by `gh project field-list`
+#[derive(Debug, Clone, Deserialize)]
+struct GhProjectField {
+    id: String,
+    name: String,
+    #[serde(default)]
+    options: Vec<GhProjectFieldOption>,
+}
 
-    Ok(PrStatus {
-        pr,
-        checks,
-        reviews,
-    })
+/// A single-select option of a project field
+#[derive(Debug, Clone, Deserialize)]
+struct GhProjectFieldOption {
+    id: String,
+    name: String,
 }
 
-/// Merge a pull request.
-pub fn merge_pr(
-    repo: &str,
-    number: u64,
-    method: Option<&str>,
-    delete_branch: bool,
-) -> Result<(), String> {
-    let number_str = number.to_string();
-    let mut args = vec!["pr", "merge", &number_str, "--repo", repo];
+/// Value to write to a project field
+#[derive(Debug, Clone, PartialEq)]
+enum ProjectFieldValue {
+    /// Single-select option ID
+    Option(String),
+    /// Free text
+    Text(String),
+}
 
-    match method {
-        Some("squash") => args.push("--squash"),
-        Some("rebase") => args.push("--rebase"),
-        _ => args.push("--merge"),
-    }
+/// Owner of a repo ("owner/name"), used to address its projects
+fn repo_owner(repo: &str) -> Result<&str, String> {
+    repo.split_once('/')
+        .map(|(owner, _)| owner)
+        .filter(|owner| !owner.is_empty())
+        .ok_or_else(|| format!("Invalid repo '{}', expected owner/name", repo))
+}
 
-    if delete_branch {
-        args.push("--delete-branch");
+/// Resolve a field name and value to the field ID and the value to set.
+///
+/// Names match case-insensitively. Single-select fields require the value to
+/// be one of their options; other fields are set as text.
+fn resolve_project_field(
+    fields: &[GhProjectField],
+    field: &str,
+    value: &str,
+) -> Result<(String, ProjectFieldValue), String> {
+    let field = fields
+        .iter()
+        .find(|f| f.name.eq_ignore_ascii_case(field))
+        .ok_or_else(|| format!("Project has no field named '{}'", field))?;
+
+    if field.options.is_empty() {
+        return Ok((field.id.clone(), ProjectFieldValue::Text(value.to_string())));
     }
 
+    let option = field
+        .options
+        .iter()
+        .find(|o| o.name.eq_ignore_ascii_case(value))
+        .ok_or_else(|| {
+            let names: Vec<&str> = field.options.iter().map(|o| o.name.as_str()).collect();
+            format!(
+                "Field '{}' has no option '{}' (options: {})",
+                field.name,
+                value,
+                names.join(", ")
+            )
+        })?;
+
+    Ok((
+        field.id.clone(),
+        ProjectFieldValue::Option(option.id.clone()),
+    ))
+}
+
+/// Run a `gh project` subcommand and return stdout
+fn run_gh_project(args: &[&str]) -> Result<Vec<u8>, String> {
     let output = Command::new("gh")
-        .args(&args)
-        .output()
+        .arg("project")
+        .args(args)
+        .output_with_timeout()
         .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
     if !output.status.success() {
         return Err(format!(
-            "gh pr merge failed: {}",
+            "gh project {} failed: {}",
+            args.first().unwrap_or(&""),
             String::from_utf8_lossy(&output.stderr)
         ));
     }
 
-    Ok(())
+    Ok(output.stdout)
 }
 
-/// Close a pull request without merging.
-pub fn close_pr(repo: &str, number: u64, comment: Option<&str>) -> Result<(), String> {
-    if let Some(c) = comment {
-        // Add comment first
-        let comment_output = Command::new("gh")
-            .args([
-                "pr",
-                "comment",
-                &number.to_string(),
-                "--repo",
-                repo,
-                "--body",
-                c,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to execute gh: {}", e))?;
+/// Add an issue to a Projects (v2) board owned by the repo owner.
+///
+/// Returns the project item ID. Adding an issue that is already on the board
+/// returns its existing item.
+pub fn add_issue_to_project(
+    repo: &str,
+    issue_number: u64,
+    project_number: u32,
+) -> Result<String, String> {
+    let owner = repo_owner(repo)?;
+    let url = format!("https://github.com/{}/issues/{}", repo, issue_number);
+    let stdout = run_gh_project(&[
+        "item-add",
+        &project_number.to_string(),
+        "--owner",
+        owner,
+        "--url",
+        &url,
+        "--format",
+        "json",
+    ])?;
 
-        if !comment_output.status.success() {
-            return Err(format!(
-                "gh pr comment failed: {}",
-                String::from_utf8_lossy(&comment_output.stderr)
-            ));
-        }
+    #[derive(Deserialize)]
+    struct GhItem {
+        id: String,
     }
 
-    let output = Command::new("gh")
-        .args(["pr", "close", &number.to_string(), "--repo", repo])
-        .output()
-        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+    let item: GhItem = serde_json::from_slice(&stdout)
+        .map_err(|e| format!("Failed to parse project item JSON: {}", e))?;
+    Ok(item.id)
+}
 
-    if !output.status.success() {
-        return Err(format!(
-            "gh pr close failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+/// Set a field on an issue's card in a Projects (v2) board.
+///
+/// Adds the issue to the board first if needed. `field` and `value` are the
+/// names shown in the GitHub UI (e.g. "Status" / "In Progress").
+pub fn set_project_field(
+    repo: &str,
+    project_number: u32,
+    issue_number: u64,
+    field: &str,
+    value: &str,
+) -> Result<(), String> {
+    let owner = repo_owner(repo)?;
+    let number = project_number.to_string();
+    let item_id = add_issue_to_project(repo, issue_number, project_number)?;
+
+    #[derive(Deserialize)]
+    struct GhProject {
+        id: String,
+    }
+
+    #[derive(Deserialize)]
+    struct GhFieldList {
+        fields: Vec<GhProjectField>,
     }
 
+    let stdout = run_gh_project(&["view", &number, "--owner", owner, "--format", "json"])?;
+    let project: GhProject = serde_json::from_slice(&stdout)
+        .map_err(|e| format!("Failed to parse project JSON: {}", e))?;
+
+    let stdout = run_gh_project(&["field-list", &number, "--owner", owner, "--format", "json"])?;
+    let field_list: GhFieldList = serde_json::from_slice(&stdout)
+        .map_err(|e| format!("Failed to parse project fields JSON: {}", e))?;
+
+    let (field_id, field_value) = resolve_project_field(&field_list.fields, field, value)?;
+    let (value_flag, value_arg) = match &field_value {
+        ProjectFieldValue::Option(id) => ("--single-select-option-id", id.as_str()),
+        ProjectFieldValue::Text(text) => ("--text", text.as_str()),
+    };
+
+    run_gh_project(&[
+        "item-edit",
+        "--id",
+        &item_id,
+        "--project-id",
+        &project.id,
+        "--field-id",
+        &field_id,
+        value_flag,
+        value_arg,
+    ])?;
+
     Ok(())
 }
 
@@ -1198,6 +2785,22 @@ pub async fn add_labels_async(
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Async wrapper for add_labels_batch
+pub async fn add_labels_batch_async(
+    repo: &str,
+    issue_numbers: &[u32],
+    labels: &[String],
+) -> Result<BatchLabelResult, String> {
+    tokio::task::spawn_blocking({
+        let repo = repo.to_string();
+        let issue_numbers: Vec<u64> = issue_numbers.iter().map(|&n| n as u64).collect();
+        let labels = labels.to_vec();
+        move || add_labels_batch(&repo, &issue_numbers, &labels)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Async wrapper for add_comment
 pub async fn add_issue_comment_async(
     repo: &str,
@@ -1213,6 +2816,30 @@ pub async fn add_issue_comment_async(
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Async wrapper for list_comments
+pub async fn list_comments_async(
+    repo: &str,
+    issue_number: u32,
+) -> Result<Vec<GitHubComment>, String> {
+    tokio::task::spawn_blocking({
+        let repo = repo.to_string();
+        move || list_comments(&repo, issue_number as u64)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Async wrapper for edit_comment
+pub async fn edit_comment_async(repo: &str, comment_id: u64, body: &str) -> Result<(), String> {
+    tokio::task::spawn_blocking({
+        let repo = repo.to_string();
+        let body = body.to_string();
+        move || edit_comment(&repo, comment_id, &body)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Async wrapper for get_issue
 pub async fn get_issue_async(repo: &str, issue_number: u32) -> Result<GitHubIssue, String> {
     tokio::task::spawn_blocking({
@@ -1241,6 +2868,7 @@ pub async fn list_issues_async(
                     Some(label_strs)
                 },
                 None,
+                IssueFields::All,
             )
         }
     })
@@ -1253,6 +2881,7 @@ pub async fn list_issues_async(
 pub async fn list_all_issues_async(
     repo: &str,
     labels: Vec<String>,
+    fields: IssueFields,
 ) -> Result<Vec<GitHubIssue>, String> {
     tokio::task::spawn_blocking({
         let repo = repo.to_string();
@@ -1267,6 +2896,7 @@ pub async fn list_all_issues_async(
                     Some(label_strs)
                 },
                 None,
+                fields,
             )
         }
     })
@@ -1310,7 +2940,7 @@ pub async fn update_issue_body_async(
                     "--body",
                     &body,
                 ])
-                .output()
+                .output_with_timeout()
                 .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
             if !output.status.success() {
@@ -1320,6 +2950,7 @@ pub async fn update_issue_body_async(
                 ));
             }
 
+            invalidate_rendered_issue(&repo, issue_number as u64);
             Ok(())
         }
     })
@@ -1379,7 +3010,7 @@ pub async fn add_pr_labels_async(
                         "--add-label",
                         label,
                     ])
-                    .output()
+                    .output_with_timeout()
                     .map_err(|e| format!("Failed to execute gh: {}", e))?;
 
                 if !output.status.success() {
@@ -1412,7 +3043,7 @@ pub async fn find_prs_for_issue_async(
             let issue_ref = format!("#{}", issue_number);
 
             // Check open PRs
-            if let Ok(open_prs) = list_prs(&repo, Some("open"), None, Some(100)) {
+            if let Ok(open_prs) = list_prs(&repo, Some("open"), None, Some(100), PrFields::All) {
                 for pr in open_prs {
                     if let Some(body) = &pr.body {
                         if body.contains(&issue_ref) {
@@ -1423,7 +3054,7 @@ pub async fn find_prs_for_issue_async(
             }
 
             // Check merged PRs (in case we're looking at historical data)
-            if let Ok(merged_prs) = list_prs(&repo, Some("merged"), None, Some(50)) {
+            if let Ok(merged_prs) = list_prs(&repo, Some("merged"), None, Some(50), PrFields::All) {
                 for pr in merged_prs {
                     if let Some(body) = &pr.body {
                         if body.contains(&issue_ref)
@@ -1455,7 +3086,7 @@ pub async fn find_pr_by_branch_async(
         let branch_name = branch_name.to_string();
         move || {
             // First check open PRs
-            let open_prs = list_prs(&repo, Some("open"), None, Some(100))?;
+            let open_prs = list_prs(&repo, Some("open"), None, Some(100), PrFields::All)?;
             if let Some(pr) = open_prs
                 .into_iter()
                 .find(|pr| pr.head_branch == branch_name)
@@ -1464,7 +3095,7 @@ pub async fn find_pr_by_branch_async(
             }
 
             // Then check merged PRs
-            let merged_prs = list_prs(&repo, Some("merged"), None, Some(50))?;
+            let merged_prs = list_prs(&repo, Some("merged"), None, Some(50), PrFields::All)?;
             if let Some(pr) = merged_prs
                 .into_iter()
                 .find(|pr| pr.head_branch == branch_name)
@@ -1473,7 +3104,7 @@ pub async fn find_pr_by_branch_async(
             }
 
             // Finally check closed (but not merged) PRs
-            let closed_prs = list_prs(&repo, Some("closed"), None, Some(50))?;
+            let closed_prs = list_prs(&repo, Some("closed"), None, Some(50), PrFields::All)?;
             if let Some(pr) = closed_prs
                 .into_iter()
                 .find(|pr| pr.head_branch == branch_name)
@@ -1488,10 +3119,134 @@ pub async fn find_pr_by_branch_async(
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Head information for a PR, used to match PRs to agent worktrees
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PrHead {
+    /// PR number
+    pub number: u64,
+    /// PR title
+    pub title: String,
+    /// PR body/description
+    pub body: Option<String>,
+    /// PR state (OPEN, CLOSED, MERGED)
+    pub state: String,
+    /// PR URL
+    pub url: String,
+    /// Source branch
+    pub head_branch: String,
+    /// SHA of the head commit
+    pub head_sha: String,
+}
+
+/// List the most recent PRs in any state with their head commit SHAs.
+pub fn list_recent_pr_heads(repo: &str, limit: u32) -> Result<Vec<PrHead>, String> {
+    let limit_str = limit.to_string();
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "list",
+            "--repo",
+            repo,
+            "--state",
+            "all",
+            "--limit",
+            &limit_str,
+            "--json",
+            "number,title,body,state,url,headRefName,headRefOid",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh pr list failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct GhPrHead {
+        number: u64,
+        #[serde(default)]
+        title: String,
+        #[serde(default)]
+        body: Option<String>,
+        #[serde(default)]
+        state: String,
+        #[serde(default)]
+        url: String,
+        #[serde(default)]
+        head_ref_name: String,
+        #[serde(default)]
+        head_ref_oid: String,
+    }
+
+    let heads: Vec<GhPrHead> = parse_gh_json(&output.stdout, "gh pr list")?;
+
+    Ok(heads
+        .into_iter()
+        .map(|p| PrHead {
+            number: p.number,
+            title: p.title,
+            body: p.body,
+            state: p.state,
+            url: p.url,
+            head_branch: p.head_ref_name,
+            head_sha: p.head_ref_oid,
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_checklist_section() {
+        let body = "## Tasks\n- [ ] not criteria\n\n## Acceptance Criteria\n- [x] Works\n- [ ] Tests passing\n* [X] Docs\nNot an item\n\n## Agent Assignment\n- [ ] other";
+        let items = parse_checklist_section(body, ACCEPTANCE_CRITERIA_HEADING);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].text, "Works");
+        assert!(items[0].checked);
+        assert!(!items[1].checked);
+        assert!(items[2].checked);
+        assert!(!acceptance_criteria_complete(body));
+
+        let done = body.replace("- [ ] Tests passing", "- [x] Tests passing");
+        assert!(acceptance_criteria_complete(&done));
+        assert!(!acceptance_criteria_complete("No checklist here"));
+    }
+
+    #[test]
+    fn test_parse_pr_checks() {
+        let output = r#"[{"bucket":"pass","name":"build","state":"SUCCESS"},{"bucket":"skipping","name":"deploy","state":"SKIPPED"},{"bucket":"pending","name":"test","state":"IN_PROGRESS"}]"#;
+        let status = parse_pr_checks(output);
+        assert_eq!(status.state, "pending");
+        assert_eq!(
+            (status.passing, status.failing, status.pending, status.total),
+            (1, 0, 1, 3)
+        );
+
+        let output = r#"[{"bucket":"pass","name":"build","state":"SUCCESS"},{"bucket":"cancel","name":"e2e","state":"CANCELLED"}]"#;
+        assert_eq!(parse_pr_checks(output).state, "failure");
+        let output = r#"[{"bucket":"pass","name":"build","state":"SUCCESS"},{"bucket":"skipping","name":"deploy","state":"SKIPPED"}]"#;
+        assert_eq!(parse_pr_checks(output).state, "success");
+        assert_eq!(parse_pr_checks("").state, "unknown");
+    }
+
+    #[test]
+    fn test_body_closes_issue() {
+        assert!(body_closes_issue("Closes #42", 42));
+        assert!(body_closes_issue(
+            "Refactor. Closes #7, fixes org/issues#42.",
+            42
+        ));
+        assert!(body_closes_issue("Resolved: #42", 42));
+        assert!(!body_closes_issue("Closes #420", 42));
+        assert!(!body_closes_issue("Related to #42", 42));
+    }
+
     #[test]
     fn test_extract_metadata() {
         let comment = r#"<!-- HANDY_AGENT_METADATA
@@ -1512,4 +3267,412 @@ mod tests {
         let metadata = extract_metadata_from_comment(comment);
         assert!(metadata.is_none());
     }
+
+    #[test]
+    fn test_find_unanswered_question() {
+        let comment = |author: &str, body: &str| GitHubComment {
+            id: 0,
+            body: body.to_string(),
+            author: author.to_string(),
+            created_at: String::new(),
+        };
+        let mut comments = vec![
+            comment("me", "<!-- HANDY_AGENT_METADATA {} -->"),
+            comment("me", "Any update?"),
+            comment("me", "Should the cache be per user?"),
+        ];
+        // A trailing `?` alone is not an agent question
+        assert!(find_unanswered_question(&comments).is_none());
+
+        comments.push(comment("me", "Which DB? <!-- HANDY_QUESTION -->"));
+        assert_eq!(
+            find_unanswered_question(&comments).map(|c| c.body.as_str()),
+            Some("Which DB? <!-- HANDY_QUESTION -->")
+        );
+
+        // A reply from the same login still answers it
+        comments.push(comment("me", "Postgres."));
+        assert!(find_unanswered_question(&comments).is_none());
+    }
+
+    #[test]
+    fn test_gh_json_tolerates_missing_fields() {
+        let issue: GhIssue = serde_json::from_str(
+            r#"{"number": 5, "title": "Bug", "labels": [{"name": "bug"}],
+                "milestone": {"title": "v1.0"}, "author": {"login": "me"}}"#,
+        )
+        .unwrap();
+        let issue = issue.into_issue("org/repo");
+        assert_eq!(issue.labels, vec!["bug"]);
+        assert_eq!(issue.milestone.as_deref(), Some("v1.0"));
+        assert!(issue.assignees.is_empty());
+        assert_eq!(issue.author, "me");
+
+        let pr: GhPr =
+            serde_json::from_str(r#"{"number": 9, "mergeable": "UNKNOWN", "author": null}"#)
+                .unwrap();
+        let pr = pr.into_pr("org/repo");
+        assert_eq!(pr.mergeable, None);
+        assert!(pr.author.is_empty());
+    }
+
+    #[test]
+    fn test_code_block() {
+        assert_eq!(code_block("ok"), "```\nok\n```");
+        assert_eq!(
+            code_block("```\ninjected\n```"),
+            "````\n```\ninjected\n```\n````"
+        );
+    }
+
+    #[test]
+    fn test_field_sets() {
+        // Every field set is a subset of the full one, so the parsers handle it
+        let subset =
+            |fields: &str, all: &str| fields.split(',').all(|f| all.split(',').any(|a| a == f));
+        assert!(subset(
+            IssueFields::Progress.json_fields(),
+            ISSUE_JSON_FIELDS
+        ));
+        assert!(subset(PrFields::Head.json_fields(), PR_JSON_FIELDS));
+
+        let issues: Vec<GhIssue> = parse_gh_json(
+            br#"[{"number": 3, "state": "CLOSED", "body": "**Epic**: #1"}]"#,
+            "gh issue list",
+        )
+        .unwrap();
+        let issue = issues.into_iter().next().unwrap().into_issue("org/repo");
+        assert_eq!(issue.state, "CLOSED");
+        assert!(issue.title.is_empty());
+    }
+
+    #[test]
+    fn test_created_url_and_comment_id_parsing() {
+        assert_eq!(
+            number_from_created_url(
+                "Warning: 1 uncommitted change\nhttps://github.com/org/repo/pull/12\n",
+                "PR"
+            ),
+            Ok(12)
+        );
+        assert!(number_from_created_url("something went wrong", "issue").is_err());
+        assert_eq!(
+            comment_id_from_url("https://github.com/org/repo/issues/1#issuecomment-987"),
+            Some(987)
+        );
+        assert_eq!(comment_id_from_url("IC_kwDOA"), None);
+    }
+
+    #[test]
+    fn test_batch_label_graphql() {
+        let query = label_lookup_query(
+            "org",
+            "repo",
+            &[5, 6],
+            &["todo".to_string(), "a\"b".to_string()],
+        );
+        assert_eq!(
+            query,
+            r#"query { repository(owner: "org", name: "repo") { l0: label(name: "todo") { id } l1: label(name: "a\"b") { id } i0: issue(number: 5) { id } i1: issue(number: 6) { id } } }"#
+        );
+
+        let mutation = add_labels_mutation(
+            &["I_1".to_string()],
+            &["L_1".to_string(), "L_2".to_string()],
+        );
+        assert_eq!(
+            mutation,
+            r#"mutation { m0: addLabelsToLabelable(input: {labelableId: "I_1", labelIds: ["L_1", "L_2"]}) { clientMutationId } }"#
+        );
+
+        let data = serde_json::json!({"l0": {"id": "L_1"}, "l1": null, "i0": {"id": "I_1"}});
+        assert_eq!(aliased_node_id(&data, "l", 0), Some("L_1".to_string()));
+        assert_eq!(aliased_node_id(&data, "l", 1), None);
+        assert_eq!(aliased_node_id(&data, "i", 1), None);
+
+        let empty = add_labels_batch("org/repo", &[1, 2], &[]).unwrap();
+        assert_eq!(empty.succeeded, vec![1, 2]);
+        assert!(!empty.fell_back);
+        assert!(add_labels_batch("repo", &[1], &["todo".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_find_metadata_comment_to_update() {
+        let comment = |id: u64, body: &str| GitHubComment {
+            id,
+            body: body.to_string(),
+            author: "bot".to_string(),
+            created_at: String::new(),
+        };
+        // Lifecycle-posted metadata has no `status`, so only the session is read
+        let lifecycle = |id: u64, session: &str| {
+            comment(
+                id,
+                &format!(
+                    "{}\n{{\"session\": \"{}\", \"agent_type\": \"claude\"}}\n-->\n🤖",
+                    METADATA_START, session
+                ),
+            )
+        };
+
+        let comments = vec![
+            lifecycle(10, "handy-agent-5"),
+            comment(11, "Looks good"),
+            lifecycle(12, "handy-agent-5-retry"),
+        ];
+        assert_eq!(
+            find_metadata_comment_to_update(&comments, "handy-agent-5"),
+            Some(10)
+        );
+        // A renamed session falls back to the latest metadata comment
+        assert_eq!(
+            find_metadata_comment_to_update(&comments, "handy-agent-5-renamed"),
+            Some(12)
+        );
+        assert_eq!(
+            find_metadata_comment_to_update(&comments[1..2], "handy-agent-5"),
+            None
+        );
+        // Comments whose numeric ID is unknown cannot be edited
+        assert_eq!(
+            find_metadata_comment_to_update(&[lifecycle(0, "handy-agent-5")], "handy-agent-5"),
+            None
+        );
+    }
+
+    fn pr_with(body: Option<&str>, head_branch: &str) -> GitHubPullRequest {
+        GitHubPullRequest {
+            number: 7,
+            title: "Add feature".to_string(),
+            body: body.map(|s| s.to_string()),
+            state: "open".to_string(),
+            url: String::new(),
+            head_branch: head_branch.to_string(),
+            base_branch: "main".to_string(),
+            is_draft: false,
+            mergeable: None,
+            labels: vec![],
+            assignees: vec![],
+            milestone: None,
+            author: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            repo: "org/repo".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_linked_issue_number() {
+        assert_eq!(
+            linked_issue_number(&pr_with(Some("Work done.\n\nCloses #42"), "feature")),
+            Some(42)
+        );
+        assert_eq!(
+            linked_issue_number(&pr_with(Some("fixes #9"), "handy-issue-3")),
+            Some(9)
+        );
+        assert_eq!(
+            linked_issue_number(&pr_with(None, "handy-issue-3")),
+            Some(3)
+        );
+        assert_eq!(
+            linked_issue_number(&pr_with(None, "issue-42-fix-login")),
+            Some(42)
+        );
+        assert_eq!(linked_issue_number(&pr_with(None, "feature")), None);
+    }
+
+    #[test]
+    fn test_parse_actions_run_link() {
+        assert_eq!(
+            parse_actions_run_link("https://github.com/org/repo/actions/runs/123/job/456"),
+            Some(("123".to_string(), Some("456".to_string())))
+        );
+        assert_eq!(
+            parse_actions_run_link("https://github.com/org/repo/actions/runs/789"),
+            Some(("789".to_string(), None))
+        );
+        assert_eq!(
+            parse_actions_run_link("https://ci.example.com/build/42"),
+            None
+        );
+    }
+    #[test]
+    fn test_set_project_item_status_requires_mapped_option() {
+        let project = ProjectStatusConfig {
+            project_id: "PVT_1".to_string(),
+            status_field_id: "PVTSSF_1".to_string(),
+            status_options: std::collections::HashMap::new(),
+            project_number: None,
+            status_field: default_project_status_field(),
+            columns: std::collections::HashMap::new(),
+        };
+        let err = set_project_item_status(&project, "PVTI_1", PROJECT_STATUS_DONE).unwrap_err();
+        assert!(err.contains("done"));
+
+        // Unconfigured project is a silent no-op
+        sync_issue_project_status(None, "org/repo", 1, PROJECT_STATUS_DONE);
+    }
+
+    #[test]
+    fn test_resolve_project_field() {
+        let fields: Vec<GhProjectField> = serde_json::from_str(
+            r#"[
+                {"id": "F_title", "name": "Title", "type": "ProjectV2Field"},
+                {"id": "F_status", "name": "Status", "type": "ProjectV2SingleSelectField",
+                 "options": [{"id": "o1", "name": "Todo"}, {"id": "o2", "name": "In Progress"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolve_project_field(&fields, "status", "in progress").unwrap(),
+            (
+                "F_status".to_string(),
+                ProjectFieldValue::Option("o2".to_string())
+            )
+        );
+        assert_eq!(
+            resolve_project_field(&fields, "Title", "x").unwrap().1,
+            ProjectFieldValue::Text("x".to_string())
+        );
+        assert!(resolve_project_field(&fields, "Status", "Blocked")
+            .unwrap_err()
+            .contains("Todo, In Progress"));
+        assert!(resolve_project_field(&fields, "Priority", "High").is_err());
+
+        let project: ProjectStatusConfig =
+            serde_json::from_str(r#"{"project_number": 3, "columns": {"done": "Shipped"}}"#)
+                .unwrap();
+        assert_eq!(project.status_field, "Status");
+        assert_eq!(
+            project.column_for(PROJECT_STATUS_TODO).as_deref(),
+            Some("Todo")
+        );
+        assert_eq!(
+            project.column_for(PROJECT_STATUS_DONE).as_deref(),
+            Some("Shipped")
+        );
+        assert!(project.maps_status(PROJECT_STATUS_IN_PROGRESS));
+        assert!(!project.maps_status("blocked"));
+    }
+
+    #[test]
+    fn test_parse_rendered_issue() {
+        let stdout = br##"{"number": 42, "title": "Fix login", "body": "- [x] done\nsee #7",
+            "body_html": "<ul class=\"contains-task-list\"><li class=\"task-list-item\"><input type=\"checkbox\" checked disabled> done</li></ul><p>see <a href=\"https://github.com/org/repo/issues/7\">#7</a></p>",
+            "updated_at": "2026-01-02T03:04:05Z", "html_url": "https://github.com/org/repo/issues/42"}"##;
+        let issue = parse_rendered_issue(stdout).unwrap();
+        assert_eq!(issue.number, 42);
+        assert!(issue.body_html.contains("type=\"checkbox\" checked"));
+        assert!(issue.body_html.contains("issues/7\">#7</a>"));
+        assert_eq!(issue.url, "https://github.com/org/repo/issues/42");
+
+        // Issues without a body have no body_html
+        let issue = parse_rendered_issue(
+            br#"{"number": 1, "title": "t", "updated_at": "", "html_url": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(issue.body_html, "");
+    }
+
+    #[test]
+    fn test_label_edit_args() {
+        let args = label_edit_args("o/r", 7, &["agent-wip", "staging"], &["agent-todo"]);
+        assert_eq!(
+            args,
+            vec![
+                "issue",
+                "edit",
+                "7",
+                "--repo",
+                "o/r",
+                "--add-label",
+                "agent-wip",
+                "--add-label",
+                "staging",
+                "--remove-label",
+                "agent-todo",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_gh_token() {
+        let env = |var: &str| match var {
+            "GITHUB_TOKEN" => Some("github_pat_env".to_string()),
+            "GH_TOKEN" => Some("  ".to_string()),
+            _ => None,
+        };
+        let resolved = resolve_gh_token_from(env, || panic!("gh should not be called")).unwrap();
+        assert_eq!(resolved.token, "github_pat_env");
+        assert_eq!(resolved.source, GhTokenSource::GithubTokenEnv);
+
+        let resolved = resolve_gh_token_from(|_| None, || Some("gho_cli\n".to_string())).unwrap();
+        assert_eq!(resolved.token, "gho_cli");
+        assert_eq!(resolved.source, GhTokenSource::GhCli);
+        assert!(resolve_gh_token_from(|_| None, || None).is_none());
+
+        let (login, scopes) = parse_user_response(
+            "HTTP/2.0 200 OK\r\nX-Oauth-Scopes: repo, read:org\r\n\r\n{\"login\":\"octocat\"}",
+        );
+        assert_eq!(login.as_deref(), Some("octocat"));
+        let scopes = scopes.unwrap();
+        assert_eq!(
+            missing_scope_warnings(&scopes),
+            vec!["GitHub token is missing the 'workflow' scope"]
+        );
+
+        // Fine-grained PATs report no scopes header
+        let (_, scopes) = parse_user_response("HTTP/2.0 200 OK\n\n{\"login\":\"octocat\"}");
+        assert!(scopes.is_none());
+    }
+
+    #[test]
+    fn test_parse_pr_reviews() {
+        let json = br#"{
+            "reviewDecision": "CHANGES_REQUESTED",
+            "reviewRequests": [{"login": "carol"}, {"name": "core-team"}],
+            "reviews": [
+                {"author": {"login": "alice"}, "state": "CHANGES_REQUESTED"},
+                {"author": {"login": "bob"}, "state": "APPROVED"},
+                {"author": {"login": "alice"}, "state": "COMMENTED"},
+                {"author": {"login": "alice"}, "state": "APPROVED"}
+            ]
+        }"#;
+        let (counts, summary) = parse_pr_reviews(json).unwrap();
+        assert_eq!(counts.approved, 2);
+        assert_eq!(counts.changes_requested, 1);
+        assert_eq!(summary.approved_by, vec!["alice", "bob"]);
+        assert!(summary.changes_requested_by.is_empty());
+        assert_eq!(summary.pending, vec!["carol", "core-team"]);
+        // GitHub's own decision (e.g. a required code owner review) still counts
+        assert!(!summary.required_approvals_met);
+
+        let (_, summary) = parse_pr_reviews(br#"{"reviewDecision": "", "reviews": []}"#).unwrap();
+        assert!(summary.required_approvals_met);
+        let (_, summary) =
+            parse_pr_reviews(br#"{"reviewDecision": "REVIEW_REQUIRED", "reviews": []}"#).unwrap();
+        assert!(!summary.required_approvals_met);
+    }
+
+    #[test]
+    fn test_parse_pr_review_comments() {
+        let reviews = br#"{"reviews":[{"author":{"login":"alice"},"body":"Please add tests","state":"CHANGES_REQUESTED"},{"author":{"login":"bob"},"body":"","state":"APPROVED"}]}"#;
+        let inline = br#"[{"user":{"login":"alice"},"body":"Handle None here","path":"src/lib.rs","line":42},{"user":{"login":"carol"},"body":"Outdated","path":"src/main.rs","line":null}]"#;
+        let comments = parse_pr_review_comments(reviews, inline).unwrap();
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].author, "alice");
+        assert_eq!(comments[0].path, None);
+        assert_eq!(comments[1].path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(comments[1].line, Some(42));
+        assert_eq!(comments[2].line, None);
+
+        // Paginated output: one array per page
+        let pages = br#"[{"user":{"login":"alice"},"body":"One","path":"a.rs","line":1}]
+[{"user":{"login":"bob"},"body":"Two","path":"b.rs","line":2}]"#;
+        let comments = parse_pr_review_comments(br#"{"reviews":[]}"#, pages).unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1].author, "bob");
+    }
 }
@@ -2,21 +2,31 @@
 //!
 //! This module provides:
 //! - Dependency detection (gh, tmux, docker)
+//! - Shareable DevOps configuration export/import
 //! - tmux session management
 //! - Docker sandbox containers for isolated agent execution
 //! - Git worktree management
 //! - GitHub issue integration
 //! - Agent orchestration
 //! - Pipeline state tracking
+//! - Retry of transient failures in external commands
+//! - Shell quoting for generated agent commands
+//! - Agent token usage and cost tracking
+//! - Desktop and webhook notifications on agent events
 
+pub mod config;
 mod dependencies;
 pub mod docker;
 pub mod github;
+pub mod notifications;
 pub mod operations;
 pub mod orchestration;
 pub mod orchestrator;
 pub mod pipeline;
+pub mod process;
+pub mod shell;
 pub mod tmux;
+pub mod usage;
 pub mod worktree;
 
 pub use dependencies::*;
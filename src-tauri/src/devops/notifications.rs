@@ -0,0 +1,201 @@
+//! Notifications on key agent events.
+//!
+//! When an agent opens a PR, finishes its work or fails, Handy can show a
+//! desktop notification and POST a JSON payload to a webhook (e.g. a Slack or
+//! Discord incoming webhook), so long-running pipelines can be followed
+//! without watching the app.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use super::github::sanitize_for_github;
+
+/// How long a webhook POST may take before it's abandoned
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Notification channels for agent events
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Show a desktop notification
+    pub desktop: bool,
+    /// URL to POST a JSON payload to (None = no webhook)
+    pub webhook_url: Option<String>,
+}
+
+impl NotificationSettings {
+    /// Whether any channel is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.desktop || self.webhook_url.is_some()
+    }
+}
+
+/// Check a webhook URL before saving it
+pub fn validate_webhook_url(url: &str) -> Result<(), String> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err("Webhook URL must start with http:// or https://".to_string());
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err("Webhook URL must not contain whitespace".to_string());
+    }
+    Ok(())
+}
+
+/// Kind of agent event
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentEventKind {
+    /// The agent opened a pull request
+    PrOpened,
+    /// The agent's work was completed (PR created, labels updated)
+    WorkCompleted,
+    /// Spawning or completing the agent failed
+    Failed,
+}
+
+/// Payload sent to every notification channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct AgentEventNotification {
+    /// What happened
+    pub event: AgentEventKind,
+    /// Repository in owner/repo format
+    pub repo: Option<String>,
+    /// Issue the agent works on
+    pub issue_number: Option<u64>,
+    /// Agent tmux session
+    pub session: Option<String>,
+    /// PR opened by the agent
+    pub pr_url: Option<String>,
+    /// Human-readable details (e.g. the error for failures)
+    pub message: String,
+    /// When the event happened (RFC 3339)
+    pub timestamp: String,
+}
+
+impl AgentEventNotification {
+    /// A new notification with the current time and no optional fields set
+    pub fn new(event: AgentEventKind, message: impl Into<String>) -> Self {
+        Self {
+            event,
+            repo: None,
+            issue_number: None,
+            session: None,
+            pr_url: None,
+            message: message.into(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Set the repo and issue number from an `owner/repo#number` reference
+    pub fn with_issue_ref(mut self, issue_ref: &str) -> Self {
+        if let Some((repo, number)) = issue_ref.rsplit_once('#') {
+            self.repo = Some(repo.to_string());
+            self.issue_number = number.parse().ok();
+        }
+        self
+    }
+
+    /// Strip credentials and home paths from the free-text fields
+    fn sanitized(mut self) -> Self {
+        self.message = sanitize_for_github(&self.message);
+        self.pr_url = self.pr_url.map(|url| sanitize_for_github(&url));
+        self.session = self.session.map(|s| sanitize_for_github(&s));
+        self
+    }
+
+    /// Title and body for a desktop notification
+    fn desktop_text(&self) -> (String, String) {
+        let issue = match (&self.repo, self.issue_number) {
+            (Some(repo), Some(number)) => format!("{}#{}", repo, number),
+            (None, Some(number)) => format!("#{}", number),
+            _ => self.session.clone().unwrap_or_else(|| "agent".to_string()),
+        };
+        let title = match self.event {
+            AgentEventKind::PrOpened => format!("PR opened for {}", issue),
+            AgentEventKind::WorkCompleted => format!("Agent finished {}", issue),
+            AgentEventKind::Failed => format!("Agent failed on {}", issue),
+        };
+        let body = match &self.pr_url {
+            Some(url) if self.message.is_empty() => url.clone(),
+            Some(url) => format!("{}\n{}", self.message, url),
+            None => self.message.clone(),
+        };
+        (title, body)
+    }
+}
+
+/// POST the notification as JSON to a webhook
+fn post_webhook(url: &str, notification: &AgentEventNotification) -> Result<(), String> {
+    let response = reqwest::blocking::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .post(url)
+        .json(notification)
+        .send()
+        // The URL often embeds a secret token, so keep it out of the error
+        .map_err(|e| format!("Webhook request failed: {}", e.without_url()))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Send an agent event to the configured notification channels.
+///
+/// Never fails: delivery problems are logged. The webhook is posted from a
+/// background thread so slow endpoints don't hold up the caller.
+pub fn notify(app: &AppHandle, notification: AgentEventNotification) {
+    let settings = crate::settings::get_settings(app).notifications;
+    if !settings.is_enabled() {
+        return;
+    }
+    let notification = notification.sanitized();
+
+    if settings.desktop {
+        let (title, body) = notification.desktop_text();
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            log::warn!("Failed to show desktop notification: {}", e);
+        }
+    }
+
+    if let Some(url) = settings.webhook_url {
+        std::thread::spawn(move || {
+            if let Err(e) = post_webhook(&url, &notification) {
+                log::warn!("Failed to send webhook notification: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_payload() {
+        let notification = AgentEventNotification::new(
+            AgentEventKind::Failed,
+            "gh failed: GH_TOKEN=ghp_abc123 is invalid",
+        )
+        .with_issue_ref("org/repo#42")
+        .sanitized();
+        assert_eq!(notification.repo.as_deref(), Some("org/repo"));
+        assert!(!notification.message.contains("ghp_abc123"));
+
+        let (title, body) = notification.desktop_text();
+        assert_eq!(title, "Agent failed on org/repo#42");
+        assert!(body.contains("[REDACTED]"));
+
+        let json = serde_json::to_value(&notification).unwrap();
+        assert_eq!(json["event"], "failed");
+        assert_eq!(json["issue_number"], 42);
+
+        assert!(validate_webhook_url("https://hooks.example.com/abc").is_ok());
+        assert!(validate_webhook_url("ftp://example.com").is_err());
+    }
+}
@@ -9,12 +9,16 @@
 //! - Recovery from crashes/reboots
 //! - Planning epics from markdown files (AI-assisted)
 //! - Orchestrating epic execution (auto-start phases)
+//! - Loading per-repo conventions from `.handy/config.toml`
+//! - Detecting how a work repo runs its tests
 
 pub mod agent_lifecycle;
 pub mod epic;
 pub mod orchestration;
 pub mod plan;
 pub mod plan_parser;
+pub mod repo_config;
+pub mod test_command;
 
 // Re-export for convenience
 pub use agent_lifecycle::*;
@@ -22,3 +26,5 @@ pub use epic::*;
 pub use orchestration::*;
 pub use plan::*;
 pub use plan_parser::*;
+pub use repo_config::*;
+pub use test_command::*;
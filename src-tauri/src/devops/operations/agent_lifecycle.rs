@@ -3,7 +3,9 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
-use crate::devops::{github, tmux, worktree};
+use super::{run_worktree_tests, DetectedTestCommand};
+use crate::devops::shell::shell_quote;
+use crate::devops::{github, orchestrator, tmux, worktree};
 
 /// Configuration for spawning an agent from a GitHub issue
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -17,6 +19,12 @@ pub struct SpawnAgentConfig {
     /// Work repository (where code lives and agent works)
     /// If None, extracts from issue body or uses issue_ref repo
     pub work_repo: Option<String>,
+    /// Subdirectory within the worktree to start the agent in (e.g., "packages/api")
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Remote to push the branch to instead of `origin` (e.g., a contributor's fork)
+    #[serde(default)]
+    pub push_remote: Option<String>,
 }
 
 /// Result of spawning an agent
@@ -66,6 +74,133 @@ pub struct PrDetectionResult {
     pub is_new: bool,
 }
 
+/// A PR that may belong to an agent, with how confident we are in the match
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PrCandidate {
+    /// PR number
+    pub pr_number: u64,
+    /// PR URL
+    pub pr_url: String,
+    /// PR title
+    pub title: String,
+    /// PR state (OPEN, CLOSED, MERGED)
+    pub state: String,
+    /// Source branch of the PR
+    pub branch_name: String,
+    /// Match confidence from 0.0 to 1.0
+    pub confidence: f64,
+    /// Human-readable reasons for the match
+    pub reasons: Vec<String>,
+}
+
+/// Result of verifying which PR an agent created
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PrVerificationResult {
+    /// tmux session name
+    pub session: String,
+    /// Issue number the agent is working on
+    pub issue_number: u32,
+    /// Repository the PRs were searched in (org/repo format)
+    pub repo: String,
+    /// Most likely PR, if any matched
+    pub best: Option<PrCandidate>,
+    /// All matching PRs, most confident first
+    pub candidates: Vec<PrCandidate>,
+    /// Whether the match is uncertain and the user should confirm it
+    pub ambiguous: bool,
+}
+
+/// Number of recent PRs to search when verifying an agent's PR
+const PR_VERIFY_LIMIT: u32 = 50;
+
+/// Number of worktree commits to compare against PR head SHAs
+const PR_VERIFY_COMMIT_LIMIT: u32 = 100;
+
+/// Confidence below which a match is reported as ambiguous
+const PR_CONFIDENT_THRESHOLD: f64 = 0.5;
+
+/// Whether a branch name refers to the issue (e.g. "issue-42-fix-login", "fix/42-login")
+fn branch_mentions_issue(branch: &str, issue_number: u32) -> bool {
+    let number = issue_number.to_string();
+    branch
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|part| part == number)
+}
+
+/// Score how likely a PR is to be the agent's PR
+fn score_pr_candidate(
+    pr: &github::PrHead,
+    issue_number: u32,
+    worktree_branch: Option<&str>,
+    worktree_commits: &[String],
+) -> Option<PrCandidate> {
+    let mut confidence: f64 = 0.0;
+    let mut reasons = Vec::new();
+
+    if worktree_commits.contains(&pr.head_sha) {
+        confidence += 0.6;
+        reasons.push("PR head commit is in the agent's worktree".to_string());
+    }
+
+    if worktree_branch == Some(pr.head_branch.as_str()) {
+        confidence += 0.5;
+        reasons.push(format!(
+            "Branch '{}' is checked out in the worktree",
+            pr.head_branch
+        ));
+    } else if pr.head_branch == format!("issue-{}", issue_number) {
+        confidence += 0.5;
+        reasons.push(format!(
+            "Branch '{}' matches the naming convention",
+            pr.head_branch
+        ));
+    } else if branch_mentions_issue(&pr.head_branch, issue_number) {
+        confidence += 0.25;
+        reasons.push(format!(
+            "Branch '{}' mentions the issue number",
+            pr.head_branch
+        ));
+    }
+
+    if pr
+        .body
+        .as_deref()
+        .is_some_and(|body| github::body_closes_issue(body, issue_number))
+    {
+        confidence += 0.35;
+        reasons.push(format!("PR body closes #{}", issue_number));
+    }
+
+    if reasons.is_empty() {
+        return None;
+    }
+
+    Some(PrCandidate {
+        pr_number: pr.number,
+        pr_url: pr.url.clone(),
+        title: pr.title.clone(),
+        state: pr.state.clone(),
+        branch_name: pr.head_branch.clone(),
+        confidence: confidence.min(1.0),
+        reasons,
+    })
+}
+
+/// Rank PR candidates and decide whether the best match is ambiguous
+fn rank_pr_candidates(mut candidates: Vec<PrCandidate>) -> (Vec<PrCandidate>, bool) {
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+    let ambiguous = match candidates.as_slice() {
+        [] => false,
+        [only] => only.confidence < PR_CONFIDENT_THRESHOLD,
+        [first, second, ..] => {
+            first.confidence < PR_CONFIDENT_THRESHOLD || first.confidence - second.confidence < 0.1
+        }
+    };
+
+    (candidates, ambiguous)
+}
+
 /// Spawn an agent for a GitHub issue
 ///
 /// This function:
@@ -75,52 +210,78 @@ pub struct PrDetectionResult {
 /// 4. Creates a tmux session
 /// 5. Sets metadata in tmux env vars
 /// 6. Posts metadata comment to GitHub
-/// 7. Adds "agent-assigned" label
-pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpawnResult, String> {
+/// 7. Adds "agent-assigned" label (plus the repo config's start labels)
+///
+/// The repo's `.handy/config.toml` supplies the agent type when neither the
+/// config nor the issue names one. Repos that require a sandbox are refused,
+/// since agents spawned here always run directly.
+///
+/// `default_work_repo` is the configured work repo for the issue's tracking
+/// repo, used when neither the config nor the issue body specify one.
+pub async fn spawn_agent_from_issue(
+    config: SpawnAgentConfig,
+    default_work_repo: Option<String>,
+) -> Result<AgentSpawnResult, String> {
     // Parse issue reference
     let (repo, issue_number) = parse_issue_ref(&config.issue_ref)?;
 
     // Fetch issue from GitHub
     let issue = github::get_issue_async(&repo, issue_number).await?;
 
-    // Extract agent type from issue body or use override
+    // Get repo path from current directory
+    // NOTE: This assumes we're running from the work_repo directory
+    // In the future, we may want to clone work_repo if it's different from tracking repo
+    let repo_path = std::env::current_dir().map_err(|e| e.to_string())?;
+    let repo_path_str = repo_path.to_string_lossy().to_string();
+    let repo_config = super::load_repo_config(&repo_path_str)?.unwrap_or_default();
+
+    // Extract agent type from override, issue body, or the repo config
     let issue_body = issue.body.as_deref().unwrap_or("");
     let agent_type = config
         .agent_type
         .or_else(|| extract_agent_type(issue_body))
+        .or(repo_config.agent_type)
         .ok_or_else(|| {
-            "Agent type not specified in config or issue body. \
+            "Agent type not specified in config, issue body or .handy/config.toml. \
              Add '**Agent Type**: <type>' to issue or provide agent_type in config"
                 .to_string()
         })?;
 
+    // Agents spawned here always run directly in tmux
+    if repo_config.sandbox.enabled == Some(true) {
+        return Err(format!(
+            "{} requires a sandbox (.handy/config.toml); spawn it from the orchestrator instead",
+            repo_path_str
+        ));
+    }
+    orchestrator::check_direct_start(&agent_type)?;
+
     // Extract epic reference from issue body (optional)
     let epic_ref = extract_epic_ref(issue_body);
 
-    // Extract work_repo from config, issue body, or default to issue_ref repo
+    // Extract work_repo from config, issue body, configured default, or issue_ref repo
     let work_repo = config
         .work_repo
         .or_else(|| extract_work_repo(issue_body))
+        .or(default_work_repo)
         .unwrap_or_else(|| repo.clone());
 
     // Generate session name
     let session_name = config
         .session_name
-        .unwrap_or_else(|| format!("handy-agent-{}", issue_number));
-
-    // Get repo path from current directory
-    // NOTE: This assumes we're running from the work_repo directory
-    // In the future, we may want to clone work_repo if it's different from tracking repo
-    let repo_path = std::env::current_dir().map_err(|e| e.to_string())?;
+        .unwrap_or_else(|| tmux::next_session_name(issue_number as u64, &agent_type));
 
     // Create worktree (blocking operation)
     let branch_name = format!("issue-{}", issue_number);
-    let repo_path_str = repo_path.to_string_lossy().to_string();
     let worktree_result = tokio::task::spawn_blocking({
         let repo_path = repo_path_str.clone();
         let branch_name = branch_name.clone();
+        let push_remote = config.push_remote.clone();
         move || {
-            let config = worktree::WorktreeConfig::default();
+            let config = worktree::WorktreeConfig {
+                push_remote,
+                ..Default::default()
+            };
             worktree::create_worktree(&repo_path, &branch_name, &config, None)
         }
     })
@@ -130,6 +291,10 @@ pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpa
 
     let worktree_path = worktree_result.path.clone();
 
+    // Validate the working subdirectory now that the worktree exists
+    let working_dir =
+        orchestrator::resolve_worktree_subpath(&worktree_path, config.subpath.as_deref())?;
+
     // Build metadata
     let machine_id = get_machine_id()?;
     let metadata = tmux::AgentMetadata {
@@ -140,14 +305,21 @@ pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpa
         agent_type: agent_type.clone(),
         machine_id: machine_id.clone(),
         started_at: chrono::Utc::now().to_rfc3339(),
+        subpath: config.subpath.clone(),
+        base_ref: None,
+        pr_number: None,
+        verbose: false,
+        paused: false,
+        push_remote: config.push_remote.clone(),
+        quarantine: false,
+        worktree_reused: false,
     };
 
     // Create tmux session in the worktree (blocking operation)
     tokio::task::spawn_blocking({
         let session_name = session_name.clone();
-        let worktree_path = worktree_path.clone();
         let metadata = metadata.clone();
-        move || tmux::create_session(&session_name, Some(&worktree_path), &metadata)
+        move || tmux::create_session(&session_name, Some(&working_dir), &metadata)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
@@ -166,6 +338,8 @@ pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpa
                 &repo,
                 issue_number as u64,
                 Some(&issue_title_for_agent),
+                &[],
+                false,
             )
         }
     })
@@ -179,8 +353,10 @@ pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpa
         .await
         .map_err(|e| format!("Failed to add GitHub comment: {}", e))?;
 
-    // Add "agent-assigned" label
-    github::add_labels_async(&repo, issue_number, &vec!["agent-assigned".to_string()])
+    // Add "agent-assigned" label, plus the repo's configured start labels
+    let mut labels = vec!["agent-assigned".to_string()];
+    labels.extend(repo_config.start_labels);
+    github::add_labels_async(&repo, issue_number, &labels)
         .await
         .map_err(|e| format!("Failed to add labels: {}", e))?;
 
@@ -193,6 +369,45 @@ pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpa
     })
 }
 
+/// Re-generate the agent metadata comment on an issue from the session's
+/// current metadata.
+///
+/// The comment posted at spawn goes stale when the session is renamed or
+/// re-sandboxed; this edits it in place instead of posting a new one.
+/// Returns the ID of the edited comment.
+pub async fn update_agent_metadata_comment(
+    repo: String,
+    issue_number: u32,
+    session_name: String,
+) -> Result<u64, String> {
+    let metadata = tokio::task::spawn_blocking({
+        let session_name = session_name.clone();
+        move || tmux::get_session_metadata(&session_name)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Failed to get session metadata: {}", e))?;
+
+    let issue = github::get_issue_async(&repo, issue_number).await?;
+    let epic_ref = extract_epic_ref(issue.body.as_deref().unwrap_or(""));
+
+    let comments = github::list_comments_async(&repo, issue_number).await?;
+    let comment_id =
+        github::find_metadata_comment_to_update(&comments, &session_name).ok_or_else(|| {
+            format!(
+                "No agent metadata comment found on {}#{}",
+                repo, issue_number
+            )
+        })?;
+
+    let body = format_agent_metadata_comment(&metadata, &issue.title, epic_ref.as_deref());
+    github::edit_comment_async(&repo, comment_id, &body)
+        .await
+        .map_err(|e| format!("Failed to update metadata comment: {}", e))?;
+
+    Ok(comment_id)
+}
+
 /// Complete agent work by creating a PR
 ///
 /// This function:
@@ -202,9 +417,15 @@ pub async fn spawn_agent_from_issue(config: SpawnAgentConfig) -> Result<AgentSpa
 /// 4. Adds labels to PR
 /// 5. Comments on issue with PR link
 /// 6. Updates epic progress if applicable
+///
+/// With `run_tests`, the repo's test commands are detected from its project
+/// files and run first (in the sandbox when one exists); a failure aborts
+/// before anything is pushed, and the commands are listed in the PR body. A
+/// repo whose tests can't be detected only gets a warning.
 pub async fn complete_agent_work(
     session: String,
     pr_title: Option<String>,
+    run_tests: bool,
 ) -> Result<AgentCompletionResult, String> {
     // Get agent metadata from tmux (blocking operation)
     let metadata = tokio::task::spawn_blocking({
@@ -214,6 +435,7 @@ pub async fn complete_agent_work(
     .await
     .map_err(|e| format!("Task join error: {}", e))?
     .map_err(|e| format!("Failed to get session metadata: {}", e))?;
+    orchestrator::check_not_quarantined(&metadata)?;
 
     // Clone values from metadata that we'll use later
     let issue_ref = metadata
@@ -232,23 +454,60 @@ pub async fn complete_agent_work(
     // Get issue details
     let issue = github::get_issue_async(&repo, issue_number).await?;
 
-    let branch_name = format!("issue-{}", issue_number);
+    // The worktree may be one the agent reused, so read its branch rather than assume it
+    let (branch_name, base_branch) = tokio::task::spawn_blocking({
+        let worktree_path = worktree_path.clone();
+        move || {
+            let branch = worktree::get_current_branch(&worktree_path)?;
+            if branch == "HEAD" {
+                return Err(format!(
+                    "Worktree {} has no branch checked out",
+                    worktree_path
+                ));
+            }
+            Ok((branch, worktree::get_default_branch(&worktree_path)?))
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
 
-    // Push branch (blocking operation)
-    tokio::task::spawn_blocking({
+    // Detect and run the repo's tests if asked (blocking operation)
+    let test_commands = tokio::task::spawn_blocking({
+        let worktree_path = worktree_path.clone();
+        let repo = repo.clone();
+        move || {
+            if !run_tests {
+                return Ok(Vec::new());
+            }
+            let (commands, outcome) =
+                run_worktree_tests(&worktree_path, Some((&repo, issue_number)));
+            outcome.map_err(|e| format!("Tests failed, not creating PR: {}", e))?;
+            Ok::<_, String>(commands)
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    // Push branch and resolve the PR head (blocking operation)
+    let head = tokio::task::spawn_blocking({
         let worktree_path = worktree_path.clone();
         let branch_name = branch_name.clone();
-        move || push_branch(&worktree_path, &branch_name)
+        let push_remote = metadata.push_remote.clone();
+        move || {
+            let remote = push_remote.as_deref().unwrap_or("origin");
+            worktree::push_branch(&worktree_path, remote, &branch_name)
+                .map_err(|e| format!("Failed to push branch: {}", e))?;
+            worktree::pr_head_ref(&worktree_path, &branch_name, push_remote.as_deref())
+        }
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?
-    .map_err(|e| format!("Failed to push branch: {}", e))?;
+    .map_err(|e| format!("Task join error: {}", e))??;
 
     // Create PR
     let pr_title = pr_title.unwrap_or_else(|| issue.title.clone());
-    let pr_body = format_pr_body(&issue.title, issue_number, &metadata);
+    let pr_body = format_pr_body(&issue.title, issue_number, &metadata, &test_commands);
 
-    let pr_url = github::create_pr_async(&repo, &pr_title, &pr_body, "main", &branch_name)
+    let pr_url = github::create_pr_async(&repo, &pr_title, &pr_body, &base_branch, &head)
         .await
         .map_err(|e| format!("Failed to create PR: {}", e))?;
 
@@ -257,10 +516,18 @@ pub async fn complete_agent_work(
         .await
         .ok(); // Non-critical, continue even if fails
 
-    // Comment on issue
+    // Comment on issue, with a digest of what changed (non-critical)
+    let digest = tokio::task::spawn_blocking({
+        let worktree_path = worktree_path.clone();
+        move || worktree::get_change_digest(&worktree_path, &base_branch)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+    .map(|d| orchestrator::change_digest_note(&d))
+    .unwrap_or_default();
     let completion_comment = format!(
-        "✅ **Work Complete**\n\nPR created: {}\n\nAgent `{}` has finished implementation.",
-        pr_url, session
+        "✅ **Work Complete**\n\nPR created: {}\n\nAgent `{}` has finished implementation.{}",
+        pr_url, session, digest
     );
     github::add_issue_comment_async(&repo, issue_number, &completion_comment)
         .await
@@ -331,6 +598,61 @@ pub async fn detect_pr_for_agent(session: &str) -> Result<Option<PrDetectionResu
     }
 }
 
+/// Verify which PR an agent created, without assuming the `issue-{number}` branch
+///
+/// Searches recent PRs in the agent's work repo and scores each one by branch
+/// name, closing keywords in the body, and whether its head commit exists in
+/// the agent's worktree. Ambiguous results should be confirmed by the user.
+pub async fn verify_agent_pr(session: &str) -> Result<PrVerificationResult, String> {
+    let session = session.to_string();
+    tokio::task::spawn_blocking(move || {
+        let metadata = tmux::get_session_metadata(&session)
+            .map_err(|e| format!("Failed to get session metadata: {}", e))?;
+
+        let issue_ref = metadata
+            .issue_ref
+            .as_ref()
+            .ok_or_else(|| "Agent has no issue reference".to_string())?;
+        let (issue_repo, issue_number) = parse_issue_ref(issue_ref)?;
+
+        // PRs are opened against the work repo, which may differ from the tracking repo
+        let repo = metadata.repo.clone().unwrap_or(issue_repo);
+
+        let (worktree_branch, worktree_commits) = match metadata.worktree.as_deref() {
+            Some(path) => (
+                worktree::get_current_branch(path).ok(),
+                worktree::list_recent_commits(path, PR_VERIFY_COMMIT_LIMIT).unwrap_or_default(),
+            ),
+            None => (None, Vec::new()),
+        };
+
+        let prs = github::list_recent_pr_heads(&repo, PR_VERIFY_LIMIT)?;
+        let candidates = prs
+            .iter()
+            .filter_map(|pr| {
+                score_pr_candidate(
+                    pr,
+                    issue_number,
+                    worktree_branch.as_deref(),
+                    &worktree_commits,
+                )
+            })
+            .collect();
+        let (candidates, ambiguous) = rank_pr_candidates(candidates);
+
+        Ok(PrVerificationResult {
+            session,
+            issue_number,
+            repo,
+            best: candidates.first().cloned(),
+            candidates,
+            ambiguous,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Parse issue reference like "org/repo#123" into (repo, number)
 fn parse_issue_ref(issue_ref: &str) -> Result<(String, u32), String> {
     let parts: Vec<&str> = issue_ref.split('#').collect();
@@ -431,7 +753,22 @@ Will update with progress.
 }
 
 /// Format PR body with standard template
-fn format_pr_body(issue_title: &str, issue_number: u32, metadata: &tmux::AgentMetadata) -> String {
+fn format_pr_body(
+    issue_title: &str,
+    issue_number: u32,
+    metadata: &tmux::AgentMetadata,
+    test_commands: &[DetectedTestCommand],
+) -> String {
+    let testing = if test_commands.is_empty() {
+        "No test command was detected for this repository.".to_string()
+    } else {
+        let lines: Vec<String> = test_commands
+            .iter()
+            .map(|t| format!("{}  # {}", t.command, t.evidence))
+            .collect();
+        format!("```bash\n{}\n```", lines.join("\n"))
+    };
+
     format!(
         r#"## Summary
 {}
@@ -440,43 +777,25 @@ fn format_pr_body(issue_title: &str, issue_number: u32, metadata: &tmux::AgentMe
 Implementation of #{} via DevOps agent.
 
 ## Testing
-```bash
-# Run tests to verify changes
-cargo test  # For Rust
-bun run test  # For TypeScript
-```
+{}
 
 ## Related Issues
-Closes #{}
+Closes #{}{}
 
 ---
 
 🤖 Generated by {} agent `{}`
 "#,
-        issue_title, issue_number, issue_number, metadata.agent_type, metadata.session,
+        issue_title,
+        issue_number,
+        testing,
+        issue_number,
+        orchestrator::base_ref_note(metadata.base_ref.as_deref()),
+        metadata.agent_type,
+        metadata.session,
     )
 }
 
-/// Push git branch to remote
-fn push_branch(worktree_path: &str, branch_name: &str) -> Result<(), String> {
-    use std::process::Command;
-
-    let output = Command::new("git")
-        .args(&["push", "-u", "origin", branch_name])
-        .current_dir(worktree_path)
-        .output()
-        .map_err(|e| format!("Failed to execute git push: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!(
-            "git push failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
-    }
-
-    Ok(())
-}
-
 /// Configuration for spawning a support worker agent for a specific task
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SupportWorkerConfig {
@@ -542,6 +861,14 @@ pub async fn spawn_support_worker(
         agent_type: format!("support-{}", config.task_type),
         machine_id: machine_id.clone(),
         started_at: chrono::Utc::now().to_rfc3339(),
+        subpath: None,
+        base_ref: None,
+        pr_number: None,
+        verbose: false,
+        paused: false,
+        push_remote: None,
+        quarantine: false,
+        worktree_reused: false,
     };
 
     // Determine working directory:
@@ -555,6 +882,14 @@ pub async fn spawn_support_worker(
         std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string())
     };
 
+    // Build the inner command based on task type
+    // Pass sandboxed flag so we can add --dangerously-skip-permissions in sandbox
+    let inner_command = build_support_worker_command(&config, config.sandboxed)?;
+    if !config.sandboxed {
+        orchestrator::check_direct_start("claude")?;
+        tmux::check_root_skip_permissions(&inner_command)?;
+    }
+
     // Create tmux session
     tokio::task::spawn_blocking({
         let session_name = session_name.clone();
@@ -566,10 +901,6 @@ pub async fn spawn_support_worker(
     .map_err(|e| format!("Task join error: {}", e))?
     .map_err(|e| format!("Failed to create tmux session: {}", e))?;
 
-    // Build the inner command based on task type
-    // Pass sandboxed flag so we can add --dangerously-skip-permissions in sandbox
-    let inner_command = build_support_worker_command(&config, config.sandboxed)?;
-
     // If sandboxed, wrap the command in a Docker container
     let command = if config.sandboxed {
         let worktree_path = config.worktree_path.as_ref().ok_or_else(|| {
@@ -586,6 +917,22 @@ pub async fn spawn_support_worker(
         inner_command
     };
 
+    // Record the pre-merge state so an aborted merge can be rolled back
+    if config.task_type == "merge" {
+        if let Some(worktree_path) = config.worktree_path.clone() {
+            let branch = pre_merge_restore_branch(config.issue_number);
+            let restore = tokio::task::spawn_blocking({
+                let branch = branch.clone();
+                move || worktree::create_restore_point(&worktree_path, &branch)
+            })
+            .await
+            .map_err(|e| format!("Task join error: {}", e))?;
+            if let Err(e) = restore {
+                log::warn!("Failed to record pre-merge restore point {}: {}", branch, e);
+            }
+        }
+    }
+
     // Send the command to the tmux session
     tokio::task::spawn_blocking({
         let session_name = session_name.clone();
@@ -623,6 +970,129 @@ pub async fn spawn_support_worker(
     })
 }
 
+/// Branch recording a worktree's state before a merge support worker touched it
+pub fn pre_merge_restore_branch(issue_number: u32) -> String {
+    format!(
+        "{}pre-merge-{}",
+        worktree::BACKUP_BRANCH_PREFIX,
+        issue_number
+    )
+}
+
+/// Whether a session name is a support worker for the issue (`handy-support-{task}-{n}`)
+fn is_support_session_for_issue(session_name: &str, issue_number: u32) -> bool {
+    session_name
+        .strip_prefix("handy-support-")
+        .and_then(|rest| rest.rsplit_once('-'))
+        .is_some_and(|(task, n)| !task.is_empty() && n == issue_number.to_string())
+}
+
+/// What `abort_support_worker` stopped
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct AbortSupportWorkerResult {
+    /// Support worker tmux sessions that were interrupted and killed
+    pub sessions_stopped: Vec<String>,
+    /// Support worker containers that were removed
+    pub containers_removed: Vec<String>,
+    /// Worktrees reset to their pre-merge restore point
+    pub worktrees_reset: Vec<String>,
+    /// Problems hit while stopping (the abort continues past them)
+    pub errors: Vec<String>,
+}
+
+/// Abort the support workers for an issue.
+///
+/// Interrupts and kills every `handy-support-*-{n}` session and removes its
+/// `handy-support-sandbox-*` container. The issue's own agent sandbox is left
+/// running. With `reset_worktree`, worktrees are reset to the restore point
+/// recorded before the merge worker started.
+pub fn abort_support_worker(
+    issue_number: u32,
+    reset_worktree: bool,
+) -> Result<AbortSupportWorkerResult, String> {
+    let sessions: Vec<String> = tmux::list_sessions()?
+        .into_iter()
+        .map(|s| s.name)
+        .filter(|name| is_support_session_for_issue(name, issue_number))
+        .collect();
+
+    let mut result = AbortSupportWorkerResult::default();
+    let mut containers = Vec::new();
+    let mut worktrees = Vec::new();
+
+    for session in &sessions {
+        let metadata = tmux::get_session_metadata(session).ok();
+        if let Some(repo) = metadata.as_ref().and_then(|m| m.repo.as_deref()) {
+            containers.extend(
+                crate::devops::docker::containers_for_issue(repo, issue_number)
+                    .into_iter()
+                    .filter(|c| crate::devops::docker::is_support_container(c)),
+            );
+        }
+        if let Some(worktree) = metadata.and_then(|m| m.worktree) {
+            worktrees.push(worktree);
+        }
+
+        // Interrupt first so the agent stops before its container disappears
+        let _ = tmux::send_keys(session, "C-c", None);
+    }
+    containers.sort();
+    containers.dedup();
+
+    for container in containers {
+        match crate::devops::docker::stop_and_remove_container(&container) {
+            Ok(()) => result.containers_removed.push(container),
+            Err(e) => result.errors.push(e),
+        }
+    }
+
+    for session in sessions {
+        match tmux::kill_session_only(&session) {
+            Ok(()) => result.sessions_stopped.push(session),
+            Err(e) => result
+                .errors
+                .push(format!("Failed to kill session {}: {}", session, e)),
+        }
+    }
+
+    if reset_worktree {
+        let branch = pre_merge_restore_branch(issue_number);
+        worktrees.dedup();
+        for worktree_path in worktrees {
+            match worktree::restore_to_point(&worktree_path, &branch) {
+                Ok(true) => result.worktrees_reset.push(worktree_path),
+                Ok(false) => result.errors.push(format!(
+                    "No pre-merge restore point {} in {}",
+                    branch, worktree_path
+                )),
+                Err(e) => result.errors.push(format!(
+                    "Failed to reset {} to {}: {}",
+                    worktree_path, branch, e
+                )),
+            }
+        }
+    }
+
+    if result.sessions_stopped.is_empty() && result.containers_removed.is_empty() {
+        if result.errors.is_empty() {
+            return Err(format!(
+                "No support worker running for issue #{}",
+                issue_number
+            ));
+        }
+        return Err(result.errors.join("; "));
+    }
+
+    log::info!(
+        "Aborted support worker for issue #{}: sessions {:?}, containers {:?}, reset {:?}",
+        issue_number,
+        result.sessions_stopped,
+        result.containers_removed,
+        result.worktrees_reset
+    );
+    Ok(result)
+}
+
 /// Build the inner command for a support worker based on task type
 ///
 /// When `sandboxed` is true, adds `--dangerously-skip-permissions` flag since
@@ -652,8 +1122,8 @@ fn build_support_worker_command(
             };
 
             // Use Claude to handle the merge, including conflict resolution if needed
-            Ok(format!(
-                r#"claude{auto_flag} "You are a Support Worker agent tasked with merging PR #{pr_number} in {repo}.
+            let prompt = format!(
+                r#"You are a Support Worker agent tasked with merging PR #{pr_number} in {repo}.
 
 Your task:
 1. First, view the PR details: gh pr view {pr_number} --repo {repo}
@@ -671,30 +1141,32 @@ If the merge fails due to merge conflicts:
 
 If CI checks are failing, analyze the failures and determine if they are blocking. Report back with what you find.
 
-Start by viewing the PR and attempting the merge.""#,
-                auto_flag = auto_flag,
+Start by viewing the PR and attempting the merge."#,
                 pr_number = pr_number,
                 repo = config.repo,
                 merge_method = merge_method,
                 delete_flag = delete_flag,
-            ))
+            );
+            Ok(format!("claude{} {}", auto_flag, shell_quote(&prompt)))
         }
         "review" => {
             let pr_number = config
                 .pr_number
                 .ok_or("PR number required for review task")?;
+            let prompt = format!(
+                "Review the PR #{} in {} and provide feedback. Check the diff, look for issues, and approve or request changes.",
+                pr_number, config.repo
+            );
             Ok(format!(
-                r#"claude{} "Review the PR #{} in {} and provide feedback. Check the diff, look for issues, and approve or request changes." --repo {}"#,
-                auto_flag, pr_number, config.repo, config.repo
+                "claude{} {} --repo {}",
+                auto_flag,
+                shell_quote(&prompt),
+                shell_quote(&config.repo)
             ))
         }
         _ => {
             // Generic task - let Claude handle it
-            Ok(format!(
-                r#"claude{} "{}""#,
-                auto_flag,
-                config.task.replace('"', "\\\"")
-            ))
+            Ok(format!("claude{} {}", auto_flag, shell_quote(&config.task)))
         }
     }
 }
@@ -712,14 +1184,16 @@ fn build_sandboxed_support_worker_command(
     repo: &str,
     issue_number: u32,
 ) -> Result<String, String> {
-    use crate::devops::docker::{container_exists_for_issue, stop_and_remove_container};
+    use crate::devops::docker::{
+        container_exists_for_issue, stop_and_remove_container, support_container_name_for_issue,
+    };
 
-    let container_name = format!("handy-support-sandbox-{}", issue_number);
+    let container_name = support_container_name_for_issue(repo, issue_number as u64);
     let image = "node:20-bookworm"; // Base image with Node.js for Claude Code
 
     // Pre-check: Remove any existing container with this issue number to avoid conflicts
     // This handles both regular sandbox and support-sandbox containers
-    if let Some(existing) = container_exists_for_issue(issue_number) {
+    if let Some(existing) = container_exists_for_issue(repo, issue_number) {
         log::warn!(
             "Found existing container {} for issue #{}, removing before spawning support worker",
             existing,
@@ -734,7 +1208,10 @@ fn build_sandboxed_support_worker_command(
     let mut docker_args = vec![
         "docker run --rm -it".to_string(),
         format!("--name {}", container_name),
-        format!("-v {}:/workspace", worktree_path),
+        format!(
+            "-v {}",
+            shell_quote(&format!("{}:/workspace", worktree_path))
+        ),
         "-w /workspace".to_string(),
     ];
 
@@ -749,7 +1226,10 @@ fn build_sandboxed_support_worker_command(
     if let Ok(home) = std::env::var("HOME") {
         let gh_dir = format!("{}/.config/gh", home);
         if std::path::Path::new(&gh_dir).exists() {
-            docker_args.push(format!("-v {}:/tmp/host-auth/.config/gh:ro", gh_dir));
+            docker_args.push(format!(
+                "-v {}",
+                shell_quote(&format!("{}:/tmp/host-auth/.config/gh:ro", gh_dir))
+            ));
         }
     }
 
@@ -863,12 +1343,11 @@ EXPECT_SCRIPT
 chmod +x /tmp/auto-accept.exp
 
 # Create wrapper script that runs Claude via expect
-# Use unquoted heredoc so CLAUDE_CMD variable expands
-CLAUDE_CMD='{inner_command}'
-cat > /tmp/run-agent.sh << AGENT_SCRIPT
+# The heredoc is quoted so the (pre-quoted) agent command is written verbatim
+cat > /tmp/run-agent.sh << 'AGENT_SCRIPT'
 #!/bin/bash
 cd /workspace
-exec /tmp/auto-accept.exp "$CLAUDE_CMD"
+exec /tmp/auto-accept.exp {inner_command}
 AGENT_SCRIPT
 chmod +x /tmp/run-agent.sh
 chown "$AGENT_USER:$AGENT_USER" /tmp/run-agent.sh /tmp/auto-accept.exp
@@ -877,10 +1356,10 @@ chown "$AGENT_USER:$AGENT_USER" /tmp/run-agent.sh /tmp/auto-accept.exp
 # Unlike su/sudo, gosu doesn't leave any privileged process in the chain
 exec gosu "$AGENT_USER" /tmp/run-agent.sh
 "#,
-        inner_command = inner_command.replace('\'', "'\\''"),
+        inner_command = shell_quote(inner_command),
     );
 
-    docker_args.push(format!("'{}'", setup_script.replace('\'', "'\\''")));
+    docker_args.push(shell_quote(&setup_script));
 
     Ok(docker_args.join(" "))
 }
@@ -901,6 +1380,19 @@ impl<T> Pipe for T {}
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_support_session_for_issue() {
+        assert!(is_support_session_for_issue("handy-support-merge-42", 42));
+        assert!(is_support_session_for_issue(
+            "handy-support-code-review-42",
+            42
+        ));
+        assert!(!is_support_session_for_issue("handy-support-merge-142", 42));
+        assert!(!is_support_session_for_issue("handy-support-42", 42));
+        assert!(!is_support_session_for_issue("handy-agent-42", 42));
+        assert_eq!(pre_merge_restore_branch(42), "handy-backup/pre-merge-42");
+    }
+
     #[test]
     fn test_parse_issue_ref() {
         let (repo, number) = parse_issue_ref("org/Handy#101").unwrap();
@@ -915,6 +1407,43 @@ mod tests {
         assert!(parse_issue_ref("org/repo#abc").is_err());
     }
 
+    fn pr_head(number: u64, branch: &str, body: &str, sha: &str) -> github::PrHead {
+        github::PrHead {
+            number,
+            title: format!("PR {}", number),
+            body: Some(body.to_string()),
+            state: "OPEN".to_string(),
+            url: format!("https://github.com/org/app/pull/{}", number),
+            head_branch: branch.to_string(),
+            head_sha: sha.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_score_and_rank_pr_candidates() {
+        let commits = vec!["abc123".to_string()];
+        let prs = [
+            pr_head(1, "agent/fix-login", "Closes #42", "abc123"),
+            pr_head(2, "fix/42-login", "", "def456"),
+            pr_head(3, "unrelated", "Fixes #7", "fff000"),
+        ];
+        let candidates: Vec<_> = prs
+            .iter()
+            .filter_map(|pr| score_pr_candidate(pr, 42, Some("agent/fix-login"), &commits))
+            .collect();
+        assert_eq!(candidates.len(), 2);
+
+        let (ranked, ambiguous) = rank_pr_candidates(candidates);
+        assert_eq!(ranked[0].pr_number, 1);
+        assert_eq!(ranked[0].confidence, 1.0);
+        assert!(!ambiguous);
+
+        // A lone weak match needs confirmation
+        let weak = score_pr_candidate(&prs[1], 42, None, &[]).unwrap();
+        let (_, ambiguous) = rank_pr_candidates(vec![weak]);
+        assert!(ambiguous);
+    }
+
     #[test]
     fn test_extract_agent_type() {
         let body = "Some text\n**Agent Type**: claude\nMore text";
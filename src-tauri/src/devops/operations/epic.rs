@@ -43,6 +43,10 @@ pub struct PhaseConfig {
     /// Dependencies - names of phases that must complete first
     #[serde(default)]
     pub dependencies: Vec<String>,
+    /// Branch that worktrees for this phase are created from (e.g., "feature/x")
+    /// If None, uses the repo's default branch
+    #[serde(default)]
+    pub base_branch: Option<String>,
 }
 
 /// Information about a created epic
@@ -84,6 +88,9 @@ pub struct SubIssueConfig {
     /// Work repository (where agent will work)
     /// If None, inherits from Epic
     pub work_repo: Option<String>,
+    /// Branch to base the agent's worktree on (inherited from the phase)
+    #[serde(default)]
+    pub base_branch: Option<String>,
 }
 
 /// Information about a created sub-issue
@@ -101,6 +108,9 @@ pub struct SubIssueInfo {
     pub work_repo: String,
     /// GitHub issue URL
     pub url: String,
+    /// Branch to base the agent's worktree on (None = repo default)
+    #[serde(default)]
+    pub base_branch: Option<String>,
 }
 
 /// Epic progress statistics
@@ -164,12 +174,18 @@ fn format_epic_body(config: &EpicConfig, work_repo: &str) -> String {
         .iter()
         .enumerate()
         .map(|(i, phase)| {
+            let base_branch_line = phase
+                .base_branch
+                .as_ref()
+                .map(|b| format!("**Base Branch**: {}\n", b))
+                .unwrap_or_default();
             format!(
-                "### Phase {}: {}\n{}\n\n**Approach**: {}\n**Status**: ⏸️ Not Started\n",
+                "### Phase {}: {}\n{}\n\n**Approach**: {}\n{}**Status**: ⏸️ Not Started\n",
                 i + 1,
                 phase.name,
                 phase.description,
-                phase.approach
+                phase.approach,
+                base_branch_line
             )
         })
         .collect::<Vec<_>>()
@@ -213,6 +229,7 @@ pub async fn create_sub_issues(
     sub_issues: Vec<SubIssueConfig>,
 ) -> Result<Vec<SubIssueInfo>, String> {
     let mut created = Vec::new();
+    let mut creation_error = None;
 
     for config in sub_issues.iter() {
         // Determine work_repo for this sub-issue (inherit from epic if not specified)
@@ -224,19 +241,16 @@ pub async fn create_sub_issues(
         // Format sub-issue body (including work_repo)
         let body = format_sub_issue_body(epic_number, &epic_repo, &work_repo, config);
 
-        // Create GitHub issue
-        let issue_number = github::create_issue_async(&epic_repo, &config.title, &body).await?;
-
-        // Add labels - only use standard labels that exist in the repo
-        // Phase info is tracked in the issue body, not via labels
-        let labels = vec!["todo".to_string()];
-        if let Err(e) = github::add_labels_async(&epic_repo, issue_number, &labels).await {
-            eprintln!(
-                "Warning: Failed to add labels to issue #{}: {}",
-                issue_number, e
-            );
-            // Continue anyway - labels are nice to have but not critical
-        }
+        // Create GitHub issue; stop at the first failure but still label
+        // the issues created so far
+        let issue_number = match github::create_issue_async(&epic_repo, &config.title, &body).await
+        {
+            Ok(number) => number,
+            Err(e) => {
+                creation_error = Some(e);
+                break;
+            }
+        };
 
         created.push(SubIssueInfo {
             issue_number,
@@ -245,10 +259,34 @@ pub async fn create_sub_issues(
             agent_type: config.agent_type.clone(),
             work_repo,
             url: format!("https://github.com/{}/issues/{}", epic_repo, issue_number),
+            base_branch: config.base_branch.clone(),
         });
     }
 
-    Ok(created)
+    // Label all created issues in one batch - only use standard labels that
+    // exist in the repo. Phase info is tracked in the issue body, not via labels
+    let labels = vec!["todo".to_string()];
+    let issue_numbers: Vec<u32> = created.iter().map(|s| s.issue_number).collect();
+    if !issue_numbers.is_empty() {
+        // Labels are nice to have but not critical, so failures only warn
+        match github::add_labels_batch_async(&epic_repo, &issue_numbers, &labels).await {
+            Ok(result) => {
+                for failure in result.failed {
+                    log::warn!(
+                        "Failed to add labels to issue #{}: {}",
+                        failure.issue_number,
+                        failure.error
+                    );
+                }
+            }
+            Err(e) => log::warn!("Failed to add labels to sub-issues: {}", e),
+        }
+    }
+
+    match creation_error {
+        Some(e) => Err(e),
+        None => Ok(created),
+    }
 }
 
 /// Format sub-issue body using standard template
@@ -265,12 +303,15 @@ fn format_sub_issue_body(
         .collect::<Vec<_>>()
         .join("\n");
 
-    // Show work repo if different from tracking repo
-    let work_repo_line = if work_repo != epic_repo {
+    // Show work repo if different from tracking repo, and the base branch if set
+    let mut work_repo_line = if work_repo != epic_repo {
         format!("**Work Repository**: {}\n", work_repo)
     } else {
         String::new()
     };
+    if let Some(base_branch) = &config.base_branch {
+        work_repo_line.push_str(&format!("**Base Branch**: {}\n", base_branch));
+    }
 
     format!(
         r#"# {}
@@ -320,16 +361,11 @@ pub async fn update_epic_progress(
     let epic = github::get_issue_async(&epic_repo, epic_number).await?;
 
     // Find all sub-issues (issues that reference this epic) - include closed for accurate counts
-    let all_issues = github::list_all_issues_async(&epic_repo, vec![]).await?;
+    let all_issues =
+        github::list_all_issues_async(&epic_repo, vec![], github::IssueFields::Progress).await?;
     let sub_issues: Vec<_> = all_issues
         .into_iter()
-        .filter(|issue| {
-            issue
-                .body
-                .as_ref()
-                .map(|b| b.contains(&format!("Epic**: #{}", epic_number)))
-                .unwrap_or(false)
-        })
+        .filter(|issue| references_epic(issue.body.as_deref(), epic_number))
         .collect();
 
     // Count completed (use case-insensitive comparison since GitHub returns uppercase)
@@ -391,6 +427,52 @@ fn update_progress_section(
     result.join("\n")
 }
 
+/// Rewrite the `**Phase**: N` line of a sub-issue body to point at `new_phase`.
+///
+/// Returns `None` if the body has no phase line.
+fn replace_phase_line(body: &str, new_phase: u32) -> Option<String> {
+    let mut found = false;
+    let lines: Vec<String> = body
+        .lines()
+        .map(|line| {
+            if !found && line.contains("**Phase**:") {
+                found = true;
+                let prefix = &line[..line.find("**Phase**:").unwrap_or(0)];
+                format!("{}**Phase**: {}", prefix, new_phase)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    if !found {
+        return None;
+    }
+
+    let mut updated = lines.join("\n");
+    if body.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+/// Point a sub-issue at a different phase by rewriting its `**Phase**` line on GitHub.
+pub async fn set_sub_issue_phase(
+    repo: &str,
+    issue_number: u32,
+    new_phase: u32,
+) -> Result<(), String> {
+    let issue = github::get_issue_async(repo, issue_number).await?;
+    let body = issue.body.unwrap_or_default();
+    let updated = replace_phase_line(&body, new_phase)
+        .ok_or_else(|| format!("Issue #{} has no **Phase** line to update", issue_number))?;
+
+    if updated != body {
+        github::update_issue_body_async(repo, issue_number, &updated).await?;
+    }
+    Ok(())
+}
+
 /// Load an existing epic from GitHub by issue number
 ///
 /// Parses the epic's body to extract phases and metadata.
@@ -449,6 +531,9 @@ pub struct ExistingSubIssue {
     pub pr_url: Option<String>,
     /// PR number if a PR has been created
     pub pr_number: Option<u64>,
+    /// Base branch from the sub-issue body (None = repo default)
+    #[serde(default)]
+    pub base_branch: Option<String>,
 }
 
 /// Recovery information for an epic
@@ -486,18 +571,12 @@ pub async fn load_epic_for_recovery(
     let epic = load_epic(repo.clone(), epic_number).await?;
 
     // Find all sub-issues that reference this epic (include closed for historical context)
-    let all_issues = github::list_all_issues_async(&repo, vec![]).await?;
+    let all_issues = github::list_all_issues_async(&repo, vec![], github::IssueFields::All).await?;
 
     // First pass: collect basic issue info
     let basic_sub_issues: Vec<_> = all_issues
         .into_iter()
-        .filter(|issue| {
-            issue
-                .body
-                .as_ref()
-                .map(|b| b.contains(&format!("Epic**: #{}", epic_number)))
-                .unwrap_or(false)
-        })
+        .filter(|issue| references_epic(issue.body.as_deref(), epic_number))
         .map(|issue| {
             // Extract phase number from body (e.g., "**Phase**: 1")
             let phase = issue.body.as_ref().and_then(|body| {
@@ -512,6 +591,10 @@ pub async fn load_epic_for_recovery(
             });
 
             let has_agent_working = issue.labels.iter().any(|l| l == "staging");
+            let base_branch = issue
+                .body
+                .as_deref()
+                .and_then(extract_base_branch_from_body);
 
             (
                 issue.number as u32,
@@ -521,6 +604,7 @@ pub async fn load_epic_for_recovery(
                 issue.labels,
                 issue.url,
                 has_agent_working,
+                base_branch,
             )
         })
         .collect();
@@ -530,7 +614,9 @@ pub async fn load_epic_for_recovery(
     let work_repo = &epic.work_repo;
     let mut sub_issues: Vec<ExistingSubIssue> = Vec::new();
 
-    for (issue_number, title, phase, state, labels, url, has_agent_working) in basic_sub_issues {
+    for (issue_number, title, phase, state, labels, url, has_agent_working, base_branch) in
+        basic_sub_issues
+    {
         // Only look up PRs for open issues (closed issues are already done)
         let (pr_url, pr_number) = if state.eq_ignore_ascii_case("open") {
             // Try to find a PR that references this issue
@@ -556,6 +642,7 @@ pub async fn load_epic_for_recovery(
             has_agent_working,
             pr_url,
             pr_number,
+            base_branch,
         });
     }
 
@@ -615,6 +702,531 @@ pub async fn load_epic_for_recovery(
     })
 }
 
+/// Whether an issue body links the issue to an epic as a sub-issue
+///
+/// The number must end there, so a sub-issue of #12 doesn't count for #1.
+fn references_epic(body: Option<&str>, epic_number: u32) -> bool {
+    let needle = format!("Epic**: #{}", epic_number);
+    body.is_some_and(|b| {
+        b.match_indices(&needle)
+            .any(|(start, _)| !b[start + needle.len()..].starts_with(|c: char| c.is_ascii_digit()))
+    })
+}
+
+/// An epic PR that is blocking progress
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EpicPrAttention {
+    /// Sub-issue the PR belongs to
+    pub issue_number: u32,
+    /// The PR
+    pub pr: github::GitHubPullRequest,
+    /// Why it needs attention (e.g. "failing checks", "merge conflicts", "awaiting review")
+    pub reasons: Vec<String>,
+}
+
+/// Aggregate status of all PRs for an epic's sub-issues
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct EpicPrSummary {
+    /// Epic issue number
+    pub epic_number: u32,
+    /// Total PRs found for the epic's sub-issues
+    pub total: u32,
+    /// Open, ready-for-review PRs
+    pub open: u32,
+    /// Open draft PRs
+    pub draft: u32,
+    /// Merged PRs
+    pub merged: u32,
+    /// Closed (unmerged) PRs
+    pub closed: u32,
+    /// Open PRs whose checks all pass
+    pub checks_passing: u32,
+    /// Open PRs with failing checks
+    pub checks_failing: u32,
+    /// Open PRs with checks still running
+    pub checks_pending: u32,
+    /// Open PRs that need attention
+    pub needs_attention: Vec<EpicPrAttention>,
+}
+
+/// Why an open PR needs attention (empty if it doesn't)
+fn pr_attention_reasons(status: &github::PrStatus) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if status.checks.state == "failure" {
+        reasons.push("failing checks".to_string());
+    }
+    if status.pr.mergeable == Some(false) {
+        reasons.push("merge conflicts".to_string());
+    }
+    if status.reviews.changes_requested > 0 {
+        reasons.push("changes requested".to_string());
+    } else if !status.pr.is_draft && status.reviews.approved == 0 {
+        reasons.push("awaiting review".to_string());
+    }
+    reasons
+}
+
+/// Summarize the PRs of an epic's sub-issues by state and check status,
+/// listing the open PRs that are blocking the epic.
+pub async fn get_epic_pr_summary(repo: String, epic_number: u32) -> Result<EpicPrSummary, String> {
+    let epic = load_epic(repo.clone(), epic_number).await?;
+    let sub_issues: Vec<u32> =
+        github::list_all_issues_async(&repo, vec![], github::IssueFields::Progress)
+            .await?
+            .into_iter()
+            .filter(|issue| references_epic(issue.body.as_deref(), epic_number))
+            .map(|issue| issue.number as u32)
+            .collect();
+
+    let mut summary = EpicPrSummary {
+        epic_number,
+        ..Default::default()
+    };
+    let mut seen = std::collections::HashSet::new();
+
+    for issue_number in sub_issues {
+        let prs = github::find_prs_for_issue_async(&epic.work_repo, issue_number).await?;
+        for pr in prs {
+            if !seen.insert(pr.number) {
+                continue;
+            }
+            summary.total += 1;
+            match pr.state.to_lowercase().as_str() {
+                "merged" => summary.merged += 1,
+                "closed" => summary.closed += 1,
+                _ => {
+                    if pr.is_draft {
+                        summary.draft += 1;
+                    } else {
+                        summary.open += 1;
+                    }
+
+                    let work_repo = epic.work_repo.clone();
+                    let status = tokio::task::spawn_blocking(move || {
+                        github::get_pr_status(&work_repo, pr.number)
+                    })
+                    .await
+                    .map_err(|e| format!("Task join error: {}", e))??;
+
+                    match status.checks.state.as_str() {
+                        "success" => summary.checks_passing += 1,
+                        "failure" => summary.checks_failing += 1,
+                        "pending" => summary.checks_pending += 1,
+                        _ => {}
+                    }
+                    let reasons = pr_attention_reasons(&status);
+                    if !reasons.is_empty() {
+                        summary.needs_attention.push(EpicPrAttention {
+                            issue_number,
+                            pr: status.pr,
+                            reasons,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// A sub-issue in an epic's dependency graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DependencyNode {
+    /// Issue number
+    pub issue_number: u32,
+    /// Issue title
+    pub title: String,
+    /// Phase number from the issue body
+    pub phase: Option<u32>,
+    /// Current state (open/closed)
+    pub state: String,
+    /// Open sub-issues this one depends on
+    pub blocked_by: Vec<u32>,
+    /// Whether the issue is open and everything it depends on is closed
+    pub ready: bool,
+}
+
+/// A dependency between two sub-issues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DependencyEdge {
+    /// The dependent sub-issue
+    pub issue_number: u32,
+    /// The sub-issue that must be done first
+    pub depends_on: u32,
+}
+
+/// A `**Dependencies**` entry that doesn't name a sub-issue of the epic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct UnresolvedDependency {
+    /// Sub-issue whose dependency couldn't be resolved
+    pub issue_number: u32,
+    /// The entry as written
+    pub reference: String,
+}
+
+/// Dependency graph of an epic's sub-issues
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EpicDependencyGraph {
+    /// Epic issue number
+    pub epic_number: u32,
+    /// Sub-issues, by issue number
+    pub nodes: Vec<DependencyNode>,
+    /// Dependencies between sub-issues
+    pub edges: Vec<DependencyEdge>,
+    /// Sub-issues ordered so each comes after everything it depends on
+    pub topological_order: Vec<u32>,
+    /// Dependencies that didn't match a sub-issue
+    pub unresolved: Vec<UnresolvedDependency>,
+}
+
+/// Extract the `**Dependencies**` field from a sub-issue body
+fn extract_dependencies_from_body(body: &str) -> Option<String> {
+    body.lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("**Dependencies**:"))
+        .map(|d| d.trim().to_string())
+}
+
+/// Issue numbers referenced as `#N` in `text`
+fn parse_issue_refs(text: &str) -> Vec<u32> {
+    text.split('#')
+        .skip(1)
+        .filter_map(|rest| {
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+/// Resolve each sub-issue's dependency list to edges.
+///
+/// Entries are comma- or semicolon-separated and either reference issues
+/// (`#12`) or name another sub-issue by title (case-insensitive).
+fn resolve_dependencies(
+    subs: &[(DependencyNode, String)],
+) -> (Vec<DependencyEdge>, Vec<UnresolvedDependency>) {
+    let mut edges = Vec::new();
+    let mut unresolved = Vec::new();
+
+    for (node, dependencies) in subs {
+        for entry in dependencies.split([',', ';']) {
+            let entry = entry.trim().trim_end_matches('.');
+            if entry.is_empty() || ["none", "n/a", "-"].contains(&entry.to_lowercase().as_str()) {
+                continue;
+            }
+
+            let refs = parse_issue_refs(entry);
+            let targets: Vec<Option<u32>> = if refs.is_empty() {
+                let by_title = subs
+                    .iter()
+                    .find(|(other, _)| other.title.trim().eq_ignore_ascii_case(entry))
+                    .map(|(other, _)| other.issue_number);
+                vec![by_title]
+            } else {
+                refs.into_iter()
+                    .map(|n| {
+                        subs.iter()
+                            .any(|(other, _)| other.issue_number == n)
+                            .then_some(n)
+                    })
+                    .collect()
+            };
+
+            for target in targets {
+                match target {
+                    Some(depends_on) => {
+                        let edge = DependencyEdge {
+                            issue_number: node.issue_number,
+                            depends_on,
+                        };
+                        if !edges.contains(&edge) {
+                            edges.push(edge);
+                        }
+                    }
+                    None => unresolved.push(UnresolvedDependency {
+                        issue_number: node.issue_number,
+                        reference: entry.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+
+    (edges, unresolved)
+}
+
+/// Order `nodes` so dependencies come first (lowest issue number breaks ties).
+///
+/// On a cycle, returns the issues forming it, starting and ending with the same issue.
+fn topological_order(nodes: &[u32], edges: &[DependencyEdge]) -> Result<Vec<u32>, Vec<u32>> {
+    use std::collections::{BTreeSet, HashMap};
+
+    let mut remaining_deps: HashMap<u32, usize> = nodes.iter().map(|&n| (n, 0)).collect();
+    for edge in edges {
+        *remaining_deps.entry(edge.issue_number).or_insert(0) += 1;
+    }
+    let mut ready: BTreeSet<u32> = remaining_deps
+        .iter()
+        .filter(|(_, &count)| count == 0)
+        .map(|(&n, _)| n)
+        .collect();
+
+    let mut order = Vec::new();
+    while let Some(next) = ready.pop_first() {
+        order.push(next);
+        for edge in edges.iter().filter(|e| e.depends_on == next) {
+            let count = remaining_deps.get_mut(&edge.issue_number).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(edge.issue_number);
+            }
+        }
+    }
+
+    if order.len() == remaining_deps.len() {
+        return Ok(order);
+    }
+
+    // Every unordered issue depends on another unordered one: walk until one repeats
+    let mut start = *remaining_deps
+        .iter()
+        .filter(|(n, _)| !order.contains(n))
+        .map(|(n, _)| n)
+        .min()
+        .unwrap();
+    let mut path = vec![start];
+    loop {
+        start = edges
+            .iter()
+            .filter(|e| e.issue_number == start && !order.contains(&e.depends_on))
+            .map(|e| e.depends_on)
+            .min()
+            .unwrap();
+        if let Some(pos) = path.iter().position(|&n| n == start) {
+            let mut cycle = path.split_off(pos);
+            cycle.push(start);
+            return Err(cycle);
+        }
+        path.push(start);
+    }
+}
+
+/// Build the dependency graph from sub-issue nodes and their dependency fields.
+fn build_dependency_graph(
+    epic_number: u32,
+    subs: Vec<(DependencyNode, String)>,
+) -> Result<EpicDependencyGraph, String> {
+    let (edges, unresolved) = resolve_dependencies(&subs);
+    let numbers: Vec<u32> = subs.iter().map(|(node, _)| node.issue_number).collect();
+    let topological_order = topological_order(&numbers, &edges).map_err(|cycle| {
+        let path: Vec<String> = cycle.iter().map(|n| format!("#{}", n)).collect();
+        format!(
+            "Epic #{} has a dependency cycle: {}",
+            epic_number,
+            path.join(" -> ")
+        )
+    })?;
+
+    let closed: Vec<u32> = subs
+        .iter()
+        .filter(|(node, _)| node.state.eq_ignore_ascii_case("closed"))
+        .map(|(node, _)| node.issue_number)
+        .collect();
+    let mut nodes: Vec<DependencyNode> = subs
+        .into_iter()
+        .map(|(mut node, _)| {
+            node.blocked_by = edges
+                .iter()
+                .filter(|e| e.issue_number == node.issue_number && !closed.contains(&e.depends_on))
+                .map(|e| e.depends_on)
+                .collect();
+            node.ready = node.state.eq_ignore_ascii_case("open") && node.blocked_by.is_empty();
+            node
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.issue_number);
+
+    Ok(EpicDependencyGraph {
+        epic_number,
+        nodes,
+        edges,
+        topological_order,
+        unresolved,
+    })
+}
+
+/// Build the dependency graph of an epic's sub-issues from their
+/// `**Dependencies**` fields.
+///
+/// Fails if the dependencies form a cycle. Entries that don't match a
+/// sub-issue are listed in `unresolved` rather than failing.
+pub async fn build_epic_dependency_graph(
+    repo: String,
+    epic_number: u32,
+) -> Result<EpicDependencyGraph, String> {
+    let subs: Vec<(DependencyNode, String)> =
+        github::list_all_issues_async(&repo, vec![], github::IssueFields::All)
+            .await?
+            .into_iter()
+            .filter(|issue| references_epic(issue.body.as_deref(), epic_number))
+            .map(|issue| {
+                let body = issue.body.unwrap_or_default();
+                let phase = body
+                    .lines()
+                    .map(str::trim)
+                    .find_map(|line| line.strip_prefix("**Phase**:"))
+                    .and_then(|p| p.trim().parse().ok());
+                let node = DependencyNode {
+                    issue_number: issue.number as u32,
+                    title: issue.title,
+                    phase,
+                    state: issue.state.to_lowercase(),
+                    blocked_by: Vec::new(),
+                    ready: false,
+                };
+                (
+                    node,
+                    extract_dependencies_from_body(&body).unwrap_or_default(),
+                )
+            })
+            .collect();
+
+    build_dependency_graph(epic_number, subs)
+}
+
+/// Body sections every sub-issue needs, as (marker, name)
+const REQUIRED_SUB_ISSUE_SECTIONS: [(&str, &str); 2] = [
+    ("**Agent Type**:", "Agent Type"),
+    ("## Acceptance Criteria", "Acceptance Criteria"),
+];
+
+/// A sub-issue whose phase doesn't exist in the epic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct OrphanedSubIssue {
+    /// Issue number
+    pub issue_number: u32,
+    /// Issue title
+    pub title: String,
+    /// Phase from the issue body (None = no parsable `**Phase**` line)
+    pub phase: Option<u32>,
+}
+
+/// A sub-issue missing required body sections
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct IncompleteSubIssue {
+    /// Issue number
+    pub issue_number: u32,
+    /// Issue title
+    pub title: String,
+    /// Names of the missing sections
+    pub missing_sections: Vec<String>,
+}
+
+/// Consistency report of an epic's phases and sub-issues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct EpicIntegrityReport {
+    /// Epic issue number
+    pub epic_number: u32,
+    /// Number of phases in the epic body
+    pub phase_count: u32,
+    /// Number of sub-issues referencing the epic
+    pub sub_issue_count: u32,
+    /// Sub-issues whose phase is out of range or missing
+    pub orphaned_sub_issues: Vec<OrphanedSubIssue>,
+    /// Phases (1-indexed) that have no sub-issues
+    pub empty_phases: Vec<u32>,
+    /// Sub-issues missing required body sections
+    pub incomplete_sub_issues: Vec<IncompleteSubIssue>,
+    /// Whether no problems were found
+    pub is_valid: bool,
+}
+
+/// Check sub-issues, given as (number, title, body), against an epic's phases
+fn check_epic_integrity(
+    epic_number: u32,
+    phase_count: u32,
+    subs: Vec<(u32, String, String)>,
+) -> EpicIntegrityReport {
+    let mut report = EpicIntegrityReport {
+        epic_number,
+        phase_count,
+        sub_issue_count: subs.len() as u32,
+        orphaned_sub_issues: Vec::new(),
+        empty_phases: Vec::new(),
+        incomplete_sub_issues: Vec::new(),
+        is_valid: false,
+    };
+    let mut covered = std::collections::HashSet::new();
+
+    for (issue_number, title, body) in subs {
+        let phase: Option<u32> = body
+            .lines()
+            .map(str::trim)
+            .find_map(|line| line.strip_prefix("**Phase**:"))
+            .and_then(|p| p.trim().parse().ok());
+        match phase {
+            Some(p) if (1..=phase_count).contains(&p) => {
+                covered.insert(p);
+            }
+            _ => report.orphaned_sub_issues.push(OrphanedSubIssue {
+                issue_number,
+                title: title.clone(),
+                phase,
+            }),
+        }
+
+        let missing_sections: Vec<String> = REQUIRED_SUB_ISSUE_SECTIONS
+            .iter()
+            .filter(|(marker, _)| !body.lines().any(|l| l.trim().starts_with(marker)))
+            .map(|(_, name)| name.to_string())
+            .collect();
+        if !missing_sections.is_empty() {
+            report.incomplete_sub_issues.push(IncompleteSubIssue {
+                issue_number,
+                title,
+                missing_sections,
+            });
+        }
+    }
+
+    report.empty_phases = (1..=phase_count).filter(|p| !covered.contains(p)).collect();
+    report.is_valid = report.orphaned_sub_issues.is_empty()
+        && report.empty_phases.is_empty()
+        && report.incomplete_sub_issues.is_empty();
+    report
+}
+
+/// Check an epic's phase-to-sub-issue integrity.
+///
+/// Reports sub-issues whose phase is out of range, phases without
+/// sub-issues, and sub-issues missing the Agent Type or Acceptance Criteria
+/// sections, typically left behind by manual edits on GitHub.
+pub async fn validate_epic_integrity(
+    repo: String,
+    epic_number: u32,
+) -> Result<EpicIntegrityReport, String> {
+    let epic = load_epic(repo.clone(), epic_number).await?;
+    let subs = github::list_all_issues_async(&repo, vec![], github::IssueFields::All)
+        .await?
+        .into_iter()
+        .filter(|issue| references_epic(issue.body.as_deref(), epic_number))
+        .map(|issue| {
+            (
+                issue.number as u32,
+                issue.title,
+                issue.body.unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    Ok(check_epic_integrity(
+        epic_number,
+        epic.phases.len() as u32,
+        subs,
+    ))
+}
+
 /// Extract work repository from epic body
 fn extract_work_repo_from_body(body: &str) -> Option<String> {
     for line in body.lines() {
@@ -632,6 +1244,15 @@ fn extract_work_repo_from_body(body: &str) -> Option<String> {
     None
 }
 
+/// Extract base branch from an epic phase section or sub-issue body
+fn extract_base_branch_from_body(body: &str) -> Option<String> {
+    body.lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("**Base Branch**:"))
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+}
+
 /// Extract phases from epic body
 fn extract_phases_from_body(body: &str) -> Vec<PhaseConfig> {
     let mut phases = Vec::new();
@@ -683,10 +1304,19 @@ fn extract_phases_from_body(body: &str) -> Vec<PhaseConfig> {
                 tasks: Vec::new(),
                 files: Vec::new(),
                 dependencies: Vec::new(),
+                base_branch: None,
             });
             continue;
         }
 
+        // Extract base branch
+        if trimmed.starts_with("**Base Branch**:") {
+            if let Some(ref mut phase) = current_phase {
+                phase.base_branch = extract_base_branch_from_body(trimmed);
+            }
+            continue;
+        }
+
         // Extract approach
         if trimmed.starts_with("**Approach**:") {
             if let Some(ref mut phase) = current_phase {
@@ -722,6 +1352,67 @@ fn extract_phases_from_body(body: &str) -> Vec<PhaseConfig> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_references_epic_matches_whole_number() {
+        let body = "# Phase 1\n\n**Epic**: #12\n**Phase**: 1";
+        assert!(references_epic(Some(body), 12));
+        assert!(!references_epic(Some(body), 1));
+        assert!(references_epic(Some("**Epic**: #1"), 1));
+        assert!(!references_epic(None, 1));
+    }
+
+    #[test]
+    fn test_pr_attention_reasons() {
+        let mut status = github::PrStatus {
+            pr: github::GitHubPullRequest {
+                number: 12,
+                title: "Phase 1".to_string(),
+                body: None,
+                state: "open".to_string(),
+                url: String::new(),
+                head_branch: "issue-3".to_string(),
+                base_branch: "main".to_string(),
+                is_draft: false,
+                mergeable: Some(true),
+                labels: vec![],
+                assignees: vec![],
+                milestone: None,
+                author: String::new(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                repo: "org/repo".to_string(),
+            },
+            checks: github::PrCheckStatus {
+                state: "success".to_string(),
+                passing: 2,
+                failing: 0,
+                pending: 0,
+                total: 2,
+            },
+            reviews: github::PrReviewStatus {
+                approved: 1,
+                changes_requested: 0,
+                pending: 0,
+            },
+            review_summary: github::ReviewSummary::default(),
+        };
+        assert!(pr_attention_reasons(&status).is_empty());
+
+        status.reviews.approved = 0;
+        status.checks.state = "failure".to_string();
+        status.pr.mergeable = Some(false);
+        assert_eq!(
+            pr_attention_reasons(&status),
+            vec!["failing checks", "merge conflicts", "awaiting review"]
+        );
+
+        // Drafts aren't waiting on reviewers yet
+        status.pr.is_draft = true;
+        status.checks.state = "pending".to_string();
+        status.pr.mergeable = None;
+        assert!(pr_attention_reasons(&status).is_empty());
+    }
+
     #[test]
     fn test_format_epic_body() {
         let config = EpicConfig {
@@ -737,6 +1428,7 @@ mod tests {
                 tasks: vec![],
                 files: vec![],
                 dependencies: vec![],
+                base_branch: None,
             }],
             labels: vec![],
         };
@@ -764,6 +1456,7 @@ mod tests {
             acceptance_criteria: vec!["Criterion 1".to_string()],
             agent_type: "claude".to_string(),
             work_repo: None,
+            base_branch: None,
         };
 
         let body = format_sub_issue_body(100, "org/repo", "org/repo", &config);
@@ -775,6 +1468,50 @@ mod tests {
         assert!(body.contains("**Agent Type**: claude"));
     }
 
+    #[test]
+    fn test_replace_phase_line() {
+        let body = "# Task\n\n**Epic**: #100\n**Phase**: 1\n**Estimated Time**: 2 hours\n";
+        let updated = replace_phase_line(body, 2).unwrap();
+        assert_eq!(
+            updated,
+            "# Task\n\n**Epic**: #100\n**Phase**: 2\n**Estimated Time**: 2 hours\n"
+        );
+        assert!(replace_phase_line("# Task\n\n**Epic**: #100", 2).is_none());
+    }
+
+    #[test]
+    fn test_phase_base_branch_roundtrip() {
+        let phase = |name: &str, base_branch: Option<&str>| PhaseConfig {
+            name: name.to_string(),
+            description: "Phase description".to_string(),
+            approach: "agent-assisted".to_string(),
+            tasks: vec![],
+            files: vec![],
+            dependencies: vec![],
+            base_branch: base_branch.map(|b| b.to_string()),
+        };
+        let config = EpicConfig {
+            title: "Test Epic".to_string(),
+            repo: "org/repo".to_string(),
+            work_repo: None,
+            goal: "Test goal".to_string(),
+            success_metrics: vec![],
+            phases: vec![
+                phase("Foundation", None),
+                phase("Integration", Some("feature/x")),
+            ],
+            labels: vec![],
+        };
+
+        let body = format_epic_body(&config, "org/repo");
+        let phases = extract_phases_from_body(&body);
+
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].base_branch, None);
+        assert_eq!(phases[1].base_branch.as_deref(), Some("feature/x"));
+        assert_eq!(phases[1].description, "Phase description");
+    }
+
     #[test]
     fn test_update_progress_section() {
         let original = r#"# Epic Title
@@ -791,4 +1528,105 @@ Some notes
         assert!(updated.contains("5/10 sub-issues completed (50%)"));
         assert!(updated.contains("## Notes"));
     }
+
+    #[test]
+    fn test_build_dependency_graph() {
+        let sub = |n: u32, title: &str, state: &str, deps: &str| {
+            (
+                DependencyNode {
+                    issue_number: n,
+                    title: title.to_string(),
+                    phase: Some(1),
+                    state: state.to_string(),
+                    blocked_by: vec![],
+                    ready: false,
+                },
+                deps.to_string(),
+            )
+        };
+
+        let graph = build_dependency_graph(
+            1,
+            vec![
+                sub(12, "Wire up API", "open", "#10, Add schema; #99"),
+                sub(10, "Set up storage", "closed", "None"),
+                sub(11, "Add schema", "open", "#10"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(graph.topological_order, vec![10, 11, 12]);
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(
+            graph.unresolved,
+            vec![UnresolvedDependency {
+                issue_number: 12,
+                reference: "#99".to_string(),
+            }]
+        );
+        let node = |n: u32| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.issue_number == n)
+                .unwrap()
+        };
+        assert!(node(11).ready);
+        assert_eq!(node(12).blocked_by, vec![11]);
+        assert!(!node(12).ready);
+
+        let err = build_dependency_graph(
+            2,
+            vec![
+                sub(1, "A", "open", "#3"),
+                sub(2, "B", "open", "#1"),
+                sub(3, "C", "open", "B"),
+                sub(4, "D", "open", ""),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err, "Epic #2 has a dependency cycle: #1 -> #3 -> #2 -> #1");
+    }
+
+    #[test]
+    fn test_check_epic_integrity() {
+        let config = SubIssueConfig {
+            title: "Add schema".to_string(),
+            phase: 1,
+            estimated_time: "2 hours".to_string(),
+            dependencies: "None".to_string(),
+            goal: "Schema".to_string(),
+            tasks: "- Write it".to_string(),
+            acceptance_criteria: vec!["Migrates".to_string()],
+            agent_type: "claude".to_string(),
+            work_repo: None,
+            base_branch: None,
+        };
+        let body = format_sub_issue_body(7, "org/repo", "org/repo", &config);
+        let report = check_epic_integrity(7, 1, vec![(10, config.title.clone(), body.clone())]);
+        assert!(report.is_valid, "{:?}", report);
+
+        let orphan = replace_phase_line(&body, 4).unwrap();
+        let report = check_epic_integrity(
+            7,
+            2,
+            vec![
+                (10, "Add schema".to_string(), body),
+                (11, "Moved".to_string(), orphan),
+                (
+                    12,
+                    "Hand-written".to_string(),
+                    "**Epic**: #7\n**Phase**: 1".to_string(),
+                ),
+            ],
+        );
+        assert!(!report.is_valid);
+        assert_eq!(report.empty_phases, vec![2]);
+        assert_eq!(report.orphaned_sub_issues.len(), 1);
+        assert_eq!(report.orphaned_sub_issues[0].phase, Some(4));
+        assert_eq!(report.incomplete_sub_issues.len(), 1);
+        assert_eq!(
+            report.incomplete_sub_issues[0].missing_sections,
+            vec!["Agent Type", "Acceptance Criteria"]
+        );
+    }
 }
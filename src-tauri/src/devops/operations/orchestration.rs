@@ -39,6 +39,8 @@ pub struct OrchestrationResult {
     pub started_phases: Vec<u32>,
     /// Any warnings during orchestration
     pub warnings: Vec<String>,
+    /// Sub-issues whose agent failed to spawn, with the error
+    pub failed_spawns: Vec<(u32, String)>,
 }
 
 /// Information about a spawned agent
@@ -85,6 +87,7 @@ pub async fn start_orchestration(
         spawned_agents: Vec::new(),
         started_phases: Vec::new(),
         warnings: Vec::new(),
+        failed_spawns: Vec::new(),
     };
 
     // Determine which phases to process (default to Phase 1)
@@ -95,9 +98,10 @@ pub async fn start_orchestration(
     };
 
     // First, check for existing sub-issues for this epic (include closed to avoid re-creating)
-    let existing_issues = github::list_all_issues_async(&epic.repo, vec![])
-        .await
-        .unwrap_or_default();
+    let existing_issues =
+        github::list_all_issues_async(&epic.repo, vec![], github::IssueFields::All)
+            .await
+            .unwrap_or_default();
     let existing_phase_issues: std::collections::HashMap<u32, _> = existing_issues
         .iter()
         .filter(|issue| {
@@ -154,6 +158,7 @@ pub async fn start_orchestration(
                 agent_type: config.default_agent_type.clone(),
                 work_repo: epic.work_repo.clone(),
                 url: existing.url.clone(),
+                base_branch: epic.phases[phase_idx].base_branch.clone(),
             });
             continue;
         }
@@ -219,6 +224,13 @@ pub async fn start_orchestration(
                     continue;
                 }
 
+                // Point the agent at the phase's files, if the plan listed any
+                let focus_files = epic
+                    .phases
+                    .get((sub_issue.phase as usize).saturating_sub(1))
+                    .map(|phase| phase.files.clone())
+                    .unwrap_or_default();
+
                 // Spawn agent
                 match spawn_agent_for_issue(
                     &epic.repo,
@@ -226,6 +238,8 @@ pub async fn start_orchestration(
                     &sub_issue.agent_type,
                     &sub_issue.work_repo,
                     &config.worktree_base,
+                    sub_issue.base_branch.as_deref(),
+                    focus_files,
                 ) {
                     Ok(agent_info) => {
                         result.spawned_agents.push(agent_info);
@@ -235,6 +249,7 @@ pub async fn start_orchestration(
                             "Failed to spawn agent for issue #{}: {}",
                             sub_issue.issue_number, e
                         ));
+                        result.failed_spawns.push((sub_issue.issue_number, e));
                     }
                 }
             }
@@ -313,6 +328,7 @@ fn create_phase_issue(
         acceptance_criteria: criteria,
         agent_type,
         work_repo: Some(work_repo.to_string()),
+        base_branch: phase.base_branch.clone(),
     }
 }
 
@@ -337,7 +353,16 @@ fn spawn_agent_for_issue(
     agent_type: &str,
     work_repo: &str,
     worktree_base: &str,
+    base_branch: Option<&str>,
+    focus_files: Vec<String>,
 ) -> Result<SpawnedAgentInfo, String> {
+    let repo_config = super::load_repo_config(worktree_base)?.unwrap_or_default();
+    let working_labels = if repo_config.start_labels.is_empty() {
+        vec!["staging".to_string()]
+    } else {
+        repo_config.start_labels
+    };
+
     // Use the orchestrator to spawn the agent
     let config = orchestrator::SpawnConfig {
         repo: repo.to_string(),
@@ -345,9 +370,23 @@ fn spawn_agent_for_issue(
         agent_type: agent_type.to_string(),
         session_name: None,
         worktree_prefix: Some("handy-agent".to_string()),
-        working_labels: vec!["staging".to_string()],
-        use_sandbox: false,    // TODO: Pass from config
-        sandbox_ports: vec![], // Auto-detect ports from project
+        working_labels,
+        use_sandbox: repo_config.sandbox.enabled.unwrap_or(false),
+        sandbox_ports: repo_config.ports, // Empty means auto-detect from project
+        sandbox_docker_args: repo_config.sandbox.extra_docker_args,
+        subpath: None,
+        base_branch: base_branch.map(|b| b.to_string()),
+        base_ref: None,
+        focus_files,
+        resume: false,
+        debug_capture: false,
+        target_machine_id: None,
+        on_branch_conflict: orchestrator::BranchConflictResolution::Fail,
+        verbose: false,
+        push_remote: None,
+        quarantine: false,
+
+        reuse_worktree: None,
     };
 
     let spawn_result = orchestrator::spawn_agent(&config, worktree_base)?;
@@ -386,7 +425,8 @@ pub async fn get_epic_phase_status(
     let body_statuses = extract_phase_statuses_from_body(&epic_body);
 
     // Get all issues that reference this epic (include closed to count completions)
-    let all_issues = github::list_all_issues_async(epic_repo, vec![]).await?;
+    let all_issues =
+        github::list_all_issues_async(epic_repo, vec![], github::IssueFields::All).await?;
 
     let mut phase_statuses = Vec::new();
 
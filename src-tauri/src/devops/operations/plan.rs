@@ -6,11 +6,14 @@
 //! 3. Generate Epic issue configuration
 //! 4. Generate N sub-issue configurations
 //! 5. Create Epic + Sub-issues on GitHub
+//!
+//! Plans can also be imported deterministically from JSON (`import_epic_plan`),
+//! without involving an agent.
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
-use crate::devops::operations;
+use crate::devops::operations::{self, EpicConfig, PhaseConfig};
 
 /// Helper: Determine which agent to use for planning
 fn determine_planning_agent(
@@ -170,6 +173,7 @@ Return ONLY valid JSON in this exact structure (no markdown, no explanation):
             acceptance_criteria: sub.acceptance_criteria.clone(),
             agent_type: sub.agent_type.clone(),
             work_repo: None, // Will inherit from epic
+            base_branch: None,
         })
         .collect();
 
@@ -202,6 +206,181 @@ Return ONLY valid JSON in this exact structure (no markdown, no explanation):
     })
 }
 
+/// Phase approaches accepted in an imported plan
+const PLAN_APPROACHES: &[&str] = &["manual", "agent-assisted", "automated"];
+
+fn default_plan_approach() -> String {
+    "manual".to_string()
+}
+
+/// Phase of an importable epic plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(deny_unknown_fields)]
+pub struct EpicPlanPhase {
+    /// Phase name, unique within the plan
+    pub name: String,
+    /// Phase description
+    #[serde(default)]
+    pub description: String,
+    /// Approach: "manual", "agent-assisted", or "automated"
+    #[serde(default = "default_plan_approach")]
+    pub approach: String,
+    /// Key tasks for this phase (each becomes a sub-issue)
+    pub tasks: Vec<String>,
+    /// Files to modify (optional context for agents)
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Names of phases that must complete first
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Branch that worktrees for this phase are created from
+    #[serde(default)]
+    pub base_branch: Option<String>,
+}
+
+/// Epic plan in the JSON format read by `import_epic_plan`
+///
+/// ```json
+/// { "title": "...", "goal": "...", "phases": [{ "name": "...", "tasks": ["..."] }] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(deny_unknown_fields)]
+pub struct EpicPlan {
+    /// Epic title (without [EPIC] prefix)
+    pub title: String,
+    /// Epic goal (1-2 sentences)
+    pub goal: String,
+    /// Success metrics
+    #[serde(default)]
+    pub success_metrics: Vec<String>,
+    /// Phases in order
+    pub phases: Vec<EpicPlanPhase>,
+    /// Labels to add to the epic
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// Check an imported plan, returning every problem found
+fn validate_epic_plan(plan: &EpicPlan) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    if plan.title.trim().is_empty() {
+        errors.push("title: must not be empty".to_string());
+    }
+    if plan.goal.trim().is_empty() {
+        errors.push("goal: must not be empty".to_string());
+    }
+    if plan.phases.is_empty() {
+        errors.push("phases: at least one phase is required".to_string());
+    }
+
+    let names: Vec<&str> = plan.phases.iter().map(|p| p.name.trim()).collect();
+    for (i, phase) in plan.phases.iter().enumerate() {
+        let name = phase.name.trim();
+        if name.is_empty() {
+            errors.push(format!("phases[{}].name: must not be empty", i));
+        } else if names[..i].contains(&name) {
+            errors.push(format!("phases[{}].name: duplicate phase '{}'", i, name));
+        }
+        if !PLAN_APPROACHES.contains(&phase.approach.as_str()) {
+            errors.push(format!(
+                "phases[{}].approach: '{}' is not one of {}",
+                i,
+                phase.approach,
+                PLAN_APPROACHES.join(", ")
+            ));
+        }
+        if phase.tasks.is_empty() {
+            errors.push(format!(
+                "phases[{}].tasks: at least one task is required",
+                i
+            ));
+        }
+        for (j, task) in phase.tasks.iter().enumerate() {
+            if task.trim().is_empty() {
+                errors.push(format!("phases[{}].tasks[{}]: must not be empty", i, j));
+            }
+        }
+        for dep in &phase.dependencies {
+            if dep.trim() == name {
+                errors.push(format!("phases[{}].dependencies: depends on itself", i));
+            } else if !names.contains(&dep.trim()) {
+                errors.push(format!(
+                    "phases[{}].dependencies: unknown phase '{}'",
+                    i, dep
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid epic plan: {}", errors.join("; ")))
+    }
+}
+
+/// Build an `EpicConfig` from a JSON epic plan, without invoking an agent.
+///
+/// The JSON must match `EpicPlan` exactly (unknown fields are rejected) and
+/// pass validation: non-empty title and goal, uniquely named phases with at
+/// least one task each, known approaches, and dependencies on existing phases.
+pub fn import_epic_plan(
+    json: &str,
+    repo: String,
+    work_repo: Option<String>,
+) -> Result<EpicConfig, String> {
+    let plan: EpicPlan =
+        serde_json::from_str(json).map_err(|e| format!("Invalid epic plan JSON: {}", e))?;
+    validate_epic_plan(&plan)?;
+
+    Ok(EpicConfig {
+        title: plan.title,
+        repo,
+        work_repo,
+        goal: plan.goal,
+        success_metrics: plan.success_metrics,
+        phases: plan
+            .phases
+            .into_iter()
+            .map(|p| PhaseConfig {
+                name: p.name,
+                description: p.description,
+                approach: p.approach,
+                tasks: p.tasks,
+                files: p.files,
+                dependencies: p.dependencies,
+                base_branch: p.base_branch,
+            })
+            .collect(),
+        labels: plan.labels,
+    })
+}
+
+/// Export an `EpicConfig` as a JSON epic plan that `import_epic_plan` accepts
+pub fn export_epic_plan(config: &EpicConfig) -> Result<String, String> {
+    let plan = EpicPlan {
+        title: config.title.clone(),
+        goal: config.goal.clone(),
+        success_metrics: config.success_metrics.clone(),
+        phases: config
+            .phases
+            .iter()
+            .map(|p| EpicPlanPhase {
+                name: p.name.clone(),
+                description: p.description.clone(),
+                approach: p.approach.clone(),
+                tasks: p.tasks.clone(),
+                files: p.files.clone(),
+                dependencies: p.dependencies.clone(),
+                base_branch: p.base_branch.clone(),
+            })
+            .collect(),
+        labels: config.labels.clone(),
+    };
+    serde_json::to_string_pretty(&plan).map_err(|e| format!("Failed to serialize epic plan: {}", e))
+}
+
 /// Helper: Parse agent output (JSON) into Epic and SubIssue configs
 #[derive(Debug, Deserialize)]
 struct PlanStructure {
@@ -325,4 +504,49 @@ mod tests {
         assert_eq!(plan.epic.title, "Test Epic");
         assert_eq!(plan.sub_issues.len(), 1);
     }
+
+    #[test]
+    fn test_import_epic_plan() {
+        let json = r#"{
+            "title": "Offline sync",
+            "goal": "Let the app work without a network connection.",
+            "phases": [
+                {"name": "Storage", "tasks": ["Add local cache"]},
+                {"name": "Sync", "approach": "agent-assisted", "tasks": ["Queue writes", "Replay on reconnect"], "dependencies": ["Storage"]}
+            ]
+        }"#;
+        let config = import_epic_plan(json, "org/tracker".to_string(), None).unwrap();
+        assert_eq!(config.title, "Offline sync");
+        assert_eq!(config.phases.len(), 2);
+        assert_eq!(config.phases[0].approach, "manual");
+        assert_eq!(config.phases[1].tasks.len(), 2);
+
+        let exported = export_epic_plan(&config).unwrap();
+        let reimported = import_epic_plan(&exported, "org/tracker".to_string(), None).unwrap();
+        assert_eq!(reimported.phases[1].dependencies, vec!["Storage"]);
+        assert_eq!(export_epic_plan(&reimported).unwrap(), exported);
+
+        let err = import_epic_plan(
+            r#"{"title": "", "goal": "g", "phases": [{"name": "A", "approach": "yolo", "tasks": [], "dependencies": ["B"]}]}"#,
+            "org/tracker".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("title: must not be empty"), "{}", err);
+        assert!(err.contains("phases[0].approach: 'yolo'"), "{}", err);
+        assert!(
+            err.contains("phases[0].tasks: at least one task"),
+            "{}",
+            err
+        );
+        assert!(err.contains("unknown phase 'B'"), "{}", err);
+
+        let err = import_epic_plan(
+            r#"{"title": "t", "goal": "g", "phases": [], "owner": "me"}"#,
+            "org/tracker".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("unknown field `owner`"), "{}", err);
+    }
 }
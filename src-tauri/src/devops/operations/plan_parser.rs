@@ -269,6 +269,7 @@ fn extract_phases(markdown: &str) -> Result<Vec<PhaseConfig>, String> {
                 tasks: Vec::new(),
                 files: Vec::new(),
                 dependencies: Vec::new(),
+                base_branch: None,
             });
             continue;
         }
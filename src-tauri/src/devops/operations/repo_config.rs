@@ -0,0 +1,161 @@
+//! Per-repo Handy configuration (`.handy/config.toml`).
+//!
+//! A work repo can ship its own conventions so every machine spawning agents
+//! against it behaves the same way:
+//!
+//! ```toml
+//! agent_type = "claude"
+//! start_labels = ["staging"]
+//! pr_labels = ["needs-review"]
+//! ports = ["3000:3000"]
+//!
+//! [sandbox]
+//! enabled = true
+//! extra_docker_args = ["--gpus", "all"]
+//!
+//! [devcontainer]
+//! post_create_commands = ["npm ci", "npm run db:migrate"]
+//!
+//! [[devcontainer.features]]
+//! id = "ghcr.io/devcontainers/features/node:1"
+//! options = { version = "20" }
+//! ```
+//!
+//! Values from the file only fill in what the caller didn't pass explicitly.
+
+use crate::devops::docker::{self, DevContainerFeature};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+
+/// Location of the config file relative to the repo root
+pub const REPO_CONFIG_PATH: &str = ".handy/config.toml";
+
+/// Sandbox settings from a repo config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepoSandboxConfig {
+    /// Whether agents for this repo should run in a Docker sandbox
+    pub enabled: Option<bool>,
+    /// Extra `docker run` arguments for the agent container, one argv item each
+    pub extra_docker_args: Vec<String>,
+}
+
+/// Customizations for generated devcontainers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepoDevContainerConfig {
+    /// Setup commands run after the container is created (e.g. install deps, migrations)
+    pub post_create_commands: Vec<String>,
+    /// Devcontainer features to add alongside the Claude Code feature
+    pub features: Vec<DevContainerFeature>,
+}
+
+/// Handy conventions declared by a work repo
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepoConfig {
+    /// Default agent type (e.g., "claude", "aider")
+    pub agent_type: Option<String>,
+    /// Labels to add to an issue when an agent starts working on it
+    pub start_labels: Vec<String>,
+    /// Labels to add to an issue when its PR is created
+    pub pr_labels: Vec<String>,
+    /// Sandbox settings
+    pub sandbox: RepoSandboxConfig,
+    /// Port mappings (host:container) that replace auto-detection
+    pub ports: Vec<String>,
+    /// Devcontainer customizations
+    pub devcontainer: RepoDevContainerConfig,
+}
+
+/// 1-based line and column of a byte offset in `content`
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+    (line, col)
+}
+
+/// Parse repo config content, reporting errors as `file:line:col: message`
+fn parse_repo_config(content: &str, file: &str) -> Result<RepoConfig, String> {
+    let config: RepoConfig = toml::from_str(content).map_err(|e| match e.span() {
+        Some(span) => {
+            let (line, col) = line_col(content, span.start);
+            format!("{}:{}:{}: {}", file, line, col, e.message())
+        }
+        None => format!("{}: {}", file, e.message()),
+    })?;
+
+    for port in &config.ports {
+        let valid = port.split_once(':').is_some_and(|(host, container)| {
+            host.parse::<u16>().is_ok() && container.parse::<u16>().is_ok()
+        });
+        if !valid {
+            return Err(format!(
+                "{}: invalid port mapping '{}' (expected host:container)",
+                file, port
+            ));
+        }
+    }
+    docker::validate_extra_docker_args(&config.sandbox.extra_docker_args)
+        .map_err(|e| format!("{}: {}", file, e))?;
+
+    Ok(config)
+}
+
+/// Load `.handy/config.toml` from a work repo.
+///
+/// Returns `Ok(None)` when the repo has no config file.
+pub fn load_repo_config(repo_path: &str) -> Result<Option<RepoConfig>, String> {
+    let path = Path::new(repo_path).join(REPO_CONFIG_PATH);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    parse_repo_config(&content, &path.to_string_lossy()).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo_config() {
+        let config = parse_repo_config(
+            "agent_type = \"aider\"\nstart_labels = [\"staging\"]\nports = [\"3000:3000\"]\n\n[sandbox]\nenabled = true\nextra_docker_args = [\"--gpus\", \"all\"]\n",
+            REPO_CONFIG_PATH,
+        )
+        .unwrap();
+        assert_eq!(config.agent_type.as_deref(), Some("aider"));
+        assert_eq!(config.start_labels, vec!["staging"]);
+        assert!(config.pr_labels.is_empty());
+        assert_eq!(config.sandbox.enabled, Some(true));
+        assert_eq!(config.sandbox.extra_docker_args, vec!["--gpus", "all"]);
+
+        let err = parse_repo_config("agent_type = \"claude\"\nports = 3000\n", REPO_CONFIG_PATH)
+            .unwrap_err();
+        assert!(err.starts_with(".handy/config.toml:2:9:"), "{}", err);
+
+        let err = parse_repo_config("ports = [\"3000\"]\n", REPO_CONFIG_PATH).unwrap_err();
+        assert!(err.contains("invalid port mapping '3000'"));
+
+        let err = parse_repo_config(
+            "[sandbox]\nextra_docker_args = [\"--rm\"]\n",
+            REPO_CONFIG_PATH,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("conflicts with flags managed by Handy"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_load_repo_config_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load_repo_config(dir.path().to_str().unwrap()), Ok(None));
+    }
+}
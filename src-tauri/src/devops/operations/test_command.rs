@@ -0,0 +1,327 @@
+//! Test command detection for work repos.
+//!
+//! Inspects the project files in a worktree to work out how its tests are
+//! run, so PR bodies can describe the real command and agents can verify
+//! their work before a PR is opened.
+
+use crate::devops::docker;
+use crate::devops::process::run_with_timeout;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Placeholder `test` script written by `npm init`
+const NPM_PLACEHOLDER_TEST: &str = "no test specified";
+
+/// Number of output lines kept when a test run fails
+const FAILURE_TAIL_LINES: usize = 40;
+
+/// How long a single test command may run before it's killed
+const TEST_RUN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Outcome of running a worktree's tests before opening a PR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct VerificationResult {
+    /// Whether every test command passed (also true when none were detected)
+    pub passed: bool,
+    /// Commands that were run
+    pub commands: Vec<String>,
+    /// Tail of the failing command's output
+    pub output: Option<String>,
+}
+
+/// A test command inferred from a project file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DetectedTestCommand {
+    /// Shell command to run from the worktree root
+    pub command: String,
+    /// Why the command was chosen (e.g. "package.json scripts.test")
+    pub evidence: String,
+}
+
+/// Whether a Makefile declares a `test` target
+fn makefile_has_test_target(content: &str) -> bool {
+    content.lines().any(|line| {
+        line.strip_prefix("test")
+            .map(|rest| rest.trim_start().starts_with(':') && !rest.contains(":="))
+            .unwrap_or(false)
+    })
+}
+
+/// The package manager implied by the lockfiles in `dir`
+fn js_runner(dir: &Path) -> &'static str {
+    if dir.join("bun.lock").exists() || dir.join("bun.lockb").exists() {
+        "bun run test"
+    } else if dir.join("pnpm-lock.yaml").exists() {
+        "pnpm test"
+    } else if dir.join("yarn.lock").exists() {
+        "yarn test"
+    } else {
+        "npm test"
+    }
+}
+
+/// Detect a Cargo project at the root or one directory below it
+fn detect_cargo(dir: &Path) -> Option<DetectedTestCommand> {
+    if let Ok(content) = std::fs::read_to_string(dir.join("Cargo.toml")) {
+        let command = if content.lines().any(|l| l.trim() == "[workspace]") {
+            "cargo test --workspace"
+        } else {
+            "cargo test"
+        };
+        return Some(DetectedTestCommand {
+            command: command.to_string(),
+            evidence: "Cargo.toml".to_string(),
+        });
+    }
+
+    let mut nested: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("Cargo.toml").is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    nested.sort();
+    nested.into_iter().next().map(|sub| DetectedTestCommand {
+        command: format!("cargo test --manifest-path {}/Cargo.toml", sub),
+        evidence: format!("{}/Cargo.toml", sub),
+    })
+}
+
+/// Detect pytest from `pyproject.toml`
+fn detect_python(dir: &Path) -> Option<DetectedTestCommand> {
+    let content = std::fs::read_to_string(dir.join("pyproject.toml")).ok()?;
+    if !content.contains("pytest") {
+        return None;
+    }
+    let command = if dir.join("uv.lock").exists() {
+        "uv run pytest"
+    } else if dir.join("poetry.lock").exists() {
+        "poetry run pytest"
+    } else {
+        "pytest"
+    };
+    Some(DetectedTestCommand {
+        command: command.to_string(),
+        evidence: "pyproject.toml references pytest".to_string(),
+    })
+}
+
+/// Infer the test commands for the project checked out at `worktree_path`.
+///
+/// A Makefile `test` target wins over everything else since it usually wraps
+/// the per-language commands; otherwise every detected ecosystem contributes
+/// one command. Returns an empty list when nothing is recognized.
+pub fn detect_test_command(worktree_path: &str) -> Result<Vec<DetectedTestCommand>, String> {
+    let dir = Path::new(worktree_path);
+    if !dir.is_dir() {
+        return Err(format!("Worktree not found: {}", worktree_path));
+    }
+
+    if let Ok(content) = std::fs::read_to_string(dir.join("Makefile")) {
+        if makefile_has_test_target(&content) {
+            return Ok(vec![DetectedTestCommand {
+                command: "make test".to_string(),
+                evidence: "Makefile test target".to_string(),
+            }]);
+        }
+    }
+
+    let mut detected = Vec::new();
+
+    if let Ok(content) = std::fs::read_to_string(dir.join("package.json")) {
+        let package: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid package.json in {}: {}", worktree_path, e))?;
+        let script = package
+            .get("scripts")
+            .and_then(|s| s.get("test"))
+            .and_then(|t| t.as_str());
+        if let Some(script) = script.filter(|s| !s.contains(NPM_PLACEHOLDER_TEST)) {
+            detected.push(DetectedTestCommand {
+                command: js_runner(dir).to_string(),
+                evidence: format!("package.json scripts.test: {}", script),
+            });
+        }
+    }
+
+    detected.extend(detect_cargo(dir));
+    detected.extend(detect_python(dir));
+
+    if dir.join("go.mod").exists() {
+        detected.push(DetectedTestCommand {
+            command: "go test ./...".to_string(),
+            evidence: "go.mod".to_string(),
+        });
+    }
+
+    Ok(detected)
+}
+
+/// Last `lines` lines of `text`
+fn output_tail(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+/// Run the detected test commands for a worktree.
+///
+/// Commands run inside `container` (the agent's sandbox) when given, and
+/// directly in the worktree otherwise. Each command is killed after
+/// `TEST_RUN_TIMEOUT`. Stops at the first failing command and returns its
+/// output tail as the error.
+pub fn run_test_commands(
+    worktree_path: &str,
+    container: Option<&str>,
+    commands: &[DetectedTestCommand],
+) -> Result<(), String> {
+    for test in commands {
+        let mut cmd = match container {
+            Some(name) => {
+                let mut cmd = Command::new("docker");
+                cmd.args(["exec", "-w", "/workspace", name, "sh", "-c", &test.command]);
+                cmd
+            }
+            None => {
+                let mut cmd = Command::new("sh");
+                cmd.args(["-c", &test.command]).current_dir(worktree_path);
+                cmd
+            }
+        };
+        let output = run_with_timeout(&mut cmd, TEST_RUN_TIMEOUT)
+            .map_err(|e| format!("Failed to run '{}': {}", test.command, e))?;
+
+        if !output.status.success() {
+            let combined = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return Err(format!(
+                "'{}' failed:\n{}",
+                test.command,
+                output_tail(&combined, FAILURE_TAIL_LINES)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Detect and run a worktree's tests, in the sandbox of `issue` (repo and
+/// number) when it has one.
+///
+/// A repo whose tests can't be detected only gets a warning and counts as
+/// having none. Returns the detected commands, and the failing command's
+/// output tail as the error.
+pub fn run_worktree_tests(
+    worktree_path: &str,
+    issue: Option<(&str, u32)>,
+) -> (Vec<DetectedTestCommand>, Result<(), String>) {
+    let commands = match detect_test_command(worktree_path) {
+        Ok(commands) => commands,
+        Err(e) => {
+            log::warn!("Failed to detect test command, not running tests: {}", e);
+            return (Vec::new(), Ok(()));
+        }
+    };
+    let container =
+        issue.and_then(|(repo, number)| docker::container_exists_for_issue(repo, number));
+    let outcome = run_test_commands(worktree_path, container.as_deref(), &commands);
+    (commands, outcome)
+}
+
+/// Run a worktree's tests (see `run_worktree_tests`), reporting the outcome
+/// instead of failing.
+pub fn verify_worktree(worktree_path: &str, issue: Option<(&str, u32)>) -> VerificationResult {
+    let (commands, outcome) = run_worktree_tests(worktree_path, issue);
+    let output = outcome.err();
+    VerificationResult {
+        passed: output.is_none(),
+        commands: commands.into_iter().map(|c| c.command).collect(),
+        output,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_test_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        assert!(detect_test_command(path).unwrap().is_empty());
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"test": "echo \"Error: no test specified\" && exit 1"}}"#,
+        )
+        .unwrap();
+        assert!(detect_test_command(path).unwrap().is_empty());
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"test": "vitest run"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("bun.lockb"), "").unwrap();
+        std::fs::create_dir(dir.path().join("src-tauri")).unwrap();
+        std::fs::write(dir.path().join("src-tauri/Cargo.toml"), "[package]\n").unwrap();
+        let detected = detect_test_command(path).unwrap();
+        assert_eq!(
+            detected
+                .iter()
+                .map(|d| d.command.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "bun run test",
+                "cargo test --manifest-path src-tauri/Cargo.toml"
+            ]
+        );
+        assert_eq!(
+            detected[0].evidence,
+            "package.json scripts.test: vitest run"
+        );
+
+        std::fs::write(
+            dir.path().join("Makefile"),
+            "TEST := 1\ntest: build\n\tgo test\n",
+        )
+        .unwrap();
+        let detected = detect_test_command(path).unwrap();
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].command, "make test");
+    }
+
+    #[test]
+    fn test_run_test_commands_reports_failure_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let command = |cmd: &str| DetectedTestCommand {
+            command: cmd.to_string(),
+            evidence: "test".to_string(),
+        };
+
+        assert!(run_test_commands(path, None, &[command("true")]).is_ok());
+
+        let err = run_test_commands(
+            path,
+            None,
+            &[command("true"), command("echo 'assertion failed'; exit 3")],
+        )
+        .unwrap_err();
+        assert!(
+            err.starts_with("'echo 'assertion failed'; exit 3' failed:"),
+            "{}",
+            err
+        );
+        assert!(err.ends_with("assertion failed"), "{}", err);
+
+        let verification = verify_worktree(path, None);
+        assert!(verification.passed);
+        assert!(verification.commands.is_empty());
+    }
+}
@@ -4,12 +4,16 @@
 //! the agent pipeline, including issue assignment, PR detection, and state management.
 //! Also provides Epic state persistence for tracking active Epic workflows.
 
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use tauri::{AppHandle, Emitter};
-use tauri_plugin_store::StoreExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
 
 use super::github::{self, GitHubPullRequest};
+use super::notifications;
 use super::operations::agent_lifecycle::{
     detect_pr_for_agent, spawn_support_worker, PrDetectionResult, SupportWorkerConfig,
 };
@@ -17,6 +21,7 @@ use super::operations::epic::{EpicInfo, EpicRecoveryInfo, ExistingSubIssue};
 use super::orchestrator::{self, SpawnConfig, SpawnResult};
 use super::pipeline::{PipelineItem, PipelineState, PipelineStatus};
 use super::tmux;
+use super::worktree;
 
 /// Store path for pipeline state.
 pub const PIPELINE_STORE_PATH: &str = "pipeline_store.json";
@@ -24,12 +29,254 @@ pub const PIPELINE_STORE_PATH: &str = "pipeline_store.json";
 /// Store path for Epic state.
 pub const EPIC_STORE_PATH: &str = "epic_store.json";
 
+/// Key in the Epic store listing the PR URLs a `PrOpened` notification was sent for
+const NOTIFIED_PRS_KEY: &str = "notified_prs";
+
+/// How many notified PR URLs are remembered
+const MAX_NOTIFIED_PRS: usize = 500;
+
+/// User override of the directory holding the pipeline and Epic stores
+/// (None = the app data dir)
+static STORE_DIRECTORY: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Held while writing a store so concurrent saves (and relocation) don't interleave
+static STORE_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Where the pipeline and Epic stores are kept.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StoreLocation {
+    /// Directory holding the store files
+    pub directory: String,
+    /// Whether `directory` is a user override rather than the app data dir
+    pub custom: bool,
+    /// Full path of the pipeline store
+    pub pipeline_store: String,
+    /// Full path of the Epic store
+    pub epic_store: String,
+}
+
+/// Validate a store directory setting; empty means the app data dir.
+pub fn parse_store_directory(dir: Option<&str>) -> Result<Option<PathBuf>, String> {
+    let Some(dir) = dir.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    let path = PathBuf::from(dir);
+    if !path.is_absolute() {
+        return Err(format!("Store directory must be an absolute path: {}", dir));
+    }
+    Ok(Some(path))
+}
+
+/// Point the pipeline and Epic stores at `dir` (None = the app data dir).
+///
+/// Only changes where stores are read and written; use `relocate_stores` to move
+/// existing state along with it.
+pub fn set_store_directory(dir: Option<PathBuf>) {
+    *STORE_DIRECTORY.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+/// The app data dir, where stores live unless relocated
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+/// Directory the stores currently live in
+fn store_directory(app: &AppHandle) -> Result<PathBuf, String> {
+    let custom = STORE_DIRECTORY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    match custom {
+        Some(dir) => Ok(dir),
+        None => app_data_dir(app),
+    }
+}
+
+fn lock_store_writes() -> std::sync::MutexGuard<'static, ()> {
+    STORE_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Copy of the last good state, kept next to a store file
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+/// Parse a store file's top-level object (Ok(None) if the file doesn't exist).
+fn read_store_object(
+    path: &Path,
+) -> Result<Option<serde_json::Map<String, serde_json::Value>>, String> {
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| format!("Corrupt store {}: {}", path.display(), e))
+}
+
+/// Read `key` from the store file at `path`.
+///
+/// Falls back to the `.bak` copy of the last good state when the file is
+/// corrupt (e.g. truncated by a crash in an older version).
+fn read_store_value(path: &Path, key: &str) -> Option<serde_json::Value> {
+    match read_store_object(path) {
+        Ok(Some(object)) => return object.get(key).cloned(),
+        Ok(None) => return None,
+        Err(e) => log::warn!("{}; falling back to backup", e),
+    }
+
+    match read_store_object(&backup_path(path)) {
+        Ok(object) => object?.get(key).cloned(),
+        Err(e) => {
+            log::warn!("{}", e);
+            None
+        }
+    }
+}
+
+/// Set `key` in the store file at `path` without ever leaving it half-written.
+///
+/// The new contents go to a temp file that is synced and then renamed over
+/// the store, so a crash leaves either the old or the new state. The
+/// previous state is copied to `.bak` first. Callers hold `STORE_WRITE_LOCK`.
+fn write_store_value(path: &Path, key: &str, value: serde_json::Value) -> Result<(), String> {
+    use std::io::Write;
+
+    let existing = read_store_object(path).ok().flatten();
+    let had_good_state = existing.is_some();
+    let mut object = existing.unwrap_or_default();
+    object.insert(key.to_string(), value);
+    let content = serde_json::to_vec_pretty(&object)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let temp = path.with_extension("json.tmp");
+    let mut file = std::fs::File::create(&temp)
+        .map_err(|e| format!("Failed to create {}: {}", temp.display(), e))?;
+    file.write_all(&content)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+
+    if had_good_state {
+        if let Err(e) = std::fs::copy(path, backup_path(path)) {
+            log::warn!("Failed to back up {}: {}", path.display(), e);
+        }
+    }
+
+    std::fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+/// Get the directory and file paths of the pipeline and Epic stores.
+pub fn get_store_location(app: &AppHandle) -> Result<StoreLocation, String> {
+    let directory = store_directory(app)?;
+    let custom = STORE_DIRECTORY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some();
+
+    let display = |path: &Path| path.to_string_lossy().to_string();
+    Ok(StoreLocation {
+        directory: display(&directory),
+        custom,
+        pipeline_store: display(&directory.join(PIPELINE_STORE_PATH)),
+        epic_store: display(&directory.join(EPIC_STORE_PATH)),
+    })
+}
+
+/// Remove a store file left behind in the directory the stores moved out of
+fn remove_store_files(path: &Path) {
+    for path in [backup_path(path), path.to_path_buf()] {
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove old {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Move the stores in `old_dir` to `target_dir`.
+///
+/// A store that only exists in `target_dir` (e.g. another profile, or a
+/// directory synced from another machine) is adopted as-is. When both
+/// directories hold state for the same store nothing is moved, since keeping
+/// either side would drop the other. Callers hold `STORE_WRITE_LOCK`.
+fn move_store_files(old_dir: &Path, target_dir: &Path) -> Result<(), String> {
+    if old_dir == target_dir {
+        return Ok(());
+    }
+
+    let mut moves = Vec::new();
+    for (file, key) in [
+        (PIPELINE_STORE_PATH, "pipeline"),
+        (EPIC_STORE_PATH, "epic_state"),
+        (EPIC_STORE_PATH, NOTIFIED_PRS_KEY),
+    ] {
+        let Some(value) = read_store_value(&old_dir.join(file), key) else {
+            continue;
+        };
+        let new_path = target_dir.join(file);
+        if read_store_value(&new_path, key).is_some() {
+            return Err(format!(
+                "{} already holds state; move or remove it before relocating the stores there",
+                new_path.display()
+            ));
+        }
+        moves.push((file, key, value));
+    }
+
+    std::fs::create_dir_all(target_dir).map_err(|e| {
+        format!(
+            "Failed to create store directory {}: {}",
+            target_dir.display(),
+            e
+        )
+    })?;
+    for (file, key, value) in &moves {
+        write_store_value(&target_dir.join(file), key, value.clone())?;
+    }
+
+    // Only clear the old directory once every store is safely written
+    for (file, _, _) in &moves {
+        remove_store_files(&old_dir.join(file));
+    }
+    Ok(())
+}
+
+/// Move the pipeline and Epic stores to `new_dir` (None = back to the app data dir).
+///
+/// State in the current stores is copied over and the old files removed.
+/// Fails without changing anything if `new_dir` already holds conflicting state.
+pub fn relocate_stores(app: &AppHandle, new_dir: Option<&str>) -> Result<StoreLocation, String> {
+    let new_dir = parse_store_directory(new_dir)?;
+    let _guard = lock_store_writes();
+
+    let old_dir = store_directory(app)?;
+    let target_dir = match &new_dir {
+        Some(dir) => dir.clone(),
+        None => app_data_dir(app)?,
+    };
+    move_store_files(&old_dir, &target_dir)?;
+
+    set_store_directory(new_dir);
+    get_store_location(app)
+}
+
 /// Configuration for assigning an issue to an agent.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct AssignIssueConfig {
     /// Repository where the issue exists (tracking repo)
     pub tracking_repo: String,
     /// Repository where work will be done
+    /// If empty, falls back to the default work repo for the tracking repo,
+    /// then to the tracking repo itself
+    #[serde(default)]
     pub work_repo: String,
     /// Issue number to assign
     pub issue_number: u64,
@@ -43,6 +290,18 @@ pub struct AssignIssueConfig {
     /// Labels to remove when work starts
     #[serde(default)]
     pub remove_labels: Vec<String>,
+    /// Branch to create the worktree from (e.g., an epic phase's base branch)
+    #[serde(default)]
+    pub base_branch: Option<String>,
+    /// Commit SHA or tag to start the worktree from (overrides `base_branch`)
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    /// Files the agent should focus on (relative to the worktree)
+    #[serde(default)]
+    pub focus_files: Vec<String>,
+    /// Continue on the issue's existing branch (and PR) if one was pushed
+    #[serde(default)]
+    pub resume: bool,
 }
 
 /// Result of assigning an issue to an agent.
@@ -82,6 +341,8 @@ pub struct PipelineSummary {
     pub in_progress: usize,
     /// Items with PRs pending review
     pub pr_pending: usize,
+    /// Items whose PR was closed without merging
+    pub pr_rejected: usize,
     /// Completed items
     pub completed: usize,
     /// Skipped items
@@ -90,28 +351,356 @@ pub struct PipelineSummary {
     pub failed: usize,
 }
 
-/// Load pipeline state from persistent storage.
-pub fn load_pipeline_state(app: &AppHandle) -> PipelineState {
-    let store = match app.store(PIPELINE_STORE_PATH) {
-        Ok(s) => s,
-        Err(_) => return PipelineState::new(),
+/// Completed items on a single day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DailyCount {
+    /// UTC date (YYYY-MM-DD)
+    pub date: String,
+    /// Number of items completed that day
+    pub count: usize,
+}
+
+/// Throughput metrics over finished pipeline items, for retrospectives.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PipelineMetrics {
+    /// Finished items considered (completed, skipped, or failed)
+    pub total: usize,
+    /// Items completed (PR merged)
+    pub completed: usize,
+    /// Items skipped
+    pub skipped: usize,
+    /// Items failed
+    pub failed: usize,
+    /// Average hours from queued to completed (None without completed items)
+    pub avg_hours_to_complete: Option<f64>,
+    /// Share of finished items that were skipped (0.0 - 1.0)
+    pub skip_rate: f64,
+    /// Share of finished items that failed (0.0 - 1.0)
+    pub failure_rate: f64,
+    /// Completed items per day, oldest first
+    pub completed_per_day: Vec<DailyCount>,
+}
+
+/// Persistence backend for pipeline state.
+///
+/// Pipeline operations go through this trait so they can run against the
+/// Tauri store in the app and an in-memory store in tests.
+pub trait PipelineStore {
+    /// Load the current pipeline state (empty if nothing is stored).
+    fn load(&self) -> PipelineState;
+    /// Persist the pipeline state.
+    fn save(&self, state: &PipelineState);
+    /// Load, modify and save the state while holding the store's write lock,
+    /// so a concurrent update isn't overwritten with stale state.
+    fn modify(&self, f: &mut dyn FnMut(&mut PipelineState));
+}
+
+/// Apply `f` to the stored pipeline state as one load-modify-save step (see
+/// `PipelineStore::modify`) and return its result.
+///
+/// `f` runs under the store lock, so slow work (GitHub, tmux) belongs
+/// outside it.
+pub fn update_pipeline<R>(store: &dyn PipelineStore, f: impl FnOnce(&mut PipelineState) -> R) -> R {
+    let mut f = Some(f);
+    let mut result = None;
+    store.modify(&mut |state| result = f.take().map(|f| f(state)));
+    result.expect("PipelineStore::modify runs the update")
+}
+
+/// Write back items updated from a snapshot, skipping any removed meanwhile
+fn write_back_items(store: &dyn PipelineStore, items: &[PipelineItem]) {
+    if items.is_empty() {
+        return;
+    }
+    update_pipeline(store, |state| {
+        for item in items {
+            if let Some(existing) = state.items.get_mut(&item.id) {
+                *existing = item.clone();
+            }
+        }
+    });
+}
+
+/// Pipeline store backed by a JSON file in the store directory.
+pub struct TauriStore<'a> {
+    app: &'a AppHandle,
+}
+
+impl<'a> TauriStore<'a> {
+    pub fn new(app: &'a AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl PipelineStore for TauriStore<'_> {
+    fn load(&self) -> PipelineState {
+        let mut state = store_directory(self.app)
+            .map(|dir| load_pipeline_store_file(&dir.join(PIPELINE_STORE_PATH)))
+            .unwrap_or_else(|_| PipelineState::new());
+
+        // The history limit is a user setting rather than part of the stored state
+        state.max_history = crate::settings::get_settings(self.app).pipeline_max_history;
+        state
+    }
+
+    fn save(&self, state: &PipelineState) {
+        let _guard = lock_store_writes();
+        self.write(state);
+    }
+
+    fn modify(&self, f: &mut dyn FnMut(&mut PipelineState)) {
+        let _guard = lock_store_writes();
+        let mut state = self.load();
+        f(&mut state);
+        self.write(&state);
+    }
+}
+
+impl TauriStore<'_> {
+    /// Write the state to disk; callers hold `lock_store_writes`
+    fn write(&self, state: &PipelineState) {
+        let result = store_directory(self.app)
+            .and_then(|dir| save_pipeline_store_file(&dir.join(PIPELINE_STORE_PATH), state));
+        if let Err(e) = result {
+            log::warn!("Failed to save pipeline state: {}", e);
+        }
+    }
+}
+
+/// Load the pipeline state stored at `path`.
+///
+/// State that doesn't match the current format is recovered entry by entry
+/// instead of being dropped as a whole.
+fn load_pipeline_store_file(path: &Path) -> PipelineState {
+    let Some(value) = read_store_value(path, "pipeline") else {
+        return PipelineState::new();
     };
+    match serde_json::from_value::<PipelineState>(value.clone()) {
+        Ok(state) => state,
+        Err(e) => {
+            let (state, dropped) = recover_pipeline_state(&value);
+            log::warn!(
+                "Pipeline store is corrupt ({}); recovered {} items, skipping {:?} (see diagnose_pipeline_store)",
+                e,
+                state.items.len(),
+                dropped
+            );
+            state
+        }
+    }
+}
+
+/// Save pipeline state to the store file at `path`.
+///
+/// A corrupt store is copied aside first so the entries its recovery skipped
+/// aren't lost; if that copy fails, nothing is written. Callers hold
+/// `lock_store_writes`.
+fn save_pipeline_store_file(path: &Path, state: &PipelineState) -> Result<(), String> {
+    if path.exists() && !diagnose_pipeline_store_file(path).healthy {
+        let backup = backup_corrupt_store(path)?;
+        log::warn!("Copied corrupt pipeline store to {}", backup.display());
+    }
+    let value = serde_json::to_value(state)
+        .map_err(|e| format!("Failed to serialize pipeline state: {}", e))?;
+    write_store_value(path, "pipeline", value)
+}
+
+/// Copy a corrupt store file aside as `<name>.json.corrupt-<timestamp>`
+fn backup_corrupt_store(path: &Path) -> Result<PathBuf, String> {
+    let backup = path.with_extension(format!(
+        "json.corrupt-{}",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    std::fs::copy(path, &backup)
+        .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    Ok(backup)
+}
 
-    if let Some(state_value) = store.get("pipeline") {
-        serde_json::from_value::<PipelineState>(state_value)
+/// In-memory pipeline store, used for testing orchestration without Tauri.
+pub struct InMemoryStore {
+    state: std::sync::Mutex<PipelineState>,
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self {
+            state: std::sync::Mutex::new(PipelineState::new()),
+        }
+    }
+}
+
+impl PipelineStore for InMemoryStore {
+    fn load(&self) -> PipelineState {
+        self.state
+            .lock()
+            .map(|s| s.clone())
             .unwrap_or_else(|_| PipelineState::new())
-    } else {
-        PipelineState::new()
     }
+
+    fn save(&self, state: &PipelineState) {
+        if let Ok(mut current) = self.state.lock() {
+            *current = state.clone();
+        }
+    }
+
+    fn modify(&self, f: &mut dyn FnMut(&mut PipelineState)) {
+        if let Ok(mut current) = self.state.lock() {
+            f(&mut current);
+        }
+    }
+}
+
+/// Load pipeline state from persistent storage.
+pub fn load_pipeline_state(app: &AppHandle) -> PipelineState {
+    TauriStore::new(app).load()
 }
 
 /// Save pipeline state to persistent storage.
 pub fn save_pipeline_state(app: &AppHandle, state: &PipelineState) {
-    if let Ok(store) = app.store(PIPELINE_STORE_PATH) {
-        if let Ok(value) = serde_json::to_value(state) {
-            let _ = store.set("pipeline", value);
+    TauriStore::new(app).save(state)
+}
+
+/// Health of the pipeline store file.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PipelineStoreDiagnosis {
+    /// Path of the pipeline store file
+    pub path: String,
+    /// Whether the file exists
+    pub exists: bool,
+    /// Whether the stored pipeline state parses as a whole
+    pub healthy: bool,
+    /// Why the file or the pipeline state failed to parse
+    pub error: Option<String>,
+    /// Active items that parse on their own
+    pub recoverable_items: usize,
+    /// History items that parse on their own
+    pub recoverable_history: usize,
+    /// Entries `repair_pipeline_store` would drop (e.g. "items.abc", "history[3]")
+    pub unparseable: Vec<String>,
+}
+
+/// Outcome of repairing the pipeline store.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PipelineStoreRepair {
+    /// Copy of the store taken before repairing (None if it was healthy)
+    pub backup_path: Option<String>,
+    /// Active items kept
+    pub recovered_items: usize,
+    /// History items kept
+    pub recovered_history: usize,
+    /// Entries dropped because they could not be parsed
+    pub dropped: Vec<String>,
+}
+
+/// Rebuild pipeline state entry by entry, skipping entries that don't parse.
+///
+/// Returns the recovered state and the entries that were dropped.
+fn recover_pipeline_state(value: &serde_json::Value) -> (PipelineState, Vec<String>) {
+    let mut state = PipelineState::new();
+    let mut dropped = Vec::new();
+
+    if let Some(items) = value.get("items").and_then(|v| v.as_object()) {
+        for (id, item) in items {
+            match serde_json::from_value::<PipelineItem>(item.clone()) {
+                Ok(item) => {
+                    state.items.insert(id.clone(), item);
+                }
+                Err(_) => dropped.push(format!("items.{}", id)),
+            }
         }
     }
+
+    if let Some(history) = value.get("history").and_then(|v| v.as_array()) {
+        for (i, item) in history.iter().enumerate() {
+            match serde_json::from_value::<PipelineItem>(item.clone()) {
+                Ok(item) => state.history.push(item),
+                Err(_) => dropped.push(format!("history[{}]", i)),
+            }
+        }
+    }
+
+    (state, dropped)
+}
+
+/// The stored pipeline value, or the backup's when the file itself is not
+/// valid JSON, along with the file's parse error.
+fn raw_pipeline_value(path: &Path) -> (Option<serde_json::Value>, Option<String>) {
+    match read_store_object(path) {
+        Ok(object) => (object.and_then(|o| o.get("pipeline").cloned()), None),
+        Err(e) => (read_store_value(path, "pipeline"), Some(e)),
+    }
+}
+
+/// Diagnose the pipeline store file at `path`.
+fn diagnose_pipeline_store_file(path: &Path) -> PipelineStoreDiagnosis {
+    let (value, file_error) = raw_pipeline_value(path);
+    let state_error = value
+        .as_ref()
+        .and_then(|v| serde_json::from_value::<PipelineState>(v.clone()).err())
+        .map(|e| format!("Invalid pipeline state: {}", e));
+    let (recovered, unparseable) = value
+        .as_ref()
+        .map(recover_pipeline_state)
+        .unwrap_or_else(|| (PipelineState::new(), Vec::new()));
+
+    let error = file_error.or(state_error);
+    PipelineStoreDiagnosis {
+        path: path.to_string_lossy().to_string(),
+        exists: path.exists(),
+        healthy: error.is_none(),
+        error,
+        recoverable_items: recovered.items.len(),
+        recoverable_history: recovered.history.len(),
+        unparseable,
+    }
+}
+
+/// Repair the pipeline store file at `path`, keeping every entry that parses.
+fn repair_pipeline_store_file(path: &Path) -> Result<PipelineStoreRepair, String> {
+    let _guard = lock_store_writes();
+    let diagnosis = diagnose_pipeline_store_file(path);
+    if diagnosis.healthy {
+        return Ok(PipelineStoreRepair {
+            backup_path: None,
+            recovered_items: diagnosis.recoverable_items,
+            recovered_history: diagnosis.recoverable_history,
+            dropped: Vec::new(),
+        });
+    }
+
+    let backup = backup_corrupt_store(path)?;
+
+    let (value, _) = raw_pipeline_value(path);
+    let (state, dropped) = value
+        .as_ref()
+        .map(recover_pipeline_state)
+        .unwrap_or_else(|| (PipelineState::new(), Vec::new()));
+    let state_value = serde_json::to_value(&state)
+        .map_err(|e| format!("Failed to serialize pipeline state: {}", e))?;
+    write_store_value(path, "pipeline", state_value)?;
+
+    Ok(PipelineStoreRepair {
+        backup_path: Some(backup.to_string_lossy().to_string()),
+        recovered_items: state.items.len(),
+        recovered_history: state.history.len(),
+        dropped,
+    })
+}
+
+/// Check whether the pipeline store parses, and what a repair would keep.
+pub fn diagnose_pipeline_store(app: &AppHandle) -> Result<PipelineStoreDiagnosis, String> {
+    let path = store_directory(app)?.join(PIPELINE_STORE_PATH);
+    Ok(diagnose_pipeline_store_file(&path))
+}
+
+/// Repair a corrupt pipeline store.
+///
+/// The bad file is copied aside (`pipeline_store.json.corrupt-<timestamp>`)
+/// and the state is rebuilt item by item, dropping only entries that can't
+/// be parsed. A file that isn't valid JSON at all is rebuilt from its `.bak`.
+pub fn repair_pipeline_store(app: &AppHandle) -> Result<PipelineStoreRepair, String> {
+    let path = store_directory(app)?.join(PIPELINE_STORE_PATH);
+    repair_pipeline_store_file(&path)
 }
 
 /// Assign an issue to an agent.
@@ -119,35 +708,70 @@ pub fn save_pipeline_state(app: &AppHandle, state: &PipelineState) {
 /// This creates a worktree, spawns a tmux session, updates labels,
 /// and creates a pipeline item to track the work.
 pub fn assign_issue_to_agent(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
+    settings: &crate::settings::AppSettings,
     config: &AssignIssueConfig,
 ) -> Result<AssignIssueResult, String> {
     // 1. Fetch the issue to ensure it exists
     let issue = github::get_issue(&config.tracking_repo, config.issue_number)?;
 
     // 2. Create spawn config
-    let settings = crate::settings::get_settings(app);
+    let work_repo = if config.work_repo.is_empty() {
+        settings
+            .default_work_repos
+            .get(&config.tracking_repo)
+            .cloned()
+            .unwrap_or_else(|| config.tracking_repo.clone())
+    } else {
+        config.work_repo.clone()
+    };
+    let repo_config = super::operations::load_repo_config(&config.repo_path)?.unwrap_or_default();
+    let agent_type = if config.agent_type.is_empty() {
+        repo_config
+            .agent_type
+            .unwrap_or_else(|| "claude".to_string())
+    } else {
+        config.agent_type.clone()
+    };
+    let working_labels = if config.start_labels.is_empty() {
+        repo_config.start_labels
+    } else {
+        config.start_labels.clone()
+    };
     let spawn_config = SpawnConfig {
-        repo: config.work_repo.clone(),
+        repo: work_repo.clone(),
         issue_number: config.issue_number,
-        agent_type: config.agent_type.clone(),
+        agent_type: agent_type.clone(),
         session_name: None,
         worktree_prefix: Some("handy".to_string()),
-        working_labels: config.start_labels.clone(),
-        use_sandbox: settings.sandbox_enabled,
-        sandbox_ports: vec![], // Auto-detect ports from project
+        working_labels,
+        use_sandbox: repo_config
+            .sandbox
+            .enabled
+            .unwrap_or(settings.sandbox_enabled),
+        sandbox_ports: repo_config.ports, // Empty means auto-detect from project
+        sandbox_docker_args: repo_config.sandbox.extra_docker_args,
+        subpath: None,
+        base_branch: config.base_branch.clone(),
+        base_ref: config.base_ref.clone(),
+        focus_files: config.focus_files.clone(),
+        resume: config.resume,
+        debug_capture: false,
+        target_machine_id: None,
+        on_branch_conflict: orchestrator::BranchConflictResolution::Fail,
+        verbose: false,
+        push_remote: None,
+        quarantine: false,
+
+        reuse_worktree: None,
     };
 
     // 3. Spawn the agent (creates worktree and session)
     let spawn_result = orchestrator::spawn_agent(&spawn_config, &config.repo_path)?;
 
     // 4. Create pipeline item
-    let mut pipeline_item = PipelineItem::from_issue(
-        &issue,
-        &config.tracking_repo,
-        &config.work_repo,
-        &config.agent_type,
-    );
+    let mut pipeline_item =
+        PipelineItem::from_issue(&issue, &config.tracking_repo, &work_repo, &agent_type);
 
     // 5. Update pipeline item with session details
     pipeline_item.start_work(
@@ -156,22 +780,36 @@ pub fn assign_issue_to_agent(
         &spawn_result.worktree.branch,
         &spawn_result.machine_id,
     );
+    if let Some(pr) = &spawn_result.existing_pr {
+        pipeline_item.attach_pr(pr);
+    }
 
-    // 6. Update labels on the issue
+    // 6. Update labels, assignee (and project board, if configured) on the issue
+    sync_item_project_status(settings.github_project.as_ref(), &pipeline_item);
     if !config.remove_labels.is_empty() {
         let remove_refs: Vec<&str> = config.remove_labels.iter().map(|s| s.as_str()).collect();
-        let _ = github::update_labels(
+        let _ = github::apply_label_changes(
             &config.tracking_repo,
             config.issue_number,
-            vec![],
-            remove_refs,
+            &[],
+            &remove_refs,
         );
     }
+    if let Some(assignee) = &settings.agent_assignee {
+        if let Err(e) =
+            github::set_issue_assignees(&config.tracking_repo, config.issue_number, &[assignee])
+        {
+            log::warn!(
+                "Failed to assign #{} to {}: {}",
+                config.issue_number,
+                assignee,
+                e
+            );
+        }
+    }
 
     // 7. Save to pipeline state
-    let mut state = load_pipeline_state(app);
-    state.add_item(pipeline_item.clone());
-    save_pipeline_state(app, &state);
+    update_pipeline(store, |state| state.add_item(pipeline_item.clone()));
 
     Ok(AssignIssueResult {
         pipeline_item,
@@ -180,7 +818,10 @@ pub fn assign_issue_to_agent(
 }
 
 /// Skip an issue and update its labels.
-pub fn skip_issue(app: &AppHandle, config: &SkipIssueConfig) -> Result<PipelineItem, String> {
+pub fn skip_issue(
+    store: &dyn PipelineStore,
+    config: &SkipIssueConfig,
+) -> Result<PipelineItem, String> {
     // 1. Fetch the issue
     let issue = github::get_issue(&config.repo, config.issue_number)?;
 
@@ -205,7 +846,12 @@ pub fn skip_issue(app: &AppHandle, config: &SkipIssueConfig) -> Result<PipelineI
         config.remove_labels.iter().map(|s| s.as_str()).collect()
     };
 
-    github::update_labels(&config.repo, config.issue_number, add_labels, remove_labels)?;
+    github::apply_label_changes(
+        &config.repo,
+        config.issue_number,
+        &add_labels,
+        &remove_labels,
+    )?;
 
     // 4. Add comment if reason provided (sanitized to prevent credential leaks)
     if let Some(reason) = &config.reason {
@@ -221,56 +867,55 @@ pub fn skip_issue(app: &AppHandle, config: &SkipIssueConfig) -> Result<PipelineI
     }
 
     // 5. Save to history
-    let mut state = load_pipeline_state(app);
-    state.history.push(pipeline_item.clone());
-    save_pipeline_state(app, &state);
+    update_pipeline(store, |state| state.push_history(pipeline_item.clone()));
 
     Ok(pipeline_item)
 }
 
 /// List all pipeline items, aggregating from multiple sources.
 pub fn list_pipeline_items(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
     work_repo: Option<&str>,
 ) -> Result<Vec<PipelineItem>, String> {
-    let mut state = load_pipeline_state(app);
-
     // Get active sessions
     let sessions = orchestrator::list_agent_statuses().unwrap_or_default();
 
-    // Aggregate pipeline state with session data
     let work_repo = work_repo.unwrap_or("");
-    let items = super::pipeline::aggregate_pipeline_state(&state, &sessions, work_repo);
-
-    // Update state with aggregated items
-    for item in &items {
-        if let Some(existing) = state.items.get_mut(&item.id) {
-            existing.session_name = item.session_name.clone();
-            existing.worktree_path = item.worktree_path.clone();
-            existing.machine_id = item.machine_id.clone();
-            existing.status = item.status;
+    let items = update_pipeline(store, |state| {
+        // Aggregate pipeline state with session data
+        let items = super::pipeline::aggregate_pipeline_state(state, &sessions, work_repo);
+
+        // Update state with aggregated items
+        for item in &items {
+            if let Some(existing) = state.items.get_mut(&item.id) {
+                existing.session_name = item.session_name.clone();
+                existing.worktree_path = item.worktree_path.clone();
+                existing.machine_id = item.machine_id.clone();
+                existing.status = item.status;
+                existing.status_history = item.status_history.clone();
+            }
         }
-    }
-
-    save_pipeline_state(app, &state);
+        items
+    });
     Ok(items)
 }
 
 /// Get pipeline history (completed items).
-pub fn get_pipeline_history(app: &AppHandle, limit: Option<usize>) -> Vec<PipelineItem> {
-    let state = load_pipeline_state(app);
+pub fn get_pipeline_history(store: &dyn PipelineStore, limit: Option<usize>) -> Vec<PipelineItem> {
+    let state = store.load();
     state.get_history(limit).into_iter().cloned().collect()
 }
 
 /// Get pipeline summary statistics.
-pub fn get_pipeline_summary(app: &AppHandle) -> PipelineSummary {
-    let state = load_pipeline_state(app);
+pub fn get_pipeline_summary(store: &dyn PipelineStore) -> PipelineSummary {
+    let state = store.load();
 
     let mut summary = PipelineSummary {
         total: state.items.len(),
         queued: 0,
         in_progress: 0,
         pr_pending: 0,
+        pr_rejected: 0,
         completed: 0,
         skipped: 0,
         failed: 0,
@@ -281,150 +926,534 @@ pub fn get_pipeline_summary(app: &AppHandle) -> PipelineSummary {
             PipelineStatus::Queued => summary.queued += 1,
             PipelineStatus::InProgress => summary.in_progress += 1,
             PipelineStatus::PrPending | PipelineStatus::PrReview => summary.pr_pending += 1,
+            PipelineStatus::PrRejected => summary.pr_rejected += 1,
             PipelineStatus::Completed => summary.completed += 1,
             PipelineStatus::Skipped => summary.skipped += 1,
             PipelineStatus::Failed => summary.failed += 1,
         }
     }
 
-    summary
+    summary
+}
+
+/// Compute throughput metrics from finished pipeline items.
+///
+/// Considers archived history plus finished items not yet archived. With
+/// `since` (RFC 3339), only items finished at or after that time are included.
+pub fn get_pipeline_metrics(
+    store: &dyn PipelineStore,
+    since: Option<&str>,
+) -> Result<PipelineMetrics, String> {
+    let since = since
+        .map(|s| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .map_err(|e| format!("Invalid 'since' timestamp '{}': {}", s, e))
+        })
+        .transpose()?;
+    let parse = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|d| d.with_timezone(&chrono::Utc))
+    };
+
+    let state = store.load();
+    let finished = state
+        .history
+        .iter()
+        .chain(state.items.values().filter(|i| i.is_complete()))
+        .filter_map(|item| {
+            let finished_at = item
+                .completed_at
+                .as_deref()
+                .or_else(|| item.entered_status_at(item.status))
+                .and_then(parse)?;
+            since
+                .is_none_or(|since| finished_at >= since)
+                .then_some((item, finished_at))
+        });
+
+    let mut metrics = PipelineMetrics {
+        total: 0,
+        completed: 0,
+        skipped: 0,
+        failed: 0,
+        avg_hours_to_complete: None,
+        skip_rate: 0.0,
+        failure_rate: 0.0,
+        completed_per_day: Vec::new(),
+    };
+    let mut hours_to_complete = Vec::new();
+    let mut per_day: std::collections::BTreeMap<String, usize> = Default::default();
+
+    for (item, finished_at) in finished {
+        metrics.total += 1;
+        match item.status {
+            PipelineStatus::Completed => {
+                metrics.completed += 1;
+                *per_day
+                    .entry(finished_at.format("%Y-%m-%d").to_string())
+                    .or_default() += 1;
+
+                let queued_at = item
+                    .entered_status_at(PipelineStatus::Queued)
+                    .unwrap_or(&item.created_at);
+                if let Some(queued_at) = parse(queued_at) {
+                    hours_to_complete
+                        .push((finished_at - queued_at).num_seconds().max(0) as f64 / 3600.0);
+                }
+            }
+            PipelineStatus::Skipped => metrics.skipped += 1,
+            PipelineStatus::Failed => metrics.failed += 1,
+            _ => {}
+        }
+    }
+
+    if !hours_to_complete.is_empty() {
+        metrics.avg_hours_to_complete =
+            Some(hours_to_complete.iter().sum::<f64>() / hours_to_complete.len() as f64);
+    }
+    if metrics.total > 0 {
+        metrics.skip_rate = metrics.skipped as f64 / metrics.total as f64;
+        metrics.failure_rate = metrics.failed as f64 / metrics.total as f64;
+    }
+    metrics.completed_per_day = per_day
+        .into_iter()
+        .map(|(date, count)| DailyCount { date, count })
+        .collect();
+
+    Ok(metrics)
+}
+
+/// Detect and link PRs to pipeline items.
+///
+/// This checks for any PRs that match pipeline item branches
+/// and links them automatically.
+pub fn detect_and_link_prs(
+    store: &dyn PipelineStore,
+    work_repo: &str,
+) -> Result<Vec<PipelineItem>, String> {
+    // Get open PRs for the repo
+    let prs = github::list_prs(
+        work_repo,
+        Some("open"),
+        None,
+        Some(100),
+        github::PrFields::Head,
+    )?;
+
+    // Check each active item without a PR
+    let updated_items = update_pipeline(store, |state| {
+        let mut updated_items = Vec::new();
+        for item in state.items.values_mut() {
+            if item.pr_number.is_none() && item.branch_name.is_some() {
+                if let Some(pr) = super::pipeline::detect_pr_for_item(item, &prs) {
+                    item.link_pr(&pr);
+                    updated_items.push(item.clone());
+                }
+            }
+        }
+        updated_items
+    });
+
+    Ok(updated_items)
+}
+
+/// Sync PR status for all pipeline items with PRs.
+///
+/// Items that complete are moved to "done" on the project board, if configured.
+/// Items whose PR was closed without merging become `PrRejected`; they are not
+/// synced again until requeued.
+pub fn sync_all_pr_statuses(
+    store: &dyn PipelineStore,
+    project: Option<&github::ProjectStatusConfig>,
+) -> Result<Vec<PipelineItem>, String> {
+    // Sync a snapshot so GitHub calls don't hold the store lock
+    let mut updated_items = Vec::new();
+    for mut item in store.load().items.into_values() {
+        if item.pr_number.is_some() && item.status != PipelineStatus::PrRejected {
+            let repo = item.work_repo.clone();
+            if super::pipeline::sync_pr_status(&mut item, &repo).unwrap_or(false) {
+                if item.status == PipelineStatus::Completed {
+                    sync_item_project_status(project, &item);
+                }
+                updated_items.push(item);
+            }
+        }
+    }
+
+    // Save updated items and archive completed ones
+    update_pipeline(store, |state| {
+        for item in &updated_items {
+            if let Some(existing) = state.items.get_mut(&item.id) {
+                *existing = item.clone();
+            }
+        }
+        state.archive_completed();
+    });
+
+    Ok(updated_items)
+}
+
+/// Send every item whose PR was closed without merging back to the queue,
+/// moving their project cards back to Todo.
+///
+/// Returns the requeued items.
+pub fn requeue_rejected_items(
+    store: &dyn PipelineStore,
+    project: Option<&github::ProjectStatusConfig>,
+) -> Vec<PipelineItem> {
+    let requeued = update_pipeline(store, |state| {
+        let mut requeued = Vec::new();
+        for item in state.items.values_mut() {
+            if item.status == PipelineStatus::PrRejected {
+                item.requeue();
+                requeued.push(item.clone());
+            }
+        }
+        requeued
+    });
+    // Board updates are network calls, so they run after the store is saved
+    for item in &requeued {
+        sync_item_project_status(project, item);
+    }
+    requeued
+}
+
+/// Move an item's project card to the column for its current status, if it has one.
+fn sync_item_project_status(project: Option<&github::ProjectStatusConfig>, item: &PipelineItem) {
+    if let Some(status) = item.status.project_status() {
+        github::sync_issue_project_status(project, &item.tracking_repo, item.issue_number, status);
+    }
+}
+
+/// Outcome of deleting the branches of a merged PR
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MergedBranchDeletion {
+    /// Work repo the branch lives in
+    pub repo: String,
+    /// Branch name
+    pub branch: String,
+    /// Merged PR
+    pub pr_number: Option<u64>,
+    /// Whether the branch was deleted on GitHub
+    pub remote_deleted: bool,
+    /// Whether the local branch of the item's worktree was deleted
+    pub local_deleted: bool,
+    /// Why the branch was left alone (e.g. it is protected)
+    pub skipped: Option<String>,
+    /// Errors hit while deleting
+    pub errors: Vec<String>,
+}
+
+/// Items whose PR was merged and whose branch is known
+fn merged_branch_items(items: &[PipelineItem]) -> Vec<&PipelineItem> {
+    items
+        .iter()
+        .filter(|item| {
+            item.pr_status == super::pipeline::PrPipelineStatus::Merged
+                && item.branch_name.as_deref().is_some_and(|b| !b.is_empty())
+        })
+        .collect()
+}
+
+/// Delete the remote and local branches of items whose PR was merged.
+///
+/// Pass the items `sync_all_pr_statuses` just updated, so only PRs that
+/// transitioned to merged are touched. Protected branches are skipped. The
+/// local branch is deleted with the worktree left on a detached HEAD, so
+/// worktree cleanup still works as before.
+pub fn delete_merged_branches(items: &[PipelineItem]) -> Vec<MergedBranchDeletion> {
+    let mut results = Vec::new();
+
+    for item in merged_branch_items(items) {
+        let branch = item.branch_name.clone().unwrap_or_default();
+        let mut result = MergedBranchDeletion {
+            repo: item.work_repo.clone(),
+            branch: branch.clone(),
+            pr_number: item.pr_number,
+            remote_deleted: false,
+            local_deleted: false,
+            skipped: None,
+            errors: Vec::new(),
+        };
+
+        match github::get_branch_protection(&item.work_repo, &branch) {
+            Ok(Some(true)) => {
+                result.skipped = Some("branch is protected".to_string());
+                results.push(result);
+                continue;
+            }
+            Ok(Some(false)) => match github::delete_remote_branch(&item.work_repo, &branch) {
+                Ok(deleted) => result.remote_deleted = deleted,
+                Err(e) => result.errors.push(e),
+            },
+            // Already deleted on merge
+            Ok(None) => {}
+            Err(e) => result.errors.push(e),
+        }
+
+        if let Some(worktree_path) = item
+            .worktree_path
+            .as_deref()
+            .filter(|p| std::path::Path::new(p).exists())
+        {
+            match worktree::delete_worktree_branch(worktree_path, &branch) {
+                Ok(()) => result.local_deleted = true,
+                Err(e) => result.errors.push(e),
+            }
+        }
+
+        for error in &result.errors {
+            log::warn!("Failed to delete merged branch {}: {}", branch, error);
+        }
+        if result.remote_deleted || result.local_deleted {
+            log::info!(
+                "Deleted merged branch {} in {} (remote: {}, local: {})",
+                branch,
+                item.work_repo,
+                result.remote_deleted,
+                result.local_deleted
+            );
+        }
+        results.push(result);
+    }
+
+    results
 }
 
-/// Detect and link PRs to pipeline items.
+/// Mark draft PRs ready for review once their work is verifiably done.
 ///
-/// This checks for any PRs that match pipeline item branches
-/// and links them automatically.
-pub fn detect_and_link_prs(app: &AppHandle, work_repo: &str) -> Result<Vec<PipelineItem>, String> {
-    let mut state = load_pipeline_state(app);
-    let mut updated_items = Vec::new();
+/// A draft is promoted when every acceptance-criteria checkbox on the linked
+/// issue is checked and all PR checks pass. Returns the promoted items.
+pub fn auto_ready_draft_prs(store: &dyn PipelineStore) -> Result<Vec<PipelineItem>, String> {
+    // Check a snapshot so GitHub calls don't hold the store lock
+    let mut promoted = Vec::new();
+
+    for mut item in store.load().items.into_values() {
+        let Some(pr_number) = item.pr_number else {
+            continue;
+        };
+        if item.status != PipelineStatus::PrReview {
+            continue;
+        }
 
-    // Get open PRs for the repo
-    let prs = github::list_prs(work_repo, Some("open"), None, Some(100))?;
+        let is_draft = match github::get_pr(&item.work_repo, pr_number) {
+            Ok(pr) => pr.is_draft,
+            Err(e) => {
+                log::warn!("Failed to fetch PR #{}: {}", pr_number, e);
+                continue;
+            }
+        };
+        if !is_draft {
+            continue;
+        }
 
-    // Check each active item without a PR
-    for item in state.items.values_mut() {
-        if item.pr_number.is_none() && item.branch_name.is_some() {
-            if let Some(pr) = super::pipeline::detect_pr_for_item(item, &prs) {
-                item.link_pr(&pr);
-                updated_items.push(item.clone());
+        let criteria_done = github::get_issue(&item.tracking_repo, item.issue_number)
+            .map(|issue| {
+                issue
+                    .body
+                    .as_deref()
+                    .is_some_and(github::acceptance_criteria_complete)
+            })
+            .unwrap_or(false);
+        if !criteria_done {
+            continue;
+        }
+
+        let checks_pass = github::get_pr_checks(&item.work_repo, pr_number)
+            .map(|checks| checks.state == "success")
+            .unwrap_or(false);
+        if !checks_pass {
+            continue;
+        }
+
+        match github::mark_pr_ready(&item.work_repo, pr_number) {
+            Ok(()) => {
+                log::info!(
+                    "Auto-promoted draft PR #{} in {} (issue #{})",
+                    pr_number,
+                    item.work_repo,
+                    item.issue_number
+                );
+                item.pr_status = super::pipeline::PrPipelineStatus::Ready;
+                promoted.push(item);
             }
+            Err(e) => log::warn!("Failed to mark PR #{} ready: {}", pr_number, e),
         }
     }
 
-    // Save updated state
-    if !updated_items.is_empty() {
-        save_pipeline_state(app, &state);
-    }
+    write_back_items(store, &promoted);
+    Ok(promoted)
+}
 
-    Ok(updated_items)
+/// A PR in the review inbox.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReviewQueueItem {
+    /// The pull request
+    pub pr: GitHubPullRequest,
+    /// Whether the user was explicitly requested as a reviewer
+    pub review_requested: bool,
+    /// ID of the Handy pipeline item that produced the PR, if any
+    pub pipeline_item_id: Option<String>,
+    /// Issue the pipeline item is working on, if any
+    pub issue_number: Option<u64>,
 }
 
-/// Sync PR status for all pipeline items with PRs.
-pub fn sync_all_pr_statuses(app: &AppHandle) -> Result<Vec<PipelineItem>, String> {
-    let mut state = load_pipeline_state(app);
-    let mut updated_items = Vec::new();
+/// Work repos referenced by the pipeline and the active epics, sorted and deduplicated.
+pub fn review_queue_repos(state: &PipelineState, epics: &EpicStoreState) -> Vec<String> {
+    let mut repos: Vec<String> = state
+        .items
+        .values()
+        .chain(state.history.iter())
+        .map(|item| item.work_repo.clone())
+        .chain(epics.active_epics.iter().map(|epic| epic.work_repo.clone()))
+        .filter(|repo| !repo.is_empty())
+        .collect();
+    repos.sort();
+    repos.dedup();
+    repos
+}
 
-    for item in state.items.values_mut() {
-        if item.pr_number.is_some() {
-            let repo = item.work_repo.clone();
-            if super::pipeline::sync_pr_status(item, &repo).unwrap_or(false) {
-                updated_items.push(item.clone());
+/// The pipeline item that produced a PR, matched by PR number or head branch
+fn pipeline_item_for_pr<'a>(
+    state: &'a PipelineState,
+    pr: &GitHubPullRequest,
+) -> Option<&'a PipelineItem> {
+    state.find_by_pr(&pr.repo, pr.number).or_else(|| {
+        state
+            .find_by_branch(&pr.head_branch)
+            .filter(|item| item.work_repo == pr.repo)
+    })
+}
+
+/// List open PRs awaiting the user's review across all pipeline repos.
+///
+/// Includes PRs where the user is a requested reviewer, plus open non-draft PRs
+/// produced by Handy agents (usually authored by the user, so GitHub never
+/// requests their review). Repos that fail to list are skipped with a warning.
+pub fn list_prs_awaiting_review(
+    store: &dyn PipelineStore,
+    epics: &EpicStoreState,
+) -> Vec<ReviewQueueItem> {
+    let state = store.load();
+    let mut queue: Vec<ReviewQueueItem> = Vec::new();
+
+    for repo in review_queue_repos(&state, epics) {
+        for (query, review_requested) in [
+            ("review-requested:@me", true),
+            ("is:open draft:false", false),
+        ] {
+            let prs = match github::search_prs(&repo, query, 100) {
+                Ok(prs) => prs,
+                Err(e) => {
+                    log::warn!("Failed to list PRs for review in {}: {}", repo, e);
+                    continue;
+                }
+            };
+            for pr in prs {
+                if queue
+                    .iter()
+                    .any(|q| q.pr.repo == pr.repo && q.pr.number == pr.number)
+                {
+                    continue;
+                }
+                let item = pipeline_item_for_pr(&state, &pr);
+                // Without a review request, only agent-produced PRs belong in the inbox
+                if !review_requested && item.is_none() {
+                    continue;
+                }
+                queue.push(ReviewQueueItem {
+                    review_requested,
+                    pipeline_item_id: item.map(|i| i.id.clone()),
+                    issue_number: item.map(|i| i.issue_number),
+                    pr,
+                });
             }
         }
     }
 
-    // Save updated state
-    if !updated_items.is_empty() {
-        save_pipeline_state(app, &state);
-    }
-
-    // Archive completed items
-    state.archive_completed();
-    save_pipeline_state(app, &state);
-
-    Ok(updated_items)
+    queue
 }
 
 /// Update a specific pipeline item's PR status.
 pub fn update_pipeline_item_pr_status(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
     item_id: &str,
 ) -> Result<Option<PipelineItem>, String> {
-    let mut state = load_pipeline_state(app);
-
-    if let Some(item) = state.items.get_mut(item_id) {
-        if item.pr_number.is_some() {
-            let repo = item.work_repo.clone();
-            super::pipeline::sync_pr_status(item, &repo)?;
-            let updated_item = item.clone();
-            save_pipeline_state(app, &state);
-            return Ok(Some(updated_item));
-        }
+    let Some(mut item) = store.load().items.remove(item_id) else {
+        return Ok(None);
+    };
+    if item.pr_number.is_none() {
+        return Ok(None);
     }
 
-    Ok(None)
+    let repo = item.work_repo.clone();
+    super::pipeline::sync_pr_status(&mut item, &repo)?;
+    write_back_items(store, std::slice::from_ref(&item));
+    Ok(Some(item))
 }
 
 /// Link a PR to a pipeline item.
 pub fn link_pr_to_pipeline_item(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
     item_id: &str,
     pr: &GitHubPullRequest,
 ) -> Result<PipelineItem, String> {
-    let mut state = load_pipeline_state(app);
-
-    if let Some(item) = state.items.get_mut(item_id) {
+    update_pipeline(store, |state| {
+        let item = state
+            .items
+            .get_mut(item_id)
+            .ok_or_else(|| format!("Pipeline item not found: {}", item_id))?;
         item.link_pr(pr);
-        let updated_item = item.clone();
-        save_pipeline_state(app, &state);
-        Ok(updated_item)
-    } else {
-        Err(format!("Pipeline item not found: {}", item_id))
-    }
+        Ok(item.clone())
+    })
 }
 
 /// Get a pipeline item by ID.
-pub fn get_pipeline_item(app: &AppHandle, item_id: &str) -> Option<PipelineItem> {
-    let state = load_pipeline_state(app);
+pub fn get_pipeline_item(store: &dyn PipelineStore, item_id: &str) -> Option<PipelineItem> {
+    let state = store.load();
     state.get_item(item_id).cloned()
 }
 
 /// Find a pipeline item by issue.
 pub fn find_pipeline_item_by_issue(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
     repo: &str,
     issue_number: u64,
 ) -> Option<PipelineItem> {
-    let state = load_pipeline_state(app);
+    let state = store.load();
     state.find_by_issue(repo, issue_number).cloned()
 }
 
 /// Find a pipeline item by session name.
-pub fn find_pipeline_item_by_session(app: &AppHandle, session_name: &str) -> Option<PipelineItem> {
-    let state = load_pipeline_state(app);
+pub fn find_pipeline_item_by_session(
+    store: &dyn PipelineStore,
+    session_name: &str,
+) -> Option<PipelineItem> {
+    let state = store.load();
     state.find_by_session(session_name).cloned()
 }
 
 /// Archive a completed pipeline item.
 pub fn archive_pipeline_item(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
     item_id: &str,
 ) -> Result<Option<PipelineItem>, String> {
-    let mut state = load_pipeline_state(app);
-    let archived = state.archive_item(item_id);
-    save_pipeline_state(app, &state);
-    Ok(archived)
+    Ok(update_pipeline(store, |state| state.archive_item(item_id)))
+}
+
+/// Prune pipeline history to the newest `keep` items. Returns how many were removed.
+pub fn prune_pipeline_history(store: &dyn PipelineStore, keep: usize) -> usize {
+    update_pipeline(store, |state| state.prune_history(keep))
 }
 
 /// Remove a pipeline item (for cleanup).
 pub fn remove_pipeline_item(
-    app: &AppHandle,
+    store: &dyn PipelineStore,
     item_id: &str,
 ) -> Result<Option<PipelineItem>, String> {
-    let mut state = load_pipeline_state(app);
-    let removed = state.remove_item(item_id);
-    save_pipeline_state(app, &state);
-    Ok(removed)
+    Ok(update_pipeline(store, |state| state.remove_item(item_id)))
 }
 
 // ========== Epic State Management ==========
@@ -524,11 +1553,19 @@ pub struct TrackedSubIssue {
     pub pr_number: Option<u64>,
 }
 
-/// Full Epic store state (can track multiple epics, though typically one active)
+/// Full Epic store state: every Epic being orchestrated, plus which one is selected
 #[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
 pub struct EpicStoreState {
-    /// Currently active Epic (the one being orchestrated)
-    pub active_epic: Option<ActiveEpicState>,
+    /// Epics currently being orchestrated
+    #[serde(default)]
+    pub active_epics: Vec<ActiveEpicState>,
+    /// Epic number of the selected Epic (the one `get_active_epic` returns)
+    #[serde(default)]
+    pub current: Option<u32>,
+    /// Tracking repo of the selected Epic. `None` in state saved before epics
+    /// were keyed by repo, where `current` alone picks the Epic.
+    #[serde(default)]
+    pub current_repo: Option<String>,
     /// History of completed epics (for reference)
     pub history: Vec<ActiveEpicState>,
     /// Maximum history to keep
@@ -543,41 +1580,176 @@ fn default_epic_history() -> usize {
 impl EpicStoreState {
     pub fn new() -> Self {
         Self {
-            active_epic: None,
+            active_epics: Vec::new(),
+            current: None,
+            current_repo: None,
             history: Vec::new(),
             max_history: default_epic_history(),
         }
     }
-}
 
-/// Load Epic state from persistent storage.
-pub fn load_epic_state(app: &AppHandle) -> EpicStoreState {
-    let store = match app.store(EPIC_STORE_PATH) {
-        Ok(s) => s,
-        Err(_) => return EpicStoreState::new(),
-    };
+    /// Index of the selected Epic, falling back to the first active one
+    fn current_index(&self) -> Option<usize> {
+        self.current
+            .and_then(|number| {
+                self.active_epics.iter().position(|e| {
+                    e.epic_number == number
+                        && self
+                            .current_repo
+                            .as_ref()
+                            .is_none_or(|repo| *repo == e.tracking_repo)
+                })
+            })
+            .or(if self.active_epics.is_empty() {
+                None
+            } else {
+                Some(0)
+            })
+    }
 
-    if let Some(state_value) = store.get("epic_state") {
-        serde_json::from_value::<EpicStoreState>(state_value)
-            .unwrap_or_else(|_| EpicStoreState::new())
-    } else {
-        EpicStoreState::new()
+    /// The selected Epic
+    pub fn current(&self) -> Option<&ActiveEpicState> {
+        self.current_index().map(|i| &self.active_epics[i])
+    }
+
+    /// The selected Epic, mutably
+    pub fn current_mut(&mut self) -> Option<&mut ActiveEpicState> {
+        self.current_index().map(|i| &mut self.active_epics[i])
+    }
+
+    /// An active Epic by tracking repo and number
+    pub fn find(&self, repo: &str, epic_number: u32) -> Option<&ActiveEpicState> {
+        self.active_epics
+            .iter()
+            .find(|e| e.tracking_repo == repo && e.epic_number == epic_number)
+    }
+
+    /// Select an active Epic, or clear the selection
+    pub fn select(&mut self, epic: Option<(&str, u32)>) {
+        self.current = epic.map(|(_, number)| number);
+        self.current_repo = epic.map(|(repo, _)| repo.to_string());
+    }
+
+    /// Index of the active Epic tracking `repo#issue_number`, preferring the selected one
+    fn index_for_issue(&self, repo: &str, issue_number: u32) -> Option<usize> {
+        let has_issue = |e: &ActiveEpicState| {
+            e.tracking_repo == repo && e.sub_issues.iter().any(|s| s.issue_number == issue_number)
+        };
+        self.current_index()
+            .filter(|&i| has_issue(&self.active_epics[i]))
+            .or_else(|| self.active_epics.iter().position(has_issue))
+    }
+
+    /// The active Epic tracking `repo#issue_number` as a sub-issue, preferring the selected one
+    pub fn epic_for_issue(&self, repo: &str, issue_number: u32) -> Option<&ActiveEpicState> {
+        self.index_for_issue(repo, issue_number)
+            .map(|i| &self.active_epics[i])
+    }
+
+    /// The active Epic tracking `repo#issue_number` as a sub-issue, mutably
+    pub fn epic_for_issue_mut(
+        &mut self,
+        repo: &str,
+        issue_number: u32,
+    ) -> Option<&mut ActiveEpicState> {
+        self.index_for_issue(repo, issue_number)
+            .map(|i| &mut self.active_epics[i])
+    }
+
+    /// Add an Epic, replacing the one with the same repo and number, and
+    /// optionally select it
+    pub fn upsert(&mut self, epic: ActiveEpicState, select: bool) {
+        let (repo, number) = (epic.tracking_repo.clone(), epic.epic_number);
+        match self
+            .active_epics
+            .iter_mut()
+            .find(|e| e.tracking_repo == repo && e.epic_number == number)
+        {
+            Some(existing) => *existing = epic,
+            None => self.active_epics.push(epic),
+        }
+        if select || self.current.is_none() {
+            self.select(Some((&repo, number)));
+        }
+    }
+
+    /// Remove an active Epic; if it was selected, the first remaining one is selected
+    pub fn remove(&mut self, repo: &str, epic_number: u32) -> Option<ActiveEpicState> {
+        let index = self
+            .active_epics
+            .iter()
+            .position(|e| e.tracking_repo == repo && e.epic_number == epic_number)?;
+        let was_selected = self.current_index() == Some(index);
+        let removed = self.active_epics.remove(index);
+        if was_selected {
+            let first = self
+                .active_epics
+                .first()
+                .map(|e| (e.tracking_repo.clone(), e.epic_number));
+            self.select(
+                first
+                    .as_ref()
+                    .map(|(repo, number)| (repo.as_str(), *number)),
+            );
+        }
+        Some(removed)
     }
 }
 
-/// Save Epic state to persistent storage.
-pub fn save_epic_state(app: &AppHandle, state: &EpicStoreState) {
-    if let Ok(store) = app.store(EPIC_STORE_PATH) {
-        if let Ok(value) = serde_json::to_value(state) {
-            let _ = store.set("epic_state", value);
+/// Convert state saved before multiple epics were supported (a single
+/// `active_epic`) to the `active_epics` list.
+fn migrate_epic_state(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = value.as_object_mut() {
+        if !obj.contains_key("active_epics") {
+            if let Some(legacy) = obj.remove("active_epic").filter(|v| !v.is_null()) {
+                let current = legacy.get("epic_number").cloned();
+                obj.insert("active_epics".to_string(), serde_json::json!([legacy]));
+                obj.insert(
+                    "current".to_string(),
+                    current.unwrap_or(serde_json::Value::Null),
+                );
+            }
         }
     }
+    value
 }
 
-/// Set the active Epic from an EpicInfo (when first linking an Epic).
-pub fn set_active_epic(app: &AppHandle, epic_info: &EpicInfo) -> ActiveEpicState {
+/// Load Epic state from persistent storage.
+pub fn load_epic_state(app: &AppHandle) -> EpicStoreState {
+    store_directory(app)
+        .ok()
+        .and_then(|dir| read_store_value(&dir.join(EPIC_STORE_PATH), "epic_state"))
+        .and_then(|value| serde_json::from_value::<EpicStoreState>(migrate_epic_state(value)).ok())
+        .unwrap_or_else(EpicStoreState::new)
+}
+
+/// Apply `f` to the stored Epic state and save it as one step, holding the
+/// store lock so a concurrent update isn't overwritten with stale state.
+/// Returns what `f` returns.
+pub fn update_epic_state<R>(app: &AppHandle, f: impl FnOnce(&mut EpicStoreState) -> R) -> R {
+    let _guard = lock_store_writes();
     let mut state = load_epic_state(app);
+    let result = f(&mut state);
+    write_epic_state(app, &state);
+    result
+}
+
+/// Write Epic state to disk; callers hold `lock_store_writes`
+fn write_epic_state(app: &AppHandle, state: &EpicStoreState) {
+    let result = store_directory(app).and_then(|dir| {
+        let value = serde_json::to_value(state)
+            .map_err(|e| format!("Failed to serialize Epic state: {}", e))?;
+        write_store_value(&dir.join(EPIC_STORE_PATH), "epic_state", value)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to save Epic state: {}", e);
+    }
+}
 
+/// Link an Epic from an EpicInfo and select it.
+///
+/// Other active epics are kept; re-linking an Epic replaces its tracked state.
+pub fn set_active_epic(app: &AppHandle, epic_info: &EpicInfo) -> ActiveEpicState {
     // Convert phases to tracked phases
     let tracked_phases: Vec<TrackedPhase> = epic_info
         .phases
@@ -593,30 +1765,28 @@ pub fn set_active_epic(app: &AppHandle, epic_info: &EpicInfo) -> ActiveEpicState
         })
         .collect();
 
-    // Preserve existing local_repo_path if we're re-linking the same epic
-    let existing_local_path = state
-        .active_epic
-        .as_ref()
-        .filter(|e| e.epic_number == epic_info.epic_number)
-        .and_then(|e| e.local_repo_path.clone());
-
-    let active = ActiveEpicState {
-        epic_number: epic_info.epic_number,
-        tracking_repo: epic_info.repo.clone(),
-        work_repo: epic_info.work_repo.clone(),
-        local_repo_path: existing_local_path,
-        title: epic_info.title.clone(),
-        url: epic_info.url.clone(),
-        phases: tracked_phases,
-        sub_issues: Vec::new(),
-        linked_at: chrono::Utc::now().to_rfc3339(),
-        last_synced_at: None,
-    };
-
-    state.active_epic = Some(active.clone());
-    save_epic_state(app, &state);
+    update_epic_state(app, |state| {
+        // Preserve existing local_repo_path if we're re-linking the same epic
+        let existing_local_path = state
+            .find(&epic_info.repo, epic_info.epic_number)
+            .and_then(|e| e.local_repo_path.clone());
+
+        let active = ActiveEpicState {
+            epic_number: epic_info.epic_number,
+            tracking_repo: epic_info.repo.clone(),
+            work_repo: epic_info.work_repo.clone(),
+            local_repo_path: existing_local_path,
+            title: epic_info.title.clone(),
+            url: epic_info.url.clone(),
+            phases: tracked_phases,
+            sub_issues: Vec::new(),
+            linked_at: chrono::Utc::now().to_rfc3339(),
+            last_synced_at: None,
+        };
 
-    active
+        state.upsert(active.clone(), true);
+        active
+    })
 }
 
 /// Extract phase status from the Epic issue body.
@@ -669,7 +1839,7 @@ fn extract_phase_statuses_from_body(
     statuses
 }
 
-/// Set the active Epic from recovery info (more complete data).
+/// Link an Epic from recovery info (more complete data) and select it.
 ///
 /// This also cross-references with active tmux sessions to populate
 /// has_agent_working more accurately.
@@ -677,8 +1847,18 @@ pub fn set_active_epic_from_recovery(
     app: &AppHandle,
     recovery: &EpicRecoveryInfo,
 ) -> ActiveEpicState {
-    let mut state = load_epic_state(app);
+    update_epic_state(app, |state| {
+        let active = active_epic_from_recovery(state, recovery);
+        state.upsert(active.clone(), true);
+        active
+    })
+}
 
+/// Build the tracked state of an Epic from recovery info.
+fn active_epic_from_recovery(
+    state: &EpicStoreState,
+    recovery: &EpicRecoveryInfo,
+) -> ActiveEpicState {
     // Extract phase statuses from the Epic body (for manually completed phases)
     let body_statuses = extract_phase_statuses_from_body(&recovery.epic_body);
 
@@ -822,12 +2002,10 @@ pub fn set_active_epic_from_recovery(
 
     // Preserve existing local_repo_path if we're re-loading the same epic
     let existing_local_path = state
-        .active_epic
-        .as_ref()
-        .filter(|e| e.epic_number == recovery.epic.epic_number)
+        .find(&recovery.epic.repo, recovery.epic.epic_number)
         .and_then(|e| e.local_repo_path.clone());
 
-    let active = ActiveEpicState {
+    ActiveEpicState {
         epic_number: recovery.epic.epic_number,
         tracking_repo: recovery.epic.repo.clone(),
         work_repo: recovery.epic.work_repo.clone(),
@@ -838,39 +2016,57 @@ pub fn set_active_epic_from_recovery(
         sub_issues: tracked_sub_issues,
         linked_at: chrono::Utc::now().to_rfc3339(),
         last_synced_at: Some(chrono::Utc::now().to_rfc3339()),
-    };
-
-    state.active_epic = Some(active.clone());
-    save_epic_state(app, &state);
-
-    active
+    }
 }
 
-/// Get the currently active Epic state.
+/// Get the selected active Epic state.
 pub fn get_active_epic(app: &AppHandle) -> Option<ActiveEpicState> {
     let state = load_epic_state(app);
-    state.active_epic
+    state.current().cloned()
 }
 
-/// Update the local repository path for the active Epic.
-pub fn set_epic_local_repo_path(app: &AppHandle, local_repo_path: &str) -> Result<(), String> {
-    let mut state = load_epic_state(app);
+/// List every active Epic.
+pub fn list_active_epics(app: &AppHandle) -> Vec<ActiveEpicState> {
+    load_epic_state(app).active_epics
+}
+
+/// Select which active Epic `get_active_epic` and the Epic commands work on.
+pub fn switch_active_epic(
+    app: &AppHandle,
+    repo: &str,
+    epic_number: u32,
+) -> Result<ActiveEpicState, String> {
+    update_epic_state(app, |state| {
+        let epic = state
+            .find(repo, epic_number)
+            .cloned()
+            .ok_or_else(|| format!("Epic {}#{} is not active", repo, epic_number))?;
+        state.select(Some((repo, epic_number)));
+        Ok(epic)
+    })
+}
 
-    if let Some(ref mut active) = state.active_epic {
+/// Update the local repository path for the selected Epic.
+pub fn set_epic_local_repo_path(app: &AppHandle, local_repo_path: &str) -> Result<(), String> {
+    update_epic_state(app, |state| {
+        let active = state
+            .current_mut()
+            .ok_or_else(|| "No active Epic to update".to_string())?;
         active.local_repo_path = Some(local_repo_path.to_string());
-        save_epic_state(app, &state);
         log::info!("Updated Epic local_repo_path to: {}", local_repo_path);
         Ok(())
-    } else {
-        Err("No active Epic to update".to_string())
-    }
+    })
 }
 
-/// Clear the active Epic (move to history if completed).
+/// Clear the selected Epic (move to history if completed).
+///
+/// The first remaining active Epic, if any, becomes selected.
 pub fn clear_active_epic(app: &AppHandle, archive: bool) -> Option<ActiveEpicState> {
-    let mut state = load_epic_state(app);
+    update_epic_state(app, |state| {
+        let current = state.current()?;
+        let (repo, number) = (current.tracking_repo.clone(), current.epic_number);
 
-    if let Some(active) = state.active_epic.take() {
+        let active = state.remove(&repo, number)?;
         if archive {
             state.history.push(active.clone());
             // Trim history
@@ -878,51 +2074,184 @@ pub fn clear_active_epic(app: &AppHandle, archive: bool) -> Option<ActiveEpicSta
                 state.history.remove(0);
             }
         }
-        save_epic_state(app, &state);
-        return Some(active);
-    }
-
-    None
+        Some(active)
+    })
 }
 
-/// Update a sub-issue's agent assignment in the active Epic.
+/// Update a sub-issue's agent assignment in whichever active Epic tracks it.
 pub fn update_epic_sub_issue_agent(
     app: &AppHandle,
+    repo: &str,
     issue_number: u32,
     session_name: Option<&str>,
     agent_type: Option<&str>,
 ) -> Result<(), String> {
-    let mut state = load_epic_state(app);
+    update_epic_state(app, |state| {
+        let sub = state
+            .epic_for_issue_mut(repo, issue_number)
+            .and_then(|active| {
+                active
+                    .sub_issues
+                    .iter_mut()
+                    .find(|s| s.issue_number == issue_number)
+            })
+            .ok_or_else(|| format!("Sub-issue {} not found in any active epic", issue_number))?;
+        sub.session_name = session_name.map(|s| s.to_string());
+        sub.agent_session = session_name.map(|s| s.to_string()); // Also set agent_session for PR tracking
+        sub.agent_type = agent_type.map(|s| s.to_string());
+        sub.has_agent_working = session_name.is_some();
+        Ok(())
+    })
+}
+
+/// Status of a phase derived from its tracked sub-issues.
+fn tracked_phase_status(
+    subs: &[&TrackedSubIssue],
+    current: TrackedPhaseStatus,
+) -> TrackedPhaseStatus {
+    if subs.is_empty() {
+        // Nothing left to derive from; only a manual skip survives
+        return if current == TrackedPhaseStatus::Skipped {
+            current
+        } else {
+            TrackedPhaseStatus::NotStarted
+        };
+    }
+
+    let open: Vec<_> = subs
+        .iter()
+        .filter(|s| !s.state.eq_ignore_ascii_case("closed"))
+        .collect();
+    if open.is_empty() {
+        TrackedPhaseStatus::Completed
+    } else if open.iter().all(|s| s.pr_url.is_some()) {
+        TrackedPhaseStatus::Ready
+    } else {
+        TrackedPhaseStatus::InProgress
+    }
+}
+
+/// Reassign a tracked sub-issue to `new_phase`, refreshing the sub-issue lists,
+/// counts and statuses of the phases it left and joined.
+///
+/// Returns the phase the sub-issue was previously in.
+fn reassign_sub_issue_phase(
+    active: &mut ActiveEpicState,
+    issue_number: u32,
+    new_phase: u32,
+) -> Result<Option<u32>, String> {
+    if !active.phases.iter().any(|p| p.phase_number == new_phase) {
+        return Err(format!(
+            "Epic #{} has no phase {}",
+            active.epic_number, new_phase
+        ));
+    }
+
+    let sub = active
+        .sub_issues
+        .iter_mut()
+        .find(|s| s.issue_number == issue_number)
+        .ok_or_else(|| format!("Sub-issue {} not found in active epic", issue_number))?;
+    let old_phase = sub.phase.replace(new_phase);
 
-    if let Some(ref mut active) = state.active_epic {
-        if let Some(sub) = active
+    for phase in active
+        .phases
+        .iter_mut()
+        .filter(|p| p.phase_number == new_phase || Some(p.phase_number) == old_phase)
+    {
+        let subs: Vec<&TrackedSubIssue> = active
             .sub_issues
-            .iter_mut()
-            .find(|s| s.issue_number == issue_number)
-        {
-            sub.session_name = session_name.map(|s| s.to_string());
-            sub.agent_session = session_name.map(|s| s.to_string()); // Also set agent_session for PR tracking
-            sub.agent_type = agent_type.map(|s| s.to_string());
-            sub.has_agent_working = session_name.is_some();
-            save_epic_state(app, &state);
-            return Ok(());
-        }
+            .iter()
+            .filter(|s| s.phase == Some(phase.phase_number))
+            .collect();
+        phase.sub_issues = subs.iter().map(|s| s.issue_number).collect();
+        phase.completed_count = subs
+            .iter()
+            .filter(|s| s.state.eq_ignore_ascii_case("closed"))
+            .count();
+        phase.total_count = subs.len();
+        phase.status = tracked_phase_status(&subs, phase.status);
+    }
+
+    Ok(old_phase)
+}
+
+/// Move a sub-issue to a different phase.
+///
+/// Rewrites the `**Phase**` line in the issue body on GitHub and, when the issue
+/// belongs to an active Epic, updates its tracked phase along with the counts
+/// and statuses of the affected phases. Returns the updated Epic state, or `None`
+/// if the issue isn't tracked by an active Epic.
+pub async fn move_sub_issue_to_phase(
+    app: &AppHandle,
+    repo: &str,
+    issue_number: u32,
+    new_phase: u32,
+) -> Result<Option<ActiveEpicState>, String> {
+    // Validate against a copy first so a bad phase never touches GitHub
+    if let Some(active) = load_epic_state(app).epic_for_issue_mut(repo, issue_number) {
+        reassign_sub_issue_phase(active, issue_number, new_phase)?;
     }
 
-    Err(format!(
-        "Sub-issue {} not found in active epic",
-        issue_number
-    ))
+    super::operations::set_sub_issue_phase(repo, issue_number, new_phase).await?;
+
+    // Apply to the state as it is now, not as it was before the GitHub update
+    let moved = update_epic_state(app, |state| {
+        let Some(active) = state.epic_for_issue_mut(repo, issue_number) else {
+            return Ok(None);
+        };
+        let old_phase = reassign_sub_issue_phase(active, issue_number, new_phase)?;
+        Ok::<_, String>(Some((old_phase, active.clone())))
+    })?;
+    let Some((old_phase, updated)) = moved else {
+        return Ok(None);
+    };
+
+    log::info!(
+        "Moved sub-issue #{} of Epic #{} from phase {:?} to phase {}",
+        issue_number,
+        updated.epic_number,
+        old_phase,
+        new_phase
+    );
+    Ok(Some(updated))
 }
 
-/// Sync the active Epic state with GitHub.
+/// Sync every active Epic with GitHub and return the selected one.
 ///
 /// This preserves locally-tracked state (pr_url, agent_session, etc.) while
-/// updating GitHub-sourced state (issue state, labels, etc.).
+/// updating GitHub-sourced state (issue state, labels, etc.). A failure to
+/// sync the selected Epic is returned; failures for the others are logged.
 pub async fn sync_active_epic(app: &AppHandle) -> Result<Option<ActiveEpicState>, String> {
     let state = load_epic_state(app);
+    let key = |e: &ActiveEpicState| (e.tracking_repo.clone(), e.epic_number);
+    let current = state.current().map(key);
+    let epics: Vec<(String, u32)> = state.active_epics.iter().map(key).collect();
+
+    let mut synced_current = None;
+    for epic in epics {
+        let is_current = Some(&epic) == current.as_ref();
+        match sync_epic(app, &epic.0, epic.1).await {
+            Ok(updated) if is_current => synced_current = updated,
+            Ok(_) => {}
+            Err(e) if is_current => return Err(e),
+            Err(e) => log::warn!("Failed to sync Epic {}#{}: {}", epic.0, epic.1, e),
+        }
+    }
+    Ok(synced_current)
+}
+
+/// Sync one active Epic with GitHub, keeping the current selection.
+///
+/// Returns `None` if the Epic isn't active.
+async fn sync_epic(
+    app: &AppHandle,
+    repo: &str,
+    epic_number: u32,
+) -> Result<Option<ActiveEpicState>, String> {
+    let state = load_epic_state(app);
 
-    if let Some(active) = &state.active_epic {
+    if let Some(active) = state.find(repo, epic_number) {
         // Save local-only state before reload
         let local_state: std::collections::HashMap<
             u32,
@@ -951,7 +2280,7 @@ pub async fn sync_active_epic(app: &AppHandle) -> Result<Option<ActiveEpicState>
         .await?;
 
         // Update with fresh data (this recreates sub_issues)
-        let mut updated = set_active_epic_from_recovery(app, &recovery);
+        let mut updated = active_epic_from_recovery(&state, &recovery);
 
         // Restore local-only state that GitHub doesn't know about
         for sub_issue in &mut updated.sub_issues {
@@ -976,9 +2305,7 @@ pub async fn sync_active_epic(app: &AppHandle) -> Result<Option<ActiveEpicState>
         }
 
         // Save the merged state
-        let mut final_state = load_epic_state(app);
-        final_state.active_epic = Some(updated.clone());
-        save_epic_state(app, &final_state);
+        update_epic_state(app, |state| state.upsert(updated.clone(), false));
 
         Ok(Some(updated))
     } else {
@@ -995,68 +2322,62 @@ pub async fn sync_active_epic(app: &AppHandle) -> Result<Option<ActiveEpicState>
 /// 3. Optionally update the Epic issue on GitHub
 pub async fn on_pipeline_item_complete(
     app: &AppHandle,
+    repo: &str,
     issue_number: u32,
     update_github: bool,
 ) -> Result<(), String> {
     let state = load_epic_state(app);
 
-    if let Some(active) = &state.active_epic {
-        // Check if this issue belongs to the active Epic
-        let belongs_to_epic = active
-            .sub_issues
-            .iter()
-            .any(|s| s.issue_number == issue_number);
+    // Find the active Epic this issue belongs to
+    if let Some(active) = state.epic_for_issue(repo, issue_number) {
+        log::info!(
+            "Pipeline item #{} completed, belongs to Epic #{}",
+            issue_number,
+            active.epic_number
+        );
 
-        if belongs_to_epic {
-            log::info!(
-                "Pipeline item #{} completed, belongs to Epic #{}",
-                issue_number,
-                active.epic_number
-            );
+        // Sync Epic state with GitHub to get latest status
+        let updated = sync_epic(app, &active.tracking_repo, active.epic_number).await?;
+
+        // Optionally update the Epic issue on GitHub with new phase status
+        if update_github {
+            if let Some(updated_state) = updated {
+                // Build phase statuses from the updated state
+                let phase_statuses: Vec<super::operations::PhaseStatus> = updated_state
+                    .phases
+                    .iter()
+                    .map(|p| super::operations::PhaseStatus {
+                        phase_number: p.phase_number,
+                        phase_name: p.name.clone(),
+                        approach: match p.status {
+                            TrackedPhaseStatus::Completed => "manual".to_string(),
+                            _ => "agent-assisted".to_string(),
+                        },
+                        total_issues: p.total_count as u32,
+                        completed_issues: p.completed_count as u32,
+                        in_progress_issues: 0, // Would need to calculate from sub_issues
+                        status: match p.status {
+                            TrackedPhaseStatus::Completed => "completed".to_string(),
+                            TrackedPhaseStatus::Ready => "ready".to_string(),
+                            TrackedPhaseStatus::InProgress => "in_progress".to_string(),
+                            TrackedPhaseStatus::NotStarted => "not_started".to_string(),
+                            TrackedPhaseStatus::Skipped => "skipped".to_string(),
+                        },
+                    })
+                    .collect();
+
+                // Update Epic issue on GitHub
+                super::operations::update_epic_phase_status_on_github(
+                    &updated_state.tracking_repo,
+                    updated_state.epic_number,
+                    &phase_statuses,
+                )
+                .await?;
 
-            // Sync Epic state with GitHub to get latest status
-            let updated = sync_active_epic(app).await?;
-
-            // Optionally update the Epic issue on GitHub with new phase status
-            if update_github {
-                if let Some(updated_state) = updated {
-                    // Build phase statuses from the updated state
-                    let phase_statuses: Vec<super::operations::PhaseStatus> = updated_state
-                        .phases
-                        .iter()
-                        .map(|p| super::operations::PhaseStatus {
-                            phase_number: p.phase_number,
-                            phase_name: p.name.clone(),
-                            approach: match p.status {
-                                TrackedPhaseStatus::Completed => "manual".to_string(),
-                                _ => "agent-assisted".to_string(),
-                            },
-                            total_issues: p.total_count as u32,
-                            completed_issues: p.completed_count as u32,
-                            in_progress_issues: 0, // Would need to calculate from sub_issues
-                            status: match p.status {
-                                TrackedPhaseStatus::Completed => "completed".to_string(),
-                                TrackedPhaseStatus::Ready => "ready".to_string(),
-                                TrackedPhaseStatus::InProgress => "in_progress".to_string(),
-                                TrackedPhaseStatus::NotStarted => "not_started".to_string(),
-                                TrackedPhaseStatus::Skipped => "skipped".to_string(),
-                            },
-                        })
-                        .collect();
-
-                    // Update Epic issue on GitHub
-                    super::operations::update_epic_phase_status_on_github(
-                        &updated_state.tracking_repo,
-                        updated_state.epic_number,
-                        &phase_statuses,
-                    )
-                    .await?;
-
-                    log::info!(
-                        "Updated Epic #{} on GitHub with phase status",
-                        updated_state.epic_number
-                    );
-                }
+                log::info!(
+                    "Updated Epic #{} on GitHub with phase status",
+                    updated_state.epic_number
+                );
             }
         }
     }
@@ -1064,8 +2385,62 @@ pub async fn on_pipeline_item_complete(
     Ok(())
 }
 
+/// Record in the store at `path` that a `PrOpened` notification went out for
+/// `pr_url`. Returns false if one already had.
+fn claim_pr_notification_in(path: &Path, pr_url: &str) -> bool {
+    let _guard = lock_store_writes();
+    let mut urls: Vec<String> = read_store_value(path, NOTIFIED_PRS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    if urls.iter().any(|url| url == pr_url) {
+        return false;
+    }
+    urls.push(pr_url.to_string());
+    let excess = urls.len().saturating_sub(MAX_NOTIFIED_PRS);
+    urls.drain(..excess);
+    if let Err(e) = write_store_value(path, NOTIFIED_PRS_KEY, serde_json::json!(urls)) {
+        log::warn!("Failed to record PR notification: {}", e);
+    }
+    true
+}
+
+/// Whether a `PrOpened` notification should be sent for `pr_url`, so each PR
+/// is announced once even across restarts
+fn claim_pr_notification(app: &AppHandle, pr_url: &str) -> bool {
+    match store_directory(app) {
+        Ok(dir) => claim_pr_notification_in(&dir.join(EPIC_STORE_PATH), pr_url),
+        Err(e) => {
+            log::warn!("Failed to locate store for PR notifications: {}", e);
+            true
+        }
+    }
+}
+
+/// Shortest allowed interval between PR-detection checks
+pub const MIN_PR_MONITOR_INTERVAL_SECS: u64 = 5;
+
+/// Default interval between PR-detection checks; kept below the Epic monitor's
+/// 30 s poll so timer jitter never makes it skip a poll
+pub const DEFAULT_PR_MONITOR_INTERVAL_SECS: u64 = 20;
+
+/// When the PR-detection monitor last ran
+static LAST_PR_CHECK: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+/// Whether enough time has passed since the last PR check, recording this one if so
+fn claim_pr_check_slot(interval: Duration) -> bool {
+    let mut last = LAST_PR_CHECK.lock().unwrap_or_else(|e| e.into_inner());
+    if last.is_some_and(|t| t.elapsed() < interval) {
+        return false;
+    }
+    *last = Some(Instant::now());
+    true
+}
+
 /// Check all active agent sessions for PR creation
 ///
+/// Returns `None` without checking GitHub when the monitor is paused in
+/// settings, or when called sooner than the configured poll interval.
+///
 /// This function:
 /// 1. Gets all active tmux sessions (agents working on issues)
 /// 2. For each session, checks if a PR exists for its branch
@@ -1074,7 +2449,20 @@ pub async fn on_pipeline_item_complete(
 ///
 /// Used by the Epic monitor to detect when agents have completed work
 /// by creating PRs, enabling automatic Epic progress updates.
-pub async fn check_sessions_for_prs(app: &AppHandle) -> Result<Vec<PrDetectionResult>, String> {
+pub async fn check_sessions_for_prs(
+    app: &AppHandle,
+) -> Result<Option<Vec<PrDetectionResult>>, String> {
+    let app_settings = crate::settings::get_settings(app);
+    if !app_settings.pr_monitor_enabled {
+        return Ok(None);
+    }
+    let interval = app_settings
+        .pr_monitor_interval_secs
+        .max(MIN_PR_MONITOR_INTERVAL_SECS);
+    if !claim_pr_check_slot(Duration::from_secs(interval)) {
+        return Ok(None);
+    }
+
     // Get all active sessions
     let sessions = tokio::task::spawn_blocking(tmux::list_sessions)
         .await
@@ -1093,22 +2481,21 @@ pub async fn check_sessions_for_prs(app: &AppHandle) -> Result<Vec<PrDetectionRe
         .collect();
 
     if agent_sessions.is_empty() {
-        return Ok(vec![]);
+        return Ok(Some(vec![]));
     }
 
     // Load previously detected PRs from state (track by issue number, not session)
     let state = load_epic_state(app);
-    let known_pr_issues: std::collections::HashSet<u32> = state
-        .active_epic
-        .as_ref()
-        .map(|e| {
+    let known_pr_issues: std::collections::HashSet<(String, u32)> = state
+        .active_epics
+        .iter()
+        .flat_map(|e| {
             e.sub_issues
                 .iter()
                 .filter(|s| s.pr_url.is_some())
-                .map(|s| s.issue_number)
-                .collect()
+                .map(|s| (e.tracking_repo.clone(), s.issue_number))
         })
-        .unwrap_or_default();
+        .collect();
 
     let mut results = Vec::new();
 
@@ -1118,7 +2505,8 @@ pub async fn check_sessions_for_prs(app: &AppHandle) -> Result<Vec<PrDetectionRe
             Ok(Some(mut result)) => {
                 // Check if this is a newly detected PR (by issue number)
                 if result.pr_url.is_some() {
-                    result.is_new = !known_pr_issues.contains(&result.issue_number);
+                    result.is_new =
+                        !known_pr_issues.contains(&(result.repo.clone(), result.issue_number));
 
                     // If new PR detected, update Epic state and emit event
                     if result.is_new {
@@ -1126,6 +2514,7 @@ pub async fn check_sessions_for_prs(app: &AppHandle) -> Result<Vec<PrDetectionRe
                             // Update the sub-issue in Epic state with PR info
                             update_sub_issue_pr_url(
                                 app,
+                                &result.repo,
                                 result.issue_number,
                                 pr_url,
                                 result.pr_number,
@@ -1149,6 +2538,18 @@ pub async fn check_sessions_for_prs(app: &AppHandle) -> Result<Vec<PrDetectionRe
                                     "repo": result.repo,
                                 }),
                             );
+
+                            if claim_pr_notification(app, pr_url) {
+                                let mut notification = notifications::AgentEventNotification::new(
+                                    notifications::AgentEventKind::PrOpened,
+                                    format!("Agent opened a PR for issue #{}", result.issue_number),
+                                );
+                                notification.repo = Some(result.repo.clone());
+                                notification.issue_number = Some(result.issue_number as u64);
+                                notification.session = Some(session.name.clone());
+                                notification.pr_url = Some(pr_url.clone());
+                                notifications::notify(app, notification);
+                            }
                         }
                     }
                 }
@@ -1163,34 +2564,528 @@ pub async fn check_sessions_for_prs(app: &AppHandle) -> Result<Vec<PrDetectionRe
         }
     }
 
-    Ok(results)
+    Ok(Some(results))
 }
 
 /// Update a sub-issue's PR URL in the Epic state
 fn update_sub_issue_pr_url(
     app: &AppHandle,
+    repo: &str,
     issue_number: u32,
     pr_url: &str,
     pr_number: Option<u64>,
 ) {
-    let mut state = load_epic_state(app);
-
-    if let Some(ref mut active) = state.active_epic {
+    update_epic_state(app, |state| {
+        let Some(active) = state.epic_for_issue_mut(repo, issue_number) else {
+            return;
+        };
         // Find and update the sub-issue
-        for sub_issue in &mut active.sub_issues {
-            if sub_issue.issue_number == issue_number {
-                sub_issue.pr_url = Some(pr_url.to_string());
-                sub_issue.pr_number = pr_number;
-                log::info!(
-                    "Updated sub-issue #{} with PR URL: {}",
-                    issue_number,
-                    pr_url
+        if let Some(sub_issue) = active
+            .sub_issues
+            .iter_mut()
+            .find(|s| s.issue_number == issue_number)
+        {
+            sub_issue.pr_url = Some(pr_url.to_string());
+            sub_issue.pr_number = pr_number;
+            log::info!(
+                "Updated sub-issue #{} with PR URL: {}",
+                issue_number,
+                pr_url
+            );
+        }
+    });
+}
+
+// ============================================================================
+// Sandbox Recovery
+// ============================================================================
+
+/// Recommended action for a sandbox container found at startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub enum SandboxRecoveryAction {
+    /// Container running with a live tmux session, reattach monitoring
+    Reattach,
+    /// Container running without a session, but its issue is still in progress
+    /// (see `reattach_sandbox` to monitor it again)
+    Keep,
+    /// Container exited or no longer tracked, stop and remove it
+    Stop,
+}
+
+/// A sandbox container recovered during startup
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RecoveredSandbox {
+    /// The container found on the Docker host
+    pub container: super::docker::HandyContainer,
+    /// tmux session working on the same issue (if any)
+    pub session_name: Option<String>,
+    /// Pipeline status of the issue (if tracked)
+    pub pipeline_status: Option<PipelineStatus>,
+    /// Whether the issue is an open sub-issue of an active epic
+    pub in_active_epic: bool,
+    /// Recommended action
+    pub recommended_action: SandboxRecoveryAction,
+}
+
+/// Decide what to do with a sandbox container found at startup.
+fn recommend_sandbox_action(
+    running: bool,
+    has_session: bool,
+    work_in_progress: bool,
+) -> SandboxRecoveryAction {
+    match (running, has_session, work_in_progress) {
+        (false, _, _) => SandboxRecoveryAction::Stop,
+        (true, true, _) => SandboxRecoveryAction::Reattach,
+        (true, false, true) => SandboxRecoveryAction::Keep,
+        (true, false, false) => SandboxRecoveryAction::Stop,
+    }
+}
+
+/// Whether `issue_ref` ("owner/repo#123") refers to `repo` and `issue_number`.
+fn issue_ref_matches(issue_ref: &str, repo: &str, issue_number: u64) -> bool {
+    issue_ref
+        .rsplit_once('#')
+        .is_some_and(|(r, n)| r == repo && n.parse::<u64>().ok() == Some(issue_number))
+}
+
+/// Recover sandbox containers that may have outlived the app.
+///
+/// Cross-references running `handy-sandbox-*` containers with tmux sessions,
+/// pipeline items, and the active epics to recommend Reattach, Keep, or Stop.
+pub fn recover_sandboxes(
+    store: &dyn PipelineStore,
+    active_epics: &[ActiveEpicState],
+) -> Result<Vec<RecoveredSandbox>, String> {
+    let containers = super::docker::list_handy_containers()?;
+    if containers.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let sessions = tmux::list_sessions().unwrap_or_default();
+    let pipeline = store.load();
+
+    let recovered = containers
+        .into_iter()
+        .map(|container| {
+            // Match on the full issue ref when the container has one, else the issue number
+            let matches = |repo: &str, number: u64| match &container.issue_ref {
+                Some(issue_ref) => issue_ref_matches(issue_ref, repo, number),
+                None => container.issue_number.map(u64::from) == Some(number),
+            };
+
+            let session_name = sessions
+                .iter()
+                .filter(|s| s.status == tmux::SessionStatus::Running)
+                .find(|s| {
+                    s.metadata.as_ref().is_some_and(|m| {
+                        m.issue_ref.as_deref().is_some_and(|r| {
+                            r.rsplit_once('#').is_some_and(|(repo, n)| {
+                                n.parse().is_ok_and(|n: u64| matches(repo, n))
+                            })
+                        })
+                    })
+                })
+                .map(|s| s.name.clone());
+
+            let pipeline_status = pipeline
+                .items
+                .values()
+                .find(|item| {
+                    matches(&item.tracking_repo, item.issue_number)
+                        || matches(&item.work_repo, item.issue_number)
+                })
+                .map(|item| item.status);
+
+            let in_active_epic = active_epics.iter().any(|epic| {
+                epic.sub_issues.iter().any(|sub| {
+                    sub.state.eq_ignore_ascii_case("open")
+                        && (matches(&epic.tracking_repo, sub.issue_number as u64)
+                            || matches(&epic.work_repo, sub.issue_number as u64))
+                })
+            });
+
+            let work_in_progress = in_active_epic
+                || matches!(
+                    pipeline_status,
+                    Some(PipelineStatus::InProgress) | Some(PipelineStatus::PrPending)
+                );
+
+            let recommended_action = recommend_sandbox_action(
+                container.running,
+                session_name.is_some(),
+                work_in_progress,
+            );
+
+            RecoveredSandbox {
+                container,
+                session_name,
+                pipeline_status,
+                in_active_epic,
+                recommended_action,
+            }
+        })
+        .collect();
+
+    Ok(recovered)
+}
+
+/// Result of reattaching a surviving sandbox container to a new tmux session
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReattachResult {
+    /// New tmux session following the container
+    pub session_name: String,
+    /// Container being followed
+    pub container_name: String,
+    /// Issue reference from the container (if known)
+    pub issue_ref: Option<String>,
+    /// Pipeline item relinked to the new session (if any)
+    pub pipeline_item_id: Option<String>,
+}
+
+/// Reattach a running sandbox container whose tmux session is gone.
+///
+/// Creates a fresh session, named from the session template like a new spawn
+/// for the container's issue, that follows the container with `docker logs -f`
+/// (rather than `docker attach`, so Ctrl-C in the session can't signal the
+/// agent), restores the session metadata, and points the matching pipeline
+/// item at the new session.
+pub fn reattach_sandbox(
+    store: &dyn PipelineStore,
+    container_name: &str,
+) -> Result<ReattachResult, String> {
+    let details = super::docker::inspect_sandbox(container_name)?;
+    if !details.running {
+        return Err(format!(
+            "Container {} is not running; nothing to reattach",
+            container_name
+        ));
+    }
+
+    let issue = details.issue_ref.as_deref().and_then(|r| {
+        let (repo, number) = r.rsplit_once('#')?;
+        Some((repo.to_string(), number.parse::<u64>().ok()?))
+    });
+
+    let agent_type = details
+        .agent_type
+        .clone()
+        .unwrap_or_else(|| "claude".to_string());
+    let session_name = match &issue {
+        Some((_, number)) => tmux::next_session_name(*number, &agent_type),
+        None => tmux::session_name_manual(container_name),
+    };
+
+    let workdir = details
+        .workdir
+        .clone()
+        .filter(|dir| std::path::Path::new(dir).is_dir());
+    let metadata = tmux::AgentMetadata {
+        session: session_name.clone(),
+        issue_ref: details.issue_ref.clone(),
+        repo: issue.as_ref().map(|(repo, _)| repo.clone()),
+        worktree: workdir.clone(),
+        agent_type,
+        machine_id: super::orchestrator::get_current_machine_id(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        subpath: None,
+        base_ref: None,
+        pr_number: None,
+        verbose: false,
+        paused: false,
+        push_remote: None,
+        quarantine: false,
+        worktree_reused: false,
+    };
+    tmux::create_session(&session_name, workdir.as_deref(), &metadata)?;
+    tmux::send_command(
+        &session_name,
+        &format!(
+            "docker logs -f --tail 200 {}",
+            super::shell::shell_quote(container_name)
+        ),
+    )?;
+
+    // Relink the pipeline item so PR detection and status sync find the session
+    let mut pipeline_item_id = None;
+    if let Some((repo, number)) = &issue {
+        pipeline_item_id = update_pipeline(store, |state| {
+            let item = state.items.values_mut().find(|item| {
+                item.issue_number == *number
+                    && (&item.tracking_repo == repo || &item.work_repo == repo)
+            })?;
+            item.session_name = Some(session_name.clone());
+            if item.worktree_path.is_none() {
+                item.worktree_path = workdir.clone();
+            }
+            Some(item.id.clone())
+        });
+    }
+
+    log::info!(
+        "Reattached container {} to session {}",
+        container_name,
+        session_name
+    );
+
+    Ok(ReattachResult {
+        session_name,
+        container_name: container_name.to_string(),
+        issue_ref: details.issue_ref,
+        pipeline_item_id,
+    })
+}
+
+// ============================================================================
+// Startup Recovery
+// ============================================================================
+
+/// What was still running from a previous run of the app
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StartupRecoveryReport {
+    /// Agent tmux sessions found at startup
+    pub sessions: Vec<tmux::RecoveredSession>,
+    /// Sandbox containers found at startup
+    pub sandboxes: Vec<RecoveredSandbox>,
+}
+
+/// Report from the last startup recovery (None until it has finished)
+static STARTUP_RECOVERY: Mutex<Option<StartupRecoveryReport>> = Mutex::new(None);
+
+/// Recover tmux sessions and sandbox containers in the background at startup.
+///
+/// Emits `startup-recovery` with the report, which is also kept for
+/// `get_startup_recovery` in case the UI wasn't listening yet.
+pub fn start_startup_recovery(app: AppHandle) {
+    std::thread::spawn(move || {
+        let sessions = tmux::recover_sessions().unwrap_or_else(|e| {
+            log::warn!("Failed to recover tmux sessions: {}", e);
+            Vec::new()
+        });
+        let sandboxes = if super::docker::is_docker_available() {
+            let store = TauriStore::new(&app);
+            recover_sandboxes(&store, &list_active_epics(&app)).unwrap_or_else(|e| {
+                log::warn!("Failed to recover sandbox containers: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        log::info!(
+            "Startup recovery found {} agent session(s) and {} sandbox container(s)",
+            sessions.len(),
+            sandboxes.len()
+        );
+        let report = StartupRecoveryReport {
+            sessions,
+            sandboxes,
+        };
+        let _ = app.emit("startup-recovery", &report);
+        *STARTUP_RECOVERY.lock().unwrap_or_else(|e| e.into_inner()) = Some(report);
+    });
+}
+
+/// Get the report from startup recovery, if it has finished.
+pub fn get_startup_recovery() -> Option<StartupRecoveryReport> {
+    STARTUP_RECOVERY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+// ============================================================================
+// Agent Heartbeat
+// ============================================================================
+
+/// How often the heartbeat captures agent panes
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Start a background thread that periodically checks agent output for changes.
+///
+/// Emits `agent-stuck` once per stall when a session's pane output has been
+/// unchanged for longer than the configured stuck threshold.
+pub fn start_agent_heartbeat(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HEARTBEAT_INTERVAL);
+        for beat in orchestrator::check_agent_heartbeats() {
+            if beat.newly_stuck {
+                log::warn!(
+                    "Agent session {} has produced no new output for {}s",
+                    beat.session,
+                    beat.unchanged_secs
+                );
+                let _ = app.emit("agent-stuck", &beat);
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Output Triggers
+// ============================================================================
+
+/// How often sessions with output triggers are captured
+const OUTPUT_TRIGGER_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Shortest interval between issue comments posted by the same trigger
+const TRIGGER_COMMENT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// When each `PostComment` trigger (by id) last posted
+static LAST_TRIGGER_COMMENTS: Lazy<Mutex<std::collections::HashMap<u64, Instant>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Whether a trigger may post a comment now, recording the post if so
+fn claim_trigger_comment_slot(trigger_id: u64) -> bool {
+    let mut last = LAST_TRIGGER_COMMENTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if last
+        .get(&trigger_id)
+        .is_some_and(|t| t.elapsed() < TRIGGER_COMMENT_INTERVAL)
+    {
+        return false;
+    }
+    last.insert(trigger_id, Instant::now());
+    true
+}
+
+/// Start a background thread that matches agent output against registered
+/// triggers and runs their actions.
+pub fn start_output_triggers(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(OUTPUT_TRIGGER_INTERVAL);
+        for fire in orchestrator::check_output_triggers() {
+            if let Err(e) = run_trigger_action(&app, &fire) {
+                log::warn!(
+                    "Output trigger {} on {} failed: {}",
+                    fire.trigger.id,
+                    fire.trigger.session_name,
+                    e
+                );
+            }
+        }
+    });
+}
+
+/// Run the action of a fired output trigger.
+fn run_trigger_action(
+    app: &AppHandle,
+    fire: &orchestrator::OutputTriggerFire,
+) -> Result<(), String> {
+    let session = &fire.trigger.session_name;
+    log::info!(
+        "Output trigger {} matched in {}: {}",
+        fire.trigger.id,
+        session,
+        fire.matched_line
+    );
+
+    match fire.trigger.action {
+        orchestrator::TriggerAction::EmitEvent => app
+            .emit("agent-output-trigger", fire)
+            .map_err(|e| format!("Failed to emit event: {}", e)),
+        orchestrator::TriggerAction::PostComment => {
+            if !claim_trigger_comment_slot(fire.trigger.id) {
+                log::debug!(
+                    "Output trigger {} posted a comment recently; skipping",
+                    fire.trigger.id
                 );
-                break;
+                return Ok(());
             }
+            let metadata = tmux::get_session_metadata(session)?;
+            let (repo, number) = metadata
+                .issue_ref
+                .as_deref()
+                .and_then(|r| r.rsplit_once('#'))
+                .and_then(|(repo, n)| n.parse::<u64>().ok().map(|n| (repo.to_string(), n)))
+                .ok_or_else(|| format!("Session {} has no linked issue", session))?;
+            let comment = format!(
+                "🤖 **Agent Output**\n\n\
+                Session `{}` printed a line matching `{}`:\n\n```\n{}\n```",
+                session,
+                fire.trigger.pattern,
+                github::sanitize_for_github(&fire.matched_line)
+            );
+            github::add_comment(&repo, number, &comment)
+        }
+        orchestrator::TriggerAction::KillSession => {
+            orchestrator::remove_output_trigger(fire.trigger.id);
+            tmux::kill_session(session)
+        }
+    }
+}
+
+// ============================================================================
+// PR Check Watching
+// ============================================================================
+
+/// Watch a PR's CI checks until they reach a terminal state or the timeout elapses
+///
+/// Polls `get_pr_checks` every `interval_secs` and emits `pr-checks-updated`
+/// whenever the check counts or overall state change. Resolves with the final
+/// status once checks succeed or fail, or returns an error on timeout.
+pub async fn watch_pr_checks(
+    app: &AppHandle,
+    repo: &str,
+    number: u64,
+    interval_secs: u64,
+    timeout_secs: u64,
+) -> Result<github::PrCheckStatus, String> {
+    let interval = std::time::Duration::from_secs(interval_secs.max(1));
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs);
+    let mut last_status: Option<github::PrCheckStatus> = None;
+
+    loop {
+        let status = tokio::task::spawn_blocking({
+            let repo = repo.to_string();
+            move || github::get_pr_checks(&repo, number)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map_err(|e| format!("Failed to get checks for PR #{}: {}", number, e))?;
+
+        // "unknown" means no checks have registered yet, so keep waiting
+        let is_terminal = status.state == "success" || status.state == "failure";
+
+        if last_status.as_ref() != Some(&status) {
+            log::debug!(
+                "PR #{} checks: {} ({}/{} passing)",
+                number,
+                status.state,
+                status.passing,
+                status.total
+            );
+
+            // Emit event for real-time UI updates
+            let _ = app.emit(
+                "pr-checks-updated",
+                serde_json::json!({
+                    "repo": repo,
+                    "pr_number": number,
+                    "state": status.state,
+                    "passing": status.passing,
+                    "failing": status.failing,
+                    "pending": status.pending,
+                    "total": status.total,
+                    "is_terminal": is_terminal,
+                }),
+            );
+            last_status = Some(status.clone());
+        }
+
+        if is_terminal {
+            log::info!("PR #{} checks finished: {}", number, status.state);
+            return Ok(status);
+        }
+
+        if std::time::Instant::now() >= deadline {
+            return Err(format!(
+                "Timed out after {}s waiting for checks on PR #{} (last state: {})",
+                timeout_secs, number, status.state
+            ));
         }
 
-        save_epic_state(app, &state);
+        tokio::time::sleep(interval).await;
     }
 }
 
@@ -1248,6 +3143,7 @@ pub struct ProcessReadyResult {
 /// with the worktree mounted, allowing it to resolve merge conflicts locally.
 pub async fn merge_ready_pr(
     app: &AppHandle,
+    repo: &str,
     issue_number: u32,
     merge_method: Option<&str>,
     delete_branch: bool,
@@ -1255,7 +3151,12 @@ pub async fn merge_ready_pr(
     let state = load_epic_state(app);
     let settings = crate::settings::get_settings(app);
 
-    let active = state.active_epic.as_ref().ok_or("No active Epic")?;
+    let active = state.epic_for_issue(repo, issue_number).ok_or_else(|| {
+        format!(
+            "{}#{} is not a sub-issue of an active Epic",
+            repo, issue_number
+        )
+    })?;
 
     // Find the sub-issue
     let sub_issue = active
@@ -1389,7 +3290,10 @@ pub async fn merge_ready_pr(
     }
 }
 
-/// Process all "Ready" sub-issues for the active Epic
+/// Process all "Ready" sub-issues for the selected Epic
+///
+/// PRs whose required approvals aren't met are skipped and reported as
+/// unsuccessful merges.
 pub async fn process_ready_prs(
     app: &AppHandle,
     merge_method: Option<&str>,
@@ -1400,23 +3304,70 @@ pub async fn process_ready_prs(
     sync_active_epic(app).await?;
 
     let state = load_epic_state(app);
-    let active = state.active_epic.as_ref().ok_or("No active Epic")?;
+    let active = state.current().ok_or("No active Epic")?;
 
     // Find all sub-issues in "Ready" state (open with PR)
-    let ready_issues: Vec<u32> = active
+    let ready_issues: Vec<TrackedSubIssue> = active
         .sub_issues
         .iter()
         .filter(|s| s.state.eq_ignore_ascii_case("open") && s.pr_url.is_some())
-        .map(|s| s.issue_number)
+        .cloned()
         .collect();
+    let tracking_repo = active.tracking_repo.clone();
+    let work_repo = active.work_repo.clone();
 
     log::info!("Found {} ready PRs to process", ready_issues.len());
 
     let mut merges = Vec::new();
     let mut completed_phases = Vec::new();
 
-    for issue_number in ready_issues {
-        let result = merge_ready_pr(app, issue_number, merge_method, delete_branch).await?;
+    for sub_issue in ready_issues {
+        let issue_number = sub_issue.issue_number;
+
+        // Only merge automatically once the required approvals are in
+        if let Some(pr_number) = sub_issue.pr_number {
+            let summary = tokio::task::spawn_blocking({
+                let work_repo = work_repo.clone();
+                move || github::get_pr_reviews(&work_repo, pr_number)
+            })
+            .await
+            .map_err(|e| format!("Task join error: {}", e))?
+            .map(|(_, summary)| summary);
+            let blocked = match summary {
+                Ok(summary) if summary.required_approvals_met => None,
+                Ok(_) => Some("Required approvals not met".to_string()),
+                Err(e) => Some(format!("Failed to check reviews: {}", e)),
+            };
+            if let Some(reason) = blocked {
+                log::info!(
+                    "Skipping PR #{} for issue #{}: {}",
+                    pr_number,
+                    issue_number,
+                    reason
+                );
+                merges.push(MergeResult {
+                    issue_number,
+                    pr_number,
+                    pr_url: sub_issue.pr_url.clone().unwrap_or_default(),
+                    success: false,
+                    error: Some(reason),
+                    phase: sub_issue.phase,
+                    support_worker_session: None,
+                    phase_complete: false,
+                    next_phase: None,
+                });
+                continue;
+            }
+        }
+
+        let result = merge_ready_pr(
+            app,
+            &tracking_repo,
+            issue_number,
+            merge_method,
+            delete_branch,
+        )
+        .await?;
 
         if result.success && result.phase_complete {
             if let Some(phase) = result.phase {
@@ -1431,7 +3382,7 @@ pub async fn process_ready_prs(
 
     // Find next phase to work on
     let state = load_epic_state(app);
-    let next_phase = if let Some(active) = &state.active_epic {
+    let next_phase = if let Some(active) = state.current() {
         active
             .phases
             .iter()
@@ -1461,6 +3412,563 @@ pub async fn process_ready_prs(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::devops::github::GitHubIssue;
+
+    #[test]
+    fn test_store_directory() {
+        assert_eq!(parse_store_directory(None), Ok(None));
+        assert_eq!(parse_store_directory(Some("  ")), Ok(None));
+        assert!(parse_store_directory(Some("profiles/work")).is_err());
+        assert_eq!(
+            parse_store_directory(Some("/srv/handy/work")),
+            Ok(Some(PathBuf::from("/srv/handy/work")))
+        );
+    }
+
+    #[test]
+    fn test_claim_pr_notification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EPIC_STORE_PATH);
+        let url = "https://github.com/org/repo/pull/7";
+        assert!(claim_pr_notification_in(&path, url));
+        assert!(!claim_pr_notification_in(&path, url));
+        assert!(claim_pr_notification_in(
+            &path,
+            "https://github.com/org/repo/pull/8"
+        ));
+    }
+
+    #[test]
+    fn test_move_store_files() {
+        let old_dir = tempfile::tempdir().unwrap();
+        let new_dir = tempfile::tempdir().unwrap();
+        let pipeline = serde_json::json!({"items": []});
+        let epic = serde_json::json!({"current": 1});
+        write_store_value(
+            &old_dir.path().join(PIPELINE_STORE_PATH),
+            "pipeline",
+            pipeline.clone(),
+        )
+        .unwrap();
+
+        // An Epic store that only exists in the target is adopted
+        let new_epic = new_dir.path().join(EPIC_STORE_PATH);
+        write_store_value(&new_epic, "epic_state", epic.clone()).unwrap();
+        move_store_files(old_dir.path(), new_dir.path()).unwrap();
+        assert_eq!(
+            read_store_value(&new_dir.path().join(PIPELINE_STORE_PATH), "pipeline"),
+            Some(pipeline.clone())
+        );
+        assert_eq!(
+            read_store_value(&new_epic, "epic_state"),
+            Some(epic.clone())
+        );
+        assert!(!old_dir.path().join(PIPELINE_STORE_PATH).exists());
+
+        // State on both sides is a conflict; neither side is touched
+        let old_epic = old_dir.path().join(EPIC_STORE_PATH);
+        let other = serde_json::json!({"current": 2});
+        write_store_value(&old_epic, "epic_state", other.clone()).unwrap();
+        assert!(move_store_files(old_dir.path(), new_dir.path()).is_err());
+        assert_eq!(read_store_value(&old_epic, "epic_state"), Some(other));
+        assert_eq!(read_store_value(&new_epic, "epic_state"), Some(epic));
+    }
+
+    #[test]
+    fn test_store_survives_partial_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EPIC_STORE_PATH);
+        assert_eq!(read_store_value(&path, "epic_state"), None);
+
+        let first = serde_json::json!({"current": 1});
+        let second = serde_json::json!({"current": 2});
+        write_store_value(&path, "epic_state", first.clone()).unwrap();
+        write_store_value(&path, "epic_state", second.clone()).unwrap();
+        assert_eq!(read_store_value(&path, "epic_state"), Some(second.clone()));
+        assert!(!path.with_extension("json.tmp").exists());
+
+        // A crash while writing the temp file leaves the store untouched
+        std::fs::write(path.with_extension("json.tmp"), "{\"epic_st").unwrap();
+        assert_eq!(read_store_value(&path, "epic_state"), Some(second.clone()));
+
+        // A truncated store falls back to the last good state
+        let content = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &content[..content.len() / 2]).unwrap();
+        assert_eq!(read_store_value(&path, "epic_state"), Some(first));
+
+        // The next save replaces the corrupt file without clobbering the backup
+        write_store_value(&path, "epic_state", second.clone()).unwrap();
+        assert_eq!(read_store_value(&path, "epic_state"), Some(second));
+        assert!(read_store_object(&backup_path(&path)).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_recommend_sandbox_action() {
+        assert_eq!(
+            recommend_sandbox_action(true, true, false),
+            SandboxRecoveryAction::Reattach
+        );
+        assert_eq!(
+            recommend_sandbox_action(true, false, true),
+            SandboxRecoveryAction::Keep
+        );
+        assert_eq!(
+            recommend_sandbox_action(true, false, false),
+            SandboxRecoveryAction::Stop
+        );
+        assert_eq!(
+            recommend_sandbox_action(false, true, true),
+            SandboxRecoveryAction::Stop
+        );
+    }
+
+    #[test]
+    fn test_reassign_sub_issue_phase() {
+        let phase = |n: u32, subs: Vec<u32>, status| TrackedPhase {
+            phase_number: n,
+            name: format!("Phase {}", n),
+            status,
+            completed_count: 0,
+            total_count: subs.len(),
+            sub_issues: subs,
+        };
+        let sub = |n: u32, phase: u32, state: &str| TrackedSubIssue {
+            issue_number: n,
+            title: format!("Task {}", n),
+            phase: Some(phase),
+            state: state.to_string(),
+            agent_type: None,
+            session_name: None,
+            agent_session: None,
+            has_agent_working: false,
+            url: String::new(),
+            pr_url: None,
+            pr_number: None,
+        };
+        let mut active = ActiveEpicState {
+            epic_number: 1,
+            tracking_repo: "org/repo".to_string(),
+            work_repo: "org/repo".to_string(),
+            local_repo_path: None,
+            title: "Epic".to_string(),
+            url: String::new(),
+            phases: vec![
+                phase(1, vec![10, 11], TrackedPhaseStatus::InProgress),
+                phase(2, vec![], TrackedPhaseStatus::NotStarted),
+            ],
+            sub_issues: vec![sub(10, 1, "closed"), sub(11, 1, "open")],
+            linked_at: String::new(),
+            last_synced_at: None,
+        };
+
+        assert_eq!(reassign_sub_issue_phase(&mut active, 11, 2), Ok(Some(1)));
+        assert_eq!(active.phases[0].sub_issues, vec![10]);
+        assert_eq!(active.phases[0].completed_count, 1);
+        assert_eq!(active.phases[0].status, TrackedPhaseStatus::Completed);
+        assert_eq!(active.phases[1].sub_issues, vec![11]);
+        assert_eq!(active.phases[1].total_count, 1);
+        assert_eq!(active.phases[1].status, TrackedPhaseStatus::InProgress);
+
+        assert!(reassign_sub_issue_phase(&mut active, 11, 3).is_err());
+        assert!(reassign_sub_issue_phase(&mut active, 99, 1).is_err());
+    }
+
+    #[test]
+    fn test_epic_store_multiple_epics() {
+        let epic = |n: u32, subs: Vec<u32>| {
+            serde_json::from_value::<ActiveEpicState>(serde_json::json!({
+                "epic_number": n,
+                "tracking_repo": "org/repo",
+                "work_repo": "org/repo",
+                "title": format!("Epic {}", n),
+                "url": "",
+                "phases": [],
+                "sub_issues": subs.iter().map(|i| serde_json::json!({
+                    "issue_number": i, "title": "", "phase": 1, "state": "open",
+                    "agent_type": null, "session_name": null,
+                    "has_agent_working": false, "url": ""
+                })).collect::<Vec<_>>(),
+                "linked_at": "",
+                "last_synced_at": null
+            }))
+            .unwrap()
+        };
+
+        let mut state = EpicStoreState::new();
+        assert!(state.current().is_none());
+        state.upsert(epic(1, vec![10, 11]), false);
+        state.upsert(epic(2, vec![20]), true);
+        state.upsert(epic(1, vec![10, 11, 12]), false);
+        assert_eq!(state.active_epics.len(), 2);
+        assert_eq!(state.current().map(|e| e.epic_number), Some(2));
+        assert_eq!(
+            state.epic_for_issue("org/repo", 12).map(|e| e.epic_number),
+            Some(1)
+        );
+        assert!(state.epic_for_issue("org/other", 12).is_none());
+        assert!(state.epic_for_issue("org/repo", 99).is_none());
+
+        // The same number in another repo is a different Epic
+        let mut other = epic(2, vec![30]);
+        other.tracking_repo = "org/other".to_string();
+        state.upsert(other, false);
+        assert_eq!(state.active_epics.len(), 3);
+        assert_eq!(state.current().map(|e| e.sub_issues.len()), Some(1));
+        assert!(state.find("org/other", 2).is_some());
+        assert!(state.remove("org/missing", 2).is_none());
+
+        assert_eq!(state.remove("org/repo", 2).map(|e| e.epic_number), Some(2));
+        assert_eq!(state.current, Some(1));
+        assert_eq!(state.current_repo.as_deref(), Some("org/repo"));
+
+        // State saved before multiple epics were supported
+        let legacy = serde_json::json!({
+            "active_epic": serde_json::to_value(epic(7, vec![70])).unwrap(),
+            "history": [],
+            "max_history": 10
+        });
+        let migrated: EpicStoreState = serde_json::from_value(migrate_epic_state(legacy)).unwrap();
+        assert_eq!(migrated.current, Some(7));
+        assert_eq!(migrated.current().map(|e| e.epic_number), Some(7));
+        let empty: EpicStoreState = serde_json::from_value(migrate_epic_state(
+            serde_json::json!({"active_epic": null, "history": []}),
+        ))
+        .unwrap();
+        assert!(empty.active_epics.is_empty());
+    }
+
+    #[test]
+    fn test_issue_ref_matches() {
+        assert!(issue_ref_matches("org/repo#42", "org/repo", 42));
+        assert!(!issue_ref_matches("org/repo#42", "org/other", 42));
+        assert!(!issue_ref_matches("org/repo#42", "org/repo", 43));
+        assert!(!issue_ref_matches("invalid", "org/repo", 42));
+    }
+
+    fn test_item(number: u64) -> PipelineItem {
+        let issue = GitHubIssue {
+            number,
+            title: format!("Issue {}", number),
+            body: None,
+            state: "open".to_string(),
+            url: format!("https://github.com/test/repo/issues/{}", number),
+            labels: vec![],
+            assignees: vec![],
+            milestone: None,
+            author: "testuser".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            repo: "test/tracking".to_string(),
+        };
+        PipelineItem::from_issue(&issue, "test/tracking", "test/repo", "claude")
+    }
+
+    #[test]
+    fn test_repair_pipeline_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PIPELINE_STORE_PATH);
+        assert!(diagnose_pipeline_store_file(&path).healthy);
+
+        let good = test_item(1);
+        let mut bad = serde_json::to_value(test_item(2)).unwrap();
+        bad["status"] = serde_json::json!("exploded");
+        let value = serde_json::json!({
+            "items": {good.id.clone(): good, "bad-item": bad.clone()},
+            "history": [test_item(3), bad],
+            "max_history": 100,
+        });
+        write_store_value(&path, "pipeline", value).unwrap();
+
+        let diagnosis = diagnose_pipeline_store_file(&path);
+        assert!(diagnosis.exists);
+        assert!(!diagnosis.healthy);
+        assert!(diagnosis
+            .error
+            .unwrap()
+            .starts_with("Invalid pipeline state"));
+        assert_eq!(diagnosis.recoverable_items, 1);
+        assert_eq!(diagnosis.recoverable_history, 1);
+        assert_eq!(diagnosis.unparseable, vec!["items.bad-item", "history[1]"]);
+
+        let repair = repair_pipeline_store_file(&path).unwrap();
+        assert!(std::path::Path::new(&repair.backup_path.unwrap()).exists());
+        assert_eq!((repair.recovered_items, repair.recovered_history), (1, 1));
+        assert_eq!(repair.dropped.len(), 2);
+        assert!(diagnose_pipeline_store_file(&path).healthy);
+        assert!(repair_pipeline_store_file(&path)
+            .unwrap()
+            .backup_path
+            .is_none());
+    }
+
+    #[test]
+    fn test_corrupt_pipeline_store_load_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PIPELINE_STORE_PATH);
+        let good = test_item(1);
+        let mut bad = serde_json::to_value(test_item(2)).unwrap();
+        bad["status"] = serde_json::json!("exploded");
+        let value = serde_json::json!({
+            "items": {good.id.clone(): good, "bad-item": bad},
+            "history": [],
+        });
+        write_store_value(&path, "pipeline", value).unwrap();
+
+        // Loading keeps the entries that parse
+        let mut state = load_pipeline_store_file(&path);
+        assert_eq!(state.items.len(), 1);
+        assert!(state.items.contains_key(&good.id));
+
+        // The first save copies the corrupt file aside, later ones don't
+        let backups = || {
+            std::fs::read_dir(dir.path())
+                .unwrap()
+                .filter(|e| {
+                    e.as_ref()
+                        .unwrap()
+                        .file_name()
+                        .to_string_lossy()
+                        .contains(".corrupt-")
+                })
+                .count()
+        };
+        state.add_item(test_item(3));
+        save_pipeline_store_file(&path, &state).unwrap();
+        assert_eq!(backups(), 1);
+        assert!(diagnose_pipeline_store_file(&path).healthy);
+        save_pipeline_store_file(&path, &state).unwrap();
+        assert_eq!(backups(), 1);
+        assert_eq!(load_pipeline_store_file(&path).items.len(), 2);
+    }
+
+    fn store_with(items: Vec<PipelineItem>) -> InMemoryStore {
+        let store = InMemoryStore::default();
+        update_pipeline(&store, |state| {
+            for item in items {
+                state.add_item(item);
+            }
+        });
+        store
+    }
+
+    #[test]
+    fn test_update_pipeline() {
+        let store = InMemoryStore::default();
+        std::thread::scope(|scope| {
+            for n in 1..=8 {
+                let store = &store;
+                scope.spawn(move || update_pipeline(store, |state| state.add_item(test_item(n))));
+            }
+        });
+        assert_eq!(store.load().items.len(), 8);
+
+        // Items removed while a snapshot was being worked on stay removed
+        let mut snapshot: Vec<PipelineItem> = store.load().items.into_values().collect();
+        let removed = snapshot[0].id.clone();
+        update_pipeline(&store, |state| state.remove_item(&removed));
+        for item in &mut snapshot {
+            item.error = Some("synced".to_string());
+        }
+        write_back_items(&store, &snapshot);
+        let state = store.load();
+        assert_eq!(state.items.len(), 7);
+        assert!(state
+            .items
+            .values()
+            .all(|i| i.error.as_deref() == Some("synced")));
+    }
+
+    #[test]
+    fn test_merged_branch_items() {
+        let mut merged = test_item(1);
+        merged.start_work("handy-issue-1", "/tmp/wt-1", "issue-1", "machine-1");
+        merged.pr_status = crate::devops::pipeline::PrPipelineStatus::Merged;
+        let mut no_branch = test_item(2);
+        no_branch.pr_status = crate::devops::pipeline::PrPipelineStatus::Merged;
+        let mut open = test_item(3);
+        open.start_work("handy-issue-3", "/tmp/wt-3", "issue-3", "machine-1");
+        open.pr_status = crate::devops::pipeline::PrPipelineStatus::Ready;
+
+        let items = [merged, no_branch, open];
+        let candidates = merged_branch_items(&items);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].branch_name.as_deref(), Some("issue-1"));
+    }
+
+    #[test]
+    fn test_in_memory_store_find_items() {
+        let mut item = test_item(1);
+        item.start_work("handy-issue-1", "/tmp/wt-1", "issue-1", "machine-1");
+        let id = item.id.clone();
+        let store = store_with(vec![item, test_item(2)]);
+
+        assert!(get_pipeline_item(&store, &id).is_some());
+        assert!(get_pipeline_item(&store, "missing").is_none());
+        assert_eq!(
+            find_pipeline_item_by_issue(&store, "test/repo", 2).map(|i| i.issue_number),
+            Some(2)
+        );
+        assert_eq!(
+            find_pipeline_item_by_session(&store, "handy-issue-1").map(|i| i.id),
+            Some(id)
+        );
+    }
+
+    #[test]
+    fn test_review_queue_matches_pipeline_items() {
+        let mut item = test_item(1);
+        item.start_work("handy-issue-1", "/tmp/wt-1", "handy-issue-1", "machine-1");
+        let mut other = test_item(2);
+        other.work_repo = "test/other".to_string();
+        let mut state = PipelineState::new();
+        state.add_item(item.clone());
+        state.add_item(other);
+
+        assert_eq!(
+            review_queue_repos(&state, &EpicStoreState::new()),
+            vec!["test/other", "test/repo"]
+        );
+
+        let mut pr = GitHubPullRequest {
+            number: 9,
+            title: "Fix issue 1".to_string(),
+            body: None,
+            state: "open".to_string(),
+            url: String::new(),
+            head_branch: "handy-issue-1".to_string(),
+            base_branch: "main".to_string(),
+            is_draft: false,
+            mergeable: None,
+            labels: vec![],
+            assignees: vec![],
+            milestone: None,
+            author: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            repo: "test/repo".to_string(),
+        };
+        assert_eq!(
+            pipeline_item_for_pr(&state, &pr).map(|i| i.id.clone()),
+            Some(item.id)
+        );
+        pr.repo = "test/other".to_string();
+        assert!(pipeline_item_for_pr(&state, &pr).is_none());
+    }
+
+    #[test]
+    fn test_pipeline_summary_counts() {
+        let mut in_progress = test_item(1);
+        in_progress.start_work("handy-issue-1", "/tmp/wt-1", "issue-1", "machine-1");
+        let mut skipped = test_item(2);
+        skipped.skip();
+        let store = store_with(vec![in_progress, skipped, test_item(3)]);
+
+        let summary = get_pipeline_summary(&store);
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.queued, 1);
+        assert_eq!(summary.in_progress, 1);
+        assert_eq!(summary.skipped, 1);
+    }
+
+    #[test]
+    fn test_requeue_rejected_items() {
+        let mut rejected = test_item(1);
+        rejected.start_work("handy-issue-1", "/tmp/wt-1", "issue-1", "machine-1");
+        rejected.pr_number = Some(7);
+        let pr = GitHubPullRequest {
+            number: 7,
+            title: "Fix issue 1".to_string(),
+            body: None,
+            state: "closed".to_string(),
+            url: String::new(),
+            head_branch: "issue-1".to_string(),
+            base_branch: "main".to_string(),
+            is_draft: false,
+            mergeable: None,
+            labels: vec![],
+            assignees: vec![],
+            milestone: None,
+            author: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            repo: "test/repo".to_string(),
+        };
+        rejected.update_pr_status(&pr, false, false);
+        assert_eq!(rejected.status, PipelineStatus::PrRejected);
+        assert!(!rejected.is_complete());
+        let store = store_with(vec![rejected, test_item(2)]);
+        assert_eq!(get_pipeline_summary(&store).pr_rejected, 1);
+
+        let requeued = requeue_rejected_items(&store, None);
+        assert_eq!(requeued.len(), 1);
+        assert_eq!(requeued[0].status, PipelineStatus::Queued);
+        assert_eq!(
+            requeued[0].status.project_status(),
+            Some(github::PROJECT_STATUS_TODO)
+        );
+        assert_eq!(requeued[0].pr_number, None);
+        assert_eq!(requeued[0].session_name, None);
+        assert!(requeued[0].error.as_deref().unwrap().contains("PR #7"));
+
+        let summary = get_pipeline_summary(&store);
+        assert_eq!(summary.pr_rejected, 0);
+        assert_eq!(summary.queued, 2);
+    }
+
+    #[test]
+    fn test_get_pipeline_metrics() {
+        let mut completed = test_item(1);
+        completed.created_at = "2024-01-01T00:00:00+00:00".to_string();
+        completed.status_history.clear();
+        completed.status = PipelineStatus::Completed;
+        completed.completed_at = Some("2024-01-01T06:00:00+00:00".to_string());
+        let mut skipped = test_item(2);
+        skipped.skip();
+        skipped.completed_at = Some("2024-01-02T00:00:00+00:00".to_string());
+        let store = store_with(vec![completed, skipped, test_item(3)]);
+
+        let metrics = get_pipeline_metrics(&store, None).unwrap();
+        assert_eq!(metrics.total, 2);
+        assert_eq!(metrics.completed, 1);
+        assert_eq!(metrics.avg_hours_to_complete, Some(6.0));
+        assert_eq!(metrics.skip_rate, 0.5);
+        assert_eq!(metrics.failure_rate, 0.0);
+        assert_eq!(
+            metrics.completed_per_day,
+            vec![DailyCount {
+                date: "2024-01-01".to_string(),
+                count: 1
+            }]
+        );
+
+        let recent = get_pipeline_metrics(&store, Some("2024-01-01T12:00:00Z")).unwrap();
+        assert_eq!(recent.total, 1);
+        assert_eq!(recent.skipped, 1);
+        assert!(get_pipeline_metrics(&store, Some("yesterday")).is_err());
+    }
+
+    #[test]
+    fn test_archive_and_remove_pipeline_items() {
+        let mut skipped = test_item(1);
+        skipped.skip();
+        let skipped_id = skipped.id.clone();
+        let queued = test_item(2);
+        let queued_id = queued.id.clone();
+        let store = store_with(vec![skipped, queued]);
+
+        // Completed items move to history when archived
+        let archived = archive_pipeline_item(&store, &skipped_id).unwrap();
+        assert!(archived.is_some());
+        assert!(get_pipeline_item(&store, &skipped_id).is_none());
+        assert_eq!(get_pipeline_history(&store, None).len(), 1);
+
+        // Removed items are dropped without entering history
+        let removed = remove_pipeline_item(&store, &queued_id).unwrap();
+        assert!(removed.is_some());
+        assert!(get_pipeline_item(&store, &queued_id).is_none());
+        assert_eq!(get_pipeline_history(&store, None).len(), 1);
+        assert!(remove_pipeline_item(&store, &queued_id).unwrap().is_none());
+    }
 
     #[test]
     fn test_skip_issue_config_defaults() {
@@ -8,12 +8,29 @@ use specta::Type;
 
 use super::docker;
 use super::github::{self, GitHubIssue, IssueAgentMetadata};
+use super::operations;
 use super::tmux::{self, AgentMetadata, PortMapping, SandboxedAgentConfig};
 use super::worktree::{self, WorktreeConfig, WorktreeCreateResult};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+/// What a spawn does when the issue's branch is already checked out in another worktree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Type)]
+pub enum BranchConflictResolution {
+    /// Fail, naming the existing worktree and a free branch name to use instead
+    #[default]
+    Fail,
+    /// Start the agent in the existing worktree
+    ReuseWorktree,
+    /// Create the worktree on a new branch with a `-2`, `-3`, ... suffix
+    NewBranch,
+}
 
 /// Configuration for spawning an agent.
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
 pub struct SpawnConfig {
     /// Repository in owner/repo format
     pub repo: String,
@@ -34,6 +51,52 @@ pub struct SpawnConfig {
     /// If not specified, ports are auto-detected from project files
     #[serde(default)]
     pub sandbox_ports: Vec<String>,
+    /// Extra `docker run` arguments for a sandboxed agent (e.g., ["--gpus", "all"])
+    #[serde(default)]
+    pub sandbox_docker_args: Vec<String>,
+    /// Optional subdirectory within the worktree to start the agent in (e.g., "packages/api")
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Branch to create the worktree from (e.g., an epic phase's integration branch)
+    /// If None, uses the repo's default branch
+    #[serde(default)]
+    pub base_branch: Option<String>,
+    /// Commit SHA or tag to start the worktree from (e.g., a release tag when
+    /// reproducing a bug). Takes precedence over `base_branch`
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    /// Files the agent should focus on (relative to its working directory)
+    #[serde(default)]
+    pub focus_files: Vec<String>,
+    /// Continue on the issue's branch if a previous attempt already pushed it
+    #[serde(default)]
+    pub resume: bool,
+    /// Log the constructed agent command and save it for `get_session_debug_info`
+    #[serde(default)]
+    pub debug_capture: bool,
+    /// Machine the agent must run on (e.g., a GPU box). Spawning on any other
+    /// machine is refused so the request can be routed to the right node
+    #[serde(default)]
+    pub target_machine_id: Option<String>,
+    /// What to do if the issue's branch is already checked out in another worktree
+    #[serde(default)]
+    pub on_branch_conflict: BranchConflictResolution,
+    /// Start the agent with its verbose flag (e.g. `claude --verbose`) for debugging
+    #[serde(default)]
+    pub verbose: bool,
+    /// Remote to push the issue branch to instead of `origin` (e.g., a
+    /// contributor's fork). PRs are then opened from `<fork owner>:<branch>`
+    #[serde(default)]
+    pub push_remote: Option<String>,
+    /// Run an untrusted issue quarantined: sandboxed with no network, no
+    /// credentials and a read-only workspace, so the agent can only analyze
+    /// the code. Requires Docker; completing the work never creates a PR
+    #[serde(default)]
+    pub quarantine: bool,
+    /// Run the agent in this existing worktree of the repo (e.g. left over
+    /// from manual work) instead of creating one; its branch is used as-is
+    #[serde(default)]
+    pub reuse_worktree: Option<String>,
 }
 
 /// Result of spawning an agent.
@@ -52,6 +115,24 @@ pub struct SpawnResult {
     pub is_sandboxed: bool,
     /// Container ID if sandboxed
     pub container_id: Option<String>,
+    /// Open PR from a previous attempt, when resuming on an existing branch
+    #[serde(default)]
+    pub existing_pr: Option<github::GitHubPullRequest>,
+}
+
+/// Result of spawning an agent on an existing PR.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PrSpawnResult {
+    /// The PR being worked on
+    pub pr: github::GitHubPullRequest,
+    /// The worktree with the PR's branch checked out
+    pub worktree: WorktreeCreateResult,
+    /// The tmux session name
+    pub session_name: String,
+    /// Machine ID where agent is running
+    pub machine_id: String,
+    /// Number of review comments included in the agent's prompt
+    pub review_comment_count: u32,
 }
 
 /// Status of an active agent.
@@ -77,17 +158,71 @@ pub struct AgentStatus {
     pub is_attached: bool,
     /// Whether this agent is on the current machine
     pub is_local: bool,
+    /// When the heartbeat last saw the session's pane output change (RFC 3339)
+    #[serde(default)]
+    pub last_output_change: Option<String>,
+    /// Whether the pane output has been unchanged for longer than the stuck threshold
+    #[serde(default)]
+    pub potentially_stuck: bool,
+    /// How the agent's branch compares to its upstream (local agents only)
+    #[serde(default)]
+    pub branch_sync: Option<worktree::BranchSyncStatus>,
+    /// Whether the agent is paused (see `tmux::pause_agent`)
+    #[serde(default)]
+    pub paused: bool,
 }
 
 /// Result of completing agent work.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct CompleteWorkResult {
-    /// The created pull request
-    pub pull_request: github::GitHubPullRequest,
-    /// Whether the issue was updated with PR link
+    /// The created pull request (None when verification failed)
+    pub pull_request: Option<github::GitHubPullRequest>,
+    /// Whether the issue was updated with PR link (or the verification failure)
     pub issue_updated: bool,
     /// Whether working labels were removed
     pub labels_updated: bool,
+    /// Test run before creating the PR, when `verify_before_pr` is set
+    #[serde(default)]
+    pub verification: Option<operations::VerificationResult>,
+}
+
+/// Marker file an agent writes at the worktree root when it has finished.
+pub const DONE_MARKER_FILE: &str = ".handy-done";
+
+/// Commit message marker an agent adds to its final commit when it has finished.
+pub const DONE_COMMIT_MARKER: &str = "[handy-done]";
+
+/// Seconds a session must be idle before an open question counts as blocking it.
+pub const QUESTION_IDLE_THRESHOLD_SECS: u64 = 120;
+
+/// Result of checking whether an agent is waiting on a reply in GitHub.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AgentQuestionStatus {
+    /// Session name
+    pub session: String,
+    /// Issue the agent is working on
+    pub issue_number: u64,
+    /// Whether the agent asked an unanswered question and has since gone idle
+    pub waiting: bool,
+    /// The unanswered question, if any
+    pub question: Option<github::GitHubComment>,
+    /// Seconds since the session's last activity (None if the session is gone)
+    pub idle_seconds: Option<u64>,
+    /// Dashboard message when waiting (e.g. "Agent #42 is waiting for your reply")
+    pub message: Option<String>,
+}
+
+/// Result of checking whether an agent has signalled completion.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AgentDoneStatus {
+    /// Session name
+    pub session: String,
+    /// Whether any completion signal was found
+    pub is_done: bool,
+    /// Whether the `.handy-done` marker file exists in the worktree
+    pub marker_found: bool,
+    /// SHA of the latest commit if its message contains `[handy-done]`
+    pub done_commit: Option<String>,
 }
 
 /// Configuration for workflow automation.
@@ -101,6 +236,15 @@ pub struct WorkflowConfig {
     pub draft_pr: bool,
     /// Whether to auto-close issue when PR merges
     pub close_on_merge: bool,
+    /// Whether to mark a draft PR as ready once its checks pass
+    #[serde(default)]
+    pub promote_draft_when_verified: bool,
+    /// The agent assignee to remove from the issue once the PR is created
+    #[serde(default)]
+    pub remove_assignee: Option<String>,
+    /// Whether to run the repo's tests before creating the PR and stop if they fail
+    #[serde(default)]
+    pub verify_before_pr: bool,
 }
 
 /// Get the current machine's identifier.
@@ -110,6 +254,225 @@ pub fn get_current_machine_id() -> String {
         .unwrap_or_else(|_| "unknown".to_string())
 }
 
+/// A machine seen in agent session metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct KnownMachine {
+    /// Machine identifier (hostname)
+    pub machine_id: String,
+    /// Whether this is the machine Handy is running on
+    pub is_current: bool,
+    /// Number of agent sessions recorded for this machine
+    pub agent_count: usize,
+}
+
+/// Group session machine IDs into known machines, always including `current`.
+fn known_machines_from_ids<'a>(
+    ids: impl IntoIterator<Item = &'a str>,
+    current: &str,
+) -> Vec<KnownMachine> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    counts.insert(current, 0);
+    for id in ids {
+        *counts.entry(id).or_insert(0) += 1;
+    }
+
+    let mut machines: Vec<KnownMachine> = counts
+        .into_iter()
+        .map(|(id, agent_count)| KnownMachine {
+            machine_id: id.to_string(),
+            is_current: id == current,
+            agent_count,
+        })
+        .collect();
+    machines.sort_by(|a, b| {
+        b.is_current
+            .cmp(&a.is_current)
+            .then_with(|| a.machine_id.cmp(&b.machine_id))
+    });
+    machines
+}
+
+/// List the machines known from agent session metadata.
+///
+/// The current machine comes first (even without agents), followed by the
+/// others alphabetically.
+pub fn list_known_machines() -> Vec<KnownMachine> {
+    let current = get_current_machine_id();
+    let sessions = tmux::list_sessions().unwrap_or_default();
+    let ids: Vec<String> = sessions
+        .iter()
+        .filter_map(|s| s.metadata.as_ref())
+        .filter(|m| m.agent_type != "master")
+        .map(|m| m.machine_id.clone())
+        .collect();
+    known_machines_from_ids(ids.iter().map(|s| s.as_str()), &current)
+}
+
+/// Refuse a quarantined spawn that would not actually be sandboxed.
+fn check_quarantine(config: &SpawnConfig, docker_available: bool) -> Result<(), String> {
+    if !config.quarantine {
+        return Ok(());
+    }
+    if !config.use_sandbox {
+        return Err(format!(
+            "Quarantine for issue #{} requires sandbox mode",
+            config.issue_number
+        ));
+    }
+    if !docker_available {
+        return Err(format!(
+            "Quarantine for issue #{} requires Docker, which is not available",
+            config.issue_number
+        ));
+    }
+    Ok(())
+}
+
+/// Refuse options that only apply to a worktree created by the spawn itself.
+fn check_reuse_worktree(config: &SpawnConfig) -> Result<(), String> {
+    let Some(existing) = &config.reuse_worktree else {
+        return Ok(());
+    };
+    let conflicting = [
+        ("resume", config.resume),
+        ("base_ref", config.base_ref.is_some()),
+        ("push_remote", config.push_remote.is_some()),
+    ];
+    if let Some((option, _)) = conflicting.iter().find(|(_, set)| *set) {
+        return Err(format!(
+            "Cannot combine reuse_worktree ({}) with {}",
+            existing, option
+        ));
+    }
+    Ok(())
+}
+
+/// Refuse to complete a quarantined agent's work with a PR.
+pub fn check_not_quarantined(metadata: &AgentMetadata) -> Result<(), String> {
+    if metadata.quarantine {
+        return Err(format!(
+            "Session '{}' is quarantined; PR creation is disabled",
+            metadata.session
+        ));
+    }
+    Ok(())
+}
+
+/// Which agent types must, or must never, run inside a sandbox.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct SandboxPolicy {
+    /// Agent types refused in direct mode (sandbox off or Docker unavailable)
+    #[serde(default)]
+    pub require_sandbox_for: Vec<String>,
+    /// Agent types that don't support containers and always run directly
+    #[serde(default)]
+    pub never_sandbox: Vec<String>,
+}
+
+/// Current sandbox policy (see `set_sandbox_policy`)
+static SANDBOX_POLICY: RwLock<SandboxPolicy> = RwLock::new(SandboxPolicy {
+    require_sandbox_for: Vec::new(),
+    never_sandbox: Vec::new(),
+});
+
+fn policy_lists(list: &[String], agent_type: &str) -> bool {
+    list.iter().any(|a| a.eq_ignore_ascii_case(agent_type))
+}
+
+impl SandboxPolicy {
+    /// Reject a policy that lists an agent type as both required and never sandboxed.
+    pub fn validate(&self) -> Result<(), String> {
+        match self
+            .require_sandbox_for
+            .iter()
+            .find(|a| policy_lists(&self.never_sandbox, a))
+        {
+            Some(agent) => Err(format!(
+                "Agent type '{}' cannot be both required and never sandboxed",
+                agent
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse to run `agent_type` outside a sandbox if the policy requires one.
+    pub fn check_direct_mode(&self, agent_type: &str) -> Result<(), String> {
+        if policy_lists(&self.require_sandbox_for, agent_type) {
+            return Err(format!(
+                "Sandbox policy requires agent type '{}' to run sandboxed; refusing to start it directly",
+                agent_type
+            ));
+        }
+        Ok(())
+    }
+
+    /// The config to spawn with under this policy.
+    ///
+    /// Never-sandboxed agents have sandbox mode turned off; agents that
+    /// require a sandbox are refused when it is off or Docker is missing.
+    fn apply(
+        &self,
+        config: &SpawnConfig,
+        docker_available: impl FnOnce() -> bool,
+    ) -> Result<SpawnConfig, String> {
+        let mut config = config.clone();
+        if policy_lists(&self.never_sandbox, &config.agent_type) {
+            if config.quarantine {
+                return Err(format!(
+                    "Agent type '{}' never runs sandboxed, so it cannot be quarantined",
+                    config.agent_type
+                ));
+            }
+            config.use_sandbox = false;
+        }
+
+        if !config.use_sandbox {
+            self.check_direct_mode(&config.agent_type)?;
+        } else if policy_lists(&self.require_sandbox_for, &config.agent_type) && !docker_available()
+        {
+            return Err(format!(
+                "Sandbox policy requires agent type '{}' to run sandboxed, but Docker is not available",
+                config.agent_type
+            ));
+        }
+        Ok(config)
+    }
+}
+
+/// Replace the sandbox policy enforced when agents are spawned.
+pub fn set_sandbox_policy(policy: SandboxPolicy) -> Result<(), String> {
+    policy.validate()?;
+    *SANDBOX_POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+    Ok(())
+}
+
+/// The sandbox policy enforced when agents are spawned.
+pub fn get_sandbox_policy() -> SandboxPolicy {
+    SANDBOX_POLICY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Refuse to start `agent_type` directly in tmux if the sandbox policy
+/// requires it to run sandboxed.
+///
+/// Every path that starts an agent outside a container checks this.
+pub fn check_direct_start(agent_type: &str) -> Result<(), String> {
+    get_sandbox_policy().check_direct_mode(agent_type)
+}
+
+/// Refuse to spawn when the config pins the agent to a different machine.
+fn check_target_machine(config: &SpawnConfig, current: &str) -> Result<(), String> {
+    match config.target_machine_id.as_deref() {
+        Some(target) if target != current => Err(format!(
+            "Issue #{} is pinned to machine '{}' but this is '{}'; spawn it from that machine instead",
+            config.issue_number, target, current
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// Common development ports by project type
 const COMMON_PORTS: &[(u16, &str)] = &[
     (3000, "React/Next.js/Node.js"),
@@ -173,49 +536,70 @@ fn parse_port_mappings(port_strings: &[String]) -> Vec<PortMapping> {
     ports
 }
 
-/// Detect common development ports based on project files.
+/// A port detected from project files, with what triggered it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DetectedPort {
+    /// Port mapping that will be exposed
+    pub mapping: PortMapping,
+    /// Why the port was added (e.g. "package.json depends on vite (dev server)")
+    pub reason: String,
+}
+
+/// Detect common development ports based on project files, with reasons.
 ///
-/// This examines the worktree for common configuration files and
-/// returns appropriate port mappings for the detected project type.
-fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
+/// This examines the worktree for common configuration files and returns
+/// port mappings for the detected project type. Each port appears once,
+/// attributed to the first file that triggered it.
+pub fn detect_ports(worktree_path: &str) -> Vec<DetectedPort> {
     let path = Path::new(worktree_path);
-    let mut ports = Vec::new();
+    let mut ports: Vec<DetectedPort> = Vec::new();
+    let add = |ports: &mut Vec<DetectedPort>, port: u16, reason: String| {
+        if !ports.iter().any(|p| p.mapping.host_port == port) {
+            ports.push(DetectedPort {
+                mapping: PortMapping::new(port),
+                reason,
+            });
+        }
+    };
 
     // Check for package.json (Node.js projects)
     let package_json = path.join("package.json");
     if package_json.exists() {
         if let Ok(content) = std::fs::read_to_string(&package_json) {
+            let dep =
+                |name: &str, what: &str| format!("package.json depends on {} ({})", name, what);
             // Next.js / React
             if content.contains("\"next\"") {
-                ports.push(PortMapping::new(3000));
+                add(&mut ports, 3000, dep("next", "dev server"));
             }
             // Vite
             if content.contains("\"vite\"") {
-                ports.push(PortMapping::new(5173));
-                ports.push(PortMapping::new(5174)); // HMR
-                ports.push(PortMapping::new(24678)); // WebSocket
+                add(&mut ports, 5173, dep("vite", "dev server"));
+                add(&mut ports, 5174, dep("vite", "HMR"));
+                add(&mut ports, 24678, dep("vite", "HMR WebSocket"));
             }
             // Create React App
             if content.contains("\"react-scripts\"") {
-                ports.push(PortMapping::new(3000));
+                add(&mut ports, 3000, dep("react-scripts", "dev server"));
             }
             // Angular
             if content.contains("\"@angular/core\"") {
-                ports.push(PortMapping::new(4200));
+                add(&mut ports, 4200, dep("@angular/core", "dev server"));
             }
             // Expo (React Native)
             if content.contains("\"expo\"") {
-                ports.push(PortMapping::new(19000));
-                ports.push(PortMapping::new(19001));
-                ports.push(PortMapping::new(8081)); // Metro
+                add(&mut ports, 19000, dep("expo", "Expo server"));
+                add(&mut ports, 19001, dep("expo", "Expo DevTools"));
+                add(&mut ports, 8081, dep("expo", "Metro bundler"));
             }
             // Generic Node.js server
-            if ports.is_empty()
-                && (content.contains("\"express\"")
-                    || content.contains("\"fastify\"")
-                    || content.contains("\"koa\""))
-            {
-                ports.push(PortMapping::new(3000));
+            if ports.is_empty() {
+                if let Some(server) = ["express", "fastify", "koa"]
+                    .into_iter()
+                    .find(|s| content.contains(&format!("\"{}\"", s)))
+                {
+                    add(&mut ports, 3000, dep(server, "Node.js server"));
+                }
             }
         }
     }
@@ -227,19 +611,26 @@ fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
 
     if manage_py.exists() {
         // Django
-        ports.push(PortMapping::new(8000));
+        add(&mut ports, 8000, "manage.py found (Django)".to_string());
     } else if pyproject.exists() || requirements.exists() {
         // Check for FastAPI or Flask
-        let check_files = [pyproject, requirements];
-        for file in &check_files {
+        let check_files = [
+            ("pyproject.toml", pyproject),
+            ("requirements.txt", requirements),
+        ];
+        for (name, file) in &check_files {
             if file.exists() {
                 if let Ok(content) = std::fs::read_to_string(file) {
                     if content.contains("fastapi") || content.contains("uvicorn") {
-                        ports.push(PortMapping::new(8000));
+                        add(
+                            &mut ports,
+                            8000,
+                            format!("{} mentions fastapi/uvicorn", name),
+                        );
                         break;
                     }
                     if content.contains("flask") {
-                        ports.push(PortMapping::new(5000));
+                        add(&mut ports, 5000, format!("{} mentions flask", name));
                         break;
                     }
                 }
@@ -251,7 +642,7 @@ fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
     let go_mod = path.join("go.mod");
     if go_mod.exists() {
         // Go web servers commonly use 8080
-        ports.push(PortMapping::new(8080));
+        add(&mut ports, 8080, "go.mod found (Go web server)".to_string());
     }
 
     // Check for Rust projects with Tauri
@@ -260,22 +651,36 @@ fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
         if let Ok(content) = std::fs::read_to_string(&cargo_toml) {
             if content.contains("tauri") {
                 // Tauri typically uses Vite or another bundler
-                ports.push(PortMapping::new(1420)); // Tauri dev server
-                ports.push(PortMapping::new(5173)); // Vite
+                add(
+                    &mut ports,
+                    1420,
+                    "Cargo.toml mentions tauri (dev server)".to_string(),
+                );
+                add(
+                    &mut ports,
+                    5173,
+                    "Cargo.toml mentions tauri (Vite)".to_string(),
+                );
             }
             // Actix/Axum/Rocket web frameworks
-            if content.contains("actix") || content.contains("axum") || content.contains("rocket") {
-                ports.push(PortMapping::new(8080));
+            if let Some(framework) = ["actix", "axum", "rocket"]
+                .into_iter()
+                .find(|f| content.contains(f))
+            {
+                add(
+                    &mut ports,
+                    8080,
+                    format!("Cargo.toml mentions {}", framework),
+                );
             }
         }
     }
 
     // Check for docker-compose.yml for additional ports
-    let docker_compose = path.join("docker-compose.yml");
-    let docker_compose_yaml = path.join("docker-compose.yaml");
-    for compose_file in &[docker_compose, docker_compose_yaml] {
+    for name in ["docker-compose.yml", "docker-compose.yaml"] {
+        let compose_file = path.join(name);
         if compose_file.exists() {
-            if let Ok(content) = std::fs::read_to_string(compose_file) {
+            if let Ok(content) = std::fs::read_to_string(&compose_file) {
                 // Simple regex-free port extraction (looks for "ports:" sections)
                 // Format: - "3000:3000" or - 3000:3000
                 for line in content.lines() {
@@ -286,10 +691,7 @@ fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
                         let port_str = trimmed.trim_matches('"');
                         if let Some((host, _container)) = port_str.split_once(':') {
                             if let Ok(port) = host.parse::<u16>() {
-                                // Don't duplicate
-                                if !ports.iter().any(|p| p.host_port == port) {
-                                    ports.push(PortMapping::new(port));
-                                }
+                                add(&mut ports, port, format!("{} maps {}", name, port_str));
                             }
                         }
                     }
@@ -298,9 +700,17 @@ fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
         }
     }
 
-    // Deduplicate
-    let mut seen = std::collections::HashSet::new();
-    ports.retain(|p| seen.insert(p.host_port));
+    ports
+}
+
+/// Detect common development ports based on project files.
+///
+/// Returns the port mappings from `detect_ports` without reasons.
+fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
+    let ports: Vec<PortMapping> = detect_ports(worktree_path)
+        .into_iter()
+        .map(|p| p.mapping)
+        .collect();
 
     log::info!(
         "Detected {} ports for project at {}: {:?}",
@@ -312,32 +722,467 @@ fn detect_project_ports(worktree_path: &str) -> Vec<PortMapping> {
     ports
 }
 
+/// Preview the ports a sandboxed agent spawned in this worktree would expose.
+///
+/// Mirrors spawn-time resolution: ports declared in the repo's
+/// `.handy/config.toml` replace auto-detection, otherwise `detect_ports` runs.
+pub fn preview_detected_ports(worktree_path: &str) -> Result<Vec<DetectedPort>, String> {
+    if !Path::new(worktree_path).is_dir() {
+        return Err(format!("Worktree path does not exist: {}", worktree_path));
+    }
+
+    let configured = operations::load_repo_config(worktree_path)?
+        .map(|c| c.ports)
+        .unwrap_or_default();
+    if configured.is_empty() {
+        return Ok(detect_ports(worktree_path));
+    }
+
+    Ok(configured
+        .iter()
+        .flat_map(|port| {
+            parse_port_mappings(std::slice::from_ref(port))
+                .into_iter()
+                .map(move |mapping| DetectedPort {
+                    mapping,
+                    reason: format!(
+                        "{} ports = \"{}\" (overrides detection)",
+                        operations::REPO_CONFIG_PATH,
+                        port
+                    ),
+                })
+        })
+        .collect())
+}
+
+/// Resolve an agent working directory inside a worktree.
+///
+/// The subpath must be relative, must not escape the worktree, and must
+/// point to an existing directory.
+pub fn resolve_worktree_subpath(
+    worktree_path: &str,
+    subpath: Option<&str>,
+) -> Result<String, String> {
+    let subpath = match subpath.map(|s| s.trim().trim_matches('/')) {
+        Some(s) if !s.is_empty() => s,
+        _ => return Ok(worktree_path.to_string()),
+    };
+
+    let relative = Path::new(subpath);
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(format!(
+            "Invalid subpath '{}': must be relative to the worktree",
+            subpath
+        ));
+    }
+
+    let full_path = Path::new(worktree_path).join(relative);
+    if !full_path.is_dir() {
+        return Err(format!(
+            "Subpath '{}' does not exist in worktree {}",
+            subpath, worktree_path
+        ));
+    }
+
+    Ok(full_path.to_string_lossy().to_string())
+}
+
+/// A step of `spawn_agent`, reported through progress events
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub enum SpawnStep {
+    /// Fetching the issue from GitHub
+    FetchingIssue,
+    /// Creating the git worktree
+    CreatingWorktree,
+    /// Creating the tmux session
+    CreatingSession,
+    /// Starting the agent (and its sandbox container, if enabled)
+    StartingAgent,
+    /// Posting the agent metadata comment on the issue
+    PostingComment,
+    /// Adding working labels to the issue
+    AddingLabels,
+}
+
+/// State of a spawn step
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub enum SpawnStepStatus {
+    /// Step is running
+    Started,
+    /// Step completed
+    Succeeded,
+    /// Step failed, ending the spawn
+    Failed,
+    /// Step had nothing to do (e.g. no working labels configured)
+    Skipped,
+}
+
+/// Progress update emitted while spawning an agent
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SpawnProgress {
+    /// Issue being spawned for
+    pub issue_number: u64,
+    /// Step the update is about
+    pub step: SpawnStep,
+    /// State of the step
+    pub status: SpawnStepStatus,
+    /// Error message when the step failed
+    pub error: Option<String>,
+}
+
+impl SpawnStep {
+    /// What the step does, for error messages
+    pub fn describe(self) -> &'static str {
+        match self {
+            SpawnStep::FetchingIssue => "fetching the issue",
+            SpawnStep::CreatingWorktree => "creating the worktree",
+            SpawnStep::CreatingSession => "creating the tmux session",
+            SpawnStep::StartingAgent => "starting the agent",
+            SpawnStep::PostingComment => "posting the agent comment",
+            SpawnStep::AddingLabels => "adding working labels",
+        }
+    }
+}
+
+/// Spawns in flight, keyed by repo and issue number; `true` once cancelled
+static ACTIVE_SPAWNS: Lazy<Mutex<HashMap<(String, u64), bool>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Cancel an in-flight spawn for an issue.
+///
+/// The spawn stops before its next step and rolls back what it created.
+/// Returns false if no spawn is running for the issue.
+pub fn cancel_spawn(repo: &str, issue_number: u64) -> bool {
+    let mut spawns = ACTIVE_SPAWNS.lock().unwrap_or_else(|e| e.into_inner());
+    match spawns.get_mut(&(repo.to_string(), issue_number)) {
+        Some(cancelled) => {
+            *cancelled = true;
+            true
+        }
+        None => false,
+    }
+}
+
+fn is_spawn_cancelled(repo: &str, issue_number: u64) -> bool {
+    let spawns = ACTIVE_SPAWNS.lock().unwrap_or_else(|e| e.into_inner());
+    spawns
+        .get(&(repo.to_string(), issue_number))
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Run one spawn step, reporting when it starts and how it ends.
+///
+/// Fails without running the step if the spawn has been cancelled.
+fn run_spawn_step<T>(
+    on_progress: &mut dyn FnMut(SpawnProgress),
+    config: &SpawnConfig,
+    step: SpawnStep,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let issue_number = config.issue_number;
+    let mut report = |status, error| {
+        on_progress(SpawnProgress {
+            issue_number,
+            step,
+            status,
+            error,
+        })
+    };
+    report(SpawnStepStatus::Started, None);
+    let result = if is_spawn_cancelled(&config.repo, issue_number) {
+        Err("Spawn cancelled".to_string())
+    } else {
+        f()
+    };
+    match &result {
+        Ok(_) => report(SpawnStepStatus::Succeeded, None),
+        Err(e) => report(SpawnStepStatus::Failed, Some(e.clone())),
+    }
+    result
+}
+
+/// What a partial spawn has created so far, undone if a later step fails
+#[derive(Debug, Default)]
+struct SpawnRollback {
+    /// Worktree created (or checked out for a resumed branch)
+    worktree: Option<WorktreeCreateResult>,
+    /// tmux session created
+    session: Option<String>,
+    /// Whether the agent metadata comment was posted
+    comment_posted: bool,
+}
+
+impl SpawnRollback {
+    /// Undo everything recorded, newest first, describing each action taken
+    fn undo(&self, config: &SpawnConfig, repo_path: &str) -> Vec<String> {
+        let mut actions = Vec::new();
+
+        if let (true, Some(session)) = (self.comment_posted, &self.session) {
+            let comment_id = github::list_comments(&config.repo, config.issue_number)
+                .map(|comments| github::find_agent_metadata_comment_id(&comments, session));
+            match comment_id {
+                Ok(Some(id)) => match github::delete_comment(&config.repo, id) {
+                    Ok(()) => actions.push("deleted the agent comment".to_string()),
+                    Err(e) => actions.push(format!("failed to delete the agent comment: {}", e)),
+                },
+                Ok(None) => {}
+                Err(e) => actions.push(format!("failed to find the agent comment: {}", e)),
+            }
+        }
+
+        // Killing the session also removes any sandbox containers for the issue
+        if let Some(session) = &self.session {
+            match tmux::kill_session(session) {
+                Ok(()) => actions.push(format!("killed tmux session {}", session)),
+                Err(e) => actions.push(format!("failed to kill tmux session {}: {}", session, e)),
+            }
+        }
+
+        if let Some(worktree) = &self.worktree {
+            // Keep a resumed branch: it holds work from a previous attempt
+            match worktree::remove_worktree(
+                repo_path,
+                &worktree.path,
+                true,
+                worktree.branch_created,
+            ) {
+                Ok(()) => actions.push(format!("removed worktree {}", worktree.path)),
+                Err(e) => actions.push(format!(
+                    "failed to remove worktree {}: {}",
+                    worktree.path, e
+                )),
+            }
+        }
+
+        actions
+    }
+}
+
+/// Error message for a failed spawn, stating where it stopped and what was undone
+fn spawn_failure_message(
+    issue_number: u64,
+    failed_step: Option<SpawnStep>,
+    error: &str,
+    rolled_back: &[String],
+) -> String {
+    let mut message = match failed_step {
+        Some(step) => format!(
+            "Spawn for issue #{} failed while {}: {}",
+            issue_number,
+            step.describe(),
+            error
+        ),
+        None => format!("Spawn for issue #{} failed: {}", issue_number, error),
+    };
+    if !rolled_back.is_empty() {
+        message.push_str(&format!(". Rolled back: {}", rolled_back.join(", ")));
+    }
+    message
+}
+
 /// Spawn a new agent to work on an issue.
 ///
 /// This creates a worktree and a tmux session. If sandbox mode is enabled
 /// and Docker is available, the agent runs inside a Docker container
 /// within the tmux session (allowing attach/detach and visibility).
 pub fn spawn_agent(config: &SpawnConfig, repo_path: &str) -> Result<SpawnResult, String> {
-    // 1. Fetch the issue to ensure it exists
-    let issue = github::get_issue(&config.repo, config.issue_number)?;
+    spawn_agent_with_progress(config, repo_path, &mut |_| {})
+}
 
-    // 2. Generate session name if not provided
-    let session_name = config.session_name.clone().unwrap_or_else(|| {
-        format!(
-            "handy-issue-{}-{}",
+/// Spawn a new agent, reporting each step to `on_progress`.
+///
+/// See `spawn_agent`. Every step reports `Started` followed by `Succeeded`,
+/// `Failed` (which also ends the spawn) or `Skipped`. If a step fails or the
+/// spawn is cancelled with `cancel_spawn`, the comment, session and worktree
+/// created so far are removed again.
+pub fn spawn_agent_with_progress(
+    config: &SpawnConfig,
+    repo_path: &str,
+    on_progress: &mut dyn FnMut(SpawnProgress),
+) -> Result<SpawnResult, String> {
+    check_target_machine(config, &get_current_machine_id())?;
+    check_reuse_worktree(config)?;
+    let config = &get_sandbox_policy().apply(config, docker::is_docker_available)?;
+    check_quarantine(config, !config.quarantine || docker::is_docker_available())?;
+    if !(config.use_sandbox && docker::is_docker_available()) {
+        // Checked before anything is created, so a refused start leaves nothing to roll back
+        tmux::check_root_skip_permissions(&tmux::build_agent_command(
+            &config.agent_type,
+            &config.repo,
             config.issue_number,
-            chrono::Utc::now().timestamp()
-        )
+            None,
+            &config.focus_files,
+            config.verbose,
+        )?)?;
+    }
+
+    let key = (config.repo.clone(), config.issue_number);
+    ACTIVE_SPAWNS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key.clone(), false);
+
+    let mut rollback = SpawnRollback::default();
+    let mut failed_step = None;
+    let result = run_spawn_steps(config, repo_path, &mut rollback, &mut |progress| {
+        if progress.status == SpawnStepStatus::Failed {
+            failed_step = Some(progress.step);
+        }
+        on_progress(progress)
     });
 
+    ACTIVE_SPAWNS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&key);
+
+    result.map_err(|e| {
+        let rolled_back = rollback.undo(config, repo_path);
+        let message = spawn_failure_message(config.issue_number, failed_step, &e, &rolled_back);
+        log::warn!("{}", message);
+        message
+    })
+}
+
+/// Create the worktree for a spawn, or pick the existing one it reuses.
+///
+/// Returns the worktree and whether it already existed. A reused worktree is
+/// recorded in `AgentMetadata::worktree_reused` so cleanup leaves it in place.
+fn prepare_spawn_worktree(
+    config: &SpawnConfig,
+    repo_path: &str,
+    worktree_config: &WorktreeConfig,
+) -> Result<(WorktreeCreateResult, bool), String> {
+    let issue_number = config.issue_number;
+    let worktree_name = format!("issue-{}", issue_number);
+    if let Some(existing) = &config.reuse_worktree {
+        let worktree = worktree::resolve_existing_worktree(repo_path, existing)?;
+        log::info!(
+            "Spawning issue #{} in existing worktree {} (branch {})",
+            issue_number,
+            worktree.path,
+            worktree.branch
+        );
+        return Ok((worktree, true));
+    }
+
+    let resume_branch = if config.resume {
+        find_resumable_branch(repo_path, worktree_config, issue_number)?
+    } else {
+        None
+    };
+    let branch = match &resume_branch {
+        Some(branch) => branch.clone(),
+        None => worktree::prefixed_worktree_name(repo_path, &worktree_name, worktree_config)?,
+    };
+
+    // Git refuses to check out a branch twice, so resolve that before `worktree add`
+    let conflict = worktree::find_branch_conflicts(repo_path, &branch)?
+        .into_iter()
+        .next();
+    let worktree = match (conflict, config.on_branch_conflict) {
+        (Some(existing), BranchConflictResolution::ReuseWorktree) => {
+            log::info!(
+                "Reusing worktree {} for issue #{} (branch {})",
+                existing.path,
+                issue_number,
+                branch
+            );
+            let worktree = WorktreeCreateResult {
+                path: existing.path,
+                branch,
+                branch_created: false,
+            };
+            return Ok((worktree, true));
+        }
+        (Some(_), BranchConflictResolution::NewBranch) => {
+            let new_branch = worktree::suggest_branch_name(repo_path, &branch, worktree_config)?;
+            let base = match &resume_branch {
+                Some(resumed) => Some(resumed.as_str()),
+                None => config.base_branch.as_deref(),
+            };
+            log::info!(
+                "Branch {} is checked out elsewhere; using {} for issue #{}",
+                branch,
+                new_branch,
+                issue_number
+            );
+            worktree::create_worktree(repo_path, &new_branch, worktree_config, base)?
+        }
+        (Some(existing), BranchConflictResolution::Fail) => {
+            let suggestion = worktree::suggest_branch_name(repo_path, &branch, worktree_config)
+                .unwrap_or_else(|_| format!("{}-2", branch));
+            return Err(branch_conflict_message(
+                &branch,
+                &existing.path,
+                &suggestion,
+            ));
+        }
+        (None, _) => match resume_branch {
+            Some(branch) => {
+                log::info!(
+                    "Resuming issue #{} on existing branch {}",
+                    issue_number,
+                    branch
+                );
+                worktree::create_worktree_existing_branch(repo_path, &branch, worktree_config)?
+            }
+            None => worktree::create_worktree(
+                repo_path,
+                &worktree_name,
+                worktree_config,
+                config.base_branch.as_deref(),
+            )?,
+        },
+    };
+    Ok((worktree, false))
+}
+
+/// The steps of `spawn_agent_with_progress`, recording what they create in `rollback`
+fn run_spawn_steps(
+    config: &SpawnConfig,
+    repo_path: &str,
+    rollback: &mut SpawnRollback,
+    on_progress: &mut dyn FnMut(SpawnProgress),
+) -> Result<SpawnResult, String> {
+    let issue_number = config.issue_number;
+
+    // 1. Fetch the issue to ensure it exists
+    let issue = run_spawn_step(on_progress, config, SpawnStep::FetchingIssue, || {
+        github::get_issue(&config.repo, config.issue_number)
+    })?;
+
+    // 2. Generate session name if not provided
+    let session_name = config
+        .session_name
+        .clone()
+        .unwrap_or_else(|| tmux::next_session_name(config.issue_number, &config.agent_type));
+
     // 3. Create worktree for isolated work
-    let worktree_name = format!("issue-{}", config.issue_number);
     let worktree_config = WorktreeConfig {
         prefix: config.worktree_prefix.clone().unwrap_or_default(),
         base_path: None,
         delete_branch_on_merge: true,
+        base_ref: config.base_ref.clone(),
+        push_remote: config.push_remote.clone(),
     };
-    let worktree = worktree::create_worktree(repo_path, &worktree_name, &worktree_config, None)?;
+    let (worktree, working_dir, worktree_reused) =
+        run_spawn_step(on_progress, config, SpawnStep::CreatingWorktree, || {
+            let (worktree, reused) = prepare_spawn_worktree(config, repo_path, &worktree_config)?;
+            // A reused worktree predates this spawn, so it is not rolled back
+            if !reused {
+                rollback.worktree = Some(worktree.clone());
+            }
+
+            // In monorepos, start the agent in the relevant package rather than the repo root
+            let working_dir = resolve_worktree_subpath(&worktree.path, config.subpath.as_deref())?;
+            Ok((worktree, working_dir, reused))
+        })?;
 
     // 4. Get machine ID
     let machine_id = hostname::get()
@@ -353,22 +1198,32 @@ pub fn spawn_agent(config: &SpawnConfig, repo_path: &str) -> Result<SpawnResult,
         agent_type: config.agent_type.clone(),
         machine_id: machine_id.clone(),
         started_at: chrono::Utc::now().to_rfc3339(),
+        subpath: config.subpath.clone(),
+        base_ref: config.base_ref.clone(),
+        pr_number: None,
+        verbose: config.verbose,
+        paused: false,
+        push_remote: config.push_remote.clone(),
+        quarantine: config.quarantine,
+        worktree_reused,
     };
-    tmux::create_session(&session_name, Some(&worktree.path), &metadata)?;
+    run_spawn_step(on_progress, config, SpawnStep::CreatingSession, || {
+        tmux::create_session(&session_name, Some(&working_dir), &metadata)
+    })?;
+    rollback.session = Some(session_name.clone());
 
     // 6. Start agent in the tmux session (sandboxed or direct)
     let is_sandboxed = config.use_sandbox && docker::is_docker_available();
 
-    if is_sandboxed {
-        // Sandbox mode: run agent inside Docker container within tmux
+    let sandbox_config = is_sandboxed.then(|| {
         // Use manual ports if provided, otherwise auto-detect from project files
         let ports = if !config.sandbox_ports.is_empty() {
             parse_port_mappings(&config.sandbox_ports)
         } else {
-            detect_project_ports(&worktree.path)
+            detect_project_ports(&working_dir)
         };
 
-        let sandbox_config = SandboxedAgentConfig {
+        SandboxedAgentConfig {
             worktree_path: worktree.path.clone(),
             memory_limit: Some("4g".to_string()),
             cpu_limit: Some("2".to_string()),
@@ -376,28 +1231,57 @@ pub fn spawn_agent(config: &SpawnConfig, repo_path: &str) -> Result<SpawnResult,
             ports,
             auto_detect_ports: config.sandbox_ports.is_empty(),
             use_agent_network: true, // Enable inter-container communication
-            remap_ports: true,       // Avoid port conflicts between agents
-        };
+            // Configured host:container mappings are published as given;
+            // detected ports are remapped to avoid conflicts between agents
+            remap_ports: config.sandbox_ports.is_empty(),
+            subpath: config.subpath.clone(),
+            verbose: config.verbose,
+            quarantine: config.quarantine,
+            extra_docker_args: config.sandbox_docker_args.clone(),
+        }
+    });
 
-        tmux::start_sandboxed_agent_in_session(
+    // Captured before starting, so a failed start can still be reproduced
+    if config.debug_capture {
+        if let Err(e) = tmux::capture_session_debug_info(
             &session_name,
             &config.agent_type,
             &config.repo,
             config.issue_number,
             Some(&issue.title),
-            &sandbox_config,
-        )?;
-    } else {
-        // Direct mode: run agent directly in tmux
-        tmux::start_agent_in_session(
-            &session_name,
-            &config.agent_type,
-            &config.repo,
-            config.issue_number,
-            Some(&issue.title),
-        )?;
+            &config.focus_files,
+            config.verbose,
+            sandbox_config.as_ref(),
+        ) {
+            log::warn!("Failed to capture debug info for {}: {}", session_name, e);
+        }
     }
 
+    run_spawn_step(on_progress, config, SpawnStep::StartingAgent, || {
+        match &sandbox_config {
+            // Sandbox mode: run agent inside Docker container within tmux
+            Some(sandbox_config) => tmux::start_sandboxed_agent_in_session(
+                &session_name,
+                &config.agent_type,
+                &config.repo,
+                config.issue_number,
+                Some(&issue.title),
+                &config.focus_files,
+                sandbox_config,
+            ),
+            // Direct mode: run agent directly in tmux
+            None => tmux::start_agent_in_session(
+                &session_name,
+                &config.agent_type,
+                &config.repo,
+                config.issue_number,
+                Some(&issue.title),
+                &config.focus_files,
+                config.verbose,
+            ),
+        }
+    })?;
+
     // 7. Add agent metadata comment to the issue
     let issue_metadata = IssueAgentMetadata {
         session: session_name.clone(),
@@ -411,14 +1295,36 @@ pub fn spawn_agent(config: &SpawnConfig, repo_path: &str) -> Result<SpawnResult,
             "working".to_string()
         },
     };
-    github::add_agent_metadata_comment(&config.repo, config.issue_number, &issue_metadata)?;
+    run_spawn_step(on_progress, config, SpawnStep::PostingComment, || {
+        github::add_agent_metadata_comment(&config.repo, config.issue_number, &issue_metadata)
+    })?;
+    rollback.comment_posted = true;
 
     // 8. Add working labels to the issue
-    if !config.working_labels.is_empty() {
-        let labels_refs: Vec<&str> = config.working_labels.iter().map(|s| s.as_str()).collect();
-        github::update_labels(&config.repo, config.issue_number, labels_refs, vec![])?;
+    if config.working_labels.is_empty() {
+        on_progress(SpawnProgress {
+            issue_number,
+            step: SpawnStep::AddingLabels,
+            status: SpawnStepStatus::Skipped,
+            error: None,
+        });
+    } else {
+        run_spawn_step(on_progress, config, SpawnStep::AddingLabels, || {
+            let labels_refs: Vec<&str> = config.working_labels.iter().map(|s| s.as_str()).collect();
+            github::update_labels(&config.repo, config.issue_number, labels_refs, vec![])
+        })?;
     }
 
+    // A resumed branch may already have a PR from the previous attempt
+    let existing_pr = if worktree.branch_created {
+        None
+    } else {
+        github::find_open_pr_for_branch(&config.repo, &worktree.branch).unwrap_or_else(|e| {
+            log::warn!("Failed to look up PR for {}: {}", worktree.branch, e);
+            None
+        })
+    };
+
     Ok(SpawnResult {
         issue,
         worktree,
@@ -426,19 +1332,222 @@ pub fn spawn_agent(config: &SpawnConfig, repo_path: &str) -> Result<SpawnResult,
         machine_id,
         is_sandboxed,
         container_id: None, // Container is managed by tmux session now
+        existing_pr,
     })
 }
 
-/// Get status of all active agents.
-pub fn list_agent_statuses() -> Result<Vec<AgentStatus>, String> {
-    // list_sessions() returns error if tmux isn't running, treat as empty list
-    let sessions = tmux::list_sessions().unwrap_or_else(|_| vec![]);
-    let current_machine = get_current_machine_id();
-    let mut statuses = Vec::new();
-
-    for session in sessions {
-        // Try to get metadata for each session
-        let metadata = tmux::get_session_metadata(&session.name).ok();
+/// Explain a branch that's already checked out and how to spawn anyway.
+fn branch_conflict_message(branch: &str, existing_path: &str, suggestion: &str) -> String {
+    format!(
+        "Branch '{}' is already checked out in worktree {}. Reuse that worktree \
+         or spawn on a new branch (e.g. '{}') instead",
+        branch, existing_path, suggestion
+    )
+}
+
+/// Find a branch pushed by a previous attempt at the issue.
+///
+/// Checks the branch name `create_worktree` would use, then a bare
+/// `issue-<number>`, fetching whichever exists on `origin`.
+fn find_resumable_branch(
+    repo_path: &str,
+    worktree_config: &WorktreeConfig,
+    issue_number: u64,
+) -> Result<Option<String>, String> {
+    let prefix = if worktree_config.prefix.is_empty() {
+        format!("{}-", worktree::get_project_name(repo_path)?)
+    } else {
+        worktree_config.prefix.clone()
+    };
+    for branch in [
+        format!("{}issue-{}", prefix, issue_number),
+        format!("issue-{}", issue_number),
+    ] {
+        if worktree::fetch_remote_branch(repo_path, &branch)? {
+            return Ok(Some(branch));
+        }
+    }
+    Ok(None)
+}
+
+/// Prompt asking an agent to address the review comments on a PR.
+fn build_pr_review_prompt(
+    repo: &str,
+    pr_number: u64,
+    pr_title: &str,
+    comments: &[github::PrReviewComment],
+) -> String {
+    let mut prompt = format!(
+        "Address the review feedback on GitHub PR {}#{} ({}). You are on the PR's branch.",
+        repo, pr_number, pr_title
+    );
+    if comments.is_empty() {
+        prompt.push_str(
+            " There are no review comments yet; check the PR discussion for requested changes.",
+        );
+    } else {
+        prompt.push_str(" Review comments:");
+        for comment in comments {
+            let location = match (&comment.path, comment.line) {
+                (Some(path), Some(line)) => format!(" on {}:{}", path, line),
+                (Some(path), None) => format!(" on {}", path),
+                _ => String::new(),
+            };
+            prompt.push_str(&format!(
+                "\n- @{}{}: {}",
+                comment.author,
+                location,
+                comment.body.trim()
+            ));
+        }
+    }
+    prompt.push_str("\nWhen done, commit your changes and push them to the PR's branch.");
+    prompt
+}
+
+/// Spawn an agent to iterate on an existing PR, e.g. to address review feedback.
+///
+/// Checks the PR's branch out into a worktree with `gh pr checkout` (or reuses
+/// the worktree that already has it), creates a session whose metadata points
+/// at the PR, and starts the agent with the PR's review comments as its prompt.
+/// On failure, the session and worktree created so far are removed again.
+pub fn spawn_agent_from_pr(
+    repo: &str,
+    repo_path: &str,
+    pr_number: u64,
+    agent_type: &str,
+) -> Result<PrSpawnResult, String> {
+    let pr = github::get_pr(repo, pr_number)?;
+    if !pr.state.eq_ignore_ascii_case("open") {
+        return Err(format!("PR #{} is {}, not open", pr_number, pr.state));
+    }
+    let comments = github::get_pr_review_comments(repo, pr_number)?;
+
+    // Only used to undo what was created; no comment is posted for PR spawns
+    let rollback_config = SpawnConfig {
+        repo: repo.to_string(),
+        issue_number: pr_number,
+        agent_type: agent_type.to_string(),
+        ..Default::default()
+    };
+    let mut rollback = SpawnRollback::default();
+    let result = run_pr_spawn_steps(repo, repo_path, &pr, &comments, agent_type, &mut rollback);
+
+    result.map_err(|e| {
+        let rolled_back = rollback.undo(&rollback_config, repo_path);
+        let message = if rolled_back.is_empty() {
+            format!("Failed to spawn agent for PR #{}: {}", pr_number, e)
+        } else {
+            format!(
+                "Failed to spawn agent for PR #{}: {} (rolled back: {})",
+                pr_number,
+                e,
+                rolled_back.join(", ")
+            )
+        };
+        log::warn!("{}", message);
+        message
+    })
+}
+
+/// The steps of `spawn_agent_from_pr`, recording what they create in `rollback`
+fn run_pr_spawn_steps(
+    repo: &str,
+    repo_path: &str,
+    pr: &github::GitHubPullRequest,
+    comments: &[github::PrReviewComment],
+    agent_type: &str,
+    rollback: &mut SpawnRollback,
+) -> Result<PrSpawnResult, String> {
+    // PR agents always run directly in tmux
+    check_direct_start(agent_type)?;
+    let branch = pr.head_branch.clone();
+
+    // Git refuses to check out a branch twice, so work where it already is
+    let worktree = match worktree::find_branch_conflicts(repo_path, &branch)?
+        .into_iter()
+        .next()
+    {
+        Some(existing) => {
+            log::info!(
+                "Reusing worktree {} for PR #{} (branch {})",
+                existing.path,
+                pr.number,
+                branch
+            );
+            WorktreeCreateResult {
+                path: existing.path,
+                branch,
+                branch_created: false,
+            }
+        }
+        None => {
+            let branch_created = !worktree::local_branch_exists(repo_path, &branch);
+            let path = worktree::create_detached_worktree(
+                repo_path,
+                &format!("pr-{}", pr.number),
+                &WorktreeConfig::default(),
+            )?;
+            let worktree = WorktreeCreateResult {
+                path,
+                branch,
+                branch_created,
+            };
+            rollback.worktree = Some(worktree.clone());
+            github::checkout_pr(&worktree.path, repo, pr.number, &worktree.branch)?;
+            worktree
+        }
+    };
+
+    let session_name = tmux::next_pr_session_name(pr.number);
+    let machine_id = get_current_machine_id();
+    let metadata = AgentMetadata {
+        session: session_name.clone(),
+        // The PR is tracked by `pr_number`; `issue_ref` only names a real issue
+        issue_ref: github::linked_issue_number(pr).map(|n| format!("{}#{}", repo, n)),
+        repo: Some(repo.to_string()),
+        worktree: Some(worktree.path.clone()),
+        agent_type: agent_type.to_string(),
+        machine_id: machine_id.clone(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        subpath: None,
+        base_ref: None,
+        pr_number: Some(pr.number),
+        verbose: false,
+        paused: false,
+        push_remote: None,
+        quarantine: false,
+        worktree_reused: false,
+    };
+    tmux::create_session(&session_name, Some(&worktree.path), &metadata)?;
+    rollback.session = Some(session_name.clone());
+
+    let prompt = build_pr_review_prompt(repo, pr.number, &pr.title, comments);
+    tmux::start_agent_with_prompt(&session_name, agent_type, &prompt)?;
+
+    Ok(PrSpawnResult {
+        pr: pr.clone(),
+        worktree,
+        session_name,
+        machine_id,
+        review_comment_count: comments.len() as u32,
+    })
+}
+
+/// Get status of all active agents.
+pub fn list_agent_statuses() -> Result<Vec<AgentStatus>, String> {
+    // list_sessions() returns error if tmux isn't running, treat as empty list
+    let sessions = tmux::list_sessions().unwrap_or_else(|_| vec![]);
+    let current_machine = get_current_machine_id();
+    let mut statuses = Vec::new();
+
+    let beats = HEARTBEATS.lock().unwrap();
+    let threshold = STUCK_THRESHOLD_SECS.load(Ordering::Relaxed);
+    let now = chrono::Utc::now();
+
+    for session in sessions {
+        // Try to get metadata for each session
+        let metadata = tmux::get_session_metadata(&session.name).ok();
 
         let agent_machine_id = metadata
             .as_ref()
@@ -466,10 +1575,33 @@ pub fn list_agent_statuses() -> Result<Vec<AgentStatus>, String> {
                 .unwrap_or_else(|| "unknown".to_string()),
             is_attached: session.attached,
             is_local: agent_machine_id == current_machine,
+            last_output_change: None,
+            potentially_stuck: false,
+            branch_sync: None,
+            paused: metadata.as_ref().is_some_and(|m| m.paused),
+        };
+        let status = match beats.get(&session.name) {
+            Some(beat) => AgentStatus {
+                last_output_change: Some(beat.last_change.to_rfc3339()),
+                // A paused agent produces no output on purpose
+                potentially_stuck: !status.paused && beat.is_stuck(now, threshold),
+                ..status
+            },
+            None => status,
         };
 
         statuses.push(status);
     }
+    drop(beats);
+
+    // Only local worktrees can be inspected; done outside the heartbeat lock
+    for status in statuses.iter_mut().filter(|s| s.is_local) {
+        if let Some(path) = status.worktree.as_deref() {
+            if Path::new(path).exists() {
+                status.branch_sync = worktree::get_branch_sync_status(path).ok();
+            }
+        }
+    }
 
     Ok(statuses)
 }
@@ -486,9 +1618,317 @@ pub fn list_remote_agent_statuses() -> Result<Vec<AgentStatus>, String> {
     Ok(all_statuses.into_iter().filter(|s| !s.is_local).collect())
 }
 
+/// Default seconds without new pane output before an agent counts as potentially stuck.
+pub const DEFAULT_STUCK_THRESHOLD_SECS: u64 = 600;
+
+/// Lines of pane output hashed by the heartbeat
+const HEARTBEAT_LINES: u32 = 50;
+
+/// Seconds without new pane output before an agent counts as potentially stuck
+static STUCK_THRESHOLD_SECS: AtomicU64 = AtomicU64::new(DEFAULT_STUCK_THRESHOLD_SECS);
+
+/// Last observed pane output per session
+static HEARTBEATS: Lazy<Mutex<HashMap<String, Heartbeat>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Set how long an agent's output may stay unchanged before it's flagged as stuck.
+pub fn set_stuck_threshold_secs(secs: u64) {
+    STUCK_THRESHOLD_SECS.store(secs.max(1), Ordering::Relaxed);
+}
+
+/// Hash of a session's recent pane output and when it last changed
+struct Heartbeat {
+    hash: u64,
+    last_change: chrono::DateTime<chrono::Utc>,
+    stuck_reported: bool,
+}
+
+impl Heartbeat {
+    fn is_stuck(&self, now: chrono::DateTime<chrono::Utc>, threshold_secs: u64) -> bool {
+        (now - self.last_change).num_seconds() >= threshold_secs as i64
+    }
+}
+
+/// Result of a heartbeat check for one agent session.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AgentHeartbeat {
+    /// Session name
+    pub session: String,
+    /// When the pane output last changed (RFC 3339)
+    pub last_output_change: String,
+    /// Seconds since the pane output last changed
+    pub unchanged_secs: u64,
+    /// Whether the output has been unchanged for longer than the stuck threshold
+    pub potentially_stuck: bool,
+    /// Whether this check is the first to find the session stuck
+    pub newly_stuck: bool,
+}
+
+/// Record a pane capture for a session, resetting its clock when the output changed.
+fn record_heartbeat(
+    beats: &mut HashMap<String, Heartbeat>,
+    session: &str,
+    output: &str,
+    now: chrono::DateTime<chrono::Utc>,
+    threshold_secs: u64,
+) -> AgentHeartbeat {
+    use std::hash::{Hash, Hasher};
+
+    // capture-pane pads the visible area with blank lines; ignore them
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    output.trim_end().hash(&mut hasher);
+    let hash = hasher.finish();
+
+    let beat = beats
+        .entry(session.to_string())
+        .or_insert_with(|| Heartbeat {
+            hash,
+            last_change: now,
+            stuck_reported: false,
+        });
+    if beat.hash != hash {
+        beat.hash = hash;
+        beat.last_change = now;
+        beat.stuck_reported = false;
+    }
+
+    let potentially_stuck = beat.is_stuck(now, threshold_secs);
+    let newly_stuck = potentially_stuck && !beat.stuck_reported;
+    if newly_stuck {
+        beat.stuck_reported = true;
+    }
+
+    AgentHeartbeat {
+        session: session.to_string(),
+        last_output_change: beat.last_change.to_rfc3339(),
+        unchanged_secs: (now - beat.last_change).num_seconds().max(0) as u64,
+        potentially_stuck,
+        newly_stuck,
+    }
+}
+
+/// Capture every agent session's recent output and update its heartbeat.
+///
+/// Sessions that no longer exist are forgotten.
+pub fn check_agent_heartbeats() -> Vec<AgentHeartbeat> {
+    let sessions: Vec<String> = tmux::list_sessions()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.metadata.is_some())
+        .map(|s| s.name)
+        .collect();
+    let threshold = STUCK_THRESHOLD_SECS.load(Ordering::Relaxed);
+
+    let captures: Vec<(String, String)> = sessions
+        .iter()
+        .filter_map(|name| {
+            tmux::get_session_output(name, Some(HEARTBEAT_LINES), None)
+                .map_err(|e| log::debug!("Heartbeat capture failed for {}: {}", name, e))
+                .ok()
+                .map(|output| (name.clone(), output))
+        })
+        .collect();
+
+    let mut beats = HEARTBEATS.lock().unwrap();
+    beats.retain(|name, _| sessions.contains(name));
+    let now = chrono::Utc::now();
+    captures
+        .iter()
+        .map(|(name, output)| record_heartbeat(&mut beats, name, output, now, threshold))
+        .collect()
+}
+
+/// Lines of pane output scanned by output triggers
+const OUTPUT_TRIGGER_LINES: u32 = 100;
+
+/// What an output trigger does when its pattern matches a new line.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerAction {
+    /// Emit an `agent-output-trigger` event
+    EmitEvent,
+    /// Post the matched line as a comment on the session's issue (at most once
+    /// every five minutes per trigger)
+    PostComment,
+    /// Kill the session (and its sandbox, if any)
+    KillSession,
+}
+
+/// A pattern watched for in an agent session's output.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OutputTrigger {
+    /// Identifier for `remove_output_trigger`
+    pub id: u64,
+    /// Session whose pane output is watched
+    pub session_name: String,
+    /// Regular expression matched against each output line
+    pub pattern: String,
+    /// What to do on a match
+    pub action: TriggerAction,
+    /// How many times the trigger has fired
+    pub fire_count: u32,
+}
+
+/// A trigger whose pattern matched a line printed since the previous check.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OutputTriggerFire {
+    /// The trigger that fired
+    pub trigger: OutputTrigger,
+    /// The output line that matched
+    pub matched_line: String,
+}
+
+/// A registered trigger with its compiled pattern and the output last scanned
+struct RegisteredTrigger {
+    trigger: OutputTrigger,
+    regex: regex::Regex,
+    last_output: Vec<String>,
+}
+
+static OUTPUT_TRIGGERS: Lazy<Mutex<Vec<RegisteredTrigger>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+static NEXT_TRIGGER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Lines of captured pane output, trimmed, without the blank rows below the cursor
+fn output_lines(output: &str) -> Vec<String> {
+    let mut lines: Vec<String> = output
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// The lines of `current` that come after what `previous` already showed.
+///
+/// The pane scrolls between captures, so the previous capture is aligned with
+/// the start of the current one at the smallest shift where they agree. A line
+/// that repeats earlier output is still new if it was printed again.
+fn unseen_lines<'a>(previous: &[String], current: &'a [String]) -> &'a [String] {
+    (0..previous.len())
+        .map(|shift| &previous[shift..])
+        .find(|overlap| current.starts_with(overlap))
+        .map_or(current, |overlap| &current[overlap.len()..])
+}
+
+/// Watch a session's output for `pattern` and run `action` on each new match.
+///
+/// Lines already on screen when the trigger is registered don't fire it.
+pub fn register_output_trigger(
+    session_name: &str,
+    pattern: &str,
+    action: TriggerAction,
+) -> Result<OutputTrigger, String> {
+    let regex = regex::Regex::new(pattern)
+        .map_err(|e| format!("Invalid trigger pattern '{}': {}", pattern, e))?;
+    let output = tmux::get_session_output(session_name, Some(OUTPUT_TRIGGER_LINES), None)?;
+
+    let trigger = OutputTrigger {
+        id: NEXT_TRIGGER_ID.fetch_add(1, Ordering::Relaxed),
+        session_name: session_name.to_string(),
+        pattern: pattern.to_string(),
+        action,
+        fire_count: 0,
+    };
+    OUTPUT_TRIGGERS.lock().unwrap().push(RegisteredTrigger {
+        trigger: trigger.clone(),
+        last_output: output_lines(&output),
+        regex,
+    });
+    Ok(trigger)
+}
+
+/// List registered output triggers.
+pub fn list_output_triggers() -> Vec<OutputTrigger> {
+    OUTPUT_TRIGGERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|t| t.trigger.clone())
+        .collect()
+}
+
+/// Remove an output trigger. Returns false if no trigger has that id.
+pub fn remove_output_trigger(id: u64) -> bool {
+    let mut triggers = OUTPUT_TRIGGERS.lock().unwrap();
+    let before = triggers.len();
+    triggers.retain(|t| t.trigger.id != id);
+    triggers.len() != before
+}
+
+/// Match a trigger against the lines printed since its last scan, returning
+/// the matching ones.
+fn scan_trigger(registered: &mut RegisteredTrigger, output: &str) -> Vec<String> {
+    let lines = output_lines(output);
+    let new: Vec<String> = unseen_lines(&registered.last_output, &lines)
+        .iter()
+        .filter(|line| registered.regex.is_match(line))
+        .cloned()
+        .collect();
+    registered.last_output = lines;
+    registered.trigger.fire_count += new.len() as u32;
+    new
+}
+
+/// Capture the panes of sessions with triggers and return the triggers that fired.
+///
+/// Triggers for sessions that no longer exist are dropped; nothing is checked
+/// or dropped while tmux cannot list its sessions.
+pub fn check_output_triggers() -> Vec<OutputTriggerFire> {
+    let mut sessions: Vec<String> = OUTPUT_TRIGGERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|t| t.trigger.session_name.clone())
+        .collect();
+    if sessions.is_empty() {
+        return Vec::new();
+    }
+    sessions.sort();
+    sessions.dedup();
+
+    // Without a session listing nothing can be scanned or pruned safely
+    let live: Vec<String> = match tmux::list_sessions() {
+        Ok(list) => list.into_iter().map(|s| s.name).collect(),
+        Err(e) => {
+            log::debug!("Skipping output triggers, tmux listing failed: {}", e);
+            return Vec::new();
+        }
+    };
+    let captures: HashMap<String, String> = sessions
+        .into_iter()
+        .filter(|name| live.contains(name))
+        .filter_map(|name| {
+            tmux::get_session_output(&name, Some(OUTPUT_TRIGGER_LINES), None)
+                .map_err(|e| log::debug!("Trigger capture failed for {}: {}", name, e))
+                .ok()
+                .map(|output| (name, output))
+        })
+        .collect();
+
+    let mut triggers = OUTPUT_TRIGGERS.lock().unwrap();
+    triggers.retain(|t| live.contains(&t.trigger.session_name));
+    let mut fires = Vec::new();
+    for registered in triggers.iter_mut() {
+        let Some(output) = captures.get(&registered.trigger.session_name) else {
+            continue;
+        };
+        for line in scan_trigger(registered, output) {
+            fires.push(OutputTriggerFire {
+                trigger: registered.trigger.clone(),
+                matched_line: line,
+            });
+        }
+    }
+    fires
+}
+
 /// Clean up an agent's resources after work is complete.
 ///
-/// This kills the tmux session and optionally removes the worktree.
+/// This kills the tmux session and optionally removes the worktree. A worktree
+/// the agent was spawned into with `reuse_worktree` is always kept.
 pub fn cleanup_agent(
     session_name: &str,
     repo_path: &str,
@@ -504,15 +1944,374 @@ pub fn cleanup_agent(
     // Remove worktree if requested
     if remove_worktree {
         if let Some(ref meta) = metadata {
-            if let Some(ref worktree_path) = meta.worktree {
-                worktree::remove_worktree(repo_path, worktree_path, true, delete_branch)?;
-            }
+            remove_agent_worktree(meta, repo_path, delete_branch)?;
         }
     }
 
     Ok(())
 }
 
+/// Remove the worktree recorded in an agent's metadata, unless it was reused.
+fn remove_agent_worktree(
+    meta: &AgentMetadata,
+    repo_path: &str,
+    delete_branch: bool,
+) -> Result<(), String> {
+    let Some(ref worktree_path) = meta.worktree else {
+        return Ok(());
+    };
+    if meta.worktree_reused {
+        log::info!(
+            "Keeping reused worktree {} of session {}",
+            worktree_path,
+            meta.session
+        );
+        return Ok(());
+    }
+    worktree::remove_worktree(repo_path, worktree_path, true, delete_branch)
+}
+
+/// How long to let interrupted agents wind down before killing their sessions
+const DRAIN_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Outcome of draining a single agent
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DrainResult {
+    /// Session name
+    pub session: String,
+    /// GitHub issue reference (if known)
+    pub issue_ref: Option<String>,
+    /// Backup branch holding uncommitted work (if any was saved)
+    pub backup_branch: Option<String>,
+    /// Sandbox containers that were stopped
+    pub stopped_containers: Vec<String>,
+    /// Whether the session was killed
+    pub stopped: bool,
+    /// Error that prevented a clean stop (if any)
+    pub error: Option<String>,
+}
+
+/// Gracefully stop every agent session on this machine.
+///
+/// For each local agent: optionally saves uncommitted work to a backup branch,
+/// interrupts the agent with Ctrl-C, then (after a short grace period shared
+/// by all agents) stops its sandbox containers and kills the session. An agent
+/// whose work couldn't be backed up is left running so nothing is lost.
+pub fn drain_all_agents(commit_wip: bool) -> Vec<DrainResult> {
+    let current_machine = get_current_machine_id();
+    let sessions: Vec<(String, AgentMetadata)> = tmux::list_sessions()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|s| Some((s.name, s.metadata?)))
+        .filter(|(_, m)| m.agent_type != "master" && m.machine_id == current_machine)
+        .collect();
+
+    // 1. Save work and interrupt every agent
+    let mut results: Vec<DrainResult> = sessions
+        .iter()
+        .map(|(session, metadata)| {
+            let mut result = DrainResult {
+                session: session.clone(),
+                issue_ref: metadata.issue_ref.clone(),
+                backup_branch: None,
+                stopped_containers: vec![],
+                stopped: false,
+                error: None,
+            };
+
+            if commit_wip {
+                if let Some(worktree) = metadata
+                    .worktree
+                    .as_deref()
+                    .filter(|w| Path::new(w).exists())
+                {
+                    match worktree::backup_worktree_state(worktree, session) {
+                        Ok(branch) => result.backup_branch = branch,
+                        Err(e) => {
+                            result.error = Some(format!("Failed to back up work: {}", e));
+                            return result;
+                        }
+                    }
+                }
+            }
+
+            // Twice, since some agents only exit on a second interrupt
+            for _ in 0..2 {
+                if let Err(e) = tmux::send_keys(session, "C-c", None) {
+                    log::warn!("Failed to interrupt {}: {}", session, e);
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(300));
+            }
+            result
+        })
+        .collect();
+
+    if results.iter().any(|r| r.error.is_none()) {
+        std::thread::sleep(DRAIN_GRACE_PERIOD);
+    }
+
+    // 2. Stop containers and kill sessions
+    let docker_available = docker::is_docker_available();
+    for (result, (_, metadata)) in results.iter_mut().zip(&sessions) {
+        if result.error.is_some() {
+            continue;
+        }
+
+        let issue = metadata.issue_ref.as_deref().and_then(|r| {
+            let (repo, number) = r.rsplit_once('#')?;
+            Some((repo, number.parse::<u32>().ok()?))
+        });
+        if let (true, Some((repo, number))) = (docker_available, issue) {
+            for container in docker::containers_for_issue(repo, number) {
+                match docker::stop_sandbox(&container) {
+                    Ok(()) => result.stopped_containers.push(container),
+                    Err(e) => log::warn!("Failed to stop container {}: {}", container, e),
+                }
+            }
+        }
+
+        match tmux::kill_session(&result.session) {
+            Ok(()) => result.stopped = true,
+            Err(e) => result.error = Some(e),
+        }
+    }
+
+    log::info!(
+        "Drained {}/{} agents",
+        results.iter().filter(|r| r.stopped).count(),
+        results.len()
+    );
+
+    results
+}
+
+/// A live agent session that shares its issue with another session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DuplicateSession {
+    /// Session name
+    pub session: String,
+    /// Session creation time (Unix timestamp)
+    pub created: u64,
+    /// Worktree the session works in
+    pub worktree: Option<String>,
+}
+
+/// Live agent sessions working on the same issue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DuplicateSessionGroup {
+    /// GitHub issue reference (e.g., "org/repo#42")
+    pub issue_ref: String,
+    /// Issue number
+    pub issue_number: u32,
+    /// The sessions, oldest first
+    pub sessions: Vec<DuplicateSession>,
+    /// Session to keep by default (the newest)
+    pub suggested_keep: String,
+}
+
+/// What `dedupe_sessions` removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct DedupeResult {
+    /// Session that was kept
+    pub kept: String,
+    /// Sessions that were killed
+    pub removed_sessions: Vec<String>,
+    /// Sandbox containers that were removed
+    pub removed_containers: Vec<String>,
+    /// Worktrees that were removed
+    pub removed_worktrees: Vec<String>,
+    /// Cleanup steps that failed (e.g., a worktree with uncommitted changes)
+    pub errors: Vec<String>,
+}
+
+/// Issue number from an `owner/repo#N` reference
+fn issue_number_from_ref(issue_ref: &str) -> Option<u32> {
+    issue_ref.rsplit_once('#')?.1.parse().ok()
+}
+
+/// Group running agent sessions by issue, keeping groups with more than one session
+fn group_duplicate_sessions(sessions: &[tmux::TmuxSession]) -> Vec<DuplicateSessionGroup> {
+    let mut by_issue: HashMap<&str, Vec<&tmux::TmuxSession>> = HashMap::new();
+    for session in sessions {
+        if !matches!(session.status, tmux::SessionStatus::Running) {
+            continue;
+        }
+        let Some(issue_ref) = session
+            .metadata
+            .as_ref()
+            .and_then(|m| m.issue_ref.as_deref())
+        else {
+            continue;
+        };
+        by_issue.entry(issue_ref).or_default().push(session);
+    }
+
+    let mut groups: Vec<DuplicateSessionGroup> = by_issue
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .filter_map(|(issue_ref, mut group)| {
+            group.sort_by(|a, b| a.created.cmp(&b.created).then(a.name.cmp(&b.name)));
+            Some(DuplicateSessionGroup {
+                issue_ref: issue_ref.to_string(),
+                issue_number: issue_number_from_ref(issue_ref)?,
+                suggested_keep: group.last()?.name.clone(),
+                sessions: group
+                    .into_iter()
+                    .map(|s| DuplicateSession {
+                        session: s.name.clone(),
+                        created: s.created,
+                        worktree: s.metadata.as_ref().and_then(|m| m.worktree.clone()),
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+    groups.sort_by(|a, b| a.issue_ref.cmp(&b.issue_ref));
+    groups
+}
+
+/// Find issues with more than one live agent session (e.g., after a double spawn).
+pub fn find_duplicate_sessions() -> Result<Vec<DuplicateSessionGroup>, String> {
+    Ok(group_duplicate_sessions(&tmux::list_sessions()?))
+}
+
+/// Kill every live session for an issue except `keep_session`.
+///
+/// Sandbox containers are named per issue, so with `remove_containers` only
+/// containers whose `/workspace` is a removed session's worktree are removed;
+/// the kept session's container is left alone. With `remove_worktrees`, the
+/// removed sessions' worktrees are deleted unless the kept session uses them.
+/// Worktrees with uncommitted changes are kept and reported in `errors`.
+pub fn dedupe_sessions(
+    issue_number: u32,
+    keep_session: &str,
+    repo_path: &str,
+    remove_containers: bool,
+    remove_worktrees: bool,
+) -> Result<DedupeResult, String> {
+    let group = find_duplicate_sessions()?
+        .into_iter()
+        .find(|g| {
+            g.issue_number == issue_number && g.sessions.iter().any(|s| s.session == keep_session)
+        })
+        .ok_or_else(|| {
+            format!(
+                "No duplicate sessions for issue #{} include '{}'",
+                issue_number, keep_session
+            )
+        })?;
+    let kept_worktree = group
+        .sessions
+        .iter()
+        .find(|s| s.session == keep_session)
+        .and_then(|s| s.worktree.clone());
+    let removed: Vec<DuplicateSession> = group
+        .sessions
+        .into_iter()
+        .filter(|s| s.session != keep_session)
+        .collect();
+
+    let mut result = DedupeResult {
+        kept: keep_session.to_string(),
+        ..Default::default()
+    };
+
+    // Other worktrees only; a session sharing the kept worktree leaves it alone
+    let stale_worktrees: Vec<String> = removed
+        .iter()
+        .filter_map(|s| s.worktree.clone())
+        .filter(|w| Some(w) != kept_worktree.as_ref())
+        .collect();
+
+    if remove_containers && docker::is_docker_available() {
+        if let Some((repo, _)) = group.issue_ref.rsplit_once('#') {
+            for container in docker::containers_for_issue(repo, issue_number) {
+                let stale = docker::container_workspace_source(&container)
+                    .is_some_and(|source| stale_worktrees.contains(&source));
+                if !stale {
+                    continue;
+                }
+                match docker::stop_and_remove_container(&container) {
+                    Ok(()) => result.removed_containers.push(container),
+                    Err(e) => result.errors.push(e),
+                }
+            }
+        }
+    }
+
+    for session in &removed {
+        match tmux::kill_session_only(&session.session) {
+            Ok(()) => result.removed_sessions.push(session.session.clone()),
+            Err(e) => result
+                .errors
+                .push(format!("Failed to kill {}: {}", session.session, e)),
+        }
+    }
+
+    if remove_worktrees {
+        for worktree_path in stale_worktrees {
+            if !Path::new(&worktree_path).exists() {
+                continue;
+            }
+            match worktree::remove_worktree(repo_path, &worktree_path, false, false) {
+                Ok(()) => result.removed_worktrees.push(worktree_path),
+                Err(e) => result
+                    .errors
+                    .push(format!("Kept worktree {}: {}", worktree_path, e)),
+            }
+        }
+    }
+
+    log::info!(
+        "Deduped issue #{}: kept {}, removed {:?}",
+        issue_number,
+        keep_session,
+        result.removed_sessions
+    );
+    Ok(result)
+}
+
+/// Issue comment section summarizing a branch's changes
+pub fn change_digest_note(digest: &worktree::ChangeDigest) -> String {
+    let mut note = format!(
+        "\n\n**Changes:** {} file{} changed, +{} / -{}",
+        digest.files_changed,
+        if digest.files_changed == 1 { "" } else { "s" },
+        digest.insertions,
+        digest.deletions
+    );
+    if !digest.file_stats.is_empty() {
+        note.push_str(&format!(
+            "\n\n<details><summary>Diff stat</summary>\n\n```\n{}\n```\n</details>",
+            digest.file_stats.join("\n")
+        ));
+    }
+    if !digest.commits.is_empty() {
+        note.push_str("\n\n**Commits:**");
+        for commit in &digest.commits {
+            let (sha, subject) = commit.split_once(' ').unwrap_or((commit, ""));
+            note.push_str(&format!("\n- `{}` {}", sha, subject));
+        }
+        if digest.omitted_commits > 0 {
+            note.push_str(&format!("\n- …and {} more", digest.omitted_commits));
+        }
+    }
+    note
+}
+
+/// PR body note recording the commit or tag a pinned worktree started from
+pub fn base_ref_note(base_ref: Option<&str>) -> String {
+    base_ref
+        .map(|r| {
+            format!(
+                "\n\n**Base ref**: `{}` (work started from this revision)",
+                r
+            )
+        })
+        .unwrap_or_default()
+}
+
 /// Create a PR from an agent's work.
 pub fn create_pr_from_agent(
     session_name: &str,
@@ -522,6 +2321,7 @@ pub fn create_pr_from_agent(
 ) -> Result<github::GitHubPullRequest, String> {
     // Get session metadata
     let metadata = tmux::get_session_metadata(session_name)?;
+    check_not_quarantined(&metadata)?;
 
     let repo = metadata
         .repo
@@ -537,17 +2337,24 @@ pub fn create_pr_from_agent(
     // Get default branch for base
     let default_branch = worktree::get_default_branch(&worktree_path)?;
 
-    // Create PR
-    github::create_pr(&repo, title, body, &default_branch, Some(&branch), draft)
+    // Create PR (from the fork, after pushing the branch there, when it has one)
+    if let Some(remote) = &metadata.push_remote {
+        worktree::push_branch(&worktree_path, remote, &branch)?;
+    }
+    let head = worktree::pr_head_ref(&worktree_path, &branch, metadata.push_remote.as_deref())?;
+    github::create_pr(&repo, title, body, &default_branch, Some(&head), draft)
 }
 
 /// Complete an agent's work by creating a PR and updating the issue.
 ///
 /// This is the main workflow automation function that:
-/// 1. Creates a PR from the agent's branch
-/// 2. Updates the issue with a link to the PR
-/// 3. Updates labels (removes working labels, adds PR labels)
-/// 4. Adds a completion comment to the issue
+/// 1. Runs the repo's tests when `verify_before_pr` is set; on failure it
+///    comments on the issue and returns without a PR
+/// 2. Creates a PR from the agent's branch
+/// 3. Updates the issue with a link to the PR
+/// 4. Updates labels (removes working labels, adds PR labels)
+/// 5. Adds a completion comment to the issue with a digest of the changes
+///    (diff stat and commit list)
 pub fn complete_agent_work(
     session_name: &str,
     pr_title: &str,
@@ -556,6 +2363,7 @@ pub fn complete_agent_work(
 ) -> Result<CompleteWorkResult, String> {
     // Get session metadata
     let metadata = tmux::get_session_metadata(session_name)?;
+    check_not_quarantined(&metadata)?;
 
     let repo = metadata
         .repo
@@ -590,58 +2398,125 @@ pub fn complete_agent_work(
     } else {
         pr_body.map(|s| s.to_string()).unwrap_or_default()
     };
+    let full_pr_body = format!(
+        "{}{}",
+        full_pr_body,
+        base_ref_note(metadata.base_ref.as_deref())
+    );
+
+    // 1. Verify: failing tests leave the work in progress, reported on the issue
+    let verification = workflow_config.verify_before_pr.then(|| {
+        let issue = issue_number.map(|n| (repo.as_str(), n as u32));
+        operations::verify_worktree(&worktree_path, issue)
+    });
+    if let Some(failed) = verification.as_ref().filter(|v| !v.passed) {
+        let mut issue_updated = false;
+        if let Some(num) = issue_number {
+            let comment = format!(
+                "⚠️ **Verification Failed**\n\n\
+                No pull request was created; the agent's work stays in progress.\n\n\
+                **Session:** `{}`\n\
+                **Branch:** `{}`\n\n{}",
+                session_name,
+                branch,
+                github::code_block(&github::sanitize_for_github(
+                    failed.output.as_deref().unwrap_or_default()
+                ))
+            );
+            issue_updated = github::add_comment(&repo, num, &comment).is_ok();
+        }
+        return Ok(CompleteWorkResult {
+            pull_request: None,
+            issue_updated,
+            labels_updated: false,
+            verification,
+        });
+    }
 
-    // 1. Create PR
+    // 2. Create PR (from the fork, after pushing the branch there, when it has one)
+    if let Some(remote) = &metadata.push_remote {
+        worktree::push_branch(&worktree_path, remote, &branch)?;
+    }
+    let head = worktree::pr_head_ref(&worktree_path, &branch, metadata.push_remote.as_deref())?;
     let pull_request = github::create_pr(
         &repo,
         pr_title,
         Some(&full_pr_body),
         &default_branch,
-        Some(&branch),
+        Some(&head),
         workflow_config.draft_pr,
     )?;
 
     let mut issue_updated = false;
     let mut labels_updated = false;
 
-    // 2. Update issue with PR link and labels
+    // 3. Update issue with PR link and labels
     if let Some(num) = issue_number {
         // Add comment linking to the PR
+        let promote_note = if pull_request.is_draft && workflow_config.promote_draft_when_verified {
+            "\n\nThis draft will be marked ready for review once checks pass."
+        } else {
+            ""
+        };
+        // What changed, so the issue thread shows the scope without opening the PR
+        let digest = match worktree::get_change_digest(&worktree_path, &default_branch) {
+            Ok(digest) => change_digest_note(&digest),
+            Err(e) => {
+                log::warn!("Failed to summarize changes for #{}: {}", num, e);
+                String::new()
+            }
+        };
         let comment = format!(
             "🤖 **Agent Work Complete**\n\n\
             Pull request created: #{}\n\n\
             **Session:** `{}`\n\
             **Machine:** `{}`\n\
-            **Branch:** `{}`",
-            pull_request.number, session_name, metadata.machine_id, branch
+            **Branch:** `{}`{}{}",
+            pull_request.number, session_name, metadata.machine_id, branch, digest, promote_note
         );
         if github::add_comment(&repo, num, &comment).is_ok() {
             issue_updated = true;
         }
 
-        // Update labels
-        let add_labels: Vec<&str> = workflow_config
-            .pr_labels
-            .iter()
-            .map(|s| s.as_str())
-            .collect();
-        let remove_labels: Vec<&str> = workflow_config
-            .working_labels
-            .iter()
-            .map(|s| s.as_str())
-            .collect();
+        // Update labels, falling back to the repo's own conventions
+        let repo_config = match operations::load_repo_config(&worktree_path) {
+            Ok(config) => config.unwrap_or_default(),
+            Err(e) => {
+                log::warn!("Ignoring repo config: {}", e);
+                operations::RepoConfig::default()
+            }
+        };
+        let pr_labels = if workflow_config.pr_labels.is_empty() {
+            &repo_config.pr_labels
+        } else {
+            &workflow_config.pr_labels
+        };
+        let working_labels = if workflow_config.working_labels.is_empty() {
+            &repo_config.start_labels
+        } else {
+            &workflow_config.working_labels
+        };
+        let add_labels: Vec<&str> = pr_labels.iter().map(|s| s.as_str()).collect();
+        let remove_labels: Vec<&str> = working_labels.iter().map(|s| s.as_str()).collect();
 
         if !add_labels.is_empty() || !remove_labels.is_empty() {
-            if github::update_labels(&repo, num, add_labels, remove_labels).is_ok() {
+            if github::apply_label_changes(&repo, num, &add_labels, &remove_labels).is_ok() {
                 labels_updated = true;
             }
         }
+
+        if let Some(assignee) = &workflow_config.remove_assignee {
+            if let Err(e) = github::remove_issue_assignee(&repo, num, assignee) {
+                log::warn!("Failed to remove assignee {} on #{}: {}", assignee, num, e);
+            }
+        }
     }
 
     Ok(CompleteWorkResult {
-        pull_request,
+        pull_request: Some(pull_request),
         issue_updated,
         labels_updated,
+        verification,
     })
 }
 
@@ -652,6 +2527,7 @@ pub fn check_and_cleanup_merged_pr(
     session_name: &str,
     repo_path: &str,
     pr_number: u64,
+    workflow_config: &WorkflowConfig,
 ) -> Result<bool, String> {
     // Get session metadata
     let metadata = tmux::get_session_metadata(session_name)?;
@@ -663,6 +2539,16 @@ pub fn check_and_cleanup_merged_pr(
     // Check PR status
     let pr_status = github::get_pr_status(&repo, pr_number)?;
 
+    // Promote a verified draft so it can be reviewed and merged
+    if pr_status.pr.is_draft
+        && workflow_config.promote_draft_when_verified
+        && pr_status.checks.state == "success"
+    {
+        if let Err(e) = promote_draft_pr(&repo, pr_number, metadata.issue_ref.as_deref()) {
+            log::warn!("Failed to promote draft PR #{}: {}", pr_number, e);
+        }
+    }
+
     // Check if PR state indicates it was merged
     if pr_status.pr.state == "merged" {
         // PR is merged, cleanup the agent
@@ -675,11 +2561,16 @@ pub fn check_and_cleanup_merged_pr(
                 .last()
                 .and_then(|n| n.parse::<u64>().ok())
             {
+                let cleaned_up = if metadata.worktree_reused {
+                    "has been cleaned up (its reused worktree was kept)"
+                } else {
+                    "and worktree have been cleaned up"
+                };
                 let comment = format!(
                     "✅ **PR Merged & Cleanup Complete**\n\n\
                     The pull request #{} has been merged.\n\
-                    Agent session `{}` and worktree have been cleaned up.",
-                    pr_number, session_name
+                    Agent session `{}` {}.",
+                    pr_number, session_name, cleaned_up
                 );
                 let _ = github::add_comment(&repo, issue_num, &comment);
             }
@@ -691,6 +2582,113 @@ pub fn check_and_cleanup_merged_pr(
     }
 }
 
+/// Check whether an agent has signalled that it finished its work.
+///
+/// Agents signal completion either by writing a `.handy-done` marker file
+/// in the worktree or by including `[handy-done]` in the message of their
+/// latest commit. This works independently of PR creation.
+pub fn detect_agent_done(session_name: &str) -> Result<AgentDoneStatus, String> {
+    let metadata = tmux::get_session_metadata(session_name)?;
+    let worktree_path = metadata
+        .worktree
+        .ok_or("Session has no associated worktree")?;
+
+    let marker_found = Path::new(&worktree_path).join(DONE_MARKER_FILE).exists();
+    let done_commit = find_done_commit(&worktree_path);
+
+    Ok(AgentDoneStatus {
+        session: session_name.to_string(),
+        is_done: marker_found || done_commit.is_some(),
+        marker_found,
+        done_commit,
+    })
+}
+
+/// SHA of the branch's latest commit if it carries `DONE_COMMIT_MARKER`.
+///
+/// Only commits made since the branch forked from the default branch count,
+/// so a marker inherited from the base branch is ignored. A worktree without
+/// such commits simply has no done commit.
+fn find_done_commit(worktree_path: &str) -> Option<String> {
+    let fork_point = worktree::branch_fork_point(worktree_path).ok()?;
+    let output = std::process::Command::new("git")
+        .args([
+            "log",
+            "-1",
+            "--format=%H%n%B",
+            &format!("{}..HEAD", fork_point),
+        ])
+        .current_dir(worktree_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let log = String::from_utf8_lossy(&output.stdout);
+    let mut lines = log.lines();
+    let sha = lines.next().unwrap_or_default().trim().to_string();
+    let has_marker = lines.any(|line| line.contains(DONE_COMMIT_MARKER));
+    (has_marker && !sha.is_empty()).then_some(sha)
+}
+
+/// Check whether an agent asked a question on its issue and is idling until answered.
+///
+/// Combines `github::find_unanswered_question` with the session's idle time;
+/// an agent is only reported as waiting once it has been idle for
+/// `QUESTION_IDLE_THRESHOLD_SECS`.
+pub fn detect_agent_question(
+    repo: &str,
+    issue_number: u64,
+    session_name: &str,
+) -> Result<AgentQuestionStatus, String> {
+    let comments = github::list_comments(repo, issue_number)?;
+    let question = github::find_unanswered_question(&comments).cloned();
+
+    let idle_seconds = match tmux::get_session_idle_seconds(session_name) {
+        Ok(secs) => Some(secs),
+        Err(e) => {
+            log::debug!("No activity for session {}: {}", session_name, e);
+            None
+        }
+    };
+
+    let waiting =
+        question.is_some() && idle_seconds.is_some_and(|secs| secs >= QUESTION_IDLE_THRESHOLD_SECS);
+
+    Ok(AgentQuestionStatus {
+        session: session_name.to_string(),
+        issue_number,
+        waiting,
+        question,
+        idle_seconds,
+        message: waiting.then(|| format!("Agent #{} is waiting for your reply", issue_number)),
+    })
+}
+
+/// Mark a draft PR as ready for review and note it on the linked issue.
+fn promote_draft_pr(repo: &str, pr_number: u64, issue_ref: Option<&str>) -> Result<(), String> {
+    github::mark_pr_ready(repo, pr_number)?;
+    log::info!(
+        "Marked draft PR #{} as ready after checks passed",
+        pr_number
+    );
+
+    if let Some(issue_num) = issue_ref
+        .and_then(|r| r.rsplit('#').next())
+        .and_then(|n| n.parse::<u64>().ok())
+    {
+        let comment = format!(
+            "✅ **Checks Passed**\n\n\
+            Draft pull request #{} has been marked ready for review.",
+            pr_number
+        );
+        let _ = github::add_comment(repo, issue_num, &comment);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -706,10 +2704,253 @@ mod tests {
             working_labels: vec![],
             use_sandbox: false,
             sandbox_ports: vec![],
+            sandbox_docker_args: vec![],
+            subpath: None,
+            base_branch: None,
+            base_ref: None,
+            focus_files: vec![],
+            resume: false,
+            debug_capture: false,
+            target_machine_id: None,
+            on_branch_conflict: BranchConflictResolution::Fail,
+            verbose: false,
+            push_remote: None,
+            quarantine: false,
+            reuse_worktree: None,
         };
         assert!(config.session_name.is_none());
     }
 
+    #[test]
+    fn test_resolve_worktree_subpath() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        std::fs::create_dir_all(dir.path().join("packages/api")).unwrap();
+
+        assert_eq!(resolve_worktree_subpath(&root, None).unwrap(), root);
+        assert_eq!(resolve_worktree_subpath(&root, Some("")).unwrap(), root);
+        assert!(resolve_worktree_subpath(&root, Some("packages/api/"))
+            .unwrap()
+            .ends_with("packages/api"));
+        assert!(resolve_worktree_subpath(&root, Some("packages/web")).is_err());
+        assert!(resolve_worktree_subpath(&root, Some("../escape")).is_err());
+    }
+
+    #[test]
+    fn test_run_spawn_step_reports_outcome() {
+        let mut events = Vec::new();
+        let mut record = |p: SpawnProgress| events.push((p.step, p.status, p.error));
+        let config = SpawnConfig {
+            repo: "org/progress".to_string(),
+            issue_number: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            run_spawn_step(&mut record, &config, SpawnStep::FetchingIssue, || Ok(5)),
+            Ok(5)
+        );
+        assert!(
+            run_spawn_step(&mut record, &config, SpawnStep::CreatingWorktree, || {
+                Err::<(), _>("branch exists".to_string())
+            })
+            .is_err()
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                (SpawnStep::FetchingIssue, SpawnStepStatus::Started, None),
+                (SpawnStep::FetchingIssue, SpawnStepStatus::Succeeded, None),
+                (SpawnStep::CreatingWorktree, SpawnStepStatus::Started, None),
+                (
+                    SpawnStep::CreatingWorktree,
+                    SpawnStepStatus::Failed,
+                    Some("branch exists".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_target_machine_and_known_machines() {
+        let mut config = SpawnConfig {
+            repo: "org/pinned".to_string(),
+            issue_number: 9,
+            ..Default::default()
+        };
+        assert!(check_target_machine(&config, "laptop").is_ok());
+        config.target_machine_id = Some("gpu-box".to_string());
+        assert!(check_target_machine(&config, "gpu-box").is_ok());
+        let err = check_target_machine(&config, "laptop").unwrap_err();
+        assert!(
+            err.contains("'gpu-box'") && err.contains("'laptop'"),
+            "{}",
+            err
+        );
+
+        let machines = known_machines_from_ids(["gpu-box", "alpha", "gpu-box"], "laptop");
+        let summary: Vec<(&str, bool, usize)> = machines
+            .iter()
+            .map(|m| (m.machine_id.as_str(), m.is_current, m.agent_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("laptop", true, 0),
+                ("alpha", false, 1),
+                ("gpu-box", false, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_trigger_fires_on_new_lines() {
+        let regex = regex::Regex::new("BUILD FAILED|PR created").unwrap();
+        let mut registered = RegisteredTrigger {
+            trigger: OutputTrigger {
+                id: 1,
+                session_name: "handy-agent-1".to_string(),
+                pattern: regex.as_str().to_string(),
+                action: TriggerAction::EmitEvent,
+                fire_count: 0,
+            },
+            last_output: output_lines("compiling\nBUILD FAILED (old)\n\n\n"),
+            regex,
+        };
+
+        assert!(scan_trigger(&mut registered, "BUILD FAILED (old)\nretrying\n\n").is_empty());
+        assert_eq!(
+            scan_trigger(
+                &mut registered,
+                "BUILD FAILED (old)\nretrying\nPR created: #12   \n"
+            ),
+            vec!["PR created: #12"]
+        );
+        assert!(scan_trigger(&mut registered, "retrying\nPR created: #12\n").is_empty());
+        // The same text printed again is a new match
+        assert_eq!(
+            scan_trigger(&mut registered, "PR created: #12\nBUILD FAILED (old)\n"),
+            vec!["BUILD FAILED (old)"]
+        );
+        assert_eq!(registered.trigger.fire_count, 2);
+    }
+
+    #[test]
+    fn test_cancelled_spawn_step_fails_without_running() {
+        let config = SpawnConfig {
+            repo: "org/cancel".to_string(),
+            issue_number: 7,
+            ..Default::default()
+        };
+        assert!(!cancel_spawn(&config.repo, 7));
+
+        ACTIVE_SPAWNS
+            .lock()
+            .unwrap()
+            .insert((config.repo.clone(), 7), false);
+        assert!(cancel_spawn(&config.repo, 7));
+
+        let mut ran = false;
+        let result = run_spawn_step(&mut |_| {}, &config, SpawnStep::StartingAgent, || {
+            ran = true;
+            Ok(())
+        });
+        ACTIVE_SPAWNS
+            .lock()
+            .unwrap()
+            .remove(&(config.repo.clone(), 7));
+
+        assert_eq!(result, Err("Spawn cancelled".to_string()));
+        assert!(!ran);
+        assert_eq!(
+            spawn_failure_message(
+                7,
+                Some(SpawnStep::StartingAgent),
+                "Spawn cancelled",
+                &[
+                    "killed tmux session s".to_string(),
+                    "removed worktree /w".to_string()
+                ]
+            ),
+            "Spawn for issue #7 failed while starting the agent: Spawn cancelled. \
+             Rolled back: killed tmux session s, removed worktree /w"
+        );
+    }
+
+    #[test]
+    fn test_detect_ports_reasons() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"devDependencies": {"vite": "^5.0.0"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("docker-compose.yml"),
+            "services:\n  web:\n    ports:\n      - \"5173:5173\"\n      - \"6379:6379\"\n",
+        )
+        .unwrap();
+
+        let ports = detect_ports(&root);
+        let found: Vec<u16> = ports.iter().map(|p| p.mapping.host_port).collect();
+        assert_eq!(found, vec![5173, 5174, 24678, 6379]);
+        assert!(ports[0].reason.contains("vite"));
+        assert!(ports[3].reason.contains("docker-compose.yml"));
+        assert_eq!(detect_project_ports(&root).len(), 4);
+    }
+
+    #[test]
+    fn test_preview_detected_ports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        std::fs::write(dir.path().join("go.mod"), "module example.com/app\n").unwrap();
+
+        let ports = preview_detected_ports(&root).unwrap();
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].mapping.host_port, 8080);
+        assert!(ports[0].reason.contains("go.mod"));
+
+        std::fs::create_dir(dir.path().join(".handy")).unwrap();
+        std::fs::write(
+            dir.path().join(operations::REPO_CONFIG_PATH),
+            "ports = [\"9000:3000\"]\n",
+        )
+        .unwrap();
+        let ports = preview_detected_ports(&root).unwrap();
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].mapping.host_port, 9000);
+        assert_eq!(ports[0].mapping.container_port, 3000);
+        assert!(ports[0].reason.contains("overrides detection"));
+
+        assert!(preview_detected_ports(&format!("{}/missing", root)).is_err());
+    }
+
+    #[test]
+    fn test_record_heartbeat() {
+        let mut beats = HashMap::new();
+        let start = chrono::Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+
+        let beat = record_heartbeat(&mut beats, "handy-agent-1", "thinking...\n\n", start, 60);
+        assert!(!beat.potentially_stuck);
+
+        // Trailing blank lines don't count as new output
+        let beat = record_heartbeat(&mut beats, "handy-agent-1", "thinking...", at(61), 60);
+        assert!(beat.potentially_stuck);
+        assert!(beat.newly_stuck);
+        assert_eq!(beat.unchanged_secs, 61);
+
+        let beat = record_heartbeat(&mut beats, "handy-agent-1", "thinking...", at(90), 60);
+        assert!(beat.potentially_stuck);
+        assert!(!beat.newly_stuck);
+
+        let beat = record_heartbeat(&mut beats, "handy-agent-1", "wrote main.rs", at(95), 60);
+        assert!(!beat.potentially_stuck);
+        assert_eq!(beat.last_output_change, at(95).to_rfc3339());
+    }
+
     #[test]
     fn test_parse_port_mappings() {
         // Simple port
@@ -737,4 +2978,287 @@ mod tests {
         ]);
         assert_eq!(ports.len(), 3);
     }
+
+    #[test]
+    fn test_build_pr_review_prompt() {
+        let comments = vec![
+            github::PrReviewComment {
+                author: "alice".to_string(),
+                body: "Please add tests\n".to_string(),
+                path: None,
+                line: None,
+            },
+            github::PrReviewComment {
+                author: "bob".to_string(),
+                body: "Handle None here".to_string(),
+                path: Some("src/lib.rs".to_string()),
+                line: Some(42),
+            },
+        ];
+        let prompt = build_pr_review_prompt("org/repo", 7, "Add parser", &comments);
+        assert!(
+            prompt.starts_with("Address the review feedback on GitHub PR org/repo#7 (Add parser).")
+        );
+        assert!(prompt
+            .contains("\n- @alice: Please add tests\n- @bob on src/lib.rs:42: Handle None here\n"));
+
+        let prompt = build_pr_review_prompt("org/repo", 7, "Add parser", &[]);
+        assert!(prompt.contains("no review comments yet"));
+    }
+
+    #[test]
+    fn test_group_duplicate_sessions() {
+        let session =
+            |name: &str, issue_ref: &str, created: u64, running: bool| tmux::TmuxSession {
+                name: name.to_string(),
+                attached: false,
+                windows: 1,
+                created,
+                metadata: Some(AgentMetadata {
+                    session: name.to_string(),
+                    issue_ref: Some(issue_ref.to_string()),
+                    repo: None,
+                    worktree: Some(format!("/work/{}", name)),
+                    agent_type: "claude".to_string(),
+                    machine_id: "m1".to_string(),
+                    started_at: String::new(),
+                    subpath: None,
+                    base_ref: None,
+                    pr_number: None,
+                    verbose: false,
+                    paused: false,
+                    push_remote: None,
+                    quarantine: false,
+                    worktree_reused: false,
+                }),
+                status: if running {
+                    tmux::SessionStatus::Running
+                } else {
+                    tmux::SessionStatus::Stopped
+                },
+            };
+        let sessions = vec![
+            session("handy-agent-42-1700000200", "org/repo#42", 200, true),
+            session("handy-agent-42", "org/repo#42", 100, true),
+            session("handy-agent-42-1700000300", "org/repo#42", 300, false),
+            session("handy-agent-7", "org/repo#7", 100, true),
+            session("handy-agent-42-other", "org/other#42", 100, true),
+        ];
+
+        let groups = group_duplicate_sessions(&sessions);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].issue_ref, "org/repo#42");
+        assert_eq!(groups[0].issue_number, 42);
+        assert_eq!(
+            groups[0]
+                .sessions
+                .iter()
+                .map(|s| s.session.as_str())
+                .collect::<Vec<_>>(),
+            vec!["handy-agent-42", "handy-agent-42-1700000200"]
+        );
+        assert_eq!(groups[0].suggested_keep, "handy-agent-42-1700000200");
+    }
+
+    #[test]
+    fn test_sandbox_policy() {
+        let policy = SandboxPolicy {
+            require_sandbox_for: vec!["claude".to_string()],
+            never_sandbox: vec!["aider".to_string()],
+        };
+        assert!(policy.validate().is_ok());
+        let mut conflicting = policy.clone();
+        conflicting.never_sandbox.push("Claude".to_string());
+        assert!(conflicting.validate().is_err());
+
+        let mut config = SpawnConfig {
+            agent_type: "claude".to_string(),
+            issue_number: 7,
+            use_sandbox: false,
+            ..Default::default()
+        };
+        assert!(policy
+            .apply(&config, || true)
+            .unwrap_err()
+            .contains("'claude' to run sandboxed"));
+        config.use_sandbox = true;
+        assert!(policy
+            .apply(&config, || false)
+            .unwrap_err()
+            .contains("Docker is not available"));
+        assert!(policy.apply(&config, || true).unwrap().use_sandbox);
+        assert!(policy.check_direct_mode("CLAUDE").is_err());
+
+        config.agent_type = "aider".to_string();
+        let applied = policy
+            .apply(&config, || panic!("docker not needed"))
+            .unwrap();
+        assert!(!applied.use_sandbox);
+        config.quarantine = true;
+        assert!(policy.apply(&config, || true).is_err());
+
+        config.agent_type = "codex".to_string();
+        config.quarantine = false;
+        config.use_sandbox = false;
+        assert!(!policy.apply(&config, || false).unwrap().use_sandbox);
+    }
+
+    #[test]
+    fn test_check_quarantine() {
+        let mut config = SpawnConfig {
+            repo: "org/untrusted".to_string(),
+            issue_number: 13,
+            quarantine: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            check_quarantine(&config, true).unwrap_err(),
+            "Quarantine for issue #13 requires sandbox mode"
+        );
+        config.use_sandbox = true;
+        assert!(check_quarantine(&config, false)
+            .unwrap_err()
+            .contains("requires Docker"));
+        assert!(check_quarantine(&config, true).is_ok());
+        config.quarantine = false;
+        config.use_sandbox = false;
+        assert!(check_quarantine(&config, false).is_ok());
+    }
+
+    #[test]
+    fn test_cleanup_keeps_conflict_reused_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let existing = dir.path().join("existing");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo_path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "base"]);
+        git(&[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "wt-issue-5",
+            existing.to_str().unwrap(),
+        ]);
+
+        let config = SpawnConfig {
+            repo: "org/repo".to_string(),
+            issue_number: 5,
+            worktree_prefix: Some("wt-".to_string()),
+            on_branch_conflict: BranchConflictResolution::ReuseWorktree,
+            ..Default::default()
+        };
+        let worktree_config = WorktreeConfig {
+            prefix: "wt-".to_string(),
+            ..Default::default()
+        };
+        let (worktree, reused) =
+            prepare_spawn_worktree(&config, repo_path, &worktree_config).unwrap();
+        assert!(reused);
+        assert_eq!(worktree.branch, "wt-issue-5");
+
+        let metadata = AgentMetadata {
+            session: "handy-agent-5".to_string(),
+            issue_ref: Some("org/repo#5".to_string()),
+            repo: Some("org/repo".to_string()),
+            worktree: Some(worktree.path.clone()),
+            agent_type: "claude".to_string(),
+            machine_id: "test".to_string(),
+            started_at: String::new(),
+            subpath: None,
+            base_ref: None,
+            pr_number: None,
+            verbose: false,
+            paused: false,
+            push_remote: None,
+            quarantine: false,
+            worktree_reused: reused,
+        };
+        remove_agent_worktree(&metadata, repo_path, true).unwrap();
+        assert!(existing.exists());
+        assert!(worktree::find_branch_conflicts(repo_path, "wt-issue-5")
+            .unwrap()
+            .iter()
+            .any(|wt| wt.path == worktree.path));
+    }
+
+    #[test]
+    fn test_find_done_commit_ignores_inherited_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo_path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "Finish #3 [handy-done]",
+        ]);
+        git(&["checkout", "-q", "-b", "issue-4"]);
+        assert_eq!(find_done_commit(repo_path), None);
+
+        git(&["commit", "-q", "--allow-empty", "-m", "Start #4"]);
+        assert_eq!(find_done_commit(repo_path), None);
+
+        git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "Finish #4\n\n[handy-done]",
+        ]);
+        let head = git(&["rev-parse", "HEAD"]);
+        assert_eq!(find_done_commit(repo_path), Some(head));
+    }
+
+    #[test]
+    fn test_check_reuse_worktree() {
+        let mut config = SpawnConfig {
+            repo: "org/repo".to_string(),
+            issue_number: 21,
+            resume: true,
+            base_ref: Some("v1.0".to_string()),
+            ..Default::default()
+        };
+        assert!(check_reuse_worktree(&config).is_ok());
+        config.reuse_worktree = Some("/work/feature".to_string());
+        assert_eq!(
+            check_reuse_worktree(&config).unwrap_err(),
+            "Cannot combine reuse_worktree (/work/feature) with resume"
+        );
+        config.resume = false;
+        assert!(check_reuse_worktree(&config)
+            .unwrap_err()
+            .ends_with("with base_ref"));
+        config.base_ref = None;
+        config.push_remote = Some("fork".to_string());
+        assert!(check_reuse_worktree(&config)
+            .unwrap_err()
+            .ends_with("with push_remote"));
+        config.push_remote = None;
+        assert!(check_reuse_worktree(&config).is_ok());
+    }
 }
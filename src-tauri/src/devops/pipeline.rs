@@ -48,6 +48,8 @@ pub enum PipelineStatus {
     PrPending,
     /// PR has been created and is being reviewed
     PrReview,
+    /// PR was closed without merging; the item can be requeued
+    PrRejected,
     /// PR has been merged, work is complete
     Completed,
     /// Issue was skipped
@@ -62,6 +64,28 @@ impl Default for PipelineStatus {
     }
 }
 
+impl PipelineStatus {
+    /// Project board status key (e.g. `github::PROJECT_STATUS_TODO`) an item in
+    /// this status is moved to, if any.
+    pub fn project_status(self) -> Option<&'static str> {
+        match self {
+            Self::Queued => Some(github::PROJECT_STATUS_TODO),
+            Self::InProgress => Some(github::PROJECT_STATUS_IN_PROGRESS),
+            Self::Completed => Some(github::PROJECT_STATUS_DONE),
+            _ => None,
+        }
+    }
+}
+
+/// A recorded change of a pipeline item's status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct StatusTransition {
+    /// Status the item moved to
+    pub status: PipelineStatus,
+    /// When the transition happened (RFC 3339)
+    pub at: String,
+}
+
 /// A pipeline item linking issue -> session -> worktree -> PR.
 ///
 /// This struct tracks the full lifecycle of an agent's work on an issue.
@@ -105,6 +129,9 @@ pub struct PipelineItem {
     pub completed_at: Option<String>,
     /// Any error message if failed
     pub error: Option<String>,
+    /// Every status the item has moved through, oldest first
+    #[serde(default)]
+    pub status_history: Vec<StatusTransition>,
 }
 
 impl PipelineItem {
@@ -121,6 +148,7 @@ impl PipelineItem {
             issue.number,
             chrono::Utc::now().timestamp()
         );
+        let created_at = chrono::Utc::now().to_rfc3339();
         Self {
             id,
             tracking_repo: tracking_repo.to_string(),
@@ -137,13 +165,37 @@ impl PipelineItem {
             pr_url: None,
             pr_status: PrPipelineStatus::None,
             status: PipelineStatus::Queued,
-            created_at: chrono::Utc::now().to_rfc3339(),
+            created_at: created_at.clone(),
             started_at: None,
             completed_at: None,
             error: None,
+            status_history: vec![StatusTransition {
+                status: PipelineStatus::Queued,
+                at: created_at,
+            }],
         }
     }
 
+    /// Change the status, recording the transition if it differs from the current one.
+    pub fn set_status(&mut self, status: PipelineStatus) {
+        if self.status == status && !self.status_history.is_empty() {
+            return;
+        }
+        self.status = status;
+        self.status_history.push(StatusTransition {
+            status,
+            at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// When the item first entered `status`, from the status history.
+    pub fn entered_status_at(&self, status: PipelineStatus) -> Option<&str> {
+        self.status_history
+            .iter()
+            .find(|t| t.status == status)
+            .map(|t| t.at.as_str())
+    }
+
     /// Mark the item as in progress with session details.
     pub fn start_work(
         &mut self,
@@ -156,12 +208,23 @@ impl PipelineItem {
         self.worktree_path = Some(worktree_path.to_string());
         self.branch_name = Some(branch_name.to_string());
         self.machine_id = Some(machine_id.to_string());
-        self.status = PipelineStatus::InProgress;
+        self.set_status(PipelineStatus::InProgress);
         self.started_at = Some(chrono::Utc::now().to_rfc3339());
     }
 
     /// Link a PR to this pipeline item.
     pub fn link_pr(&mut self, pr: &GitHubPullRequest) {
+        self.attach_pr(pr);
+        self.set_status(if self.pr_status == PrPipelineStatus::Merged {
+            PipelineStatus::Completed
+        } else {
+            PipelineStatus::PrReview
+        });
+    }
+
+    /// Record a PR on this item without changing its status
+    /// (e.g. an agent resuming work on an existing PR).
+    pub fn attach_pr(&mut self, pr: &GitHubPullRequest) {
         self.pr_number = Some(pr.number);
         self.pr_url = Some(pr.url.clone());
         self.pr_status = if pr.state == "merged" {
@@ -173,11 +236,6 @@ impl PipelineItem {
         } else {
             PrPipelineStatus::Ready
         };
-        self.status = if self.pr_status == PrPipelineStatus::Merged {
-            PipelineStatus::Completed
-        } else {
-            PipelineStatus::PrReview
-        };
     }
 
     /// Update PR status from a GitHubPullRequest.
@@ -202,28 +260,51 @@ impl PipelineItem {
         };
 
         // Update overall status based on PR status
-        self.status = match self.pr_status {
+        let status = match self.pr_status {
             PrPipelineStatus::Merged => {
                 self.completed_at = Some(chrono::Utc::now().to_rfc3339());
                 PipelineStatus::Completed
             }
             PrPipelineStatus::Closed => {
-                self.completed_at = Some(chrono::Utc::now().to_rfc3339());
-                PipelineStatus::Failed
+                self.error = Some(format!("PR #{} was closed without merging", pr.number));
+                PipelineStatus::PrRejected
             }
             _ => PipelineStatus::PrReview,
         };
+        self.set_status(status);
     }
 
     /// Mark as skipped.
     pub fn skip(&mut self) {
-        self.status = PipelineStatus::Skipped;
+        self.set_status(PipelineStatus::Skipped);
         self.completed_at = Some(chrono::Utc::now().to_rfc3339());
     }
 
+    /// Send an item whose PR was rejected back to the queue.
+    ///
+    /// The rejection is kept in `error`; the PR, session and worktree are
+    /// cleared so the next agent starts fresh.
+    pub fn requeue(&mut self) {
+        if let Some(pr_number) = self.pr_number {
+            self.error = Some(format!(
+                "Requeued after PR #{} was closed without merging",
+                pr_number
+            ));
+        }
+        self.session_name = None;
+        self.worktree_path = None;
+        self.branch_name = None;
+        self.machine_id = None;
+        self.pr_number = None;
+        self.pr_url = None;
+        self.pr_status = PrPipelineStatus::None;
+        self.started_at = None;
+        self.set_status(PipelineStatus::Queued);
+    }
+
     /// Mark as failed with an error message.
     pub fn fail(&mut self, error: &str) {
-        self.status = PipelineStatus::Failed;
+        self.set_status(PipelineStatus::Failed);
         self.error = Some(error.to_string());
         self.completed_at = Some(chrono::Utc::now().to_rfc3339());
     }
@@ -257,7 +338,7 @@ pub struct PipelineState {
     pub max_history: usize,
 }
 
-fn default_max_history() -> usize {
+pub fn default_max_history() -> usize {
     100
 }
 
@@ -319,11 +400,7 @@ impl PipelineState {
     pub fn archive_item(&mut self, id: &str) -> Option<PipelineItem> {
         if let Some(item) = self.items.remove(id) {
             if item.is_complete() {
-                self.history.push(item.clone());
-                // Trim history if needed
-                while self.history.len() > self.max_history {
-                    self.history.remove(0);
-                }
+                self.push_history(item.clone());
             }
             Some(item)
         } else {
@@ -331,6 +408,19 @@ impl PipelineState {
         }
     }
 
+    /// Append an item to history, dropping the oldest beyond `max_history`.
+    pub fn push_history(&mut self, item: PipelineItem) {
+        self.history.push(item);
+        self.prune_history(self.max_history);
+    }
+
+    /// Keep only the newest `keep` history items. Returns how many were removed.
+    pub fn prune_history(&mut self, keep: usize) -> usize {
+        let excess = self.history.len().saturating_sub(keep);
+        self.history.drain(..excess);
+        excess
+    }
+
     /// Get all active pipeline items.
     pub fn get_active_items(&self) -> Vec<&PipelineItem> {
         self.items
@@ -401,9 +491,10 @@ pub fn aggregate_pipeline_state(
                 item.worktree_path = session.worktree.clone();
                 item.machine_id = Some(session.machine_id.clone());
 
-                // Update status based on session state
-                if !item.is_complete() {
-                    item.status = PipelineStatus::InProgress;
+                // Update status based on session state; a rejected PR stays
+                // visible until the item is requeued
+                if !item.is_complete() && item.status != PipelineStatus::PrRejected {
+                    item.set_status(PipelineStatus::InProgress);
                 }
             }
         }
@@ -461,6 +552,7 @@ mod tests {
             url: "https://github.com/test/repo/issues/123".to_string(),
             labels: vec![],
             assignees: vec![],
+            milestone: None,
             author: "testuser".to_string(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
@@ -472,15 +564,36 @@ mod tests {
         assert!(!item.is_active());
         assert!(!item.is_complete());
 
+        assert_eq!(
+            item.status.project_status(),
+            Some(github::PROJECT_STATUS_TODO)
+        );
+
         item.start_work("session-1", "/tmp/worktree", "issue-123", "machine-1");
         assert_eq!(item.status, PipelineStatus::InProgress);
         assert!(item.is_active());
         assert!(!item.is_complete());
+        assert_eq!(
+            item.status.project_status(),
+            Some(github::PROJECT_STATUS_IN_PROGRESS)
+        );
 
         item.skip();
         assert_eq!(item.status, PipelineStatus::Skipped);
+        assert_eq!(item.status.project_status(), None);
         assert!(!item.is_active());
         assert!(item.is_complete());
+
+        let statuses: Vec<_> = item.status_history.iter().map(|t| t.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                PipelineStatus::Queued,
+                PipelineStatus::InProgress,
+                PipelineStatus::Skipped
+            ]
+        );
+        assert!(item.entered_status_at(PipelineStatus::Completed).is_none());
     }
 
     #[test]
@@ -495,6 +608,7 @@ mod tests {
             url: "https://github.com/test/repo/issues/123".to_string(),
             labels: vec![],
             assignees: vec![],
+            milestone: None,
             author: "testuser".to_string(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
@@ -508,4 +622,45 @@ mod tests {
         assert!(state.get_item(&item_id).is_some());
         assert!(state.find_by_issue("test/repo", 123).is_some());
     }
+
+    #[test]
+    fn test_history_trims_oldest_first() {
+        let mut state = PipelineState::new();
+        state.max_history = 2;
+
+        let issue = GitHubIssue {
+            number: 1,
+            title: "Test Issue".to_string(),
+            body: None,
+            state: "open".to_string(),
+            url: "https://github.com/test/repo/issues/1".to_string(),
+            labels: vec![],
+            assignees: vec![],
+            milestone: None,
+            author: "testuser".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            repo: "test/repo".to_string(),
+        };
+
+        for number in 1..=3 {
+            let mut item = PipelineItem::from_issue(
+                &GitHubIssue {
+                    number,
+                    ..issue.clone()
+                },
+                "test/tracking",
+                "test/repo",
+                "claude",
+            );
+            item.skip();
+            state.push_history(item);
+        }
+        let numbers: Vec<u64> = state.history.iter().map(|i| i.issue_number).collect();
+        assert_eq!(numbers, vec![2, 3]);
+
+        assert_eq!(state.prune_history(1), 1);
+        assert_eq!(state.history[0].issue_number, 3);
+        assert_eq!(state.prune_history(5), 0);
+    }
 }
@@ -0,0 +1,491 @@
+//! Retry and timeout support for external commands (gh, git, tmux, docker).
+//!
+//! Transient failures such as a held git index lock, a busy Docker daemon, or
+//! GitHub API hiccups surface as hard errors. Idempotent read operations can
+//! use `output_with_retry()` to retry those cases with exponential backoff.
+//! Mutating operations should keep using `output()` unless retrying is known
+//! to be safe.
+//!
+//! Network-touching commands (`gh`, `docker pull`) can hang indefinitely, so
+//! they run under a timeout: `output_with_retry()` applies it automatically
+//! and mutating calls use `output_with_timeout()`.
+//!
+//! With command debugging on, every command run through these helpers is
+//! logged (credential-sanitized) and kept in a short history for bug reports.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default number of retries for read operations
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// Default initial backoff (doubled after each attempt)
+pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Default timeout for `gh` commands
+pub const DEFAULT_GH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default timeout for `docker pull`
+pub const DEFAULT_DOCKER_PULL_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// User overrides of the default timeouts in seconds (0 = use the default)
+static GH_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+static DOCKER_PULL_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+
+/// Timeout overrides for network-touching commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct CommandTimeouts {
+    /// Seconds before a `gh` command is killed (None = 60)
+    #[serde(default)]
+    pub gh_secs: Option<u64>,
+    /// Seconds before a `docker pull` is killed (None = 900)
+    #[serde(default)]
+    pub docker_pull_secs: Option<u64>,
+}
+
+/// Apply timeout overrides for subsequent commands
+pub fn set_command_timeouts(timeouts: &CommandTimeouts) {
+    GH_TIMEOUT_SECS.store(timeouts.gh_secs.unwrap_or(0), Ordering::Relaxed);
+    DOCKER_PULL_TIMEOUT_SECS.store(timeouts.docker_pull_secs.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn timeout_or_default(secs: &AtomicU64, default: Duration) -> Duration {
+    match secs.load(Ordering::Relaxed) {
+        0 => default,
+        secs => Duration::from_secs(secs),
+    }
+}
+
+/// Current timeout for `gh` commands
+pub fn gh_timeout() -> Duration {
+    timeout_or_default(&GH_TIMEOUT_SECS, DEFAULT_GH_TIMEOUT)
+}
+
+/// Current timeout for `docker pull`
+pub fn docker_pull_timeout() -> Duration {
+    timeout_or_default(&DOCKER_PULL_TIMEOUT_SECS, DEFAULT_DOCKER_PULL_TIMEOUT)
+}
+
+/// Timeout for a command that talks to the network, or None for local commands
+fn network_timeout(cmd: &Command) -> Option<Duration> {
+    let program = Path::new(cmd.get_program()).file_name()?.to_str()?;
+    match program {
+        "gh" => Some(gh_timeout()),
+        "docker" if cmd.get_args().next().is_some_and(|a| a == "pull") => {
+            Some(docker_pull_timeout())
+        }
+        _ => None,
+    }
+}
+
+/// Run a command like `Command::output`, killing it if it outlives `timeout`.
+///
+/// stdin is closed; stdout and stderr are captured. A command that times out
+/// is killed and an `io::ErrorKind::TimedOut` error is returned.
+pub fn run_with_timeout(cmd: &mut Command, timeout: Duration) -> io::Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain both pipes on threads so a chatty child can't block on a full pipe
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = drain(
+        child
+            .stdout
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+    let stderr = drain(
+        child
+            .stderr
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+
+    let deadline = Instant::now() + timeout;
+    let mut poll = Duration::from_millis(5);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "{} timed out after {}s",
+                    cmd.get_program().to_string_lossy(),
+                    timeout.as_secs()
+                ),
+            ));
+        }
+        std::thread::sleep(poll.min(deadline.saturating_duration_since(Instant::now())));
+        poll = (poll * 2).min(Duration::from_millis(100));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Run a command, applying the network timeout if it has one
+fn output_with_network_timeout(cmd: &mut Command) -> io::Result<Output> {
+    output_recorded(cmd, |cmd| match network_timeout(cmd) {
+        Some(timeout) => run_with_timeout(cmd, timeout),
+        None => cmd.output(),
+    })
+}
+
+/// Number of executed commands kept while command debugging is on
+const COMMAND_HISTORY_SIZE: usize = 100;
+
+/// Characters of stdout/stderr kept per history entry
+const COMMAND_OUTPUT_LIMIT: usize = 2000;
+
+/// Whether executed commands are logged and recorded
+static DEBUG_COMMANDS: AtomicBool = AtomicBool::new(false);
+
+/// Most recent executed commands, oldest first
+static COMMAND_HISTORY: Mutex<VecDeque<ExecutedCommand>> = Mutex::new(VecDeque::new());
+
+/// An external command recorded while command debugging is on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ExecutedCommand {
+    /// Program and arguments as a shell command line, credentials redacted
+    pub command: String,
+    /// Exit code (None if killed by a signal or the command failed to run)
+    pub exit_code: Option<i32>,
+    /// Start of stdout, credentials redacted
+    pub stdout: String,
+    /// Start of stderr, credentials redacted
+    pub stderr: String,
+    /// Error running the command (e.g. not found, timed out)
+    pub error: Option<String>,
+    /// How long the command ran in milliseconds
+    pub duration_ms: u64,
+    /// When the command finished (RFC 3339)
+    pub finished_at: String,
+}
+
+/// Turn logging and recording of executed commands on or off
+pub fn set_command_debug(enabled: bool) {
+    DEBUG_COMMANDS.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        COMMAND_HISTORY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+/// The `n` most recently executed commands, newest first
+pub fn last_commands(n: usize) -> Vec<ExecutedCommand> {
+    COMMAND_HISTORY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .rev()
+        .take(n)
+        .cloned()
+        .collect()
+}
+
+/// Sanitized and truncated command output
+fn history_output(bytes: &[u8]) -> String {
+    let text = super::docker::sanitize_sensitive_data(&String::from_utf8_lossy(bytes));
+    match text.char_indices().nth(COMMAND_OUTPUT_LIMIT) {
+        Some((end, _)) => format!("{}… (truncated)", &text[..end]),
+        None => text,
+    }
+}
+
+/// Log and record a finished command if command debugging is on
+fn record_command(cmd: &Command, result: &io::Result<Output>, started: Instant) {
+    if !DEBUG_COMMANDS.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let argv: Vec<String> = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| super::shell::shell_quote(&arg.to_string_lossy()))
+        .collect();
+    let mut entry = ExecutedCommand {
+        command: super::docker::sanitize_sensitive_data(&argv.join(" ")),
+        exit_code: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+        duration_ms: started.elapsed().as_millis() as u64,
+        finished_at: chrono::Utc::now().to_rfc3339(),
+    };
+    match result {
+        Ok(output) => {
+            entry.exit_code = output.status.code();
+            entry.stdout = history_output(&output.stdout);
+            entry.stderr = history_output(&output.stderr);
+        }
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+
+    log::info!(
+        "[cmd] {} -> {} ({} ms)",
+        entry.command,
+        entry
+            .exit_code
+            .map(|c| c.to_string())
+            .or_else(|| entry.error.clone())
+            .unwrap_or_else(|| "signal".to_string()),
+        entry.duration_ms
+    );
+
+    let mut history = COMMAND_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+    if history.len() >= COMMAND_HISTORY_SIZE {
+        history.pop_front();
+    }
+    history.push_back(entry);
+}
+
+/// Run a command like `Command::output`, recording it for command debugging
+fn output_recorded(
+    cmd: &mut Command,
+    run: impl FnOnce(&mut Command) -> io::Result<Output>,
+) -> io::Result<Output> {
+    let started = Instant::now();
+    let result = run(cmd);
+    record_command(cmd, &result, started);
+    result
+}
+
+/// stderr substrings that indicate a transient failure worth retrying
+const RETRYABLE_PATTERNS: &[&str] = &[
+    // git: another process holds the index/ref lock
+    "index.lock",
+    "cannot lock ref",
+    // tmux: server starting or restarting
+    "server exited unexpectedly",
+    "lost server",
+    // docker: daemon busy
+    "context deadline exceeded",
+    "connection reset by peer",
+    // gh: network and GitHub API hiccups
+    "i/o timeout",
+    "TLS handshake timeout",
+    "HTTP 502",
+    "HTTP 503",
+    "HTTP 504",
+];
+
+/// Docker sockets whose presence suggests the daemon is installed and may be starting
+const DOCKER_SOCKETS: &[&str] = &["/var/run/docker.sock", ".docker/run/docker.sock"];
+
+/// Whether the Docker daemon might still be starting up
+///
+/// A missing daemon only counts as transient if a socket exists (or
+/// DOCKER_HOST is set); otherwise Docker simply isn't running.
+fn docker_daemon_may_be_starting() -> bool {
+    if std::env::var("DOCKER_HOST").is_ok_and(|h| !h.is_empty()) {
+        return true;
+    }
+    let home = std::env::var("HOME").unwrap_or_default();
+    DOCKER_SOCKETS.iter().any(|socket| {
+        if socket.starts_with('/') {
+            Path::new(socket).exists()
+        } else {
+            Path::new(&home).join(socket).exists()
+        }
+    })
+}
+
+/// Whether a failed command's stderr indicates a transient, retryable error
+pub fn is_retryable_error(stderr: &str) -> bool {
+    if RETRYABLE_PATTERNS.iter().any(|p| stderr.contains(p)) {
+        return true;
+    }
+    stderr.contains("Cannot connect to the Docker daemon") && docker_daemon_may_be_starting()
+}
+
+/// Run a command, retrying transient failures with exponential backoff.
+///
+/// Only failures whose stderr matches `is_retryable_error` are retried; other
+/// failures and spawn errors (e.g. binary not found) are returned immediately.
+/// Returns the output of the last attempt.
+pub fn run_command_with_retry(
+    cmd: &mut Command,
+    retries: u32,
+    backoff: Duration,
+) -> io::Result<Output> {
+    let mut delay = backoff;
+    let mut attempt = 0;
+
+    loop {
+        let output = output_with_network_timeout(cmd)?;
+        if output.status.success() || attempt >= retries {
+            return Ok(output);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !is_retryable_error(&stderr) {
+            return Ok(output);
+        }
+
+        attempt += 1;
+        log::debug!(
+            "Retrying {:?} ({}/{}) after transient error: {}",
+            cmd.get_program(),
+            attempt,
+            retries,
+            stderr.trim()
+        );
+        std::thread::sleep(delay);
+        delay *= 2;
+    }
+}
+
+/// Extension for running commands with the default retry and timeout policy.
+pub trait CommandRetryExt {
+    /// Like `Command::output`, but retries transient failures.
+    fn output_with_retry(&mut self) -> io::Result<Output>;
+
+    /// Like `Command::output`, but kills network commands that hang.
+    fn output_with_timeout(&mut self) -> io::Result<Output>;
+
+    /// `Command::output`, recorded when command debugging is on.
+    fn output_logged(&mut self) -> io::Result<Output>;
+}
+
+impl CommandRetryExt for Command {
+    fn output_with_retry(&mut self) -> io::Result<Output> {
+        run_command_with_retry(self, DEFAULT_RETRIES, DEFAULT_BACKOFF)
+    }
+
+    fn output_with_timeout(&mut self) -> io::Result<Output> {
+        output_with_network_timeout(self)
+    }
+
+    fn output_logged(&mut self) -> io::Result<Output> {
+        output_recorded(self, |cmd| cmd.output())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable_error() {
+        assert!(is_retryable_error(
+            "fatal: Unable to create '/repo/.git/index.lock': File exists."
+        ));
+        assert!(is_retryable_error("HTTP 502: Bad Gateway"));
+        assert!(!is_retryable_error(
+            "no server running on /tmp/tmux-0/handy"
+        ));
+        assert!(!is_retryable_error("Could not resolve to an Issue"));
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        let output = run_with_timeout(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+
+        let start = Instant::now();
+        let err = run_with_timeout(
+            Command::new("sh").args(["-c", "sleep 5"]),
+            Duration::from_millis(100),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_network_timeout() {
+        assert_eq!(
+            network_timeout(Command::new("gh").args(["pr", "list"])),
+            Some(DEFAULT_GH_TIMEOUT)
+        );
+        assert_eq!(
+            network_timeout(Command::new("docker").args(["pull", "node:20"])),
+            Some(DEFAULT_DOCKER_PULL_TIMEOUT)
+        );
+        assert_eq!(network_timeout(Command::new("docker").arg("ps")), None);
+        assert_eq!(network_timeout(Command::new("git").arg("status")), None);
+    }
+
+    #[test]
+    fn test_command_history() {
+        set_command_debug(true);
+        Command::new("sh")
+            .args(["-c", "echo ghp_abc123secret; exit 3"])
+            .output_logged()
+            .unwrap();
+        let last = last_commands(10);
+        set_command_debug(false);
+
+        let entry = last
+            .iter()
+            .find(|c| c.command.contains("exit 3"))
+            .expect("command recorded");
+        assert!(entry.command.starts_with("'sh' '-c'"));
+        assert!(!entry.command.contains("ghp_abc123secret"));
+        assert_eq!(entry.exit_code, Some(3));
+        assert_eq!(entry.stdout.trim(), "[REDACTED]");
+    }
+
+    #[test]
+    fn test_run_command_with_retry_returns_non_retryable_failure() {
+        let output = run_command_with_retry(
+            Command::new("sh").args(["-c", "echo 'not found' >&2; exit 1"]),
+            3,
+            Duration::from_millis(1),
+        )
+        .unwrap();
+        assert!(!output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "not found");
+    }
+
+    #[test]
+    fn test_run_command_with_retry_retries_transient_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("attempted");
+        // Fails with a lock error on the first attempt, succeeds on the second
+        let script = format!(
+            "if [ -f '{0}' ]; then echo ok; else touch '{0}'; echo 'index.lock exists' >&2; exit 1; fi",
+            marker.display()
+        );
+        let output = run_command_with_retry(
+            Command::new("sh").args(["-c", &script]),
+            2,
+            Duration::from_millis(1),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+    }
+}
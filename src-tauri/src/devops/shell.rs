@@ -0,0 +1,51 @@
+//! Shell quoting for command strings built from untrusted input.
+//!
+//! Agent and sandbox commands are sent to tmux or `sh -c` as a single string,
+//! so anything interpolated into them (issue titles, task descriptions, paths)
+//! must be quoted to prevent word splitting and command injection.
+
+/// Quote a string as a single POSIX shell word.
+///
+/// Wraps the value in single quotes, inside which the shell performs no
+/// expansion at all (`$()`, backticks, `$VAR`, globs and newlines are literal).
+/// Embedded single quotes are written as `'\''`.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Run `printf %s <quoted>` through sh and return what the shell passed through
+    fn round_trip(s: &str) -> String {
+        let output = Command::new("sh")
+            .args(["-c", &format!("printf %s {}", shell_quote(s))])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_shell_quote_simple() {
+        assert_eq!(shell_quote("hello world"), "'hello world'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_shell_quote_adversarial_round_trip() {
+        for title in [
+            "Fix `rm -rf ~` in installer",
+            "Use $(whoami) and $HOME",
+            "Don't break 'quotes' \"here\"",
+            "Line one\nline two; echo injected",
+            "Glob * and ? and [abc] && || ; | > <",
+            "'; touch /tmp/handy-pwned; echo '",
+        ] {
+            assert_eq!(round_trip(title), title);
+        }
+    }
+}
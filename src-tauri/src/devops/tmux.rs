@@ -6,7 +6,12 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::RwLock;
+
+use super::process::CommandRetryExt;
+use super::shell::shell_quote;
 
 /// Session naming prefix for all Handy agent sessions
 const SESSION_PREFIX: &str = "handy-agent-";
@@ -14,6 +19,15 @@ const SESSION_PREFIX: &str = "handy-agent-";
 /// Base prefix for all Handy-related tmux sessions (includes master)
 const HANDY_PREFIX: &str = "handy-";
 
+/// Default template for agent session names
+pub const DEFAULT_SESSION_NAME_TEMPLATE: &str = "handy-agent-{issue}";
+
+/// User override of the session name template (None = default)
+static SESSION_NAME_TEMPLATE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Directory debug captures are written to, set at startup from the app data dir
+static DEBUG_CAPTURE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
 /// Custom socket name to avoid macOS /private/tmp permission issues
 const SOCKET_NAME: &str = "handy";
 
@@ -24,6 +38,15 @@ const ENV_WORKTREE: &str = "HANDY_WORKTREE";
 const ENV_AGENT_TYPE: &str = "HANDY_AGENT_TYPE";
 const ENV_MACHINE_ID: &str = "HANDY_MACHINE_ID";
 const ENV_STARTED_AT: &str = "HANDY_STARTED_AT";
+const ENV_SUBPATH: &str = "HANDY_SUBPATH";
+const ENV_BASE_REF: &str = "HANDY_BASE_REF";
+const ENV_PR_NUMBER: &str = "HANDY_PR_NUMBER";
+const ENV_VERBOSE: &str = "HANDY_VERBOSE";
+const ENV_PAUSED: &str = "HANDY_PAUSED";
+const ENV_PAUSED_PGID: &str = "HANDY_PAUSED_PGID";
+const ENV_PUSH_REMOTE: &str = "HANDY_PUSH_REMOTE";
+const ENV_QUARANTINE: &str = "HANDY_QUARANTINE";
+const ENV_WORKTREE_REUSED: &str = "HANDY_WORKTREE_REUSED";
 
 /// Status of an agent session
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
@@ -53,6 +76,32 @@ pub struct AgentMetadata {
     pub machine_id: String,
     /// ISO timestamp when session started
     pub started_at: String,
+    /// Subdirectory of the worktree the agent works in (monorepo packages)
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Commit SHA or tag the worktree was started from, if pinned
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    /// Pull request the agent is iterating on, when spawned from a PR
+    #[serde(default)]
+    pub pr_number: Option<u64>,
+    /// Whether the agent was started with its verbose flag
+    #[serde(default)]
+    pub verbose: bool,
+    /// Whether the agent is paused (see `pause_agent`)
+    #[serde(default)]
+    pub paused: bool,
+    /// Remote the agent's branch is pushed to, when not `origin` (e.g., a fork)
+    #[serde(default)]
+    pub push_remote: Option<String>,
+    /// Whether the agent runs quarantined (no network, credentials or writes);
+    /// completing its work never creates a PR
+    #[serde(default)]
+    pub quarantine: bool,
+    /// Whether the agent was spawned into an existing worktree it does not own;
+    /// cleaning up the agent leaves such a worktree in place
+    #[serde(default)]
+    pub worktree_reused: bool,
 }
 
 /// Information about a tmux session
@@ -110,7 +159,7 @@ pub struct RecoveredSession {
 pub fn is_tmux_running() -> bool {
     Command::new("tmux")
         .args(["-L", SOCKET_NAME, "list-sessions"])
-        .output()
+        .output_logged()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
@@ -118,7 +167,7 @@ pub fn is_tmux_running() -> bool {
 /// Get the current machine's hostname for identification
 fn get_machine_id() -> String {
     Command::new("hostname")
-        .output()
+        .output_logged()
         .ok()
         .and_then(|o| {
             if o.status.success() {
@@ -141,7 +190,7 @@ pub fn list_sessions() -> Result<Vec<TmuxSession>, String> {
             "-F",
             "#{session_name}\t#{session_attached}\t#{session_windows}\t#{session_created}",
         ])
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to list tmux sessions: {}", e))?;
 
     if !output.status.success() {
@@ -201,7 +250,7 @@ fn check_session_has_active_process(session_name: &str) -> bool {
             "-F",
             "#{pane_current_command}",
         ])
-        .output()
+        .output_logged()
         .map(|o| {
             if o.status.success() {
                 let cmd = String::from_utf8_lossy(&o.stdout).trim().to_string();
@@ -218,7 +267,7 @@ fn check_session_has_active_process(session_name: &str) -> bool {
 pub fn get_session_metadata(session_name: &str) -> Result<AgentMetadata, String> {
     let output = Command::new("tmux")
         .args(["-L", SOCKET_NAME, "show-environment", "-t", session_name])
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to get session environment: {}", e))?;
 
     if !output.status.success() {
@@ -253,6 +302,14 @@ pub fn get_session_metadata(session_name: &str) -> Result<AgentMetadata, String>
             .get(ENV_STARTED_AT)
             .cloned()
             .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+        subpath: env_vars.get(ENV_SUBPATH).cloned(),
+        base_ref: env_vars.get(ENV_BASE_REF).cloned(),
+        pr_number: env_vars.get(ENV_PR_NUMBER).and_then(|n| n.parse().ok()),
+        verbose: env_vars.get(ENV_VERBOSE).is_some_and(|v| v == "1"),
+        paused: env_vars.get(ENV_PAUSED).is_some_and(|v| v == "1"),
+        push_remote: env_vars.get(ENV_PUSH_REMOTE).cloned(),
+        quarantine: env_vars.get(ENV_QUARANTINE).is_some_and(|v| v == "1"),
+        worktree_reused: env_vars.get(ENV_WORKTREE_REUSED).is_some_and(|v| v == "1"),
     })
 }
 
@@ -287,7 +344,7 @@ pub fn create_session(
 
     let output = Command::new("tmux")
         .args(&full_args)
-        .output()
+        .output_logged()
         .map_err(|e| format!("Failed to create session: {}", e))?;
 
     if !output.status.success() {
@@ -311,6 +368,27 @@ pub fn create_session(
     if let Some(ref worktree) = metadata.worktree {
         set_session_env(session_name, ENV_WORKTREE, worktree)?;
     }
+    if let Some(ref subpath) = metadata.subpath {
+        set_session_env(session_name, ENV_SUBPATH, subpath)?;
+    }
+    if let Some(ref base_ref) = metadata.base_ref {
+        set_session_env(session_name, ENV_BASE_REF, base_ref)?;
+    }
+    if let Some(pr_number) = metadata.pr_number {
+        set_session_env(session_name, ENV_PR_NUMBER, &pr_number.to_string())?;
+    }
+    if let Some(ref push_remote) = metadata.push_remote {
+        set_session_env(session_name, ENV_PUSH_REMOTE, push_remote)?;
+    }
+    if metadata.quarantine {
+        set_session_env(session_name, ENV_QUARANTINE, "1")?;
+    }
+    if metadata.verbose {
+        set_session_env(session_name, ENV_VERBOSE, "1")?;
+    }
+    if metadata.worktree_reused {
+        set_session_env(session_name, ENV_WORKTREE_REUSED, "1")?;
+    }
 
     Ok(())
 }
@@ -327,7 +405,7 @@ fn set_session_env(session_name: &str, key: &str, value: &str) -> Result<(), Str
             key,
             value,
         ])
-        .output()
+        .output_logged()
         .map_err(|e| format!("Failed to set environment: {}", e))?;
 
     if !output.status.success() {
@@ -348,22 +426,18 @@ pub fn kill_session(session_name: &str) -> Result<(), String> {
     if let Ok(metadata) = get_session_metadata(session_name) {
         // Extract issue number from issue_ref (e.g., "org/repo#123" -> 123)
         if let Some(issue_ref) = &metadata.issue_ref {
-            if let Some(issue_num) = issue_ref
-                .split('#')
-                .last()
-                .and_then(|n| n.parse::<u32>().ok())
+            if let Some((repo, issue_num)) = issue_ref
+                .rsplit_once('#')
+                .and_then(|(repo, n)| n.parse::<u32>().ok().map(|n| (repo, n)))
             {
                 // Try to kill sandbox containers (both agent and support worker patterns)
-                let container_patterns = [
-                    format!("handy-sandbox-{}", issue_num),
-                    format!("handy-support-sandbox-{}", issue_num),
-                ];
+                let container_patterns = super::docker::containers_for_issue(repo, issue_num);
 
                 for container_name in &container_patterns {
                     // Force remove the container (ignore errors - container may not exist)
                     let _ = Command::new("docker")
                         .args(["rm", "-f", container_name])
-                        .output();
+                        .output_logged();
                     log::debug!("Attempted to remove Docker container: {}", container_name);
                 }
             }
@@ -371,9 +445,14 @@ pub fn kill_session(session_name: &str) -> Result<(), String> {
     }
 
     // Now kill the tmux session
+    kill_session_only(session_name)
+}
+
+/// Kill a tmux session without touching any Docker containers for its issue
+pub fn kill_session_only(session_name: &str) -> Result<(), String> {
     let output = Command::new("tmux")
         .args(["-L", SOCKET_NAME, "kill-session", "-t", session_name])
-        .output()
+        .output_logged()
         .map_err(|e| format!("Failed to kill session: {}", e))?;
 
     if !output.status.success() {
@@ -386,9 +465,103 @@ pub fn kill_session(session_name: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Get recent output from a session's pane
-pub fn get_session_output(session_name: &str, lines: Option<u32>) -> Result<String, String> {
+/// A pane in a tmux session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TmuxPane {
+    /// Pane target within the session (`<window>.<pane>`), as accepted by
+    /// `get_session_output` and `send_keys`
+    pub target: String,
+    /// Window index
+    pub window_index: u32,
+    /// Window name
+    pub window_name: String,
+    /// Pane index within the window
+    pub pane_index: u32,
+    /// Command running in the pane's foreground
+    pub current_command: String,
+    /// Whether this is the active pane of the active window, which is what
+    /// commands without a pane target use
+    pub active: bool,
+}
+
+/// Parse a `list-panes` line formatted as
+/// `#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_current_command}\t#{pane_active}\t#{window_active}`
+fn parse_pane_line(line: &str) -> Option<TmuxPane> {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() < 6 {
+        return None;
+    }
+    let window_index: u32 = parts[0].parse().ok()?;
+    let pane_index: u32 = parts[2].parse().ok()?;
+
+    Some(TmuxPane {
+        target: format!("{}.{}", window_index, pane_index),
+        window_index,
+        window_name: parts[1].to_string(),
+        pane_index,
+        current_command: parts[3].to_string(),
+        active: parts[4] == "1" && parts[5] == "1",
+    })
+}
+
+/// List every window and pane in a session
+pub fn list_session_panes(session_name: &str) -> Result<Vec<TmuxPane>, String> {
+    let output = Command::new("tmux")
+        .args([
+            "-L",
+            SOCKET_NAME,
+            "list-panes",
+            "-s",
+            "-t",
+            session_name,
+            "-F",
+            "#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_current_command}\t#{pane_active}\t#{window_active}",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to list panes: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "tmux error: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_pane_line)
+        .collect())
+}
+
+/// tmux target for a pane of a session; None targets the active pane.
+///
+/// Panes are addressed as `<window>.<pane>` (or just `<window>`) so a
+/// target can't reach outside the session.
+fn pane_target(session_name: &str, pane: Option<&str>) -> Result<String, String> {
+    let Some(pane) = pane else {
+        return Ok(session_name.to_string());
+    };
+    let valid = pane
+        .split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        && pane.split('.').count() <= 2;
+    if !valid {
+        return Err(format!(
+            "Invalid pane target '{}', expected <window>.<pane>",
+            pane
+        ));
+    }
+    Ok(format!("{}:{}", session_name, pane))
+}
+
+/// Get recent output from a session's pane (the active one unless `pane` is given)
+pub fn get_session_output(
+    session_name: &str,
+    lines: Option<u32>,
+    pane: Option<&str>,
+) -> Result<String, String> {
     let line_count = lines.unwrap_or(100).to_string();
+    let target = pane_target(session_name, pane)?;
 
     let output = Command::new("tmux")
         .args([
@@ -396,12 +569,12 @@ pub fn get_session_output(session_name: &str, lines: Option<u32>) -> Result<Stri
             SOCKET_NAME,
             "capture-pane",
             "-t",
-            session_name,
+            &target,
             "-p",
             "-S",
             &format!("-{}", line_count),
         ])
-        .output()
+        .output_logged()
         .map_err(|e| format!("Failed to capture pane: {}", e))?;
 
     if !output.status.success() {
@@ -414,6 +587,35 @@ pub fn get_session_output(session_name: &str, lines: Option<u32>) -> Result<Stri
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Seconds since a session last produced output or received input
+pub fn get_session_idle_seconds(session_name: &str) -> Result<u64, String> {
+    let output = Command::new("tmux")
+        .args([
+            "-L",
+            SOCKET_NAME,
+            "display-message",
+            "-p",
+            "-t",
+            session_name,
+            "#{window_activity}",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to get session activity: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "tmux error: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let last_activity: i64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid session activity timestamp: {}", e))?;
+    Ok((chrono::Utc::now().timestamp() - last_activity).max(0) as u64)
+}
+
 /// Send a command to a session
 /// If the command is empty, sends just Enter key
 /// Special key sequences: Enter, Escape, Tab, Space, BSpace, Up, Down, Left, Right, etc.
@@ -430,7 +632,7 @@ pub fn send_command(session_name: &str, command: &str) -> Result<(), String> {
 
     let output = Command::new("tmux")
         .args(&args)
-        .output()
+        .output_logged()
         .map_err(|e| format!("Failed to send command: {}", e))?;
 
     if !output.status.success() {
@@ -444,11 +646,13 @@ pub fn send_command(session_name: &str, command: &str) -> Result<(), String> {
 }
 
 /// Send raw keys to a session without appending Enter
-/// Use this for special keys like Escape, Tab, or partial input
-pub fn send_keys(session_name: &str, keys: &str) -> Result<(), String> {
+/// Use this for special keys like Escape, Tab, or partial input.
+/// Keys go to the active pane unless `pane` is given
+pub fn send_keys(session_name: &str, keys: &str, pane: Option<&str>) -> Result<(), String> {
+    let target = pane_target(session_name, pane)?;
     let output = Command::new("tmux")
-        .args(["-L", SOCKET_NAME, "send-keys", "-t", session_name, keys])
-        .output()
+        .args(["-L", SOCKET_NAME, "send-keys", "-t", &target, keys])
+        .output_logged()
         .map_err(|e| format!("Failed to send keys: {}", e))?;
 
     if !output.status.success() {
@@ -461,6 +665,250 @@ pub fn send_keys(session_name: &str, keys: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Foreground process group of a pane's terminal, from `ps -o tpgid=` output.
+///
+/// `None` when only the pane's shell (whose process group is the pane PID)
+/// is in the foreground, i.e. no agent is running.
+fn parse_foreground_pgid(ps_output: &str, pane_pid: i32) -> Option<i32> {
+    ps_output
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|&pgid| pgid > 0 && pgid != pane_pid)
+}
+
+/// Foreground process group of a session's pane, if it isn't the shell's
+fn pane_foreground_pgid(session_name: &str) -> Result<Option<i32>, String> {
+    let output = Command::new("tmux")
+        .args([
+            "-L",
+            SOCKET_NAME,
+            "display-message",
+            "-p",
+            "-t",
+            session_name,
+            "#{pane_pid}",
+        ])
+        .output_with_retry()
+        .map_err(|e| format!("Failed to get pane PID: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "tmux error: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let pane_pid: i32 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid pane PID: {}", e))?;
+
+    let output = Command::new("ps")
+        .args(["-o", "tpgid=", "-p", &pane_pid.to_string()])
+        .output_logged()
+        .map_err(|e| format!("Failed to run ps: {}", e))?;
+    Ok(parse_foreground_pgid(
+        &String::from_utf8_lossy(&output.stdout),
+        pane_pid,
+    ))
+}
+
+/// Process group of the agent running in a session's pane
+fn agent_process_group(session_name: &str) -> Result<i32, String> {
+    pane_foreground_pgid(session_name)?
+        .ok_or_else(|| format!("No agent process is running in session {}", session_name))
+}
+
+/// Read one environment variable of a tmux session
+fn get_session_env(session_name: &str, key: &str) -> Option<String> {
+    let output = Command::new("tmux")
+        .args([
+            "-L",
+            SOCKET_NAME,
+            "show-environment",
+            "-t",
+            session_name,
+            key,
+        ])
+        .output_logged()
+        .ok()
+        .filter(|o| o.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .strip_prefix(key)?
+        .strip_prefix('=')
+        .map(str::to_string)
+}
+
+/// Stop a local agent, recording its process group for `resume_local_agent`
+fn pause_local_agent(session_name: &str) -> Result<(), String> {
+    let pgid = agent_process_group(session_name)?;
+    signal_process_group(pgid, "STOP")?;
+    set_session_env(session_name, ENV_PAUSED_PGID, &pgid.to_string())
+}
+
+/// Continue a local agent stopped by `pause_local_agent`.
+///
+/// Once the agent stops, the pane's shell takes the terminal back and lists
+/// it as a stopped job, so it has to be brought back with `fg`; a bare SIGCONT
+/// would leave it in the background. Only an agent still holding the terminal
+/// (no job-control shell in the pane) is continued with a signal.
+fn resume_local_agent(session_name: &str) -> Result<(), String> {
+    let pgid: i32 = get_session_env(session_name, ENV_PAUSED_PGID)
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("No paused agent process recorded for {}", session_name))?;
+    if pane_foreground_pgid(session_name)? == Some(pgid) {
+        signal_process_group(pgid, "CONT")
+    } else {
+        send_command(session_name, "fg")
+    }
+}
+
+/// Send a signal (e.g. "STOP", "CONT") to a whole process group
+fn signal_process_group(pgid: i32, signal: &str) -> Result<(), String> {
+    let output = Command::new("kill")
+        .args([&format!("-{}", signal), "--", &format!("-{}", pgid)])
+        .output_logged()
+        .map_err(|e| format!("Failed to run kill: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to send SIG{} to process group {}: {}",
+            signal,
+            pgid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Agent containers (not support workers) of a sandboxed session
+fn session_agent_containers(metadata: &AgentMetadata) -> Vec<String> {
+    metadata
+        .issue_ref
+        .as_deref()
+        .and_then(|r| r.rsplit_once('#'))
+        .and_then(|(repo, n)| n.parse::<u32>().ok().map(|n| (repo, n)))
+        .map(|(repo, n)| super::docker::containers_for_issue(repo, n))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| !super::docker::is_support_container(c))
+        .collect()
+}
+
+/// Pause or resume the agent in a session
+fn set_agent_paused(session_name: &str, paused: bool) -> Result<(), String> {
+    let metadata = get_session_metadata(session_name)?;
+    if metadata.paused == paused {
+        return Err(format!(
+            "Agent in session {} is already {}",
+            session_name,
+            if paused { "paused" } else { "running" }
+        ));
+    }
+
+    let containers = session_agent_containers(&metadata);
+    if containers.is_empty() {
+        if paused {
+            pause_local_agent(session_name)?;
+        } else {
+            resume_local_agent(session_name)?;
+        }
+    } else {
+        for container in &containers {
+            if paused {
+                super::docker::pause_container(container)?;
+            } else {
+                super::docker::unpause_container(container)?;
+            }
+        }
+    }
+
+    set_session_env(session_name, ENV_PAUSED, if paused { "1" } else { "0" })
+}
+
+/// Pause the agent in a session without killing it.
+///
+/// Sandboxed agents are frozen with `docker pause`; local agents get SIGSTOP
+/// on the pane's foreground process group, which is recorded in the session
+/// environment. The tmux session stays alive and the agent continues where it
+/// left off on `resume_agent`.
+pub fn pause_agent(session_name: &str) -> Result<(), String> {
+    set_agent_paused(session_name, true)
+}
+
+/// Resume an agent paused with `pause_agent`.
+pub fn resume_agent(session_name: &str) -> Result<(), String> {
+    set_agent_paused(session_name, false)
+}
+
+/// Smallest terminal size accepted by `resize_session`
+const MIN_COLS: u16 = 20;
+const MIN_ROWS: u16 = 5;
+
+/// Largest terminal size accepted by `resize_session`
+const MAX_COLS: u16 = 1000;
+const MAX_ROWS: u16 = 500;
+
+/// Clamp a requested terminal size to sane bounds
+fn clamp_terminal_size(cols: u16, rows: u16) -> (u16, u16) {
+    (
+        cols.clamp(MIN_COLS, MAX_COLS),
+        rows.clamp(MIN_ROWS, MAX_ROWS),
+    )
+}
+
+/// Resize a session's window so output wraps at the UI terminal's width
+///
+/// Uses `resize-window` (tmux 2.9+), which also pins the window to a manual
+/// size so attached clients don't resize it back. Falls back to
+/// `resize-pane` on older tmux versions.
+pub fn resize_session(session_name: &str, cols: u16, rows: u16) -> Result<(), String> {
+    let (cols, rows) = clamp_terminal_size(cols, rows);
+    let (cols, rows) = (cols.to_string(), rows.to_string());
+
+    let output = Command::new("tmux")
+        .args([
+            "-L",
+            SOCKET_NAME,
+            "resize-window",
+            "-t",
+            session_name,
+            "-x",
+            &cols,
+            "-y",
+            &rows,
+        ])
+        .output_logged()
+        .map_err(|e| format!("Failed to resize session: {}", e))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let output = Command::new("tmux")
+        .args([
+            "-L",
+            SOCKET_NAME,
+            "resize-pane",
+            "-t",
+            session_name,
+            "-x",
+            &cols,
+            "-y",
+            &rows,
+        ])
+        .output_logged()
+        .map_err(|e| format!("Failed to resize session: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "tmux error: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
 /// Recover agent sessions on startup
 pub fn recover_sessions() -> Result<Vec<RecoveredSession>, String> {
     let current_machine = get_machine_id();
@@ -501,6 +949,15 @@ pub fn recover_sessions() -> Result<Vec<RecoveredSession>, String> {
     Ok(recovered)
 }
 
+/// Result of restarting an agent in its session
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RestartResult {
+    /// Session name
+    pub session: String,
+    /// Branch holding uncommitted work from before the restart, if there was any
+    pub backup_branch: Option<String>,
+}
+
 /// Result of attempting to recover/restart sessions
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct RecoveryResult {
@@ -544,7 +1001,7 @@ pub fn recover_all_sessions(
             RecoveryAction::Restart => {
                 if auto_restart {
                     match restart_agent(&session.metadata.session) {
-                        Ok(()) => RecoveryResult {
+                        Ok(_) => RecoveryResult {
                             session: session.metadata.session.clone(),
                             success: true,
                             action: RecoveryAction::Restart,
@@ -620,7 +1077,7 @@ pub fn recover_all_sessions(
 }
 
 /// Port mapping configuration for container
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct PortMapping {
     /// Host port to bind
     pub host_port: u16,
@@ -677,6 +1134,16 @@ pub struct SandboxedAgentConfig {
     pub use_agent_network: bool,
     /// Whether to remap ports to unique ranges (avoids conflicts between agents)
     pub remap_ports: bool,
+    /// Optional subdirectory of the worktree to use as the container working directory
+    pub subpath: Option<String>,
+    /// Start the agent with its verbose flag
+    pub verbose: bool,
+    /// Quarantine the agent for untrusted issues: no network, no credentials,
+    /// and a read-only workspace. Overrides the network and port settings.
+    /// The agent CLI must already be in the image since nothing can be installed
+    pub quarantine: bool,
+    /// Extra `docker run` arguments, one argv item each. Ignored in quarantine
+    pub extra_docker_args: Vec<String>,
 }
 
 /// Build a Docker command that runs the agent inside a container
@@ -692,8 +1159,29 @@ fn build_sandboxed_agent_command(
     repo: &str,
     issue_number: u64,
     issue_title: Option<&str>,
+    focus_files: &[String],
     config: &SandboxedAgentConfig,
 ) -> Result<String, String> {
+    build_sandboxed_docker_args(
+        agent_type,
+        repo,
+        issue_number,
+        issue_title,
+        focus_files,
+        config,
+    )
+    .map(|args| args.join(" "))
+}
+
+/// The arguments of `build_sandboxed_agent_command`, one option per element
+fn build_sandboxed_docker_args(
+    agent_type: &str,
+    repo: &str,
+    issue_number: u64,
+    issue_title: Option<&str>,
+    focus_files: &[String],
+    config: &SandboxedAgentConfig,
+) -> Result<Vec<String>, String> {
     use super::docker;
 
     // First get the base agent command
@@ -702,23 +1190,40 @@ fn build_sandboxed_agent_command(
         repo,
         issue_number,
         issue_title,
+        focus_files,
+        config.verbose,
         config.auto_accept,
     )?;
 
     // Build docker run command
-    let container_name = format!("handy-sandbox-{}", issue_number);
-    let image = "node:20-bookworm"; // Base image with Node.js for Claude Code
+    let container_name = docker::container_name_for_issue(repo, issue_number);
+    // Prewarmed handy-agent-base if built, otherwise the Node.js base image
+    let image = docker::preferred_agent_image();
+
+    let workdir = match config.subpath.as_deref().map(|s| s.trim_matches('/')) {
+        Some(sub) if !sub.is_empty() => format!("/workspace/{}", sub),
+        _ => "/workspace".to_string(),
+    };
 
+    let mount_mode = if config.quarantine { ":ro" } else { "" };
     let mut docker_args = vec![
         "docker run --rm -it".to_string(),
         format!("--name {}", container_name),
-        format!("-v {}:/workspace", config.worktree_path),
-        "-w /workspace".to_string(),
+        format!(
+            "-v {}",
+            shell_quote(&format!(
+                "{}:/workspace{}",
+                config.worktree_path, mount_mode
+            ))
+        ),
+        format!("-w {}", shell_quote(&workdir)),
     ];
 
     // Join the shared agent network if enabled
     // This allows containers to communicate via container names as hostnames
-    if config.use_agent_network {
+    if config.quarantine {
+        docker_args.push("--network none".to_string());
+    } else if config.use_agent_network {
         // Ensure network exists (will be created if needed)
         if let Err(e) = docker::ensure_agent_network() {
             log::warn!("Failed to create agent network: {}", e);
@@ -731,17 +1236,20 @@ fn build_sandboxed_agent_command(
 
     // Add resource limits
     if let Some(ref mem) = config.memory_limit {
-        docker_args.push(format!("-m {}", mem));
+        docker_args.push(format!("-m {}", shell_quote(mem)));
     }
     if let Some(ref cpu) = config.cpu_limit {
-        docker_args.push(format!("--cpus {}", cpu));
+        docker_args.push(format!("--cpus {}", shell_quote(cpu)));
     }
 
     // Add port mappings (with optional remapping to unique ranges)
-    if config.remap_ports {
+    if config.quarantine {
+        // Nothing is reachable without a network
+    } else if config.remap_ports {
         // Remap ports to unique ranges to avoid conflicts between agents
         for port_mapping in &config.ports {
-            let host_port = docker::remap_port_to_range(port_mapping.container_port, issue_number);
+            let host_port =
+                docker::remap_port_to_range(port_mapping.container_port, repo, issue_number);
             let remapped = PortMapping {
                 host_port,
                 container_port: port_mapping.container_port,
@@ -756,92 +1264,326 @@ fn build_sandboxed_agent_command(
         }
     }
 
-    // Pass through credentials from host environment
-    docker_args.push("-e GH_TOKEN".to_string());
-    docker_args.push("-e GITHUB_TOKEN".to_string());
-    docker_args.push("-e ANTHROPIC_API_KEY".to_string());
+    // Pass through credentials from host environment (never into quarantine)
+    if config.quarantine {
+        docker_args.push(format!("-e {}=1", ENV_QUARANTINE));
+    } else {
+        docker_args.push("-e GH_TOKEN".to_string());
+        docker_args.push("-e GITHUB_TOKEN".to_string());
+        docker_args.push("-e ANTHROPIC_API_KEY".to_string());
+    }
 
     // Add context env vars
-    docker_args.push(format!("-e HANDY_ISSUE_REF={}#{}", repo, issue_number));
-    docker_args.push(format!("-e HANDY_AGENT_TYPE={}", agent_type));
-    docker_args.push(format!("-e HANDY_CONTAINER_NAME={}", container_name));
+    let env_arg =
+        |name: &str, value: &str| format!("-e {}", shell_quote(&format!("{}={}", name, value)));
+    docker_args.push(env_arg(
+        "HANDY_ISSUE_REF",
+        &format!("{}#{}", repo, issue_number),
+    ));
+    docker_args.push(env_arg("HANDY_AGENT_TYPE", agent_type));
+    docker_args.push(env_arg("HANDY_CONTAINER_NAME", &container_name));
 
     // Add port range info so the agent knows which ports it can use
-    if config.remap_ports {
-        let (base, end) = docker::allocate_port_range(issue_number);
+    if config.remap_ports && !config.quarantine {
+        let (base, end) = docker::allocate_port_range(repo, issue_number);
         docker_args.push(format!("-e HANDY_PORT_RANGE_BASE={}", base));
         docker_args.push(format!("-e HANDY_PORT_RANGE_END={}", end));
     }
 
+    // Add user-supplied docker args (must come before the image). They could
+    // re-open the network or mount credentials, so quarantine drops them
+    if config.quarantine {
+        if !config.extra_docker_args.is_empty() {
+            log::warn!("Ignoring extra docker args for quarantined agent");
+        }
+    } else {
+        docker::validate_extra_docker_args(&config.extra_docker_args)?;
+        docker_args.extend(config.extra_docker_args.iter().map(|a| shell_quote(a)));
+    }
+
     // Add image and command
-    docker_args.push(image.to_string());
+    docker_args.push(image);
     docker_args.push("sh -c".to_string());
 
-    // Install Claude Code and run the agent command
-    let install_and_run = format!("npm install -g @anthropic/claude-code && {}", inner_command);
-    docker_args.push(format!("'{}'", install_and_run.replace('\'', "'\\''")));
+    // Install Claude Code (unless the image already has it) and run the agent command
+    let install_and_run = if config.quarantine {
+        inner_command
+    } else {
+        format!(
+            "(command -v claude >/dev/null 2>&1 || npm install -g @anthropic/claude-code) && {}",
+            inner_command
+        )
+    };
+    docker_args.push(shell_quote(&install_and_run));
+
+    Ok(docker_args)
+}
+
+/// Commands and container settings an agent session was started with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct SessionDebugInfo {
+    /// Session name
+    pub session: String,
+    /// When the capture was taken (RFC 3339)
+    pub captured_at: String,
+    /// Whether the agent runs in a Docker sandbox
+    pub sandboxed: bool,
+    /// Command sent to the tmux session, with secrets redacted
+    pub command: String,
+    /// Names of environment variables passed to the container
+    pub env_vars: Vec<String>,
+    /// Volume mounts (host:container)
+    pub mounts: Vec<String>,
+    /// Port mappings (host:container)
+    pub ports: Vec<String>,
+}
+
+/// Pull env var names, mounts and ports out of `build_sandboxed_docker_args` output
+fn debug_info_from_docker_args(args: &[String]) -> SessionDebugInfo {
+    let mut info = SessionDebugInfo {
+        sandboxed: true,
+        ..Default::default()
+    };
+    for arg in args {
+        if let Some(env) = arg.strip_prefix("-e ") {
+            let env = env.trim_start_matches('\'');
+            info.env_vars
+                .push(env.split('=').next().unwrap_or(env).to_string());
+        } else if let Some(mount) = arg.strip_prefix("-v ") {
+            info.mounts.push(mount.trim_matches('\'').to_string());
+        } else if let Some(port) = arg.strip_prefix("-p ") {
+            info.ports.push(port.to_string());
+        }
+    }
+    info
+}
+
+/// Set the directory debug captures are written to (see `capture_session_debug_info`).
+pub fn set_debug_capture_dir(dir: PathBuf) {
+    *DEBUG_CAPTURE_DIR.write().unwrap_or_else(|e| e.into_inner()) = Some(dir);
+}
+
+/// Where the debug capture for a session is written within `dir`.
+///
+/// The session name becomes a file name, so only names made of the characters
+/// `render_session_name` produces are accepted.
+fn session_debug_file(dir: &Path, session_name: &str) -> Result<PathBuf, String> {
+    let valid = !session_name.is_empty()
+        && session_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid session name: {}", session_name));
+    }
+    Ok(dir.join(format!("{}.json", session_name)))
+}
+
+/// `session_debug_file` in the configured debug capture directory
+fn configured_session_debug_file(session_name: &str) -> Result<PathBuf, String> {
+    let dir = DEBUG_CAPTURE_DIR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or("Debug capture directory is not set")?;
+    session_debug_file(&dir, session_name)
+}
+
+/// Record how an agent session was started, for support and reproduction.
+///
+/// Rebuilds the agent command exactly as `start_agent_in_session` or
+/// `start_sandboxed_agent_in_session` does, logs it with secrets redacted
+/// and writes it to a per-session debug file read by `get_session_debug_info`.
+/// The file is only readable by the current user.
+pub fn capture_session_debug_info(
+    session_name: &str,
+    agent_type: &str,
+    repo: &str,
+    issue_number: u64,
+    issue_title: Option<&str>,
+    focus_files: &[String],
+    verbose: bool,
+    sandbox_config: Option<&SandboxedAgentConfig>,
+) -> Result<SessionDebugInfo, String> {
+    let mut info = match sandbox_config {
+        Some(config) => {
+            let args = build_sandboxed_docker_args(
+                agent_type,
+                repo,
+                issue_number,
+                issue_title,
+                focus_files,
+                config,
+            )?;
+            SessionDebugInfo {
+                command: args.join(" "),
+                ..debug_info_from_docker_args(&args)
+            }
+        }
+        None => SessionDebugInfo {
+            command: build_agent_command(
+                agent_type,
+                repo,
+                issue_number,
+                issue_title,
+                focus_files,
+                verbose,
+            )?,
+            ..Default::default()
+        },
+    };
+    info.session = session_name.to_string();
+    info.captured_at = chrono::Utc::now().to_rfc3339();
+    info.command = super::docker::sanitize_sensitive_data(&info.command);
+
+    log::info!("[{}] agent command: {}", session_name, info.command);
+    if info.sandboxed {
+        log::info!(
+            "[{}] env: {}; mounts: {}; ports: {}",
+            session_name,
+            info.env_vars.join(", "),
+            info.mounts.join(", "),
+            info.ports.join(", ")
+        );
+    }
+
+    let path = configured_session_debug_file(session_name)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(&info)
+        .map_err(|e| format!("Failed to serialize debug info: {}", e))?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    // The mode only applies to new files; tighten one left by an older capture
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+    }
+    std::io::Write::write_all(&mut file, json.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(info)
+}
 
-    Ok(docker_args.join(" "))
+/// Read the debug capture written when a session was spawned with debug capture on.
+pub fn get_session_debug_info(session_name: &str) -> Result<SessionDebugInfo, String> {
+    let path = configured_session_debug_file(session_name)?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!(
+                "No debug capture for session {} (spawn it with debug capture enabled)",
+                session_name
+            ))
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse debug info: {}", e))
 }
 
 /// Build the inner agent command (used both directly and inside containers)
+///
+/// `focus_files` (paths relative to the agent's working directory) are named
+/// in the prompt, and passed to aider as files to add to its chat. `verbose`
+/// adds the agent's own verbosity flag, where it has one.
 fn build_agent_command_inner(
     agent_type: &str,
     repo: &str,
     issue_number: u64,
     issue_title: Option<&str>,
+    focus_files: &[String],
+    verbose: bool,
     auto_accept: bool,
 ) -> Result<String, String> {
-    let title_arg = issue_title
-        .map(|t| {
-            let escaped = t.replace('\'', "'\\''");
-            format!(" --title '{}'", escaped)
-        })
-        .unwrap_or_default();
+    // Titles come from GitHub and may contain quotes, backticks or `$()`,
+    // so prompts are built as plain text and quoted as a single shell word
+    let title_suffix = issue_title.map(|t| format!(" ({})", t)).unwrap_or_default();
+    let focus_suffix = if focus_files.is_empty() {
+        String::new()
+    } else {
+        format!(" Focus on these files: {}.", focus_files.join(", "))
+    };
 
     let command = match agent_type.to_lowercase().as_str() {
         "claude" => {
+            let prompt = shell_quote(&format!(
+                "Work on GitHub issue {}#{}: Implement the requirements described in the issue.{} When done, commit your changes and create a PR. If you finish without creating a PR, include [handy-done] in your final commit message. If you need to ask a question, comment on the issue and include {} in the comment.",
+                repo, issue_number, focus_suffix, super::github::QUESTION_MARKER
+            ));
+            let verbose_flag = if verbose { "--verbose " } else { "" };
             if auto_accept {
                 // In sandbox, we can safely skip permissions
                 format!(
-                    "claude --dangerously-skip-permissions 'Work on GitHub issue {}#{}: Implement the requirements described in the issue. When done, commit your changes and create a PR.'",
-                    repo, issue_number
+                    "claude {}--dangerously-skip-permissions {}",
+                    verbose_flag, prompt
                 )
             } else {
-                format!(
-                    "claude 'Work on GitHub issue {}#{}: Implement the requirements described in the issue. When done, commit your changes and create a PR.'",
-                    repo, issue_number
-                )
+                format!("claude {}{}", verbose_flag, prompt)
             }
         }
         "aider" => {
-            format!(
-                "aider --message 'Work on GitHub issue {}#{}{}. Implement the requirements and commit when done.'",
-                repo, issue_number, title_arg
-            )
+            // aider takes the files to edit as positional arguments
+            let mut command = format!(
+                "aider {}--message {}",
+                if verbose { "--verbose " } else { "" },
+                shell_quote(&format!(
+                    "Work on GitHub issue {}#{}{}. Implement the requirements and commit when done.{}",
+                    repo, issue_number, title_suffix, focus_suffix
+                ))
+            );
+            for file in focus_files {
+                command.push(' ');
+                command.push_str(&shell_quote(file));
+            }
+            command
         }
         "codex" | "openai" => {
             format!(
-                "codex 'Implement GitHub issue {}#{}{}'",
-                repo, issue_number, title_arg
+                "codex {}",
+                shell_quote(&format!(
+                    "Implement GitHub issue {}#{}{}.{}",
+                    repo, issue_number, title_suffix, focus_suffix
+                ))
             )
         }
         "gemini" => {
             format!(
-                "gemini-cli 'Work on GitHub issue {}#{}{}'",
-                repo, issue_number, title_arg
+                "gemini-cli {}{}",
+                if verbose { "--debug " } else { "" },
+                shell_quote(&format!(
+                    "Work on GitHub issue {}#{}{}.{}",
+                    repo, issue_number, title_suffix, focus_suffix
+                ))
             )
         }
         "ollama" | "local" => {
             format!(
-                "ollama run codellama 'Implement GitHub issue {}#{}{}'",
-                repo, issue_number, title_arg
+                "ollama run {}codellama {}",
+                if verbose { "--verbose " } else { "" },
+                shell_quote(&format!(
+                    "Implement GitHub issue {}#{}{}.{}",
+                    repo, issue_number, title_suffix, focus_suffix
+                ))
             )
         }
         "manual" => {
             format!(
-                "echo '🔧 Manual work session for issue {}#{}. The worktree is ready for you to work in.'",
-                repo, issue_number
+                "echo {}",
+                shell_quote(&format!(
+                    "🔧 Manual work session for issue {}#{}. The worktree is ready for you to work in.",
+                    repo, issue_number
+                ))
             )
         }
         _ => {
@@ -865,9 +1607,28 @@ pub fn build_agent_command(
     repo: &str,
     issue_number: u64,
     issue_title: Option<&str>,
+    focus_files: &[String],
+    verbose: bool,
 ) -> Result<String, String> {
     // Non-sandboxed mode: don't auto-accept
-    build_agent_command_inner(agent_type, repo, issue_number, issue_title, false)
+    build_agent_command_inner(
+        agent_type,
+        repo,
+        issue_number,
+        issue_title,
+        focus_files,
+        verbose,
+        false,
+    )
+}
+
+/// Refuse a direct (non-sandboxed) agent command that skips permission prompts
+/// while Handy runs as root, since Claude dies silently in the pane then.
+pub fn check_root_skip_permissions(command: &str) -> Result<(), String> {
+    if command.contains("--dangerously-skip-permissions") && super::is_running_as_root() {
+        return Err(super::ROOT_CLAUDE_WARNING.to_string());
+    }
+    Ok(())
 }
 
 /// Start an agent in an existing tmux session
@@ -880,8 +1641,52 @@ pub fn start_agent_in_session(
     repo: &str,
     issue_number: u64,
     issue_title: Option<&str>,
+    focus_files: &[String],
+    verbose: bool,
+) -> Result<(), String> {
+    let command = build_agent_command(
+        agent_type,
+        repo,
+        issue_number,
+        issue_title,
+        focus_files,
+        verbose,
+    )?;
+    check_root_skip_permissions(&command)?;
+    send_command(session_name, &command)
+}
+
+/// Build the command to start an agent with a custom prompt
+///
+/// Used for work that isn't an issue implementation, such as addressing
+/// review feedback on a PR. Like `build_agent_command`, it never auto-accepts.
+pub fn build_prompt_command(agent_type: &str, prompt: &str) -> Result<String, String> {
+    let prompt = shell_quote(prompt);
+    let command = match agent_type.to_lowercase().as_str() {
+        "claude" => format!("claude {}", prompt),
+        "aider" => format!("aider --message {}", prompt),
+        "codex" | "openai" => format!("codex {}", prompt),
+        "gemini" => format!("gemini-cli {}", prompt),
+        "ollama" | "local" => format!("ollama run codellama {}", prompt),
+        "manual" => format!("echo {}", prompt),
+        _ => {
+            return Err(format!(
+                "Unknown agent type '{}'. Supported types: claude, aider, codex, gemini, ollama, manual",
+                agent_type
+            ));
+        }
+    };
+    Ok(command)
+}
+
+/// Start an agent with a custom prompt in an existing tmux session
+pub fn start_agent_with_prompt(
+    session_name: &str,
+    agent_type: &str,
+    prompt: &str,
 ) -> Result<(), String> {
-    let command = build_agent_command(agent_type, repo, issue_number, issue_title)?;
+    let command = build_prompt_command(agent_type, prompt)?;
+    check_root_skip_permissions(&command)?;
     send_command(session_name, &command)
 }
 
@@ -903,18 +1708,26 @@ pub fn start_sandboxed_agent_in_session(
     repo: &str,
     issue_number: u64,
     issue_title: Option<&str>,
+    focus_files: &[String],
     sandbox_config: &SandboxedAgentConfig,
 ) -> Result<(), String> {
-    let command =
-        build_sandboxed_agent_command(agent_type, repo, issue_number, issue_title, sandbox_config)?;
+    let command = build_sandboxed_agent_command(
+        agent_type,
+        repo,
+        issue_number,
+        issue_title,
+        focus_files,
+        sandbox_config,
+    )?;
     send_command(session_name, &command)
 }
 
 /// Restart an agent in an existing session
 ///
 /// Use this for recovery when a session exists but the agent process has stopped.
-/// This will attempt to restart the agent with the same context.
-pub fn restart_agent(session_name: &str) -> Result<(), String> {
+/// This will attempt to restart the agent with the same context, after saving
+/// any uncommitted work in the worktree to a backup branch.
+pub fn restart_agent(session_name: &str) -> Result<RestartResult, String> {
     // Get metadata to rebuild the agent command
     let metadata = get_session_metadata(session_name)?;
 
@@ -929,6 +1742,35 @@ pub fn restart_agent(session_name: &str) -> Result<(), String> {
         .and_then(|n| n.parse::<u64>().ok())
         .ok_or("Session has no valid issue reference - cannot restart")?;
 
+    // The agent is restarted directly in the session, never in a container
+    super::orchestrator::check_direct_start(&metadata.agent_type)?;
+
+    // Preserve uncommitted work from the previous run so a regressing
+    // fresh run doesn't lose it
+    let backup_branch = match metadata.worktree.as_deref() {
+        Some(worktree) if std::path::Path::new(worktree).exists() => {
+            super::worktree::backup_worktree_state(worktree, session_name)
+                .map_err(|e| format!("Failed to back up worktree before restart: {}", e))?
+        }
+        _ => None,
+    };
+    if let Some(branch) = &backup_branch {
+        log::info!(
+            "Backed up uncommitted work for {} to branch {}",
+            session_name,
+            branch
+        );
+    }
+
+    // Return to the agent's working subdirectory in case the shell moved
+    if let (Some(worktree), Some(subpath)) = (&metadata.worktree, &metadata.subpath) {
+        let dir = std::path::Path::new(worktree).join(subpath);
+        send_command(
+            session_name,
+            &format!("cd {}", shell_quote(&dir.to_string_lossy())),
+        )?;
+    }
+
     // Start the agent with the stored metadata
     start_agent_in_session(
         session_name,
@@ -936,7 +1778,14 @@ pub fn restart_agent(session_name: &str) -> Result<(), String> {
         &repo,
         issue_number,
         None, // We don't store the title in metadata, agent will fetch from GitHub
-    )
+        &[],
+        metadata.verbose,
+    )?;
+
+    Ok(RestartResult {
+        session: session_name.to_string(),
+        backup_branch,
+    })
 }
 
 /// Generate a session name for an issue
@@ -949,6 +1798,91 @@ pub fn session_name_manual(suffix: &str) -> String {
     format!("{}manual-{}", SESSION_PREFIX, suffix)
 }
 
+/// Set the template used for new agent session names (None = default)
+pub fn set_session_name_template(template: Option<&str>) {
+    let template = template.map(str::trim).filter(|t| !t.is_empty());
+    *SESSION_NAME_TEMPLATE
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = template.map(str::to_string);
+}
+
+/// Render a session name template.
+///
+/// Supports `{issue}`, `{agent}` and `{short_ts}` (UTC `MMDDHHMM`). Characters
+/// tmux doesn't allow in session names are replaced with `-`, and the result
+/// always starts with `handy-` so the session is recognized as Handy's.
+pub fn render_session_name(template: &str, issue_number: u64, agent_type: &str) -> String {
+    let name: String = template
+        .replace("{issue}", &issue_number.to_string())
+        .replace("{agent}", agent_type)
+        .replace(
+            "{short_ts}",
+            &chrono::Utc::now().format("%m%d%H%M").to_string(),
+        )
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if name.starts_with(HANDY_PREFIX) {
+        name
+    } else {
+        format!("{}{}", HANDY_PREFIX, name)
+    }
+}
+
+/// First of `base`, `base-2`, `base-3`, ... not in `existing`
+pub fn allocate_session_name(base: &str, existing: &[String]) -> String {
+    if !existing.iter().any(|s| s == base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|name| !existing.contains(name))
+        .unwrap_or_default()
+}
+
+/// Names of all sessions on the Handy tmux server
+fn list_session_names() -> Vec<String> {
+    Command::new("tmux")
+        .args(["-L", SOCKET_NAME, "list-sessions", "-F", "#{session_name}"])
+        .output_with_retry()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Name for a new agent session, shared by every spawn path.
+///
+/// Renders the configured template and appends `-2`, `-3`, ... if a session
+/// with that name already exists.
+pub fn next_session_name(issue_number: u64, agent_type: &str) -> String {
+    let template = SESSION_NAME_TEMPLATE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SESSION_NAME_TEMPLATE.to_string());
+    let base = render_session_name(&template, issue_number, agent_type);
+    allocate_session_name(&base, &list_session_names())
+}
+
+/// Name for a new session working on a PR: `handy-agent-pr-<number>`,
+/// with `-2`, `-3`, ... appended if that name is taken.
+pub fn next_pr_session_name(pr_number: u64) -> String {
+    let base = format!("{}pr-{}", SESSION_PREFIX, pr_number);
+    allocate_session_name(&base, &list_session_names())
+}
+
 /// Ensure a master tmux session exists for orchestration and management.
 /// This session serves as a persistent handler for background tasks.
 /// Returns Ok(true) if the session was created, Ok(false) if it already exists.
@@ -968,7 +1902,7 @@ pub fn ensure_master_session() -> Result<bool, String> {
     // Create master session directly (bypassing create_session to avoid list_sessions check)
     let output = Command::new("tmux")
         .args(["-L", SOCKET_NAME, "new-session", "-d", "-s", MASTER_SESSION])
-        .output()
+        .output_logged()
         .map_err(|e| format!("Failed to create master session: {}", e))?;
 
     if !output.status.success() {
@@ -994,15 +1928,232 @@ pub fn ensure_master_session() -> Result<bool, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_foreground_pgid() {
+        assert_eq!(parse_foreground_pgid(" 4321\n", 1000), Some(4321));
+        // Only the shell is in the foreground
+        assert_eq!(parse_foreground_pgid("1000\n", 1000), None);
+        assert_eq!(parse_foreground_pgid("-1", 1000), None);
+        assert_eq!(parse_foreground_pgid("", 1000), None);
+    }
+
+    #[test]
+    fn test_session_panes() {
+        let panes: Vec<TmuxPane> = [
+            "0\tagent\t0\tclaude\t1\t1",
+            "0\tagent\t1\tnpm\t0\t1",
+            "1\tlogs\t0\ttail\t1\t0",
+            "garbage",
+        ]
+        .iter()
+        .filter_map(|line| parse_pane_line(line))
+        .collect();
+        assert_eq!(panes.len(), 3);
+        assert_eq!(panes[1].target, "0.1");
+        assert_eq!(panes[1].current_command, "npm");
+        assert_eq!(
+            panes.iter().map(|p| p.active).collect::<Vec<_>>(),
+            vec![true, false, false]
+        );
+
+        assert_eq!(pane_target("handy-agent-1", None).unwrap(), "handy-agent-1");
+        assert_eq!(
+            pane_target("handy-agent-1", Some("1.0")).unwrap(),
+            "handy-agent-1:1.0"
+        );
+        assert_eq!(
+            pane_target("handy-agent-1", Some("2")).unwrap(),
+            "handy-agent-1:2"
+        );
+        for bad in ["", "other:0", "0.1.2", "%3", "0."] {
+            assert!(pane_target("handy-agent-1", Some(bad)).is_err(), "{}", bad);
+        }
+    }
+
     #[test]
     fn test_session_name_generation() {
         assert_eq!(session_name_for_issue(42), "handy-agent-42");
         assert_eq!(session_name_manual("test"), "handy-agent-manual-test");
     }
 
+    #[test]
+    fn test_render_and_allocate_session_name() {
+        assert_eq!(
+            render_session_name(DEFAULT_SESSION_NAME_TEMPLATE, 42, "claude"),
+            "handy-agent-42"
+        );
+        assert_eq!(
+            render_session_name("{agent}.{issue}", 7, "aider"),
+            "handy-aider-7"
+        );
+        let with_ts = render_session_name("handy-{issue}-{short_ts}", 7, "claude");
+        assert_eq!(with_ts.len(), "handy-7-".len() + 8);
+
+        let existing = vec!["handy-agent-42".to_string(), "handy-agent-42-2".to_string()];
+        assert_eq!(
+            allocate_session_name("handy-agent-7", &existing),
+            "handy-agent-7"
+        );
+        assert_eq!(
+            allocate_session_name("handy-agent-42", &existing),
+            "handy-agent-42-3"
+        );
+    }
+
+    #[test]
+    fn test_build_agent_command_quotes_adversarial_title() {
+        let title = "Fix `id` and $(touch /tmp/pwned); it's 'broken'";
+        let command = build_agent_command("aider", "org/repo", 7, Some(title), &[], false).unwrap();
+        let expected_prompt = format!(
+            "Work on GitHub issue org/repo#7 ({}). Implement the requirements and commit when done.",
+            title
+        );
+        assert_eq!(
+            command,
+            format!("aider --message {}", shell_quote(&expected_prompt))
+        );
+
+        // The shell sees the whole prompt as one literal argument
+        let output = Command::new("sh")
+            .args(["-c", &command.replacen("aider --message", "printf %s", 1)])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), expected_prompt);
+    }
+
+    #[test]
+    fn test_build_agent_command_focus_files() {
+        let files = vec!["src/lib.rs".to_string(), "docs/my notes.md".to_string()];
+
+        let command = build_agent_command("aider", "org/repo", 7, None, &files, false).unwrap();
+        assert!(command.ends_with(" 'src/lib.rs' 'docs/my notes.md'"));
+        assert!(command.contains("Focus on these files: src/lib.rs, docs/my notes.md."));
+
+        let command = build_agent_command("codex", "org/repo", 7, None, &files, false).unwrap();
+        assert!(command.contains("Focus on these files: src/lib.rs, docs/my notes.md."));
+
+        let command = build_agent_command("codex", "org/repo", 7, None, &[], false).unwrap();
+        assert!(!command.contains("Focus"));
+    }
+
+    #[test]
+    fn test_build_agent_command_verbose() {
+        let command = build_agent_command("claude", "org/repo", 7, None, &[], true).unwrap();
+        assert!(command.starts_with("claude --verbose '"));
+        let command = build_agent_command("claude", "org/repo", 7, None, &[], false).unwrap();
+        assert!(!command.contains("--verbose"));
+
+        let command = build_agent_command("aider", "org/repo", 7, None, &[], true).unwrap();
+        assert!(command.starts_with("aider --verbose --message "));
+
+        let command =
+            build_agent_command_inner("claude", "org/repo", 7, None, &[], true, true).unwrap();
+        assert!(command.starts_with("claude --verbose --dangerously-skip-permissions "));
+    }
+
+    #[test]
+    fn test_clamp_terminal_size() {
+        assert_eq!(clamp_terminal_size(120, 30), (120, 30));
+        assert_eq!(clamp_terminal_size(0, 0), (MIN_COLS, MIN_ROWS));
+        assert_eq!(
+            clamp_terminal_size(u16::MAX, u16::MAX),
+            (MAX_COLS, MAX_ROWS)
+        );
+    }
+
     #[test]
     fn test_is_tmux_running() {
         // Just ensure it doesn't panic
         let _ = is_tmux_running();
     }
+
+    #[test]
+    fn test_quarantined_docker_args() {
+        let config = SandboxedAgentConfig {
+            worktree_path: "/work/untrusted-13".to_string(),
+            memory_limit: Some("2g".to_string()),
+            cpu_limit: Some("1.5; id".to_string()),
+            auto_accept: true,
+            ports: vec![PortMapping::new(3000)],
+            auto_detect_ports: false,
+            use_agent_network: true,
+            remap_ports: true,
+            subpath: None,
+            verbose: false,
+            quarantine: true,
+            extra_docker_args: vec!["--gpus".to_string(), "all".to_string()],
+        };
+        let args =
+            build_sandboxed_docker_args("claude", "org/untrusted", 13, None, &[], &config).unwrap();
+        assert!(args.contains(&"-v '/work/untrusted-13:/workspace:ro'".to_string()));
+        assert!(args.contains(&"--network none".to_string()));
+        assert!(args.contains(&"-e HANDY_QUARANTINE=1".to_string()));
+        assert!(args.contains(&"-m '2g'".to_string()));
+        assert!(args.contains(&"--cpus '1.5; id'".to_string()));
+        assert!(args.contains(&"-e 'HANDY_ISSUE_REF=org/untrusted#13'".to_string()));
+        assert!(args.contains(&"-e 'HANDY_AGENT_TYPE=claude'".to_string()));
+        assert!(!args
+            .iter()
+            .any(|a| a.contains("TOKEN") || a.contains("API_KEY")));
+        assert!(!args
+            .iter()
+            .any(|a| a.starts_with("-p ") || a.contains("PORT_RANGE")));
+        assert!(!args.last().unwrap().contains("npm install"));
+        assert!(!args.iter().any(|a| a.contains("--gpus")));
+
+        let config = SandboxedAgentConfig {
+            quarantine: false,
+            use_agent_network: false,
+            extra_docker_args: vec!["-v".to_string(), "/cache:/cache; id".to_string()],
+            ..config
+        };
+        let args =
+            build_sandboxed_docker_args("claude", "org/repo", 7, None, &[], &config).unwrap();
+        let image = args
+            .iter()
+            .position(|a| *a == crate::devops::docker::preferred_agent_image());
+        let extra = args.iter().position(|a| a == "'/cache:/cache; id'");
+        assert!(extra.is_some() && extra < image, "{:?}", args);
+    }
+
+    #[test]
+    fn test_session_debug_file() {
+        let dir = Path::new("/data/debug-captures");
+        assert_eq!(
+            session_debug_file(dir, "handy-agent-5").unwrap(),
+            dir.join("handy-agent-5.json")
+        );
+        for name in [
+            "",
+            "../handy-agent-5",
+            "handy/agent",
+            "handy agent",
+            "..\\x",
+        ] {
+            assert!(session_debug_file(dir, name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_debug_info_from_docker_args() {
+        let args: Vec<String> = [
+            "docker run --rm -it",
+            "--name handy-agent-x",
+            "-v '/home/me/wt/issue-5:/workspace'",
+            "-w '/workspace'",
+            "-p 3000:3000",
+            "-e GH_TOKEN",
+            "-e 'HANDY_ISSUE_REF=org/repo#5'",
+            "node:20",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let info = debug_info_from_docker_args(&args);
+        assert!(info.sandboxed);
+        assert_eq!(info.env_vars, vec!["GH_TOKEN", "HANDY_ISSUE_REF"]);
+        assert_eq!(info.mounts, vec!["/home/me/wt/issue-5:/workspace"]);
+        assert_eq!(info.ports, vec!["3000:3000"]);
+    }
 }
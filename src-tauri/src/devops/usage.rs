@@ -0,0 +1,402 @@
+//! Agent token usage and cost tracking.
+//!
+//! Claude Code records per-message usage in its session transcripts
+//! (`~/.claude/projects/<encoded-cwd>/*.jsonl`). Agents can also drop a
+//! `.handy-usage.json` file in their worktree. Both formats have changed
+//! across agent versions, so parsing is deliberately lenient: unknown lines
+//! are skipped and missing data yields `None` rather than an error.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::tmux;
+
+/// Generic usage file an agent may write in its worktree
+const USAGE_FILE: &str = ".handy-usage.json";
+
+/// Token usage and estimated cost for an agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct AgentUsage {
+    /// Session name (empty when aggregated)
+    pub session: String,
+    /// Input tokens (excluding cache reads/writes)
+    pub input_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+    /// Tokens written to the prompt cache
+    pub cache_creation_tokens: u64,
+    /// Tokens read from the prompt cache
+    pub cache_read_tokens: u64,
+    /// Cost in USD (reported by the agent when available, otherwise estimated)
+    pub estimated_cost_usd: f64,
+    /// Models seen in the usage data
+    pub models: Vec<String>,
+    /// Files the usage was read from
+    pub sources: Vec<String>,
+}
+
+impl AgentUsage {
+    fn add(&mut self, other: &AgentUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.estimated_cost_usd += other.estimated_cost_usd;
+        for model in &other.models {
+            if !self.models.contains(model) {
+                self.models.push(model.clone());
+            }
+        }
+        self.sources.extend(other.sources.iter().cloned());
+    }
+
+    fn has_tokens(&self) -> bool {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+            > 0
+            || self.estimated_cost_usd > 0.0
+    }
+}
+
+/// Usage aggregated across sessions
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UsageSummary {
+    /// Combined usage of all sessions with data
+    pub total: AgentUsage,
+    /// Per-session usage
+    pub sessions: Vec<AgentUsage>,
+    /// Sessions where no usage data was found
+    pub sessions_without_usage: Vec<String>,
+}
+
+/// Approximate USD pricing per million tokens: (input, output)
+fn model_pricing(model: &str) -> (f64, f64) {
+    let model = model.to_lowercase();
+    if model.contains("opus") {
+        (15.0, 75.0)
+    } else if model.contains("haiku") {
+        (0.8, 4.0)
+    } else {
+        // Sonnet pricing as the default for unknown models
+        (3.0, 15.0)
+    }
+}
+
+/// Estimate cost for a single usage record
+fn estimate_cost(model: &str, input: u64, output: u64, cache_write: u64, cache_read: u64) -> f64 {
+    let (input_rate, output_rate) = model_pricing(model);
+    // Cache writes bill at 1.25x input, cache reads at 0.1x input
+    (input as f64 * input_rate
+        + output as f64 * output_rate
+        + cache_write as f64 * input_rate * 1.25
+        + cache_read as f64 * input_rate * 0.1)
+        / 1_000_000.0
+}
+
+/// Read a u64 from the first matching key (snake_case or camelCase variants)
+fn get_u64(obj: &Value, keys: &[&str]) -> u64 {
+    keys.iter()
+        .find_map(|k| obj.get(*k).and_then(Value::as_u64))
+        .unwrap_or(0)
+}
+
+/// Read an f64 from the first matching key
+fn get_f64(obj: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter()
+        .find_map(|k| obj.get(*k).and_then(Value::as_f64))
+}
+
+/// Parse one usage record (a transcript line or a usage file object)
+///
+/// Accepts `usage` nested under `message` (current Claude Code), at the top
+/// level (older versions), or the object itself being the usage block.
+fn parse_usage_record(record: &Value) -> Option<AgentUsage> {
+    let message = record.get("message").unwrap_or(record);
+    let usage = message
+        .get("usage")
+        .or_else(|| record.get("usage"))
+        .unwrap_or(record);
+
+    let input = get_u64(usage, &["input_tokens", "inputTokens"]);
+    let output = get_u64(usage, &["output_tokens", "outputTokens"]);
+    let cache_write = get_u64(
+        usage,
+        &["cache_creation_input_tokens", "cacheCreationInputTokens"],
+    );
+    let cache_read = get_u64(usage, &["cache_read_input_tokens", "cacheReadInputTokens"]);
+
+    let model = message
+        .get("model")
+        .or_else(|| record.get("model"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    // Prefer a cost reported by the agent over our estimate
+    let cost = get_f64(record, &["costUSD", "cost_usd", "total_cost_usd"])
+        .or_else(|| get_f64(usage, &["costUSD", "cost_usd"]))
+        .unwrap_or_else(|| estimate_cost(&model, input, output, cache_write, cache_read));
+
+    let parsed = AgentUsage {
+        input_tokens: input,
+        output_tokens: output,
+        cache_creation_tokens: cache_write,
+        cache_read_tokens: cache_read,
+        estimated_cost_usd: cost,
+        models: if model.is_empty() || model.starts_with('<') {
+            vec![]
+        } else {
+            vec![model]
+        },
+        ..Default::default()
+    };
+
+    parsed.has_tokens().then_some(parsed)
+}
+
+/// Identity of the API response a record's usage belongs to.
+///
+/// Claude Code writes one transcript line per content block, each repeating
+/// the response's usage, and resumed sessions copy earlier lines into the new
+/// transcript, so records are only counted once per message/request id.
+fn usage_record_id(record: &Value) -> Option<String> {
+    let message_id = record
+        .get("message")
+        .and_then(|m| m.get("id"))
+        .and_then(Value::as_str);
+    let request_id = record.get("requestId").and_then(Value::as_str);
+    match (message_id, request_id) {
+        (None, None) => None,
+        (m, r) => Some(format!(
+            "{}:{}",
+            m.unwrap_or_default(),
+            r.unwrap_or_default()
+        )),
+    }
+}
+
+/// Sum usage from JSONL content, skipping lines that aren't usage records
+/// and records already counted in `seen`
+fn parse_usage_jsonl(content: &str, seen: &mut HashSet<String>) -> AgentUsage {
+    let mut total = AgentUsage::default();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if let Some(id) = usage_record_id(&record) {
+            if !seen.insert(id) {
+                continue;
+            }
+        }
+        if let Some(usage) = parse_usage_record(&record) {
+            total.add(&usage);
+        }
+    }
+    total
+}
+
+/// Claude Code's config directory (`$CLAUDE_CONFIG_DIR` or `~/.claude`)
+fn claude_config_dir() -> Option<PathBuf> {
+    std::env::var("CLAUDE_CONFIG_DIR")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|home| Path::new(&home).join(".claude"))
+        })
+}
+
+/// Claude Code's project directory name for a working directory
+/// (every non-alphanumeric character becomes '-')
+fn encode_project_dir(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Read usage for an agent working in `dir`, or None if no usage data exists
+pub fn usage_for_directory(dir: &str) -> Option<AgentUsage> {
+    let mut total = AgentUsage::default();
+    let mut seen = HashSet::new();
+
+    // Generic usage file written by the agent
+    let usage_file = Path::new(dir).join(USAGE_FILE);
+    if let Ok(content) = std::fs::read_to_string(&usage_file) {
+        let usage = match serde_json::from_str::<Value>(&content) {
+            Ok(value) => parse_usage_record(&value),
+            // Some agents append one record per line
+            Err(_) => Some(parse_usage_jsonl(&content, &mut seen)),
+        };
+        if let Some(mut usage) = usage.filter(AgentUsage::has_tokens) {
+            usage.sources = vec![usage_file.to_string_lossy().to_string()];
+            total.add(&usage);
+        }
+    }
+
+    // Claude Code session transcripts
+    if let Some(config_dir) = claude_config_dir() {
+        let project_dir = config_dir
+            .join("projects")
+            .join(encode_project_dir(dir.trim_end_matches('/')));
+        if let Ok(entries) = std::fs::read_dir(&project_dir) {
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                    continue;
+                }
+                let Ok(content) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let mut usage = parse_usage_jsonl(&content, &mut seen);
+                if usage.has_tokens() {
+                    usage.sources = vec![path.to_string_lossy().to_string()];
+                    total.add(&usage);
+                }
+            }
+        }
+    }
+
+    total.has_tokens().then_some(total)
+}
+
+/// Get token usage for an agent session, or None if unavailable
+pub fn get_agent_usage(session_name: &str) -> Result<Option<AgentUsage>, String> {
+    let metadata = tmux::get_session_metadata(session_name)?;
+    let Some(worktree) = metadata.worktree else {
+        return Ok(None);
+    };
+
+    // The agent may have been started in a monorepo subdirectory
+    let mut dirs = vec![worktree.clone()];
+    if let Some(sub) = metadata
+        .subpath
+        .as_deref()
+        .map(|s| s.trim_matches('/'))
+        .filter(|s| !s.is_empty())
+    {
+        dirs.push(format!("{}/{}", worktree.trim_end_matches('/'), sub));
+    }
+
+    let mut total = AgentUsage::default();
+    for dir in &dirs {
+        if let Some(usage) = usage_for_directory(dir) {
+            total.add(&usage);
+        }
+    }
+
+    if !total.has_tokens() {
+        return Ok(None);
+    }
+    total.session = session_name.to_string();
+    Ok(Some(total))
+}
+
+/// Aggregate usage across sessions (all Handy agent sessions when `session_names` is None)
+pub fn get_total_usage(session_names: Option<Vec<String>>) -> Result<UsageSummary, String> {
+    let session_names = match session_names {
+        Some(names) => names,
+        None => tmux::list_sessions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.metadata.is_some())
+            .map(|s| s.name)
+            .collect(),
+    };
+
+    let mut summary = UsageSummary {
+        total: AgentUsage::default(),
+        sessions: Vec::new(),
+        sessions_without_usage: Vec::new(),
+    };
+
+    for session in session_names {
+        match get_agent_usage(&session) {
+            Ok(Some(usage)) => {
+                summary.total.add(&usage);
+                summary.sessions.push(usage);
+            }
+            Ok(None) => summary.sessions_without_usage.push(session),
+            Err(e) => {
+                log::debug!("No usage for session {}: {}", session, e);
+                summary.sessions_without_usage.push(session);
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_usage_jsonl_mixed_formats() {
+        let content = r#"{"type":"user","message":{"role":"user","content":"hi"}}
+{"type":"assistant","message":{"model":"claude-sonnet-4","usage":{"input_tokens":1000,"output_tokens":200,"cache_read_input_tokens":5000}}}
+not json at all
+{"type":"assistant","costUSD":0.5,"usage":{"inputTokens":10,"outputTokens":20}}
+"#;
+        let usage = parse_usage_jsonl(content, &mut HashSet::new());
+        assert_eq!(usage.input_tokens, 1010);
+        assert_eq!(usage.output_tokens, 220);
+        assert_eq!(usage.cache_read_tokens, 5000);
+        assert_eq!(usage.models, vec!["claude-sonnet-4"]);
+
+        // 1000 * 3 + 200 * 15 + 5000 * 0.3 per million, plus the reported 0.5
+        let expected = (3000.0 + 3000.0 + 1500.0) / 1_000_000.0 + 0.5;
+        assert!((usage.estimated_cost_usd - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_usage_jsonl_dedupes_messages() {
+        // One response split over two content-block lines, then copied into a resumed session
+        let line = |block: &str| {
+            format!(
+                r#"{{"type":"assistant","requestId":"req_1","message":{{"id":"msg_1","model":"claude-sonnet-4","content":[{{"type":"{}"}}],"usage":{{"input_tokens":100,"output_tokens":10}}}}}}"#,
+                block
+            )
+        };
+        let first = format!("{}\n{}\n", line("text"), line("tool_use"));
+        let mut seen = HashSet::new();
+        let usage = parse_usage_jsonl(&first, &mut seen);
+        assert_eq!(usage.input_tokens, 100);
+        assert_eq!(usage.output_tokens, 10);
+
+        let resumed = format!(
+            "{}\n{}\n",
+            line("text"),
+            r#"{"requestId":"req_2","message":{"id":"msg_2","usage":{"input_tokens":5,"output_tokens":1}}}"#
+        );
+        let usage = parse_usage_jsonl(&resumed, &mut seen);
+        assert_eq!(usage.input_tokens, 5);
+        assert_eq!(usage.output_tokens, 1);
+    }
+
+    #[test]
+    fn test_usage_for_directory_reads_usage_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(usage_for_directory(dir.path().to_str().unwrap()).is_none());
+
+        std::fs::write(
+            dir.path().join(USAGE_FILE),
+            r#"{"input_tokens": 100, "output_tokens": 50, "cost_usd": 0.01}"#,
+        )
+        .unwrap();
+        let usage = usage_for_directory(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(usage.input_tokens, 100);
+        assert_eq!(usage.output_tokens, 50);
+        assert!((usage.estimated_cost_usd - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_encode_project_dir() {
+        assert_eq!(
+            encode_project_dir("/Users/me/handy-worktrees/issue.42"),
+            "-Users-me-handy-worktrees-issue-42"
+        );
+    }
+}
@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
+
+use super::process::CommandRetryExt;
 
 /// Configuration for worktree creation.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -16,6 +19,12 @@ pub struct WorktreeConfig {
     pub base_path: Option<String>,
     /// Auto-delete branch after merge
     pub delete_branch_on_merge: bool,
+    /// Commit SHA or tag to branch from instead of the base branch's HEAD
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    /// Remote the branch is pushed to (e.g., a contributor's fork); `origin` if None
+    #[serde(default)]
+    pub push_remote: Option<String>,
 }
 
 impl Default for WorktreeConfig {
@@ -24,6 +33,8 @@ impl Default for WorktreeConfig {
             prefix: String::new(),
             base_path: None,
             delete_branch_on_merge: true,
+            base_ref: None,
+            push_remote: None,
         }
     }
 }
@@ -140,7 +151,7 @@ pub fn list_worktrees(repo_path: &str) -> Result<Vec<WorktreeInfo>, String> {
     let output = Command::new("git")
         .args(["worktree", "list", "--porcelain"])
         .current_dir(repo_path)
-        .output()
+        .output_with_retry()
         .map_err(|e| format!("Failed to execute git worktree list: {}", e))?;
 
     if !output.status.success() {
@@ -263,6 +274,129 @@ pub fn check_collision(
     Ok(result)
 }
 
+/// Find the worktrees that already have `branch_name` checked out.
+///
+/// Git refuses to check out a branch in a second worktree, so a non-empty
+/// result means a new worktree for the branch can't be created.
+pub fn find_branch_conflicts(
+    repo_path: &str,
+    branch_name: &str,
+) -> Result<Vec<WorktreeInfo>, String> {
+    Ok(list_worktrees(repo_path)?
+        .into_iter()
+        .filter(|wt| wt.branch.as_deref() == Some(branch_name))
+        .collect())
+}
+
+/// Suggest a free name for a branch that is taken: `<branch>-2`, `<branch>-3`, ...
+///
+/// A name is free when no local branch has it and its worktree path is available.
+pub fn suggest_branch_name(
+    repo_path: &str,
+    branch_name: &str,
+    config: &WorktreeConfig,
+) -> Result<String, String> {
+    for n in 2..100 {
+        let candidate = format!("{}-{}", branch_name, n);
+        if !local_branch_exists(repo_path, &candidate)
+            && resolve_worktree_path(repo_path, &candidate, config).is_ok()
+        {
+            return Ok(candidate);
+        }
+    }
+    Err(format!("No free branch name found for '{}'", branch_name))
+}
+
+/// Final name and location of a worktree, as `create_worktree` would use them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ResolvedWorktreePath {
+    /// Prefixed worktree name (also the branch name for new worktrees)
+    pub name: String,
+    /// Absolute path of the worktree directory
+    pub path: String,
+}
+
+/// The prefixed name (and branch name) `create_worktree` would use for `name`.
+pub fn prefixed_worktree_name(
+    repo_path: &str,
+    name: &str,
+    config: &WorktreeConfig,
+) -> Result<String, String> {
+    if config.prefix.contains('/') || config.prefix.contains('\\') {
+        return Err(format!(
+            "Worktree prefix '{}' must not contain path separators",
+            config.prefix
+        ));
+    }
+    if name.is_empty() || name.split('/').any(|part| part == ".." || part == ".") {
+        return Err(format!("Invalid worktree name '{}'", name));
+    }
+
+    let prefix = if config.prefix.is_empty() {
+        format!("{}-", get_project_name(repo_path)?)
+    } else {
+        config.prefix.clone()
+    };
+
+    // Branches created by `create_worktree` already carry the prefix
+    Ok(if name.starts_with(&prefix) {
+        name.to_string()
+    } else {
+        format!("{}{}", prefix, name)
+    })
+}
+
+/// Resolve where a worktree for `name` would be created.
+///
+/// The name gets `config.prefix` (default `<project>-`) unless it already
+/// carries it, and lives under `config.base_path` (default: the repo's parent).
+/// The resulting path must be absolute, must not exist yet, and must be
+/// outside the repository so worktrees never nest inside the repo.
+pub fn resolve_worktree_path(
+    repo_path: &str,
+    name: &str,
+    config: &WorktreeConfig,
+) -> Result<ResolvedWorktreePath, String> {
+    let worktree_name = prefixed_worktree_name(repo_path, name, config)?;
+    let repo_root = get_repo_root(repo_path)?;
+
+    let base_path = match &config.base_path {
+        Some(base) if !Path::new(base).is_absolute() => {
+            return Err(format!("Worktree base path must be absolute: {}", base));
+        }
+        Some(base) => PathBuf::from(base),
+        None => Path::new(&repo_root)
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("Repository {} has no parent directory", repo_root))?,
+    };
+    let worktree_path = base_path.join(&worktree_name);
+    let worktree_path_str = worktree_path.to_string_lossy().to_string();
+
+    if worktree_path.exists() {
+        return Err(format!(
+            "Worktree path already exists: {}",
+            worktree_path_str
+        ));
+    }
+
+    // Compare resolved paths so symlinks and `..` can't sneak a worktree into the repo
+    let canonical_root = std::fs::canonicalize(&repo_root).unwrap_or_else(|_| repo_root.into());
+    let canonical_base = std::fs::canonicalize(&base_path).unwrap_or(base_path);
+    if canonical_base.starts_with(&canonical_root) {
+        return Err(format!(
+            "Worktree path {} is inside the repository {}; use a base path outside it",
+            worktree_path_str,
+            canonical_root.display()
+        ));
+    }
+
+    Ok(ResolvedWorktreePath {
+        name: worktree_name,
+        path: worktree_path_str,
+    })
+}
+
 /// Create a new git worktree with a new branch.
 ///
 /// # Arguments
@@ -279,35 +413,25 @@ pub fn create_worktree(
     config: &WorktreeConfig,
     base_branch: Option<&str>,
 ) -> Result<WorktreeCreateResult, String> {
-    let repo_root = get_repo_root(repo_path)?;
-    let project_name = get_project_name(repo_path)?;
+    // Fail before creating anything if the branch could never be pushed
+    if let Some(remote) = &config.push_remote {
+        verify_remote(repo_path, remote)?;
+    }
 
-    // Determine base branch
-    let base = match base_branch {
-        Some(b) => b.to_string(),
-        None => get_default_branch(repo_path)?,
+    // Determine base: a pinned commit/tag wins over the base branch
+    let base = match (&config.base_ref, base_branch) {
+        (Some(base_ref), _) => {
+            verify_commit_ref(repo_path, base_ref)?;
+            base_ref.clone()
+        }
+        (None, Some(b)) => b.to_string(),
+        (None, None) => get_default_branch(repo_path)?,
     };
 
     // Build the worktree path and branch name
-    let prefix = if config.prefix.is_empty() {
-        format!("{}-", project_name)
-    } else {
-        config.prefix.clone()
-    };
-
-    let worktree_name = format!("{}{}", prefix, name);
-    let branch_name = worktree_name.clone();
-
-    // Determine worktree directory
-    let base_path = config.base_path.clone().unwrap_or_else(|| {
-        Path::new(&repo_root)
-            .parent()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| repo_root.clone())
-    });
-
-    let worktree_path = PathBuf::from(&base_path).join(&worktree_name);
-    let worktree_path_str = worktree_path.to_string_lossy().to_string();
+    let resolved = resolve_worktree_path(repo_path, name, config)?;
+    let branch_name = resolved.name;
+    let worktree_path_str = resolved.path;
 
     // Check for collisions
     let collision = check_collision(repo_path, &worktree_path_str, &branch_name)?;
@@ -341,6 +465,19 @@ pub fn create_worktree(
         ));
     }
 
+    // A plain `git push` from the agent then goes to the fork too
+    if let Some(remote) = &config.push_remote {
+        run_git(
+            &worktree_path_str,
+            &[
+                "config",
+                &format!("branch.{}.pushRemote", branch_name),
+                remote,
+            ],
+            None,
+        )?;
+    }
+
     Ok(WorktreeCreateResult {
         path: worktree_path_str,
         branch: branch_name,
@@ -348,39 +485,89 @@ pub fn create_worktree(
     })
 }
 
-/// Create a worktree using an existing branch.
-pub fn create_worktree_existing_branch(
-    repo_path: &str,
-    branch_name: &str,
-    config: &WorktreeConfig,
-) -> Result<WorktreeCreateResult, String> {
-    let repo_root = get_repo_root(repo_path)?;
-    let project_name = get_project_name(repo_path)?;
+/// Push a worktree's branch to `remote`, setting it as the upstream
+pub fn push_branch(worktree_path: &str, remote: &str, branch_name: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["push", "-u", remote, branch_name])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to execute git push: {}", e))?;
 
-    // Build the worktree path
-    let prefix = if config.prefix.is_empty() {
-        format!("{}-", project_name)
-    } else {
-        config.prefix.clone()
-    };
+    if !output.status.success() {
+        return Err(format!(
+            "git push failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
 
-    let worktree_name = format!("{}{}", prefix, branch_name);
+    Ok(())
+}
 
-    // Determine worktree directory
-    let base_path = config.base_path.clone().unwrap_or_else(|| {
-        Path::new(&repo_root)
-            .parent()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| repo_root.clone())
-    });
+/// Check that a commit SHA, tag or branch names a commit in the repo.
+///
+/// Returns the full SHA it resolves to.
+pub fn verify_commit_ref(repo_path: &str, git_ref: &str) -> Result<String, String> {
+    run_git(
+        repo_path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", git_ref),
+        ],
+        None,
+    )
+    .map_err(|_| format!("Base ref '{}' does not exist in {}", git_ref, repo_path))
+}
 
-    let worktree_path = PathBuf::from(&base_path).join(&worktree_name);
-    let worktree_path_str = worktree_path.to_string_lossy().to_string();
+/// Check that `remote` is configured in the repo.
+///
+/// Returns the remote's URL.
+pub fn verify_remote(repo_path: &str, remote: &str) -> Result<String, String> {
+    run_git(repo_path, &["remote", "get-url", remote], None)
+        .map_err(|_| format!("Remote '{}' does not exist in {}", remote, repo_path))
+}
 
-    // Check if path exists
-    if Path::new(&worktree_path_str).exists() {
-        return Err(format!("Directory already exists: {}", worktree_path_str));
+/// Extract the owner from a GitHub remote URL.
+///
+/// Handles `https://github.com/owner/repo(.git)`, `git@github.com:owner/repo.git`
+/// and `ssh://git@github.com/owner/repo.git`.
+pub fn parse_remote_owner(url: &str) -> Option<String> {
+    let path = url.trim().trim_end_matches('/').trim_end_matches(".git");
+    let (prefix, repo) = path.rsplit_once('/')?;
+    let owner = prefix.rsplit(['/', ':']).next()?;
+    if owner.is_empty() || repo.is_empty() || owner.contains('@') {
+        return None;
     }
+    Some(owner.to_string())
+}
+
+/// The `--head` value for a PR from `branch`.
+///
+/// Branches pushed to another remote (a fork) are qualified as `owner:branch`
+/// so the PR is opened against the upstream repo from the fork.
+pub fn pr_head_ref(
+    worktree_path: &str,
+    branch: &str,
+    push_remote: Option<&str>,
+) -> Result<String, String> {
+    let Some(remote) = push_remote else {
+        return Ok(branch.to_string());
+    };
+    let url = verify_remote(worktree_path, remote)?;
+    let owner = parse_remote_owner(&url)
+        .ok_or_else(|| format!("Cannot determine owner of remote '{}' ({})", remote, url))?;
+    Ok(format!("{}:{}", owner, branch))
+}
+
+/// Create a worktree using an existing branch.
+pub fn create_worktree_existing_branch(
+    repo_path: &str,
+    branch_name: &str,
+    config: &WorktreeConfig,
+) -> Result<WorktreeCreateResult, String> {
+    // Build the worktree path (validated to be new and outside the repo)
+    let worktree_path_str = resolve_worktree_path(repo_path, branch_name, config)?.path;
 
     // Create the worktree using existing branch
     let output = Command::new("git")
@@ -403,6 +590,45 @@ pub fn create_worktree_existing_branch(
     })
 }
 
+/// Create a git worktree with a detached HEAD at the default branch.
+///
+/// For callers that check out the branch themselves, e.g. `gh pr checkout`
+/// for a PR whose branch may live in a fork.
+pub fn create_detached_worktree(
+    repo_path: &str,
+    name: &str,
+    config: &WorktreeConfig,
+) -> Result<String, String> {
+    let worktree_path_str = resolve_worktree_path(repo_path, name, config)?.path;
+    let base = get_default_branch(repo_path)?;
+
+    let output = Command::new("git")
+        .args(["worktree", "add", "--detach", &worktree_path_str, &base])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to execute git worktree add: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git worktree add failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(worktree_path_str)
+}
+
+/// Whether a local branch named `branch_name` exists.
+pub fn local_branch_exists(repo_path: &str, branch_name: &str) -> bool {
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    run_git(
+        repo_path,
+        &["show-ref", "--verify", "--quiet", &branch_ref],
+        None,
+    )
+    .is_ok()
+}
+
 /// Remove a git worktree.
 ///
 /// # Arguments
@@ -483,6 +709,41 @@ pub fn get_worktree_info(repo_path: &str, worktree_path: &str) -> Result<Worktre
         .ok_or_else(|| format!("Worktree not found: {}", worktree_path))
 }
 
+/// Validate an existing worktree to run an agent in instead of creating one.
+///
+/// The path must be a linked worktree of `repo_path` (not its main checkout)
+/// with a branch checked out; that branch is returned as the worktree's.
+pub fn resolve_existing_worktree(
+    repo_path: &str,
+    worktree_path: &str,
+) -> Result<WorktreeCreateResult, String> {
+    let wanted = std::fs::canonicalize(worktree_path)
+        .map_err(|e| format!("Worktree not found: {}: {}", worktree_path, e))?;
+
+    let worktree = list_worktrees(repo_path)?
+        .into_iter()
+        .find(|wt| std::fs::canonicalize(&wt.path).is_ok_and(|p| p == wanted))
+        .ok_or_else(|| format!("{} is not a git worktree of {}", worktree_path, repo_path))?;
+    if worktree.is_main {
+        return Err(format!(
+            "{} is the main checkout of the repo, not a separate worktree",
+            worktree_path
+        ));
+    }
+    let branch = worktree.branch.ok_or_else(|| {
+        format!(
+            "Worktree {} has a detached HEAD; check out a branch first",
+            worktree_path
+        )
+    })?;
+
+    Ok(WorktreeCreateResult {
+        path: worktree.path,
+        branch,
+        branch_created: false,
+    })
+}
+
 /// Prune stale worktree entries.
 pub fn prune_worktrees(repo_path: &str) -> Result<(), String> {
     let output = Command::new("git")
@@ -501,6 +762,612 @@ pub fn prune_worktrees(repo_path: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Fetch a branch from `origin` if it exists there.
+///
+/// Returns `Ok(false)` when the remote has no such branch. After a successful
+/// fetch, `git worktree add <path> <branch>` creates a local tracking branch.
+pub fn fetch_remote_branch(repo_path: &str, branch_name: &str) -> Result<bool, String> {
+    let refspec = format!("refs/heads/{0}:refs/remotes/origin/{0}", branch_name);
+    let output = Command::new("git")
+        .args(["fetch", "--quiet", "origin", &refspec])
+        .current_dir(repo_path)
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute git fetch: {}", e))?;
+
+    if output.status.success() {
+        return Ok(true);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("couldn't find remote ref") {
+        return Ok(false);
+    }
+    Err(format!("git fetch failed: {}", stderr))
+}
+
+/// Get the branch currently checked out in a worktree.
+pub fn get_current_branch(worktree_path: &str) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(worktree_path)
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute git rev-parse: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git rev-parse failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Add `pattern` to the repository's `info/exclude` unless it's already there.
+///
+/// Works from a linked worktree too; the exclude file is shared by all of them.
+pub fn exclude_from_git(worktree_path: &str, pattern: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args([
+            "rev-parse",
+            "--path-format=absolute",
+            "--git-path",
+            "info/exclude",
+        ])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to execute git rev-parse: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git rev-parse failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let exclude = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let existing = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+
+    if let Some(dir) = exclude.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    std::fs::write(&exclude, format!("{}{}{}\n", existing, separator, pattern))
+        .map_err(|e| format!("Failed to write {}: {}", exclude.display(), e))
+}
+
+/// Delete a worktree's local branch, leaving the worktree on a detached HEAD.
+///
+/// Files and uncommitted changes in the worktree are untouched, so a session
+/// still running there keeps working until the worktree is cleaned up.
+pub fn delete_worktree_branch(worktree_path: &str, branch: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .current_dir(worktree_path)
+        .output()
+        .map_err(|e| format!("Failed to execute git rev-parse: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git rev-parse failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let git_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    // A branch can't be deleted while it is checked out
+    if get_current_branch(worktree_path)? == branch {
+        let output = Command::new("git")
+            .args(["checkout", "--detach"])
+            .current_dir(worktree_path)
+            .output()
+            .map_err(|e| format!("Failed to execute git checkout: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "git checkout --detach failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+
+    let output = Command::new("git")
+        .args(["--git-dir", &git_dir, "branch", "-D", branch])
+        .output()
+        .map_err(|e| format!("Failed to delete branch: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git branch -D failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// The commit a worktree's branch forked from the default branch at
+/// (preferring the remote-tracking branch when there is one).
+pub fn branch_fork_point(worktree_path: &str) -> Result<String, String> {
+    let default_branch = get_default_branch(worktree_path)?;
+    let remote_branch = format!("origin/{}", default_branch);
+    let base = if run_git(
+        worktree_path,
+        &["rev-parse", "--verify", "-q", &remote_branch],
+        None,
+    )
+    .is_ok()
+    {
+        remote_branch
+    } else {
+        default_branch
+    };
+    run_git(worktree_path, &["merge-base", "HEAD", &base], None)
+}
+
+/// List the SHAs of the most recent commits on a worktree's branch since it
+/// forked from the default branch (base history is excluded).
+pub fn list_recent_commits(worktree_path: &str, limit: u32) -> Result<Vec<String>, String> {
+    let merge_base = branch_fork_point(worktree_path)?;
+
+    let output = Command::new("git")
+        .args([
+            "rev-list",
+            &format!("--max-count={}", limit),
+            &format!("{}..HEAD", merge_base.trim()),
+        ])
+        .current_dir(worktree_path)
+        .output_with_retry()
+        .map_err(|e| format!("Failed to execute git rev-list: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git rev-list failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+/// How a worktree's branch compares to its upstream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct BranchSyncStatus {
+    /// Commits on the branch that aren't on the upstream (or on any remote
+    /// branch, when there is no upstream) - i.e. commits to push
+    pub ahead: u32,
+    /// Commits on the upstream that the branch doesn't have
+    pub behind: u32,
+    /// Whether the branch tracks a remote branch
+    pub has_upstream: bool,
+    /// Whether the worktree has uncommitted changes (including untracked files)
+    pub dirty: bool,
+}
+
+/// Parse `git rev-list --left-right --count` output (`<ahead>\t<behind>`).
+fn parse_left_right_count(output: &str) -> Option<(u32, u32)> {
+    let mut counts = output.split_whitespace().map(|n| n.parse::<u32>().ok());
+    Some((counts.next()??, counts.next()??))
+}
+
+/// Compare a worktree's branch with its upstream.
+///
+/// Without an upstream, `ahead` counts commits not yet on any remote branch
+/// and `behind` is 0.
+pub fn get_branch_sync_status(worktree_path: &str) -> Result<BranchSyncStatus, String> {
+    let dirty = !run_git(worktree_path, &["status", "--porcelain"], None)?.is_empty();
+
+    let has_upstream = run_git(
+        worktree_path,
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ],
+        None,
+    )
+    .is_ok();
+
+    let (ahead, behind) = if has_upstream {
+        let counts = run_git(
+            worktree_path,
+            &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+            None,
+        )?;
+        parse_left_right_count(&counts)
+            .ok_or_else(|| format!("Unexpected git rev-list output: {}", counts))?
+    } else {
+        let count = run_git(
+            worktree_path,
+            &["rev-list", "--count", "HEAD", "--not", "--remotes"],
+            None,
+        )?;
+        let ahead = count
+            .parse()
+            .map_err(|_| format!("Unexpected git rev-list output: {}", count))?;
+        (ahead, 0)
+    };
+
+    Ok(BranchSyncStatus {
+        ahead,
+        behind,
+        has_upstream,
+        dirty,
+    })
+}
+
+/// Most commits listed in a change digest
+const DIGEST_MAX_COMMITS: usize = 20;
+
+/// What a branch changed relative to the branch it will merge into.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct ChangeDigest {
+    /// Number of files changed
+    pub files_changed: u32,
+    /// Lines added
+    pub insertions: u32,
+    /// Lines removed
+    pub deletions: u32,
+    /// Per-file `git diff --stat` lines
+    pub file_stats: Vec<String>,
+    /// Commits on the branch as `<short sha> <subject>`, oldest first
+    pub commits: Vec<String>,
+    /// Commits not listed because of the size limit
+    pub omitted_commits: usize,
+}
+
+/// Parse the summary line of `git diff --stat` / `--shortstat`
+/// (e.g. "3 files changed, 10 insertions(+), 2 deletions(-)").
+fn parse_shortstat(line: &str) -> (u32, u32, u32) {
+    let mut counts = (0, 0, 0);
+    for part in line.split(',') {
+        let mut words = part.split_whitespace();
+        let Some(n) = words.next().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        match words.next() {
+            Some(w) if w.starts_with("file") => counts.0 = n,
+            Some(w) if w.starts_with("insertion") => counts.1 = n,
+            Some(w) if w.starts_with("deletion") => counts.2 = n,
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// Summarize a worktree's changes since it diverged from `base_branch`.
+///
+/// Compares against `origin/<base_branch>` when that exists, so a stale local
+/// base branch doesn't inflate the digest.
+pub fn get_change_digest(worktree_path: &str, base_branch: &str) -> Result<ChangeDigest, String> {
+    let remote_base = format!("origin/{}", base_branch);
+    let base = if verify_commit_ref(worktree_path, &remote_base).is_ok() {
+        remote_base
+    } else {
+        base_branch.to_string()
+    };
+
+    let stat = run_git(
+        worktree_path,
+        &["diff", "--stat=100", &format!("{}...HEAD", base)],
+        None,
+    )?;
+    let mut lines: Vec<&str> = stat.lines().collect();
+    let (files_changed, insertions, deletions) =
+        lines.pop().map(parse_shortstat).unwrap_or_default();
+
+    let log = run_git(
+        worktree_path,
+        &[
+            "log",
+            "--reverse",
+            "--format=%h %s",
+            &format!("{}..HEAD", base),
+        ],
+        None,
+    )?;
+    let mut commits: Vec<String> = log.lines().map(str::to_string).collect();
+    let omitted_commits = commits.len().saturating_sub(DIGEST_MAX_COMMITS);
+    commits.truncate(DIGEST_MAX_COMMITS);
+
+    Ok(ChangeDigest {
+        files_changed,
+        insertions,
+        deletions,
+        file_stats: lines.iter().map(|l| l.trim().to_string()).collect(),
+        commits,
+        omitted_commits,
+    })
+}
+
+/// Prefix for branches holding worktree backups
+pub const BACKUP_BRANCH_PREFIX: &str = "handy-backup/";
+
+/// Run a git command in `dir` (optionally with a separate index) and return trimmed stdout.
+fn run_git(dir: &str, args: &[&str], index_file: Option<&Path>) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.args(args).current_dir(dir);
+    if let Some(index) = index_file {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to execute git {}: {}", args[0], e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Commit the worktree's current state (including untracked files) on top of HEAD.
+///
+/// Uses a temporary index so the worktree, the real index, and the stash list
+/// are left untouched. Returns the commit SHA.
+fn snapshot_commit(worktree_path: &str, message: &str) -> Result<String, String> {
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let index = temp_dir.path().join("index");
+
+    run_git(worktree_path, &["read-tree", "HEAD"], Some(&index))?;
+    run_git(worktree_path, &["add", "-A"], Some(&index))?;
+    let tree = run_git(worktree_path, &["write-tree"], Some(&index))?;
+
+    run_git(
+        worktree_path,
+        &["commit-tree", &tree, "-p", "HEAD", "-m", message],
+        None,
+    )
+}
+
+/// Snapshot uncommitted work (including untracked files) to a backup branch.
+///
+/// Uses a temporary index so the worktree, the real index, and the stash list
+/// are left untouched. Returns the branch name (`handy-backup/<label>-<timestamp>`),
+/// or None if the worktree has no uncommitted changes.
+pub fn backup_worktree_state(worktree_path: &str, label: &str) -> Result<Option<String>, String> {
+    let status = run_git(worktree_path, &["status", "--porcelain"], None)?;
+    if status.is_empty() {
+        return Ok(None);
+    }
+
+    let message = format!("Handy backup of uncommitted work ({})", label);
+    let commit = snapshot_commit(worktree_path, &message)?;
+
+    let branch = format!(
+        "{}{}-{}",
+        BACKUP_BRANCH_PREFIX,
+        label,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    );
+    run_git(worktree_path, &["branch", &branch, &commit], None)?;
+
+    Ok(Some(branch))
+}
+
+/// Subject of restore-point commits that carry uncommitted work
+const RESTORE_POINT_SUBJECT: &str = "Handy restore point";
+
+/// Record the worktree's current state on `branch` so it can be restored later.
+///
+/// The branch points at HEAD when the worktree is clean, otherwise at a
+/// snapshot commit holding the uncommitted work. An existing branch is moved.
+pub fn create_restore_point(worktree_path: &str, branch: &str) -> Result<(), String> {
+    let status = run_git(worktree_path, &["status", "--porcelain"], None)?;
+    let commit = if status.is_empty() {
+        run_git(worktree_path, &["rev-parse", "HEAD"], None)?
+    } else {
+        snapshot_commit(worktree_path, RESTORE_POINT_SUBJECT)?
+    };
+    run_git(worktree_path, &["branch", "-f", branch, &commit], None)?;
+    Ok(())
+}
+
+/// Reset a worktree to a restore point made by `create_restore_point`.
+///
+/// Aborts any in-progress merge, moves HEAD back to the recorded commit, drops
+/// files created since, and brings back the uncommitted work captured with the
+/// restore point. Commits already pushed are not touched on the remote.
+/// Returns false if `branch` doesn't exist.
+pub fn restore_to_point(worktree_path: &str, branch: &str) -> Result<bool, String> {
+    if run_git(
+        worktree_path,
+        &["rev-parse", "--verify", "--quiet", branch],
+        None,
+    )
+    .is_err()
+    {
+        return Ok(false);
+    }
+
+    // Nothing to abort is fine
+    let _ = run_git(worktree_path, &["merge", "--abort"], None);
+
+    let subject = run_git(worktree_path, &["log", "-1", "--format=%s", branch], None)?;
+    let has_snapshot = subject == RESTORE_POINT_SUBJECT;
+    let head = if has_snapshot {
+        format!("{}^", branch)
+    } else {
+        branch.to_string()
+    };
+
+    run_git(worktree_path, &["reset", "-q", "--hard", &head], None)?;
+    run_git(worktree_path, &["clean", "-q", "-fd"], None)?;
+    if has_snapshot {
+        run_git(worktree_path, &["checkout", branch, "--", "."], None)?;
+        // Unstage so previously untracked files are untracked again
+        run_git(worktree_path, &["reset", "-q"], None)?;
+    }
+
+    Ok(true)
+}
+
+/// Git subcommands `run_git_in_worktree` may run
+const ALLOWED_GIT_SUBCOMMANDS: &[&str] = &[
+    "status",
+    "log",
+    "diff",
+    "stash",
+    "cherry-pick",
+    "reset",
+    "fetch",
+    "pull",
+];
+
+/// Subcommands that only read the worktree (allowed in read-only mode)
+const READ_ONLY_GIT_SUBCOMMANDS: &[&str] = &["status", "log", "diff"];
+
+/// Options that run programs, write files outside the worktree or redirect
+/// git to another repository
+const BLOCKED_GIT_OPTIONS: &[&str] = &[
+    "-c",
+    "--config",
+    "--config-env",
+    "--exec",
+    "--upload-pack",
+    "--receive-pack",
+    "--output",
+    "--git-dir",
+    "--work-tree",
+    "--exec-path",
+    "--namespace",
+    "--ext-diff",
+    "--textconv",
+];
+
+/// Real options that are also prefixes of a blocked option (git matches
+/// exact names before abbreviations)
+const ALLOWED_OPTION_PREFIXES: &[&str] = &["--text"];
+
+/// How long an allowlisted git command (e.g. a `fetch` or `pull`) may run
+const GIT_COMMAND_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Whether `option` names a blocked option, including the unique
+/// abbreviations git accepts for long options (`--upload-p` for `--upload-pack`)
+fn is_blocked_git_option(option: &str) -> bool {
+    if BLOCKED_GIT_OPTIONS.contains(&option) {
+        return true;
+    }
+    option.len() > 2
+        && option.starts_with("--")
+        && !ALLOWED_OPTION_PREFIXES.contains(&option)
+        && BLOCKED_GIT_OPTIONS
+            .iter()
+            .any(|blocked| blocked.starts_with(option))
+}
+
+/// Output of a git command run with `run_git_in_worktree`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct GitCommandOutput {
+    /// Exit code (None if git was killed by a signal)
+    pub exit_code: Option<i32>,
+    /// Standard output
+    pub stdout: String,
+    /// Standard error
+    pub stderr: String,
+}
+
+/// Check git arguments against the subcommand allowlist.
+///
+/// The first argument must be an allowed subcommand, so global options such
+/// as `-c core.sshCommand=...` can't be slipped in before it. Options that
+/// run programs or escape the worktree, or abbreviations of them, are
+/// rejected anywhere in the list.
+fn validate_git_args(args: &[String]) -> Result<(), String> {
+    let subcommand = args
+        .first()
+        .ok_or_else(|| "No git subcommand given".to_string())?;
+    if !ALLOWED_GIT_SUBCOMMANDS.contains(&subcommand.as_str()) {
+        return Err(format!(
+            "git {} is not allowed. Allowed subcommands: {}",
+            subcommand,
+            ALLOWED_GIT_SUBCOMMANDS.join(", ")
+        ));
+    }
+
+    for arg in &args[1..] {
+        if arg.chars().any(char::is_control) {
+            return Err(format!(
+                "Git argument contains control characters: {:?}",
+                arg
+            ));
+        }
+        let option = arg.split('=').next().unwrap_or(arg);
+        let blocked = is_blocked_git_option(option)
+            // Attached short form, e.g. `-ccore.sshCommand=...`
+            || (arg.starts_with("-c") && !arg.starts_with("--") && arg.contains('='));
+        if blocked {
+            return Err(format!("Git option '{}' is not allowed", option));
+        }
+        // Remote helpers that run arbitrary commands
+        if arg.starts_with("ext::") || arg.starts_with("fd::") {
+            return Err(format!("Git transport in '{}' is not allowed", arg));
+        }
+    }
+    Ok(())
+}
+
+/// Whether git arguments only read the worktree (`status`, `log`, `diff`)
+pub fn is_read_only_git_command(args: &[String]) -> bool {
+    args.first()
+        .is_some_and(|sub| READ_ONLY_GIT_SUBCOMMANDS.contains(&sub.as_str()))
+}
+
+/// Run an allowlisted git command in a worktree.
+///
+/// For recovery work like stashing, cherry-picking or resetting an agent's
+/// branch without a terminal. A failing git command is not an error: its exit
+/// code and output are returned for the caller to show. A command still running
+/// after `GIT_COMMAND_TIMEOUT` is killed.
+pub fn run_git_in_worktree(
+    worktree_path: &str,
+    args: &[String],
+) -> Result<GitCommandOutput, String> {
+    validate_git_args(args)?;
+    if !Path::new(worktree_path).is_dir() || !is_inside_worktree(worktree_path)? {
+        return Err(format!("Not a git worktree: {}", worktree_path));
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(args)
+        .current_dir(worktree_path)
+        // Fail instead of waiting for credentials nobody can type
+        .env("GIT_TERMINAL_PROMPT", "0");
+    let output = super::process::run_with_timeout(&mut cmd, GIT_COMMAND_TIMEOUT).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::TimedOut {
+            format!(
+                "git {} timed out after {}s",
+                args.first().map(String::as_str).unwrap_or_default(),
+                GIT_COMMAND_TIMEOUT.as_secs()
+            )
+        } else {
+            format!("Failed to execute git: {}", e)
+        }
+    })?;
+
+    Ok(GitCommandOutput {
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
 /// Check if a path is inside a git worktree or repository.
 pub fn is_inside_worktree(path: &str) -> Result<bool, String> {
     let output = Command::new("git")
@@ -537,6 +1404,429 @@ mod tests {
         assert!(!check.has_collision);
     }
 
+    #[test]
+    fn test_fetch_remote_branch_and_reuse() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = dir.path().join("origin");
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&origin).unwrap();
+        let origin_path = origin.to_str().unwrap();
+        let git = |path: &str, args: &[&str]| run_git(path, args, None).unwrap();
+        git(origin_path, &["init", "-q", "-b", "main"]);
+        git(origin_path, &["config", "user.email", "test@example.com"]);
+        git(origin_path, &["config", "user.name", "Test"]);
+        git(
+            origin_path,
+            &["commit", "-q", "--allow-empty", "-m", "initial"],
+        );
+        git(origin_path, &["branch", "repo-issue-42"]);
+        git(
+            dir.path().to_str().unwrap(),
+            &["clone", "-q", origin_path, "repo"],
+        );
+        let repo_path = repo.to_str().unwrap();
+
+        assert!(!fetch_remote_branch(repo_path, "repo-issue-7").unwrap());
+        assert!(fetch_remote_branch(repo_path, "repo-issue-42").unwrap());
+
+        let worktree =
+            create_worktree_existing_branch(repo_path, "repo-issue-42", &WorktreeConfig::default())
+                .unwrap();
+        assert!(worktree.path.ends_with("/repo-issue-42"));
+        assert_eq!(get_current_branch(&worktree.path).unwrap(), "repo-issue-42");
+    }
+
+    #[test]
+    fn test_branch_sync_status() {
+        assert_eq!(parse_left_right_count("3\t5\n"), Some((3, 5)));
+        assert_eq!(parse_left_right_count("3"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let origin = dir.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        let origin_path = origin.to_str().unwrap();
+        let git = |path: &str, args: &[&str]| run_git(path, args, None).unwrap();
+        git(origin_path, &["init", "-q", "-b", "main"]);
+        git(origin_path, &["config", "user.email", "test@example.com"]);
+        git(origin_path, &["config", "user.name", "Test"]);
+        git(origin_path, &["commit", "-q", "--allow-empty", "-m", "one"]);
+        git(
+            dir.path().to_str().unwrap(),
+            &["clone", "-q", origin_path, "repo"],
+        );
+        let repo = dir.path().join("repo");
+        let repo_path = repo.to_str().unwrap();
+        git(repo_path, &["config", "user.email", "test@example.com"]);
+        git(repo_path, &["config", "user.name", "Test"]);
+
+        git(origin_path, &["commit", "-q", "--allow-empty", "-m", "two"]);
+        git(repo_path, &["fetch", "-q"]);
+        git(repo_path, &["commit", "-q", "--allow-empty", "-m", "local"]);
+        std::fs::write(repo.join("notes.txt"), "wip").unwrap();
+        let status = get_branch_sync_status(repo_path).unwrap();
+        assert_eq!(
+            status,
+            BranchSyncStatus {
+                ahead: 1,
+                behind: 1,
+                has_upstream: true,
+                dirty: true,
+            }
+        );
+
+        git(repo_path, &["checkout", "-q", "-b", "feature"]);
+        git(repo_path, &["commit", "-q", "--allow-empty", "-m", "more"]);
+        let status = get_branch_sync_status(repo_path).unwrap();
+        assert!(!status.has_upstream);
+        assert_eq!((status.ahead, status.behind), (2, 0));
+    }
+
+    #[test]
+    fn test_find_branch_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        let git = |args: &[&str]| run_git(repo_path, args, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "initial"]);
+
+        let config = WorktreeConfig::default();
+        let worktree = create_worktree(repo_path, "issue-5", &config, None).unwrap();
+        assert_eq!(worktree.branch, "repo-issue-5");
+        assert_eq!(
+            prefixed_worktree_name(repo_path, "issue-5", &config).unwrap(),
+            "repo-issue-5"
+        );
+
+        let conflicts = find_branch_conflicts(repo_path, "repo-issue-5").unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].path.ends_with("/repo-issue-5"));
+        assert!(find_branch_conflicts(repo_path, "repo-issue-6")
+            .unwrap()
+            .is_empty());
+
+        git(&["branch", "repo-issue-5-2"]);
+        assert_eq!(
+            suggest_branch_name(repo_path, "repo-issue-5", &config).unwrap(),
+            "repo-issue-5-3"
+        );
+    }
+
+    #[test]
+    fn test_create_worktree_at_base_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        let git = |args: &[&str]| run_git(repo_path, args, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "release"]);
+        git(&["tag", "v1.0.0"]);
+        let tagged = git(&["rev-parse", "HEAD"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "later"]);
+
+        assert_eq!(verify_commit_ref(repo_path, "v1.0.0").unwrap(), tagged);
+        let config = WorktreeConfig {
+            base_ref: Some("v0.9.0".to_string()),
+            ..Default::default()
+        };
+        let err = create_worktree(repo_path, "issue-1", &config, None).unwrap_err();
+        assert!(err.contains("Base ref 'v0.9.0' does not exist"), "{}", err);
+
+        let config = WorktreeConfig {
+            base_ref: Some("v1.0.0".to_string()),
+            ..Default::default()
+        };
+        let worktree = create_worktree(repo_path, "issue-1", &config, Some("main")).unwrap();
+        assert_eq!(
+            run_git(&worktree.path, &["rev-parse", "HEAD"], None).unwrap(),
+            tagged
+        );
+    }
+
+    #[test]
+    fn test_get_change_digest() {
+        assert_eq!(
+            parse_shortstat(" 3 files changed, 10 insertions(+), 2 deletions(-)"),
+            (3, 10, 2)
+        );
+        assert_eq!(parse_shortstat(" 1 file changed, 1 deletion(-)"), (1, 0, 1));
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| run_git(repo_path, args, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "initial"]);
+        git(&["checkout", "-q", "-b", "issue-1"]);
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "new\nfile\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Add b"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "Follow up"]);
+
+        let digest = get_change_digest(repo_path, "main").unwrap();
+        assert_eq!(
+            (digest.files_changed, digest.insertions, digest.deletions),
+            (2, 2, 1)
+        );
+        assert_eq!(digest.file_stats.len(), 2);
+        assert!(digest.file_stats[1].starts_with("b.txt"));
+        assert_eq!(digest.commits.len(), 2);
+        assert!(digest.commits[0].ends_with(" Add b"));
+        assert_eq!(digest.omitted_commits, 0);
+    }
+
+    #[test]
+    fn test_resolve_existing_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        let git = |args: &[&str]| run_git(repo_path, args, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "initial"]);
+
+        let manual = dir.path().join("manual-work");
+        let manual_path = manual.to_str().unwrap();
+        git(&["worktree", "add", "-q", "-b", "fix-login", manual_path]);
+        let detached = dir.path().join("detached");
+        git(&[
+            "worktree",
+            "add",
+            "-q",
+            "--detach",
+            detached.to_str().unwrap(),
+        ]);
+
+        let reused = resolve_existing_worktree(repo_path, manual_path).unwrap();
+        assert_eq!(reused.branch, "fix-login");
+        assert!(!reused.branch_created);
+
+        let err = resolve_existing_worktree(repo_path, repo_path).unwrap_err();
+        assert!(err.contains("main checkout"), "{}", err);
+        let err = resolve_existing_worktree(repo_path, detached.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("detached HEAD"), "{}", err);
+        let err = resolve_existing_worktree(repo_path, dir.path().to_str().unwrap()).unwrap_err();
+        assert!(err.contains("is not a git worktree"), "{}", err);
+        assert!(resolve_existing_worktree(repo_path, "/nonexistent/worktree").is_err());
+    }
+
+    #[test]
+    fn test_list_recent_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| run_git(repo_path, args, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "base"]);
+        git(&["checkout", "-q", "-b", "issue-42"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "agent work"]);
+        let agent_commit = git(&["rev-parse", "HEAD"]).trim().to_string();
+
+        assert_eq!(
+            list_recent_commits(repo_path, 10).unwrap(),
+            vec![agent_commit]
+        );
+    }
+
+    #[test]
+    fn test_push_remote() {
+        for url in [
+            "https://github.com/contributor/Handy.git",
+            "git@github.com:contributor/Handy.git",
+            "ssh://git@github.com/contributor/Handy",
+        ] {
+            assert_eq!(parse_remote_owner(url).as_deref(), Some("contributor"));
+        }
+        assert_eq!(parse_remote_owner("not-a-url"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        run_git(repo_path, &["init", "-q", "-b", "main"], None).unwrap();
+        run_git(
+            repo_path,
+            &[
+                "remote",
+                "add",
+                "fork",
+                "git@github.com:contributor/Handy.git",
+            ],
+            None,
+        )
+        .unwrap();
+
+        let config = WorktreeConfig {
+            push_remote: Some("upstream".to_string()),
+            ..Default::default()
+        };
+        let err = create_worktree(repo_path, "issue-1", &config, None).unwrap_err();
+        assert!(err.contains("Remote 'upstream' does not exist"), "{}", err);
+
+        assert_eq!(pr_head_ref(repo_path, "issue-1", None).unwrap(), "issue-1");
+        assert_eq!(
+            pr_head_ref(repo_path, "issue-1", Some("fork")).unwrap(),
+            "contributor:issue-1"
+        );
+
+        // The branch pushes to the fork by default
+        run_git(
+            repo_path,
+            &["config", "user.email", "test@example.com"],
+            None,
+        )
+        .unwrap();
+        run_git(repo_path, &["config", "user.name", "Test"], None).unwrap();
+        run_git(
+            repo_path,
+            &["commit", "-q", "--allow-empty", "-m", "initial"],
+            None,
+        )
+        .unwrap();
+        let config = WorktreeConfig {
+            push_remote: Some("fork".to_string()),
+            ..Default::default()
+        };
+        let created = create_worktree(repo_path, "issue-1", &config, None).unwrap();
+        assert_eq!(
+            run_git(
+                &created.path,
+                &["config", &format!("branch.{}.pushRemote", created.branch)],
+                None
+            )
+            .unwrap(),
+            "fork"
+        );
+    }
+
+    #[test]
+    fn test_exclude_from_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+        run_git(repo_path, &["init", "-q", "-b", "main"], None).unwrap();
+        let exclude = dir.path().join(".git/info/exclude");
+        std::fs::write(&exclude, "*.log").unwrap();
+
+        exclude_from_git(repo_path, ".handy/prompt.md").unwrap();
+        exclude_from_git(repo_path, ".handy/prompt.md").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&exclude).unwrap(),
+            "*.log\n.handy/prompt.md\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_worktree_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        run_git(repo_path, &["init", "-q", "-b", "main"], None).unwrap();
+        let parent = dir.path().to_string_lossy().to_string();
+
+        let resolved =
+            resolve_worktree_path(repo_path, "issue-42", &WorktreeConfig::default()).unwrap();
+        assert_eq!(resolved.name, "repo-issue-42");
+        assert!(Path::new(&resolved.path).is_absolute());
+        assert!(resolved.path.ends_with("/repo-issue-42"));
+        // Already-prefixed names aren't prefixed twice
+        assert_eq!(
+            resolve_worktree_path(repo_path, "repo-issue-42", &WorktreeConfig::default())
+                .unwrap()
+                .name,
+            "repo-issue-42"
+        );
+
+        let with = |prefix: &str, base_path: Option<String>| WorktreeConfig {
+            prefix: prefix.to_string(),
+            base_path,
+            ..Default::default()
+        };
+        let err = |config: WorktreeConfig| {
+            resolve_worktree_path(repo_path, "issue-42", &config).unwrap_err()
+        };
+        assert!(err(with("a/b-", None)).contains("path separators"));
+        assert!(err(with("", Some("worktrees".to_string()))).contains("must be absolute"));
+        assert!(
+            err(with("", Some(format!("{}/repo/sub", parent)))).contains("inside the repository")
+        );
+        assert!(err(with("", Some(format!("{}/repo/../repo", parent))))
+            .contains("inside the repository"));
+        std::fs::create_dir(dir.path().join("repo-issue-42")).unwrap();
+        assert!(err(WorktreeConfig::default()).contains("already exists"));
+        assert!(resolve_worktree_path(repo_path, "../escape", &WorktreeConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_delete_worktree_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let repo_path = repo.to_str().unwrap();
+        let git = |path: &str, args: &[&str]| run_git(path, args, None).unwrap();
+        git(repo_path, &["init", "-q", "-b", "main"]);
+        git(repo_path, &["config", "user.email", "test@example.com"]);
+        git(repo_path, &["config", "user.name", "Test"]);
+        git(
+            repo_path,
+            &["commit", "-q", "--allow-empty", "-m", "initial"],
+        );
+        let worktree = dir.path().join("issue-42");
+        let worktree_path = worktree.to_str().unwrap();
+        git(
+            repo_path,
+            &["worktree", "add", "-q", "-b", "issue-42", worktree_path],
+        );
+        std::fs::write(worktree.join("wip.txt"), "uncommitted").unwrap();
+
+        delete_worktree_branch(worktree_path, "issue-42").unwrap();
+
+        assert_eq!(get_current_branch(worktree_path).unwrap(), "HEAD");
+        assert!(worktree.join("wip.txt").exists());
+        assert!(git(repo_path, &["branch", "--list", "issue-42"]).is_empty());
+    }
+
+    #[test]
+    fn test_backup_worktree_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| run_git(path, args, None).unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-qm", "initial"]);
+
+        // Clean worktree: nothing to back up
+        assert_eq!(backup_worktree_state(path, "handy-agent-1").unwrap(), None);
+
+        std::fs::write(dir.path().join("a.txt"), "two").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "untracked").unwrap();
+        let branch = backup_worktree_state(path, "handy-agent-1")
+            .unwrap()
+            .unwrap();
+        assert!(branch.starts_with("handy-backup/handy-agent-1-"));
+
+        // The backup holds both changes, and the worktree is left as it was
+        assert_eq!(git(&["show", &format!("{}:a.txt", branch)]), "two");
+        assert_eq!(git(&["show", &format!("{}:new.txt", branch)]), "untracked");
+        assert!(git(&["status", "--porcelain"]).contains("?? new.txt"));
+    }
+
     #[test]
     fn test_worktree_config_default() {
         let config = WorktreeConfig::default();
@@ -544,4 +1834,76 @@ mod tests {
         assert!(config.base_path.is_none());
         assert!(config.delete_branch_on_merge);
     }
+
+    #[test]
+    fn test_restore_point_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| run_git(path, args, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::write(dir.path().join("lib.rs"), "v1").unwrap();
+        git(&["add", "lib.rs"]);
+        git(&["commit", "-q", "-m", "initial"]);
+        let head = git(&["rev-parse", "HEAD"]);
+
+        assert!(!restore_to_point(path, "handy-backup/pre-merge-7").unwrap());
+
+        // Uncommitted edits and untracked files are part of the restore point
+        std::fs::write(dir.path().join("lib.rs"), "v1 wip").unwrap();
+        std::fs::write(dir.path().join("notes.md"), "todo").unwrap();
+        create_restore_point(path, "handy-backup/pre-merge-7").unwrap();
+
+        // Simulate a bad conflict resolution
+        std::fs::write(dir.path().join("lib.rs"), "merged").unwrap();
+        std::fs::write(dir.path().join("conflict.txt"), "<<<<<<<").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "merge main"]);
+
+        assert!(restore_to_point(path, "handy-backup/pre-merge-7").unwrap());
+        assert_eq!(git(&["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "v1 wip"
+        );
+        assert!(dir.path().join("notes.md").exists());
+        assert!(!dir.path().join("conflict.txt").exists());
+        assert_eq!(git(&["status", "--porcelain"]), "M lib.rs\n?? notes.md");
+    }
+
+    #[test]
+    fn test_run_git_in_worktree() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(validate_git_args(&args(&["stash", "list"])).is_ok());
+        assert!(validate_git_args(&args(&["log", "-c", "--oneline"])).is_err());
+        assert!(validate_git_args(&args(&["-c", "core.sshCommand=evil", "fetch"])).is_err());
+        assert!(validate_git_args(&args(&["push", "origin"])).is_err());
+        assert!(validate_git_args(&args(&["fetch", "--upload-pack=evil"])).is_err());
+        assert!(validate_git_args(&args(&["pull", "ext::sh -c evil"])).is_err());
+        assert!(validate_git_args(&args(&["diff", "--output=/tmp/x"])).is_err());
+        assert!(validate_git_args(&args(&["fetch", "--upload-p=evil"])).is_err());
+        assert!(validate_git_args(&args(&["diff", "--out=/tmp/x"])).is_err());
+        assert!(validate_git_args(&args(&["diff", "--ext"])).is_err());
+        assert!(validate_git_args(&args(&["diff", "--text", "--stat"])).is_ok());
+        assert!(validate_git_args(&[]).is_err());
+        assert!(is_read_only_git_command(&args(&["status"])));
+        assert!(!is_read_only_git_command(&args(&["reset", "--hard"])));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let git = |list: &[&str]| run_git(path, list, None).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "initial"]);
+
+        let output = run_git_in_worktree(path, &args(&["log", "--format=%s"])).unwrap();
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.stdout.trim(), "initial");
+
+        let output = run_git_in_worktree(path, &args(&["cherry-pick", "nope"])).unwrap();
+        assert_ne!(output.exit_code, Some(0));
+        assert!(!output.stderr.is_empty());
+    }
 }
@@ -545,23 +545,37 @@ pub fn run() {
         commands::sidecar_config::get_sidecar_quick_config,
         commands::sidecar_config::set_sidecar_quick_config_field,
         commands::devops::check_devops_dependencies,
+        commands::devops::get_dependency_versions,
         commands::devops::launch_cli_auth,
         commands::devops::attach_tmux_session,
         commands::devops::list_tmux_sessions,
         commands::devops::get_tmux_session_metadata,
         commands::devops::create_tmux_session,
         commands::devops::kill_tmux_session,
+        commands::devops::list_tmux_session_panes,
         commands::devops::get_tmux_session_output,
         commands::devops::send_tmux_command,
         commands::devops::send_tmux_keys,
+        commands::devops::resize_tmux_session,
         commands::devops::recover_tmux_sessions,
+        commands::devops::recover_sandboxes,
+        commands::devops::get_startup_recovery,
+        commands::devops::reattach_sandbox,
+        commands::devops::get_agent_usage,
+        commands::devops::get_total_usage,
         commands::devops::restart_agent_in_session,
+        commands::devops::pause_agent,
+        commands::devops::resume_agent,
         commands::devops::recover_all_agent_sessions,
         commands::devops::is_tmux_running,
         commands::devops::ensure_master_tmux_session,
         commands::devops::list_git_worktrees,
         commands::devops::get_git_worktree_info,
+        commands::devops::get_branch_sync_status,
+        commands::devops::run_git_in_worktree,
         commands::devops::check_worktree_collision,
+        commands::devops::find_branch_conflicts,
+        commands::devops::preview_worktree_path,
         commands::devops::create_git_worktree,
         commands::devops::create_git_worktree_existing_branch,
         commands::devops::remove_git_worktree,
@@ -573,6 +587,7 @@ pub fn run() {
         commands::devops::list_github_issues,
         commands::devops::get_github_issue,
         commands::devops::get_github_issue_with_agent,
+        commands::devops::get_issue_rendered,
         commands::devops::create_github_issue,
         commands::devops::comment_on_github_issue,
         commands::devops::assign_agent_to_issue,
@@ -583,52 +598,96 @@ pub fn run() {
         commands::devops::list_github_prs,
         commands::devops::get_github_pr,
         commands::devops::get_github_pr_status,
+        commands::devops::get_github_pr_check_logs,
         commands::devops::create_github_pr,
         commands::devops::merge_github_pr,
         commands::devops::close_github_pr,
         commands::devops::spawn_agent,
+        commands::devops::spawn_agent_from_pr,
+        commands::devops::cancel_spawn,
+        commands::devops::get_session_debug_info,
         commands::devops::list_agent_statuses,
         commands::devops::cleanup_agent,
+        commands::devops::find_duplicate_sessions,
+        commands::devops::dedupe_sessions,
+        commands::devops::drain_all_agents,
         commands::devops::create_pr_from_agent,
         commands::devops::complete_agent_work,
         commands::devops::check_and_cleanup_merged_pr,
+        commands::devops::mark_github_pr_ready,
+        commands::devops::detect_agent_done,
+        commands::devops::detect_agent_question,
         commands::devops::get_current_machine_id,
+        commands::devops::list_known_machines,
+        commands::devops::register_output_trigger,
+        commands::devops::list_output_triggers,
+        commands::devops::remove_output_trigger,
         commands::devops::list_local_agent_statuses,
         commands::devops::list_remote_agent_statuses,
         commands::devops::toggle_agent_enabled,
         commands::devops::get_enabled_agents,
+        commands::devops::list_agent_types,
         commands::devops::set_enabled_agents,
         commands::devops::get_sandbox_enabled,
         commands::devops::set_sandbox_enabled,
+        commands::devops::get_sandbox_policy,
+        commands::devops::set_sandbox_policy,
+        commands::devops::is_monitor_enabled,
+        commands::devops::set_monitor_enabled,
+        commands::devops::get_monitor_interval,
+        commands::devops::set_monitor_interval,
+        commands::devops::get_default_work_repo,
+        commands::devops::set_default_work_repo,
+        commands::devops::export_devops_config,
+        commands::devops::import_devops_config,
+        commands::devops::get_github_project_config,
+        commands::devops::set_github_project_config,
+        commands::devops::add_issue_to_project,
+        commands::devops::set_project_field,
+        commands::devops::set_github_issue_project_status,
         commands::devops::create_epic,
         commands::devops::create_sub_issues,
         commands::devops::update_epic_progress,
         commands::devops::spawn_agent_from_issue,
+        commands::devops::update_agent_metadata_comment,
         commands::devops::complete_agent_work_with_pr,
+        commands::devops::detect_test_command,
         commands::devops::plan_epic_from_markdown,
+        commands::devops::import_epic_plan,
+        commands::devops::export_epic_plan,
         commands::devops::list_epic_plan_templates,
         commands::devops::start_epic_orchestration,
         commands::devops::get_epic_phase_status,
         commands::devops::load_epic,
         commands::devops::load_epic_for_recovery,
+        commands::devops::get_epic_pr_summary,
+        commands::devops::build_epic_dependency_graph,
+        commands::devops::validate_epic_integrity,
         commands::devops::update_epic_phase_status_on_github,
         commands::devops::mark_epic_phase_status,
         // Epic state persistence commands
         commands::devops::get_active_epic_state,
+        commands::devops::list_active_epics,
+        commands::devops::switch_active_epic,
         commands::devops::set_active_epic_state,
         commands::devops::set_active_epic_from_recovery,
         commands::devops::clear_active_epic_state,
         commands::devops::sync_active_epic_state,
         commands::devops::update_epic_sub_issue_agent,
+        commands::devops::move_sub_issue_to_phase,
         commands::devops::set_epic_local_repo_path,
         commands::devops::on_pipeline_item_complete,
         commands::devops::merge_ready_pr,
+        commands::devops::abort_support_worker,
         commands::devops::process_ready_prs,
+        commands::devops::watch_pr_checks,
         // Docker sandbox commands
         commands::devops::is_docker_available,
+        commands::devops::prewarm_sandbox_image,
         commands::devops::spawn_sandbox,
         commands::devops::get_sandbox_status,
         commands::devops::get_sandbox_logs,
+        commands::devops::get_container_diff,
         commands::devops::stop_sandbox,
         commands::devops::remove_sandbox,
         commands::devops::list_sandboxes,
@@ -636,19 +695,56 @@ pub fn run() {
         commands::devops::is_devcontainer_cli_available,
         commands::devops::setup_devcontainer,
         commands::devops::start_devcontainer,
+        commands::devops::start_devcontainer_streaming,
+        commands::devops::detect_ports,
         commands::devops::exec_in_devcontainer,
         // Agent network commands
         commands::devops::ensure_agent_network,
         commands::devops::get_agent_network_info,
         commands::devops::list_network_containers,
+        commands::devops::get_network_topology,
+        commands::devops::list_allocated_ports,
+        commands::devops::recreate_agent_network,
         // Pipeline orchestration commands
         commands::devops::assign_issue_to_agent_pipeline,
         commands::devops::skip_issue,
         commands::devops::list_pipeline_items,
         commands::devops::get_pipeline_history,
+        commands::devops::prune_pipeline_history,
+        commands::devops::diagnose_pipeline_store,
+        commands::devops::repair_pipeline_store,
+        commands::devops::get_pipeline_max_history,
+        commands::devops::set_pipeline_max_history,
+        commands::devops::get_store_location,
+        commands::devops::relocate_stores,
         commands::devops::get_pipeline_summary,
+        commands::devops::get_pipeline_metrics,
         commands::devops::detect_and_link_prs,
         commands::devops::sync_all_pr_statuses,
+        commands::devops::requeue_rejected_items,
+        commands::devops::list_prs_awaiting_review,
+        commands::devops::get_auto_ready_draft_prs,
+        commands::devops::set_auto_ready_draft_prs,
+        commands::devops::get_auto_delete_merged_branches,
+        commands::devops::set_auto_delete_merged_branches,
+        commands::devops::get_command_timeouts,
+        commands::devops::set_command_timeouts,
+        commands::devops::get_session_name_template,
+        commands::devops::set_session_name_template,
+        commands::devops::get_debug_commands,
+        commands::devops::set_debug_commands,
+        commands::devops::get_sensitive_patterns,
+        commands::devops::register_sensitive_pattern,
+        commands::devops::remove_sensitive_pattern,
+        commands::devops::get_stuck_threshold_secs,
+        commands::devops::set_stuck_threshold_secs,
+        commands::devops::get_agent_assignee,
+        commands::devops::set_agent_assignee,
+        commands::devops::get_notification_settings,
+        commands::devops::set_notification_settings,
+        commands::devops::get_readonly_mode,
+        commands::devops::set_readonly_mode,
+        commands::devops::get_last_commands,
         commands::devops::update_pipeline_item_pr_status,
         commands::devops::get_pipeline_item,
         commands::devops::find_pipeline_item_by_issue,
@@ -657,9 +753,15 @@ pub fn run() {
         commands::devops::archive_pipeline_item,
         commands::devops::remove_pipeline_item,
         commands::devops::check_sessions_for_prs,
+        commands::devops::verify_agent_pr,
         commands::devops::cleanup_orphaned_containers,
         commands::devops::check_claude_auth_volume,
+        commands::devops::list_claude_auth_files,
+        commands::devops::clear_claude_auth_volume,
+        commands::devops::backup_claude_auth_volume,
         commands::devops::launch_claude_auth_setup,
+        commands::devops::cancel_claude_auth,
+        commands::devops::wait_for_claude_auth,
         helpers::clamshell::is_laptop,
         vad_model::is_vad_model_ready,
         vad_model::download_vad_model_if_needed,
@@ -711,6 +813,7 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_macos_permissions::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
@@ -727,9 +830,23 @@ pub fn run() {
             let file_log_level: log::Level = tauri_log_level.into();
             // Store the file log level in the atomic for the filter to use
             FILE_LOG_LEVEL.store(file_log_level.to_level_filter() as u8, Ordering::Relaxed);
+            devops::config::apply_runtime_settings(&settings);
+            commands::devops::set_read_only(settings.readonly);
+            match devops::orchestration::parse_store_directory(settings.store_directory.as_deref())
+            {
+                Ok(dir) => devops::orchestration::set_store_directory(dir),
+                Err(e) => log::warn!("Ignoring store directory: {}", e),
+            }
+            match app.path().app_data_dir() {
+                Ok(dir) => devops::tmux::set_debug_capture_dir(dir.join("debug-captures")),
+                Err(e) => log::warn!("Debug capture unavailable: {}", e),
+            }
             let app_handle = app.handle().clone();
 
             initialize_core_logic(&app_handle);
+            devops::orchestration::start_startup_recovery(app_handle.clone());
+            devops::orchestration::start_agent_heartbeat(app_handle.clone());
+            devops::orchestration::start_output_triggers(app_handle.clone());
 
             // Show main window only if not starting hidden
             if !settings.start_hidden {
@@ -762,7 +879,18 @@ pub fn run() {
             }
             _ => {}
         })
-        .invoke_handler(specta_builder.invoke_handler())
+        .invoke_handler({
+            let invoke_handler = specta_builder.invoke_handler();
+            move |invoke| {
+                // Read-only observer mode refuses mutating DevOps commands here,
+                // so no command can forget to check it
+                if commands::devops::blocked_by_read_only(invoke.message.command()) {
+                    invoke.resolver.reject("read-only mode");
+                    return true;
+                }
+                invoke_handler(invoke)
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
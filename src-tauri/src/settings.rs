@@ -329,6 +329,57 @@ pub struct AppSettings {
     // DevOps sandbox mode - run agents in Docker containers
     #[serde(default = "default_sandbox_enabled")]
     pub sandbox_enabled: bool,
+    // DevOps default work repo per tracking repo (tracking_repo -> work_repo)
+    #[serde(default)]
+    pub default_work_repos: HashMap<String, String>,
+    // DevOps GitHub Projects (v2) board to keep in sync with pipeline status
+    #[serde(default)]
+    pub github_project: Option<crate::devops::github::ProjectStatusConfig>,
+    // DevOps PR-detection monitor - paused while false (e.g. during rate limiting)
+    #[serde(default = "default_pr_monitor_enabled")]
+    pub pr_monitor_enabled: bool,
+    // DevOps PR-detection monitor - minimum seconds between checks
+    #[serde(default = "default_pr_monitor_interval_secs")]
+    pub pr_monitor_interval_secs: u64,
+    // DevOps pipeline history - maximum completed items kept in the store
+    #[serde(default = "crate::devops::pipeline::default_max_history")]
+    pub pipeline_max_history: usize,
+    // DevOps: mark draft PRs ready once acceptance criteria are checked and checks pass
+    #[serde(default)]
+    pub auto_ready_draft_prs: bool,
+    // DevOps: delete remote and local branches once their PR is merged
+    #[serde(default)]
+    pub auto_delete_merged_branches: bool,
+    // DevOps timeout overrides for gh and docker pull commands
+    #[serde(default)]
+    pub command_timeouts: crate::devops::process::CommandTimeouts,
+    // DevOps agent session name template ({issue}, {agent}, {short_ts}); None = default
+    #[serde(default)]
+    pub session_name_template: Option<String>,
+    // DevOps: log every gh/docker/tmux command and keep recent ones for get_last_commands
+    #[serde(default)]
+    pub debug_commands: bool,
+    // DevOps: extra regexes for secrets redacted from logs and GitHub comments
+    #[serde(default)]
+    pub custom_sensitive_patterns: Vec<String>,
+    // DevOps observer mode: refuse spawn/kill/merge and other mutating commands
+    #[serde(default)]
+    pub readonly: bool,
+    // DevOps: seconds of unchanged agent output before the session is flagged as stuck
+    #[serde(default = "default_stuck_threshold_secs")]
+    pub stuck_threshold_secs: u64,
+    // DevOps: GitHub account assigned to issues while an agent works on them; None = don't assign
+    #[serde(default)]
+    pub agent_assignee: Option<String>,
+    // DevOps: desktop/webhook notifications when agents open PRs, finish or fail
+    #[serde(default)]
+    pub notifications: crate::devops::notifications::NotificationSettings,
+    // DevOps: directory for pipeline_store.json and epic_store.json; None = app data dir
+    #[serde(default)]
+    pub store_directory: Option<String>,
+    // DevOps: agent types that must (or must never) run in a sandbox
+    #[serde(default)]
+    pub sandbox_policy: crate::devops::orchestrator::SandboxPolicy,
 }
 
 fn default_model() -> String {
@@ -435,6 +486,18 @@ fn default_sandbox_enabled() -> bool {
     false
 }
 
+fn default_pr_monitor_enabled() -> bool {
+    true
+}
+
+fn default_pr_monitor_interval_secs() -> u64 {
+    crate::devops::orchestration::DEFAULT_PR_MONITOR_INTERVAL_SECS
+}
+
+fn default_stuck_threshold_secs() -> u64 {
+    crate::devops::orchestrator::DEFAULT_STUCK_THRESHOLD_SECS
+}
+
 fn default_post_process_provider_id() -> String {
     "openai".to_string()
 }
@@ -656,6 +719,23 @@ pub fn get_default_settings() -> AppSettings {
         onichan_silence_threshold: default_onichan_silence_threshold(),
         enabled_agents: default_enabled_agents(),
         sandbox_enabled: default_sandbox_enabled(),
+        default_work_repos: HashMap::new(),
+        github_project: None,
+        pr_monitor_enabled: default_pr_monitor_enabled(),
+        pr_monitor_interval_secs: default_pr_monitor_interval_secs(),
+        pipeline_max_history: crate::devops::pipeline::default_max_history(),
+        auto_ready_draft_prs: false,
+        auto_delete_merged_branches: false,
+        command_timeouts: crate::devops::process::CommandTimeouts::default(),
+        session_name_template: None,
+        debug_commands: false,
+        custom_sensitive_patterns: Vec::new(),
+        readonly: false,
+        stuck_threshold_secs: default_stuck_threshold_secs(),
+        agent_assignee: None,
+        notifications: Default::default(),
+        store_directory: None,
+        sandbox_policy: Default::default(),
     }
 }
 
@@ -41,6 +41,7 @@ export const EpicMonitor: React.FC = () => {
 
   // Handle merging a single PR
   const handleMergePR = async (issueNumber: number) => {
+    if (!activeEpic) return;
     setMergingIssue(issueNumber);
     try {
       const result = await invoke<{
@@ -49,6 +50,7 @@ export const EpicMonitor: React.FC = () => {
         phase_complete: boolean;
         next_phase?: number;
       }>("merge_ready_pr", {
+        repo: activeEpic.tracking_repo,
         issueNumber,
         mergeMethod: "squash",
         deleteBranch: true,
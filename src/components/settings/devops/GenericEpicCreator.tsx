@@ -599,6 +599,7 @@ export function GenericEpicCreator() {
 
       // Update the Epic state to reflect the agent assignment
       await invoke("update_epic_sub_issue_agent", {
+        repo: activeEpic.tracking_repo,
         issueNumber,
         sessionName: result.spawn_result.session_name,
         agentType: "claude",
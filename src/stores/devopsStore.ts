@@ -557,7 +557,8 @@ export const useDevOpsStore = create<DevOpsStore>()(
         // Check active sessions for PR creation (new feature)
         try {
           const prCheckResult = await commands.checkSessionsForPrs();
-          if (prCheckResult.status === "ok") {
+          // null means the check was skipped (monitor paused or throttled)
+          if (prCheckResult.status === "ok" && prCheckResult.data !== null) {
             const prResults = prCheckResult.data;
             for (const result of prResults) {
               if (result.is_new && result.pr_url) {
@@ -608,6 +609,7 @@ export const useDevOpsStore = create<DevOpsStore>()(
 
               try {
                 const mergeResult = await commands.mergeReadyPr(
+                  updatedEpic.tracking_repo,
                   subIssue.issue_number,
                   "squash", // Default to squash merge
                   true, // Delete branch after merge
@@ -663,6 +665,7 @@ export const useDevOpsStore = create<DevOpsStore>()(
             if (epicMonitor.autoUpdateGithub) {
               try {
                 await commands.onPipelineItemComplete(
+                  updatedEpic.tracking_repo,
                   subIssue.issue_number,
                   true,
                 );